@@ -18,15 +18,16 @@ async fn main() {
         .ok()
         .and_then(|s| s.parse().ok());
     let instance_id = env::var("MAESTRO_INSTANCE_ID").ok();
+    let project_hash = env::var("MAESTRO_PROJECT_HASH").ok();
 
     // Log configuration for debugging (to stderr so it doesn't interfere with MCP protocol)
     eprintln!(
-        "[maestro-mcp-server] Starting with config: status_url={:?}, session_id={:?}, instance_id={:?}",
-        status_url, session_id, instance_id
+        "[maestro-mcp-server] Starting with config: status_url={:?}, session_id={:?}, instance_id={:?}, project_hash={:?}",
+        status_url, session_id, instance_id, project_hash
     );
 
     // Create and run the MCP server
-    let server = McpServer::new(status_url, session_id, instance_id);
+    let server = McpServer::new(status_url, session_id, instance_id, project_hash);
 
     if let Err(e) = server.run().await {
         eprintln!("[maestro-mcp-server] Error: {}", e);
@@ -58,9 +58,10 @@ impl McpServer {
         status_url: Option<String>,
         session_id: Option<u32>,
         instance_id: Option<String>,
+        project_hash: Option<String>,
     ) -> Self {
         Self {
-            status_reporter: StatusReporter::new(status_url, session_id, instance_id),
+            status_reporter: StatusReporter::new(status_url, session_id, instance_id, project_hash),
         }
     }
 
@@ -255,7 +256,7 @@ mod tests {
 
     /// Helper: create an McpServer with no status URL (won't make HTTP calls).
     fn test_server() -> McpServer {
-        McpServer::new(None, Some(1), Some("test-instance".to_string()))
+        McpServer::new(None, Some(1), Some("test-instance".to_string()), None)
     }
 
     /// Helper: deserialize a JsonRpcRequest from JSON.
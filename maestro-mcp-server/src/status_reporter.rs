@@ -29,6 +29,9 @@ pub struct StatusPayload {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub needs_input_prompt: Option<String>,
+    /// Lets Maestro route the status when the session isn't registered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_hash: Option<String>,
     pub timestamp: String,
 }
 
@@ -38,6 +41,7 @@ pub struct StatusReporter {
     status_url: Option<String>,
     session_id: Option<u32>,
     instance_id: Option<String>,
+    project_hash: Option<String>,
 }
 
 impl StatusReporter {
@@ -45,12 +49,14 @@ impl StatusReporter {
         status_url: Option<String>,
         session_id: Option<u32>,
         instance_id: Option<String>,
+        project_hash: Option<String>,
     ) -> Self {
         Self {
             client: reqwest::Client::new(),
             status_url,
             session_id,
             instance_id,
+            project_hash,
         }
     }
 
@@ -82,6 +88,7 @@ impl StatusReporter {
             state: state.to_string(),
             message: message.to_string(),
             needs_input_prompt,
+            project_hash: self.project_hash.clone(),
             timestamp: chrono::Utc::now().to_rfc3339(),
         };
 
@@ -163,7 +170,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_no_url_returns_ok() {
-        let reporter = StatusReporter::new(None, Some(1), Some("test".to_string()));
+        let reporter = StatusReporter::new(None, Some(1), Some("test".to_string()), None);
         let result = reporter.report_status("idle", "Ready", None).await;
         assert!(result.is_ok());
     }
@@ -175,6 +182,7 @@ mod tests {
             Some("http://127.0.0.1:19999/status".to_string()),
             Some(1),
             Some("test".to_string()),
+            None,
         );
         let result = reporter.report_status("idle", "Ready", None).await;
         // Should return Ok due to graceful degradation (not crash)
@@ -211,6 +219,7 @@ mod tests {
             Some(format!("http://{}/status", addr)),
             Some(1),
             Some("test".to_string()),
+            None,
         );
 
         let result = reporter.report_status("idle", "Ready", None).await;
@@ -253,6 +262,7 @@ mod tests {
             Some(format!("http://{}/status", addr)),
             Some(1),
             Some("test".to_string()),
+            None,
         );
 
         let result = reporter.report_status("working", "Testing", None).await;
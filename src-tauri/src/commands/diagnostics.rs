@@ -0,0 +1,205 @@
+//! Tauri commands for environment self-checks ("Doctor" screen).
+//!
+//! Each check is independent and never fails the command as a whole: a missing
+//! tool or unwritable directory is reported as a failed check with a detail
+//! message the user can act on.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::core::mcp_config_writer::find_maestro_mcp_path;
+use crate::core::StatusServer;
+use crate::git::{Git, GitError};
+use crate::github::{GitHub, GitHubError};
+
+/// Outcome of a single diagnostic check.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+impl DiagnosticCheck {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Aggregated result of all diagnostic checks.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    /// True only when every check passed.
+    pub ok: bool,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl Diagnostics {
+    fn from_checks(checks: Vec<DiagnosticCheck>) -> Self {
+        Self {
+            ok: checks.iter().all(|c| c.ok),
+            checks,
+        }
+    }
+}
+
+/// Checks that git is on `$PATH` and reports its version.
+async fn check_git() -> DiagnosticCheck {
+    const NAME: &str = "git";
+    match Git::new(std::env::temp_dir()).run(&["--version"]).await {
+        Ok(output) => DiagnosticCheck::pass(NAME, output.trimmed()),
+        Err(e @ GitError::GitNotFound) => DiagnosticCheck::fail(NAME, e.to_string()),
+        Err(e) => DiagnosticCheck::fail(NAME, format!("git --version failed: {}", e)),
+    }
+}
+
+/// Checks that the GitHub CLI is installed and authenticated.
+async fn check_gh() -> DiagnosticCheck {
+    const NAME: &str = "gh";
+    match GitHub::new(std::env::temp_dir()).auth_status().await {
+        Ok(status) if status.logged_in => {
+            let detail = match status.username {
+                Some(user) => format!("Authenticated as {}", user),
+                None => "Authenticated".to_string(),
+            };
+            DiagnosticCheck::pass(NAME, detail)
+        }
+        Ok(_) => DiagnosticCheck::fail(NAME, GitHubError::NotAuthenticated.to_string()),
+        Err(e) => DiagnosticCheck::fail(NAME, e.to_string()),
+    }
+}
+
+/// Reports which `maestro-mcp-server` binary would be used, if any.
+fn check_mcp_binary(path: Option<PathBuf>) -> DiagnosticCheck {
+    const NAME: &str = "maestro-mcp-server";
+    match path {
+        Some(path) => DiagnosticCheck::pass(NAME, path.display().to_string()),
+        None => DiagnosticCheck::fail(
+            NAME,
+            "Binary not found next to the app or in any known install location",
+        ),
+    }
+}
+
+/// Reports the status server port, or that it failed to start.
+fn check_status_server(port: Option<u16>) -> DiagnosticCheck {
+    const NAME: &str = "status server";
+    match port {
+        Some(port) => DiagnosticCheck::pass(NAME, format!("Listening on 127.0.0.1:{}", port)),
+        None => DiagnosticCheck::fail(NAME, "Status server is not running"),
+    }
+}
+
+/// Verifies that `dir` exists (creating it if needed) and accepts new files.
+async fn check_dir_writable(name: &str, dir: &Path) -> DiagnosticCheck {
+    if let Err(e) = tokio::fs::create_dir_all(dir).await {
+        return DiagnosticCheck::fail(name, format!("Cannot create {}: {}", dir.display(), e));
+    }
+
+    let probe = dir.join(format!(".maestro-write-test.{}", std::process::id()));
+    match tokio::fs::write(&probe, b"").await {
+        Ok(()) => {
+            let _ = tokio::fs::remove_file(&probe).await;
+            DiagnosticCheck::pass(name, format!("{} is writable", dir.display()))
+        }
+        Err(e) => DiagnosticCheck::fail(name, format!("Cannot write to {}: {}", dir.display(), e)),
+    }
+}
+
+/// Runs all environment checks and returns a per-check report.
+///
+/// Checks git, gh (including authentication), the resolved
+/// `maestro-mcp-server` path, the status server port, and write access
+/// to `~/.claude`.
+#[tauri::command]
+pub async fn run_diagnostics(app: AppHandle) -> Diagnostics {
+    // The status server is managed once it finishes starting, so it may be absent.
+    let status_port = app
+        .try_state::<Arc<StatusServer>>()
+        .map(|server| server.port());
+
+    let claude_dir = match directories::BaseDirs::new() {
+        Some(dirs) => check_dir_writable("~/.claude", &dirs.home_dir().join(".claude")).await,
+        None => DiagnosticCheck::fail("~/.claude", "Could not determine home directory"),
+    };
+
+    let (git, gh) = tokio::join!(check_git(), check_gh());
+
+    Diagnostics::from_checks(vec![
+        git,
+        gh,
+        check_mcp_binary(find_maestro_mcp_path()),
+        check_status_server(status_port),
+        claude_dir,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_ok_only_when_all_checks_pass() {
+        let passing = Diagnostics::from_checks(vec![
+            check_mcp_binary(Some(PathBuf::from("/opt/maestro/maestro-mcp-server"))),
+            check_status_server(Some(9900)),
+        ]);
+        assert!(passing.ok);
+        assert_eq!(passing.checks.len(), 2);
+        assert_eq!(passing.checks[0].detail, "/opt/maestro/maestro-mcp-server");
+        assert!(passing.checks[1].detail.contains("9900"));
+
+        let failing = Diagnostics::from_checks(vec![
+            check_mcp_binary(None),
+            check_status_server(Some(9900)),
+        ]);
+        assert!(!failing.ok);
+        assert!(!failing.checks[0].ok);
+        assert!(failing.checks[1].ok);
+
+        let json = serde_json::to_value(&failing).unwrap();
+        assert_eq!(json["checks"][0]["name"], "maestro-mcp-server");
+        assert_eq!(json["checks"][0]["ok"], false);
+    }
+
+    #[tokio::test]
+    async fn test_check_dir_writable_creates_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("nested").join(".claude");
+
+        let check = check_dir_writable("~/.claude", &target).await;
+
+        assert!(check.ok, "{}", check.detail);
+        assert!(target.is_dir());
+        assert_eq!(std::fs::read_dir(&target).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_dir_writable_reports_file_in_the_way() {
+        let dir = tempfile::tempdir().unwrap();
+        let blocker = dir.path().join("blocker");
+        std::fs::write(&blocker, "").unwrap();
+
+        let check = check_dir_writable("~/.claude", &blocker.join(".claude")).await;
+
+        assert!(!check.ok);
+        assert!(check.detail.starts_with("Cannot create"));
+    }
+}
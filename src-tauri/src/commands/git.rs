@@ -1,6 +1,7 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-use crate::git::{BranchInfo, CommitInfo, FileChange, Git, GitError, GitUserConfig, RemoteInfo, WorktreeInfo};
+use crate::git::{clean_confirmation_token, BranchDetail, BranchInfo, CommitGroup, CommitInfo, ConflictResolution, ConflictedFile, FileChange, Git, GitError, GitUserConfig, GroupBy, HeadInfo, HookInfo, RemoteInfo, WorktreeInfo};
 
 /// Information about a detected git repository within a workspace.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -17,6 +18,15 @@ pub struct RepositoryInfo {
     pub remote_url: Option<String>,
 }
 
+/// Files a `git clean` would remove, plus the token required to execute it.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CleanPreview {
+    /// Repository-relative paths; directories end with `/`.
+    pub paths: Vec<String>,
+    /// Pass back to `git_clean_execute` to confirm this exact preview.
+    pub token: String,
+}
+
 /// Returns `Err(GitError::NotARepo)` if the given path string is empty.
 fn validate_repo_path(repo_path: &str) -> Result<(), GitError> {
     if repo_path.is_empty() {
@@ -29,11 +39,29 @@ fn validate_repo_path(repo_path: &str) -> Result<(), GitError> {
 
 /// Exposes `Git::list_branches` to the frontend.
 /// Returns all local and remote branches (excluding HEAD pointer entries).
+/// With `include_details`, also fills in upstream and worktree association
+/// via `Git::list_branches_with_details`.
 #[tauri::command]
-pub async fn git_branches(repo_path: String) -> Result<Vec<BranchInfo>, GitError> {
+pub async fn git_branches(
+    repo_path: String,
+    include_details: Option<bool>,
+) -> Result<Vec<BranchInfo>, GitError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.list_branches().await
+    if include_details.unwrap_or(false) {
+        git.list_branches_with_details().await
+    } else {
+        git.list_branches().await
+    }
+}
+
+/// Exposes `Git::list_branches_detailed` to the frontend.
+/// Returns local and remote branches with their latest commit, newest first.
+#[tauri::command]
+pub async fn git_list_branches_detailed(repo_path: String) -> Result<Vec<BranchDetail>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.list_branches_detailed().await
 }
 
 /// Exposes `Git::current_branch` to the frontend.
@@ -45,6 +73,15 @@ pub async fn git_current_branch(repo_path: String) -> Result<String, GitError> {
     git.current_branch().await
 }
 
+/// Exposes `Git::current_head` to the frontend.
+/// Unlike `git_current_branch`, tells a branch apart from a detached commit.
+#[tauri::command]
+pub async fn git_current_head(repo_path: String) -> Result<HeadInfo, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.current_head().await
+}
+
 /// Exposes `Git::uncommitted_count` to the frontend.
 /// Returns the number of dirty files (staged + unstaged + untracked).
 #[tauri::command]
@@ -56,21 +93,32 @@ pub async fn git_uncommitted_count(repo_path: String) -> Result<usize, GitError>
 
 /// Exposes `Git::worktree_list` to the frontend.
 /// Returns all worktrees (including the main one) with path, HEAD, and branch info.
+/// With `managed_only`, the main worktree and detached entries are left out
+/// (see `Git::managed_worktrees`).
 #[tauri::command]
-pub async fn git_worktree_list(repo_path: String) -> Result<Vec<WorktreeInfo>, GitError> {
+pub async fn git_worktree_list(
+    repo_path: String,
+    managed_only: Option<bool>,
+) -> Result<Vec<WorktreeInfo>, GitError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.worktree_list().await
+    if managed_only.unwrap_or(false) {
+        git.managed_worktrees().await
+    } else {
+        git.worktree_list().await
+    }
 }
 
 /// Exposes `Git::worktree_add` to the frontend.
 /// Creates a new worktree at `path`, optionally on a new branch from `checkout_ref`.
+/// `no_checkout` leaves the working tree empty (`--no-checkout`).
 #[tauri::command]
 pub async fn git_worktree_add(
     repo_path: String,
     path: String,
     new_branch: Option<String>,
     checkout_ref: Option<String>,
+    no_checkout: Option<bool>,
 ) -> Result<WorktreeInfo, GitError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
@@ -79,6 +127,7 @@ pub async fn git_worktree_add(
         &wt_path,
         new_branch.as_deref(),
         checkout_ref.as_deref(),
+        no_checkout.unwrap_or(false),
     )
     .await
 }
@@ -97,17 +146,70 @@ pub async fn git_worktree_remove(
     git.worktree_remove(&wt_path, force).await
 }
 
-/// Exposes `Git::commit_log` to the frontend.
-/// Returns up to `max_count` commits in topological order across all or current branch.
+/// Exposes `Git::commit_log_page` to the frontend.
+/// Returns up to `max_count` commits in topological order across all or current branch,
+/// after skipping the newest `skip` (default 0) so older pages can be loaded.
+/// `verify_signatures` adds each commit's GPG signature status (slower), and
+/// `include_notes` each commit's git note.
 #[tauri::command]
 pub async fn git_commit_log(
     repo_path: String,
     max_count: usize,
     all_branches: bool,
+    skip: Option<usize>,
+    verify_signatures: Option<bool>,
+    include_notes: Option<bool>,
 ) -> Result<Vec<CommitInfo>, GitError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.commit_log(max_count, all_branches).await
+    git.commit_log_page(
+        max_count,
+        skip.unwrap_or(0),
+        all_branches,
+        verify_signatures.unwrap_or(false),
+        include_notes.unwrap_or(false),
+    )
+    .await
+}
+
+/// Exposes `Git::commit_log_grouped` to the frontend.
+/// Returns up to `max` commits of the current branch grouped by author or day.
+#[tauri::command]
+pub async fn git_commit_log_grouped(
+    repo_path: String,
+    max: usize,
+    group_by: GroupBy,
+) -> Result<Vec<CommitGroup>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.commit_log_grouped(max, group_by).await
+}
+
+/// Exposes `Git::commits_between` to the frontend.
+/// Returns up to `max` commits in `head` that are not in `base`, newest first.
+#[tauri::command]
+pub async fn git_commits_between(
+    repo_path: String,
+    base: String,
+    head: String,
+    max: usize,
+) -> Result<Vec<CommitInfo>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.commits_between(&base, &head, max).await
+}
+
+/// Exposes `Git::merge_base` to the frontend.
+/// Returns the commit where `a` and `b` diverged, or null for unrelated histories.
+#[tauri::command]
+pub async fn git_merge_base(
+    repo_path: String,
+    a: String,
+    b: String,
+) -> Result<Option<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.merge_base(&a, &b).await
 }
 
 /// Checks out a branch by name.
@@ -119,6 +221,40 @@ pub async fn git_checkout_branch(repo_path: String, branch_name: String) -> Resu
     git.checkout_branch(&branch_name).await
 }
 
+/// Exposes `Git::check_ref_name` to the frontend.
+/// Returns whether `branch_name` is a valid new branch name, without creating it.
+#[tauri::command]
+pub async fn git_check_branch_name(
+    repo_path: String,
+    branch_name: String,
+) -> Result<bool, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.check_ref_name(&branch_name).await
+}
+
+/// Exposes `Git::list_hooks` to the frontend.
+/// Lists hook scripts (enabled and disabled) for a repo or worktree.
+#[tauri::command]
+pub async fn git_list_hooks(repo_path: String) -> Result<Vec<HookInfo>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.list_hooks().await
+}
+
+/// Exposes `Git::set_hook_enabled` to the frontend.
+/// Disables a hook by renaming it to `<name>.disabled`, or re-enables it.
+#[tauri::command]
+pub async fn git_set_hook_enabled(
+    repo_path: String,
+    name: String,
+    enabled: bool,
+) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.set_hook_enabled(&name, enabled).await
+}
+
 /// Creates a new branch, optionally from a specific starting point.
 #[tauri::command]
 pub async fn git_create_branch(
@@ -192,6 +328,52 @@ pub async fn git_remove_remote(repo_path: String, name: String) -> Result<(), Gi
     git.remove_remote(&name).await
 }
 
+/// Fetches a remote with `--prune` and returns the remote-tracking refs
+/// that were removed. `env` adds variables such as `GIT_SSH_COMMAND` or
+/// `HTTPS_PROXY` for the fetch.
+#[tauri::command]
+pub async fn git_fetch_prune(
+    repo_path: String,
+    remote: String,
+    env: Option<HashMap<String, String>>,
+) -> Result<Vec<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path).with_env(env.unwrap_or_default());
+    git.fetch_prune(&remote).await
+}
+
+/// Lists local branches whose upstream branch has been deleted.
+#[tauri::command]
+pub async fn git_list_stale_branches(repo_path: String) -> Result<Vec<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.list_stale_tracking_branches().await
+}
+
+/// Lists local branches already merged into `into`.
+#[tauri::command]
+pub async fn git_list_merged_branches(
+    repo_path: String,
+    into: String,
+) -> Result<Vec<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.merged_branches(&into).await
+}
+
+/// Deletes local branches merged into `into`, skipping any checked out in a
+/// worktree. With `dry_run`, only reports what would be deleted.
+#[tauri::command]
+pub async fn git_delete_merged_branches(
+    repo_path: String,
+    into: String,
+    dry_run: bool,
+) -> Result<Vec<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.delete_merged_branches(&into, dry_run).await
+}
+
 /// Gets refs (branches and tags) pointing to a specific commit.
 #[tauri::command]
 pub async fn git_refs_for_commit(
@@ -204,11 +386,16 @@ pub async fn git_refs_for_commit(
 }
 
 /// Tests connectivity to a remote.
-/// Returns true if reachable, false otherwise.
+/// Returns true if reachable, false otherwise. `env` adds variables such as
+/// `GIT_SSH_COMMAND` or `HTTPS_PROXY` for the check.
 #[tauri::command]
-pub async fn git_test_remote(repo_path: String, remote_name: String) -> Result<bool, GitError> {
+pub async fn git_test_remote(
+    repo_path: String,
+    remote_name: String,
+    env: Option<HashMap<String, String>>,
+) -> Result<bool, GitError> {
     validate_repo_path(&repo_path)?;
-    let git = Git::new(&repo_path);
+    let git = Git::new(&repo_path).with_env(env.unwrap_or_default());
     git.test_remote(&remote_name).await
 }
 
@@ -244,6 +431,69 @@ pub async fn git_set_default_branch(
     git.set_default_branch(&branch, global).await
 }
 
+/// Exposes `Git::remote_default_branch` to the frontend.
+/// Returns the branch the remote's HEAD points at, or `None` if unknown.
+#[tauri::command]
+pub async fn git_remote_default_branch(
+    repo_path: String,
+    remote: String,
+) -> Result<Option<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.remote_default_branch(&remote).await
+}
+
+/// Exposes `Git::conflicted_files` to the frontend.
+/// Lists unmerged paths with their conflict state and marker counts.
+#[tauri::command]
+pub async fn git_conflicted_files(repo_path: String) -> Result<Vec<ConflictedFile>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.conflicted_files().await
+}
+
+/// Exposes `Git::resolve_conflict` to the frontend.
+/// Settles one conflicted path with the given strategy and stages it.
+#[tauri::command]
+pub async fn git_resolve_conflict(
+    repo_path: String,
+    path: String,
+    strategy: ConflictResolution,
+) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.resolve_conflict(&path, strategy).await
+}
+
+/// Exposes `Git::clean_preview` to the frontend.
+/// Lists untracked (and optionally ignored) paths along with a confirmation token.
+#[tauri::command]
+pub async fn git_clean_preview(
+    repo_path: String,
+    include_dirs: bool,
+    include_ignored: bool,
+) -> Result<CleanPreview, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let paths = git.clean_preview(include_dirs, include_ignored).await?;
+    let token = clean_confirmation_token(&paths);
+    Ok(CleanPreview { paths, token })
+}
+
+/// Exposes `Git::clean_execute` to the frontend.
+/// Fails with `CleanNotConfirmed` unless `token` matches the current preview.
+#[tauri::command]
+pub async fn git_clean_execute(
+    repo_path: String,
+    include_dirs: bool,
+    include_ignored: bool,
+    token: String,
+) -> Result<Vec<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.clean_execute(include_dirs, include_ignored, &token).await
+}
+
 /// Checks if a path is a git repository root.
 /// Returns true if the path contains a .git directory or file (could be a worktree).
 #[tauri::command]
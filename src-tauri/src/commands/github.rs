@@ -1,9 +1,79 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::commands::mcp::project_store_name;
+use crate::commands::worktree::get_fallback_branch;
+use crate::core::session_manager::SessionManager;
+use crate::git::Git;
 use crate::github::{
-    AuthStatus, CreatePullRequestOptions, DiscussionDetail, DiscussionInfo, GitHub, GitHubError,
+    group_review_threads, issue_to_markdown, parse_remote_host, pr_to_markdown, AuthStatus,
+    CreatePullRequestOptions, DiscussionDetail, DiscussionInfo, ForkParent, GitHub, GitHubError,
     IssueDetail, IssueFilter, IssueInfo, MergeMethod, PullRequestDetail, PullRequestFilter,
-    PullRequestInfo,
+    PullRequestInfo, RateLimitInfo, RepoMetadata, ReviewThread, UserInfo,
 };
 
+/// Repository metadata per canonical project path. Owner, name and
+/// visibility rarely change, so entries live until explicitly refreshed.
+static REPO_METADATA: LazyLock<DashMap<PathBuf, RepoMetadata>> = LazyLock::new(DashMap::new);
+
+/// Returns owner, name, default branch, remote URL and visibility of the
+/// project's GitHub repository.
+///
+/// The result is cached per project; pass `refresh: true` to look it up again.
+#[tauri::command]
+pub async fn get_repo_metadata(
+    project_path: String,
+    refresh: Option<bool>,
+) -> Result<RepoMetadata, GitHubError> {
+    repo_metadata_inner(
+        Path::new(&project_path),
+        refresh.unwrap_or(false),
+        GitHub::new,
+    )
+    .await
+}
+
+/// Inner implementation for `get_repo_metadata`, extracted for testability.
+///
+/// Uses the `origin` remote, or the first remote if there is no `origin`.
+pub(crate) async fn repo_metadata_inner(
+    project_path: &Path,
+    refresh: bool,
+    make_gh: impl FnOnce(PathBuf) -> GitHub,
+) -> Result<RepoMetadata, GitHubError> {
+    let key = std::fs::canonicalize(project_path).unwrap_or_else(|_| project_path.to_path_buf());
+    if !refresh {
+        if let Some(cached) = REPO_METADATA.get(&key) {
+            return Ok(cached.clone());
+        }
+    }
+
+    let remote_url = primary_remote_url(&key).await?;
+    let metadata = make_gh(key.clone()).repo_metadata(&remote_url).await?;
+    REPO_METADATA.insert(key, metadata.clone());
+    Ok(metadata)
+}
+
+/// Returns the URL of the `origin` remote, or of the first remote if there
+/// is no `origin`.
+async fn primary_remote_url(repo_path: &Path) -> Result<String, GitHubError> {
+    let remotes = Git::new(repo_path)
+        .list_remotes()
+        .await
+        .map_err(|_| GitHubError::NotGitHubRepo)?;
+    remotes
+        .iter()
+        .find(|r| r.name == "origin")
+        .or_else(|| remotes.first())
+        .map(|r| r.url.clone())
+        .ok_or(GitHubError::NotGitHubRepo)
+}
+
 /// Checks if the user is authenticated with GitHub CLI.
 #[tauri::command]
 pub async fn github_auth_status(repo_path: String) -> Result<AuthStatus, GitHubError> {
@@ -11,6 +81,44 @@ pub async fn github_auth_status(repo_path: String) -> Result<AuthStatus, GitHubE
     gh.auth_status().await
 }
 
+/// Returns the remaining GitHub API quota for core, GraphQL and search.
+#[tauri::command]
+pub async fn github_rate_limit(repo_path: String) -> Result<RateLimitInfo, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.rate_limit().await
+}
+
+/// Returns a GitHub user's name and avatar URL, cached across calls.
+///
+/// The user is looked up on the host of the repository's primary remote,
+/// falling back to github.com.
+#[tauri::command]
+pub async fn github_user_info(repo_path: String, login: String) -> Result<UserInfo, GitHubError> {
+    let host = primary_remote_url(Path::new(&repo_path))
+        .await
+        .ok()
+        .and_then(|url| parse_remote_host(&url))
+        .unwrap_or_else(|| "github.com".to_string());
+    let gh = GitHub::new(&repo_path);
+    gh.user_info(&host, &login).await
+}
+
+/// Calls a GitHub REST endpoint Maestro doesn't otherwise wrap via `gh api`,
+/// returning the parsed JSON response.
+///
+/// `method` is limited to GET, POST, PATCH, and PUT; `fields` are sent as
+/// string fields (query parameters for GET).
+#[tauri::command]
+pub async fn github_api(
+    repo_path: String,
+    method: String,
+    endpoint: String,
+    fields: Option<Vec<(String, String)>>,
+) -> Result<serde_json::Value, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.api(&method, &endpoint, fields.unwrap_or_default()).await
+}
+
 /// Lists pull requests with optional filtering.
 #[tauri::command]
 pub async fn github_list_prs(
@@ -38,7 +146,27 @@ pub async fn github_get_pr(
     gh.get_pull_request(number).await
 }
 
+/// Renders a pull request's description and comments as a markdown document.
+#[tauri::command]
+pub async fn export_pr_markdown(repo_path: String, number: u64) -> Result<String, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    Ok(pr_to_markdown(&gh.get_pull_request(number).await?))
+}
+
+/// Gets a pull request's inline review comments, grouped into threads.
+#[tauri::command]
+pub async fn github_pr_review_comments(
+    repo_path: String,
+    number: u64,
+) -> Result<Vec<ReviewThread>, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    Ok(group_review_threads(gh.pr_review_comments(number).await?))
+}
+
 /// Creates a new pull request.
+///
+/// Pass `head_repo` (`owner/name`) when `head` was pushed to a fork; the PR
+/// then targets the fork's parent, and an empty `base` uses its default branch.
 #[tauri::command]
 pub async fn github_create_pr(
     repo_path: String,
@@ -47,6 +175,7 @@ pub async fn github_create_pr(
     base: String,
     head: String,
     draft: bool,
+    head_repo: Option<String>,
 ) -> Result<PullRequestInfo, GitHubError> {
     let gh = GitHub::new(&repo_path);
     let options = CreatePullRequestOptions {
@@ -55,18 +184,228 @@ pub async fn github_create_pr(
         base,
         head,
         draft,
+        head_repo,
     };
     gh.create_pull_request(options).await
 }
 
+/// Returns the repository the project's repo was forked from, or `None` if
+/// it isn't a fork, so the create form can pre-fill the upstream base.
+#[tauri::command]
+pub async fn github_fork_parent(repo_path: String) -> Result<Option<ForkParent>, GitHubError> {
+    GitHub::new(&repo_path).fork_parent(None).await
+}
+
+/// Result of `github_create_pr_for_session`, reporting what each step did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPullRequest {
+    /// The session branch used as the PR head.
+    pub head: String,
+    /// The base branch the PR targets.
+    pub base: String,
+    /// Remote the branch was pushed to, or `None` if it already had an upstream.
+    pub pushed_to: Option<String>,
+    /// The created pull request.
+    pub pull_request: PullRequestInfo,
+}
+
+/// Creates a pull request from a session's branch.
+///
+/// The head is the session's assigned branch and the base is the repository's
+/// default branch. If the head has no upstream yet, it is pushed with
+/// `--set-upstream` before the PR is created.
+#[tauri::command]
+pub async fn github_create_pr_for_session(
+    session_mgr: State<'_, SessionManager>,
+    project_path: String,
+    session_id: u32,
+    title: String,
+    body: String,
+    draft: bool,
+) -> Result<SessionPullRequest, String> {
+    create_pr_for_session_inner(
+        &session_mgr,
+        &project_path,
+        session_id,
+        title,
+        body,
+        draft,
+        GitHub::new,
+    )
+    .await
+}
+
+/// Testable core of `github_create_pr_for_session`; `make_gh` builds the
+/// `gh` runner for the session's working directory.
+pub(crate) async fn create_pr_for_session_inner(
+    session_mgr: &SessionManager,
+    project_path: &str,
+    session_id: u32,
+    title: String,
+    body: String,
+    draft: bool,
+    make_gh: impl FnOnce(PathBuf) -> GitHub,
+) -> Result<SessionPullRequest, String> {
+    let project = std::fs::canonicalize(project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?;
+    let session = session_mgr
+        .get_session(session_id)
+        .filter(|s| Path::new(&s.project_path) == project)
+        .ok_or_else(|| format!("Session {} not found in '{}'", session_id, project_path))?;
+    let head = session
+        .branch
+        .ok_or_else(|| format!("Session {} has no branch assigned", session_id))?;
+
+    // Run git and gh from the session's worktree when it has one
+    let repo_dir = session.worktree_path.map(PathBuf::from).unwrap_or(project);
+    let git = Git::new(&repo_dir);
+
+    let base = get_fallback_branch(&git, &head)
+        .await
+        .ok_or_else(|| format!("Could not determine a base branch for '{}'", head))?;
+
+    let upstream = git
+        .upstream_branch(&head)
+        .await
+        .map_err(|e| format!("Failed to check upstream of '{}': {}", head, e))?;
+    let pushed_to = match upstream {
+        Some(_) => None,
+        None => {
+            let remote = push_remote(&git).await?;
+            git.push(&remote, &head, true)
+                .await
+                .map_err(|e| format!("Failed to push '{}' to {}: {}", head, remote, e))?;
+            Some(remote)
+        }
+    };
+
+    let options = CreatePullRequestOptions {
+        title,
+        body,
+        base: base.clone(),
+        head: head.clone(),
+        draft,
+        head_repo: None,
+    };
+    let pull_request = make_gh(repo_dir)
+        .create_pull_request(options)
+        .await
+        .map_err(|e| format!("Failed to create pull request: {}", e))?;
+
+    Ok(SessionPullRequest {
+        head,
+        base,
+        pushed_to,
+        pull_request,
+    })
+}
+
+/// Picks the remote to publish a new branch to: `origin` if present,
+/// otherwise the first configured remote.
+async fn push_remote(git: &Git) -> Result<String, String> {
+    let remotes = git
+        .list_remotes()
+        .await
+        .map_err(|e| format!("Failed to list remotes: {}", e))?;
+    remotes
+        .iter()
+        .find(|r| r.name == "origin")
+        .or_else(|| remotes.first())
+        .map(|r| r.name.clone())
+        .ok_or_else(|| "Repository has no remote to push to".to_string())
+}
+
+/// Project store key holding the [`MergeDefaults`].
+const MERGE_DEFAULTS_KEY: &str = "merge_defaults";
+
+/// A project's merge policy, used when a merge doesn't specify its own.
+/// Unset projects merge with a merge commit and keep the branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MergeDefaults {
+    pub method: MergeMethod,
+    pub delete_branch: bool,
+}
+
+/// Picks the merge method and branch deletion for a merge, preferring the
+/// caller's choices over the project's stored defaults.
+pub(crate) fn resolve_merge_options(
+    method: Option<MergeMethod>,
+    delete_branch: Option<bool>,
+    defaults: Option<MergeDefaults>,
+) -> (MergeMethod, bool) {
+    let defaults = defaults.unwrap_or_default();
+    (
+        method.unwrap_or(defaults.method),
+        delete_branch.unwrap_or(defaults.delete_branch),
+    )
+}
+
+/// Reads the project's stored merge defaults, if any have been saved.
+fn load_merge_defaults(
+    app: &AppHandle,
+    project_path: &str,
+) -> Result<Option<MergeDefaults>, String> {
+    let store = app
+        .store(project_store_name(project_path)?)
+        .map_err(|e| e.to_string())?;
+    store
+        .get(MERGE_DEFAULTS_KEY)
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the project's merge defaults, or the built-in ones if unset.
+#[tauri::command]
+pub async fn get_merge_defaults(
+    app: AppHandle,
+    project_path: String,
+) -> Result<MergeDefaults, String> {
+    Ok(load_merge_defaults(&app, &project_path)?.unwrap_or_default())
+}
+
+/// Saves the merge method and branch deletion used for the project's merges
+/// when the caller doesn't choose.
+#[tauri::command]
+pub async fn set_merge_defaults(
+    app: AppHandle,
+    project_path: String,
+    defaults: MergeDefaults,
+) -> Result<(), String> {
+    let store = app
+        .store(project_store_name(&project_path)?)
+        .map_err(|e| e.to_string())?;
+    store.set(MERGE_DEFAULTS_KEY, serde_json::json!(defaults));
+    store.save().map_err(|e| e.to_string())?;
+
+    log::debug!("Saved merge defaults for project: {}", project_path);
+    Ok(())
+}
+
 /// Merges a pull request.
+///
+/// `method` and `delete_branch` fall back to the project's merge defaults
+/// (see [`set_merge_defaults`]) when omitted.
 #[tauri::command]
 pub async fn github_merge_pr(
+    app: AppHandle,
     repo_path: String,
     number: u64,
-    method: MergeMethod,
-    delete_branch: bool,
+    method: Option<MergeMethod>,
+    delete_branch: Option<bool>,
 ) -> Result<(), GitHubError> {
+    let defaults = if method.is_none() || delete_branch.is_none() {
+        load_merge_defaults(&app, &repo_path).unwrap_or_else(|e| {
+            log::warn!("Failed to load merge defaults for {}: {}", repo_path, e);
+            None
+        })
+    } else {
+        None
+    };
+    let (method, delete_branch) = resolve_merge_options(method, delete_branch, defaults);
+
     let gh = GitHub::new(&repo_path);
     gh.merge_pull_request(number, method, delete_branch).await
 }
@@ -126,6 +465,13 @@ pub async fn github_get_issue(
     gh.get_issue(number).await
 }
 
+/// Renders an issue's description and comments as a markdown document.
+#[tauri::command]
+pub async fn export_issue_markdown(repo_path: String, number: u64) -> Result<String, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    Ok(issue_to_markdown(&gh.get_issue(number).await?))
+}
+
 /// Adds a comment to an issue.
 #[tauri::command]
 pub async fn github_comment_issue(
@@ -171,3 +517,201 @@ pub async fn github_comment_discussion(
     let gh = GitHub::new(&repo_path);
     gh.comment_discussion(number, &body).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session_manager::AiMode;
+    #[cfg(unix)]
+    use crate::github::runner::fake_gh;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_merge_options_fall_back_to_stored_defaults() {
+        let stored = MergeDefaults {
+            method: MergeMethod::Squash,
+            delete_branch: true,
+        };
+
+        assert_eq!(
+            resolve_merge_options(None, None, Some(stored)),
+            (MergeMethod::Squash, true)
+        );
+        assert_eq!(
+            resolve_merge_options(Some(MergeMethod::Rebase), Some(false), Some(stored)),
+            (MergeMethod::Rebase, false)
+        );
+        // Nothing stored: a plain merge that keeps the branch
+        assert_eq!(
+            resolve_merge_options(None, None, None),
+            (MergeMethod::Merge, false)
+        );
+
+        // The stored JSON round-trips through the frontend's shape
+        let json = serde_json::json!(stored);
+        assert_eq!(
+            json,
+            serde_json::json!({"method": "squash", "deleteBranch": true})
+        );
+        assert_eq!(
+            serde_json::from_value::<MergeDefaults>(json).unwrap(),
+            stored
+        );
+        // Fields missing from older stores take their defaults
+        assert_eq!(
+            serde_json::from_value::<MergeDefaults>(serde_json::json!({"method": "rebase"}))
+                .unwrap(),
+            MergeDefaults {
+                method: MergeMethod::Rebase,
+                delete_branch: false,
+            }
+        );
+    }
+
+    /// Writes a fake `gh` that logs its arguments, answers `pr create` with a
+    /// PR URL, `pr view` with a minimal PR payload, and `repo view` with a
+    /// public repo whose default branch is `main`.
+    #[cfg(unix)]
+    fn write_gh_spy(dir: &Path, log: &Path) -> GitHub {
+        let script = format!(
+            "echo \"$*\" >> '{}'\n\
+             case \"$1 $2\" in\n\
+             'pr create') echo 'https://github.com/octo/repo/pull/7' ;;\n\
+             'repo view') printf '{{\"defaultBranchRef\":{{\"name\":\"main\"}},\"isPrivate\":false}}' ;;\n\
+             'pr view') printf '{{\"number\":7,\"title\":\"t\",\"body\":\"\",\"state\":\"OPEN\",\
+             \"author\":{{\"login\":\"octo\"}},\"createdAt\":\"\",\"updatedAt\":\"\",\
+             \"headRefName\":\"feature\",\"baseRefName\":\"main\",\"isDraft\":false,\
+             \"additions\":0,\"deletions\":0,\"changedFiles\":0,\
+             \"url\":\"https://github.com/octo/repo/pull/7\"}}' ;;\n\
+             esac\n",
+            log.display()
+        );
+        fake_gh(dir, &script)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_create_pr_for_session_pushes_only_without_upstream() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("project");
+        let remote = dir.path().join("remote.git");
+        std::fs::create_dir(&project).unwrap();
+        let project = std::fs::canonicalize(&project).unwrap();
+        let remote_str = remote.to_string_lossy().into_owned();
+
+        let git = Git::new(&project);
+        git.run(&["init"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        git.run(&["config", "init.defaultBranch", "main"])
+            .await
+            .unwrap();
+        std::fs::write(project.join("README.md"), "# Test").unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        git.run(&["branch", "-M", "main"]).await.unwrap();
+        git.run(&["init", "--bare", &remote_str]).await.unwrap();
+        git.run(&["remote", "add", "origin", &remote_str])
+            .await
+            .unwrap();
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+
+        let project_str = project.to_string_lossy().into_owned();
+        let sessions = SessionManager::new();
+        sessions
+            .create_session(1, AiMode::Claude, project_str.clone())
+            .unwrap();
+        sessions
+            .assign_branch(1, "feature".to_string(), None)
+            .unwrap();
+
+        let log = dir.path().join("gh.log");
+        let spy = write_gh_spy(dir.path(), &log);
+        let make_gh = |path: PathBuf| spy.for_repo(path);
+
+        let result = create_pr_for_session_inner(
+            &sessions,
+            &project_str,
+            1,
+            "t".into(),
+            "b".into(),
+            false,
+            make_gh,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.base, "main");
+        assert_eq!(result.head, "feature");
+        assert_eq!(result.pushed_to.as_deref(), Some("origin"));
+        assert_eq!(result.pull_request.number, 7);
+        let calls = std::fs::read_to_string(&log).unwrap();
+        assert!(calls.contains("--base main --head feature"));
+
+        // With an upstream in place, a new local commit is not pushed
+        std::fs::write(project.join("notes.txt"), "wip").unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "wip"]).await.unwrap();
+        let result = create_pr_for_session_inner(
+            &sessions,
+            &project_str,
+            1,
+            "t".into(),
+            "b".into(),
+            false,
+            make_gh,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.pushed_to, None);
+        let local = git.run(&["rev-parse", "feature"]).await.unwrap();
+        let remote_head = Git::new(&remote)
+            .run(&["rev-parse", "feature"])
+            .await
+            .unwrap();
+        assert_ne!(local.trimmed(), remote_head.trimmed());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_repo_metadata_uses_origin_and_caches_per_project() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        let git = Git::new(&project);
+        git.run(&["init"]).await.unwrap();
+        git.run(&["remote", "add", "fork", "https://github.com/o/widgets"])
+            .await
+            .unwrap();
+        git.run(&["remote", "add", "origin", "git@github.com:octo/widgets.git"])
+            .await
+            .unwrap();
+
+        let log = dir.path().join("gh.log");
+        let spy = write_gh_spy(dir.path(), &log);
+        let make_gh = |path: PathBuf| spy.for_repo(path);
+        let gh_calls = || std::fs::read_to_string(&log).unwrap().lines().count();
+
+        let meta = repo_metadata_inner(&project, false, make_gh).await.unwrap();
+        assert_eq!(meta.owner, "octo");
+        assert_eq!(meta.name, "widgets");
+        assert_eq!(meta.default_branch, "main");
+        assert_eq!(meta.remote_url, "git@github.com:octo/widgets.git");
+        assert!(!meta.is_private);
+        assert_eq!(gh_calls(), 1);
+
+        // Cached: no second `gh repo view`, unless a refresh is requested
+        let cached = repo_metadata_inner(&project, false, make_gh).await.unwrap();
+        assert_eq!(cached, meta);
+        assert_eq!(gh_calls(), 1);
+        repo_metadata_inner(&project, true, make_gh).await.unwrap();
+        assert_eq!(gh_calls(), 2);
+
+        let serialized = serde_json::to_value(&meta).unwrap();
+        assert_eq!(serialized["defaultBranch"], "main");
+        assert_eq!(serialized["isPrivate"], false);
+    }
+}
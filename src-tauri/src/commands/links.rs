@@ -0,0 +1,94 @@
+//! Tauri command for opening GitHub links in the default browser.
+
+use url::Url;
+
+/// Hosts `open_url` accepts: github.com, plus the enterprise host `gh` is
+/// pointed at through `GH_HOST`, if any.
+fn allowed_url_hosts() -> Vec<String> {
+    let mut hosts = vec!["github.com".to_string(), "www.github.com".to_string()];
+    if let Ok(host) = std::env::var("GH_HOST") {
+        let host = host.trim().to_ascii_lowercase();
+        if !host.is_empty() && !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    hosts
+}
+
+/// Parses `url` and checks it is an https URL on one of `allowed_hosts`.
+pub(crate) fn validate_open_url(url: &str, allowed_hosts: &[String]) -> Result<Url, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    if parsed.scheme() != "https" {
+        return Err(format!(
+            "Cannot open '{}': only https URLs are allowed",
+            url
+        ));
+    }
+    let host = parsed.host_str().unwrap_or_default();
+    if !allowed_hosts.iter().any(|allowed| allowed == host) {
+        return Err(format!(
+            "Cannot open '{}': host '{}' is not an allowed GitHub host",
+            url, host
+        ));
+    }
+    Ok(parsed)
+}
+
+#[cfg(target_os = "macos")]
+fn open_in_browser(url: &Url) -> std::io::Result<()> {
+    std::process::Command::new("open")
+        .arg(url.as_str())
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+fn open_in_browser(url: &Url) -> std::io::Result<()> {
+    use crate::core::windows_process::StdCommandExt;
+
+    // `cmd /c start` would reinterpret `&` in query strings
+    std::process::Command::new("rundll32")
+        .arg("url.dll,FileProtocolHandler")
+        .arg(url.as_str())
+        .hide_console_window()
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn open_in_browser(url: &Url) -> std::io::Result<()> {
+    std::process::Command::new("xdg-open")
+        .arg(url.as_str())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Opens a pull request, issue or discussion URL in the default browser.
+///
+/// Only https URLs on github.com or the configured enterprise host are
+/// opened, so the frontend cannot hand the OS opener a `file://` path or an
+/// arbitrary site.
+#[tauri::command]
+pub async fn open_url(url: String) -> Result<(), String> {
+    let parsed = validate_open_url(&url, &allowed_url_hosts())?;
+    open_in_browser(&parsed).map_err(|e| format!("Failed to open '{}': {}", url, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_open_url_allows_only_https_github_hosts() {
+        let hosts = vec!["github.com".to_string(), "git.corp.example".to_string()];
+
+        assert!(validate_open_url("https://github.com/o/r/pull/7", &hosts).is_ok());
+        assert!(validate_open_url("https://git.corp.example/o/r/issues/3", &hosts).is_ok());
+
+        assert!(validate_open_url("file:///etc/passwd", &hosts).is_err());
+        assert!(validate_open_url("http://github.com/o/r/pull/7", &hosts).is_err());
+        assert!(validate_open_url("https://evil.example/o/r", &hosts).is_err());
+        assert!(validate_open_url("https://github.com.evil.example/o/r", &hosts).is_err());
+        assert!(validate_open_url("not a url", &hosts).is_err());
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! These commands expose the MarketplaceManager functionality to the frontend.
 
-use tauri::{AppHandle, Emitter, State};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_store::StoreExt;
 
 use crate::core::marketplace_manager::MarketplaceManager;
@@ -11,48 +14,80 @@ use crate::core::marketplace_models::*;
 /// Store filename for marketplace data persistence.
 const MARKETPLACE_STORE: &str = "marketplace.json";
 
-/// Saves marketplace data to the Tauri store.
-async fn save_marketplace_data(app: &AppHandle, manager: &MarketplaceManager) -> Result<(), String> {
-    let store = app.store(MARKETPLACE_STORE).map_err(|e| e.to_string())?;
+/// How long `persist` waits for further mutations before writing the store.
+const PERSIST_DEBOUNCE: Duration = Duration::from_millis(250);
 
-    let sources = manager.get_sources();
-    let installed = manager.get_installed_plugins();
+/// Bumped by every `persist`, `flush` and `hydrate`; a scheduled write only runs if no
+/// newer one was requested in the meantime.
+static PERSIST_GENERATION: AtomicU64 = AtomicU64::new(0);
 
-    store.set("sources", serde_json::json!(sources));
-    store.set("installed_plugins", serde_json::json!(installed));
-    store.save().map_err(|e| e.to_string())?;
+/// Writes the manager's current data to the Tauri store.
+fn write_store(app: &AppHandle, manager: &MarketplaceManager) -> Result<(), String> {
+    let store = app.store(MARKETPLACE_STORE).map_err(|e| e.to_string())?;
+    for (key, value) in manager.to_store_entries().map_err(|e| e.to_string())? {
+        store.set(key, value);
+    }
+    store.save().map_err(|e| e.to_string())
+}
 
+/// Schedules a write of marketplace data to the store after a mutation.
+///
+/// Bursts of mutations (e.g. toggling several sources) coalesce into one
+/// write of whatever the data is when the debounce expires.
+pub(crate) fn persist(app: &AppHandle) {
+    let generation = PERSIST_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(PERSIST_DEBOUNCE).await;
+        if PERSIST_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let manager = app.state::<MarketplaceManager>();
+        if let Err(e) = write_store(&app, &manager) {
+            log::warn!("Failed to persist marketplace data: {}", e);
+        }
+    });
+}
+
+/// Loads marketplace data from the Tauri store into the manager. Called at
+/// startup.
+///
+/// Cancels any pending debounced write: it would otherwise save the
+/// pre-reload data over what was just loaded.
+pub(crate) fn hydrate(app: &AppHandle, manager: &MarketplaceManager) -> Result<(), String> {
+    PERSIST_GENERATION.fetch_add(1, Ordering::SeqCst);
+    let store = app.store(MARKETPLACE_STORE).map_err(|e| e.to_string())?;
+    manager.load_store_entries(|key| store.get(key));
     Ok(())
 }
 
-/// Loads marketplace data from the Tauri store.
+/// Reloads marketplace data from the Tauri store.
+///
+/// Data is already hydrated at startup; this stays for callers that want to
+/// pick up changes made to the store file directly.
 #[tauri::command]
 pub async fn load_marketplace_data(
     app: AppHandle,
     state: State<'_, MarketplaceManager>,
 ) -> Result<(), String> {
-    let store = app.store(MARKETPLACE_STORE).map_err(|e| e.to_string())?;
+    hydrate(&app, &state)
+}
 
-    // Build MarketplaceData from stored values
-    let sources = store
-        .get("sources")
-        .and_then(|v| serde_json::from_value::<Vec<MarketplaceSource>>(v).ok())
-        .unwrap_or_default();
-
-    let installed_plugins = store
-        .get("installed_plugins")
-        .and_then(|v| serde_json::from_value::<Vec<InstalledPlugin>>(v).ok())
-        .unwrap_or_default();
-
-    // Create JSON blob and load into manager
-    let data = MarketplaceData {
-        sources,
-        installed_plugins,
-    };
-    let json = serde_json::to_string(&data).map_err(|e| e.to_string())?;
-    state.load_from_json(&json).map_err(|e| e.to_string())?;
+/// Writes marketplace data to the store immediately, superseding any
+/// pending debounced write.
+#[tauri::command]
+pub async fn flush_marketplace_data(
+    app: AppHandle,
+    state: State<'_, MarketplaceManager>,
+) -> Result<(), String> {
+    flush(&app, &state)
+}
 
-    Ok(())
+/// Writes marketplace data to the store now, cancelling any pending
+/// debounced write. Used by `flush_marketplace_data` and on app shutdown.
+pub(crate) fn flush(app: &AppHandle, manager: &MarketplaceManager) -> Result<(), String> {
+    PERSIST_GENERATION.fetch_add(1, Ordering::SeqCst);
+    write_store(app, manager)
 }
 
 // ========== Source Management Commands ==========
@@ -75,7 +110,7 @@ pub async fn add_marketplace_source(
     is_official: bool,
 ) -> Result<MarketplaceSource, String> {
     let source = state.add_source(name, repository_url, is_official);
-    save_marketplace_data(&app, &state).await?;
+    persist(&app);
     Ok(source)
 }
 
@@ -87,7 +122,7 @@ pub async fn remove_marketplace_source(
     source_id: String,
 ) -> Result<(), String> {
     state.remove_source(&source_id).map_err(|e| e.to_string())?;
-    save_marketplace_data(&app, &state).await?;
+    persist(&app);
     Ok(())
 }
 
@@ -99,10 +134,24 @@ pub async fn toggle_marketplace_source(
     source_id: String,
 ) -> Result<bool, String> {
     let new_state = state.toggle_source(&source_id).map_err(|e| e.to_string())?;
-    save_marketplace_data(&app, &state).await?;
+    persist(&app);
     Ok(new_state)
 }
 
+/// Reorders marketplace sources, highest precedence first.
+#[tauri::command]
+pub async fn reorder_marketplace_sources(
+    app: AppHandle,
+    state: State<'_, MarketplaceManager>,
+    ordered_ids: Vec<String>,
+) -> Result<(), String> {
+    state
+        .reorder_sources(ordered_ids)
+        .map_err(|e| e.to_string())?;
+    persist(&app);
+    Ok(())
+}
+
 // ========== Marketplace Fetching Commands ==========
 
 /// Refreshes a single marketplace source.
@@ -113,7 +162,7 @@ pub async fn refresh_marketplace(
     source_id: String,
 ) -> Result<Vec<MarketplacePlugin>, String> {
     let plugins = state.fetch_marketplace(&source_id).await.map_err(|e| e.to_string())?;
-    save_marketplace_data(&app, &state).await?;
+    persist(&app);
 
     // Emit event
     let _ = app.emit("marketplace:refresh-complete", &source_id);
@@ -122,13 +171,21 @@ pub async fn refresh_marketplace(
 }
 
 /// Refreshes all enabled marketplace sources.
+///
+/// Emits `marketplace-refresh-progress` as each source completes and
+/// `marketplace-refresh-done` once all have, so the UI can show progress
+/// before this resolves.
 #[tauri::command]
 pub async fn refresh_all_marketplaces(
     app: AppHandle,
     state: State<'_, MarketplaceManager>,
 ) -> Result<(), String> {
-    let results = state.refresh_all_marketplaces().await;
-    save_marketplace_data(&app, &state).await?;
+    let results = state
+        .refresh_all_marketplaces(|event, payload| {
+            let _ = app.emit(event, payload);
+        })
+        .await;
+    persist(&app);
 
     // Log any errors
     for (source_id, result) in results {
@@ -161,7 +218,33 @@ pub async fn get_installed_plugins(
     Ok(state.get_installed_plugins())
 }
 
+/// Lists installed plugins with a newer version in their marketplace.
+///
+/// Uses the cached catalogs, so marketplaces should be refreshed first.
+#[tauri::command]
+pub async fn check_plugin_updates(
+    state: State<'_, MarketplaceManager>,
+) -> Result<Vec<PluginUpdate>, String> {
+    Ok(state.check_updates())
+}
+
+/// Reports whether an installed plugin's marketplace and catalog entry still
+/// exist, and whether its version has drifted from the catalog.
+#[tauri::command]
+pub async fn get_plugin_provenance(
+    state: State<'_, MarketplaceManager>,
+    installed_plugin_id: String,
+) -> Result<Provenance, String> {
+    state
+        .plugin_provenance(&installed_plugin_id)
+        .map_err(|e| e.to_string())
+}
+
 /// Installs a plugin from a marketplace.
+///
+/// The install is tracked under `operation_id` (generated if omitted), which
+/// is announced in `marketplace:install-started` and accepted by
+/// `cancel_install`.
 #[tauri::command]
 pub async fn install_marketplace_plugin(
     app: AppHandle,
@@ -169,13 +252,28 @@ pub async fn install_marketplace_plugin(
     marketplace_plugin_id: String,
     scope: InstallScope,
     project_path: Option<String>,
+    operation_id: Option<String>,
 ) -> Result<InstalledPlugin, String> {
+    let operation_id = operation_id.unwrap_or_else(MarketplaceManager::new_operation_id);
+    let _ = app.emit(
+        "marketplace:install-started",
+        serde_json::json!({
+            "operationId": operation_id,
+            "marketplacePluginId": marketplace_plugin_id,
+        }),
+    );
+
     let installed = state
-        .install_plugin(&marketplace_plugin_id, scope, project_path.as_deref())
+        .install_plugin(
+            &marketplace_plugin_id,
+            scope,
+            project_path.as_deref(),
+            &operation_id,
+        )
         .await
         .map_err(|e| e.to_string())?;
 
-    save_marketplace_data(&app, &state).await?;
+    persist(&app);
 
     // Emit event
     let _ = app.emit("marketplace:plugin-installed", &installed);
@@ -183,6 +281,16 @@ pub async fn install_marketplace_plugin(
     Ok(installed)
 }
 
+/// Cancels an in-progress install, killing its clone and removing partial files.
+/// Returns `false` if the install already finished or was never started.
+#[tauri::command]
+pub async fn cancel_install(
+    state: State<'_, MarketplaceManager>,
+    operation_id: String,
+) -> Result<bool, String> {
+    Ok(state.cancel_install(&operation_id))
+}
+
 /// Uninstalls a plugin by its installed ID.
 #[tauri::command]
 pub async fn uninstall_plugin(
@@ -195,7 +303,7 @@ pub async fn uninstall_plugin(
         .await
         .map_err(|e| e.to_string())?;
 
-    save_marketplace_data(&app, &state).await?;
+    persist(&app);
 
     // Emit event
     let _ = app.emit("marketplace:plugin-uninstalled", &installed_plugin_id);
@@ -1,6 +1,6 @@
 //! IPC commands for MCP server discovery and session configuration.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -9,9 +9,11 @@ use sha2::{Digest, Sha256};
 use tauri::{AppHandle, State};
 use tauri_plugin_store::StoreExt;
 
-use crate::core::mcp_config_writer;
-use crate::core::mcp_manager::{McpManager, McpServerConfig};
-use crate::core::status_server::StatusServer;
+use crate::core::mcp_config_writer::{self, EffectiveMcpServer, MaestroMcpStatus, McpJsonHealth};
+use crate::core::mcp_manager::{self, McpManager, McpServerConfig, ResolvedMcpDefaults};
+use crate::core::plugin_manager::PluginManager;
+use crate::core::session_manager::{normalize_tags, SessionManager};
+use crate::core::status_server::{SessionStatusPayload, StatusServer};
 
 /// Store filename for custom MCP servers (global, user-level).
 const CUSTOM_MCP_SERVERS_STORE: &str = "mcp-custom-servers.json";
@@ -37,6 +39,21 @@ pub struct McpCustomServer {
     pub is_enabled: bool,
     /// ISO timestamp of when the server was created.
     pub created_at: String,
+    /// Session tags this server is limited to; untagged servers are added to
+    /// every session.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl McpCustomServer {
+    /// Whether this server belongs in a session tagged `session_tags`: an
+    /// untagged server always does, a tagged one only if a tag is shared.
+    /// Tags are normalized first, in case they were stored before saving
+    /// normalized them.
+    pub fn applies_to(&self, session_tags: &BTreeSet<String>) -> bool {
+        let tags = normalize_tags(&self.tags);
+        tags.is_empty() || !tags.is_disjoint(session_tags)
+    }
 }
 
 /// Status server info returned to the frontend.
@@ -57,6 +74,33 @@ fn hash_project_path(path: &str) -> String {
     format!("{:x}", &result)[..12].to_string()
 }
 
+/// Returns the main checkout of the repository when `dir` is a linked git
+/// worktree, found through the `commondir` of the gitdir its `.git` file
+/// points at. `None` for main checkouts and non-repositories.
+fn main_checkout(dir: &Path) -> Option<PathBuf> {
+    let dotgit = std::fs::read_to_string(dir.join(".git")).ok()?;
+    let gitdir = dir.join(dotgit.strip_prefix("gitdir:")?.trim());
+    let common = std::fs::read_to_string(gitdir.join("commondir")).ok()?;
+    let common = std::fs::canonicalize(gitdir.join(common.trim())).ok()?;
+    if common.file_name()? != ".git" {
+        return None;
+    }
+    common.parent().map(Path::to_path_buf)
+}
+
+/// Name of the per-project store for `project_path`, shared by every
+/// project-scoped command. A linked worktree shares the store of its main
+/// checkout, so sessions on a worktree see the project's defaults.
+pub(crate) fn project_store_name(project_path: &str) -> Result<String, String> {
+    let canonical = std::fs::canonicalize(project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?;
+    let root = main_checkout(&canonical).unwrap_or(canonical);
+    Ok(format!(
+        "maestro-{}.json",
+        hash_project_path(&root.to_string_lossy())
+    ))
+}
+
 /// Discovers and returns MCP servers configured in the project's `.mcp.json`.
 ///
 /// The project path is canonicalized before lookup. Results are cached.
@@ -87,21 +131,56 @@ pub async fn refresh_project_mcp_servers(
     Ok(state.refresh_project_servers(&canonical))
 }
 
-/// Gets the enabled MCP server names for a specific session.
+/// Gets the MCP servers enabled for a specific session.
 ///
-/// If not explicitly set, returns all available servers as enabled.
+/// Returns the enabled discovered servers (all of them if not explicitly set)
+/// followed by the servers bundled in the session's enabled plugins, each
+/// carrying its `source` so plugin servers can be attributed.
 #[tauri::command]
 pub async fn get_session_mcp_servers(
     state: State<'_, McpManager>,
+    plugin_state: State<'_, PluginManager>,
     project_path: String,
     session_id: u32,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<McpServerConfig>, String> {
     let canonical = std::fs::canonicalize(&project_path)
         .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
         .to_string_lossy()
         .into_owned();
 
-    Ok(state.get_session_enabled(&canonical, session_id))
+    let enabled_names = state.get_session_enabled(&canonical, session_id);
+    let mut servers: Vec<McpServerConfig> = state
+        .get_project_servers(&canonical)
+        .into_iter()
+        .filter(|s| enabled_names.contains(&s.name))
+        .collect();
+
+    servers.extend(enabled_plugin_servers(&plugin_state, &canonical, session_id).await);
+
+    Ok(servers)
+}
+
+/// Servers bundled in the plugins a session has enabled, attributed to
+/// their plugin.
+async fn enabled_plugin_servers(
+    plugins: &PluginManager,
+    canonical: &str,
+    session_id: u32,
+) -> Vec<McpServerConfig> {
+    let enabled_plugins = plugins.get_session_plugins(canonical, session_id).await;
+    let mut servers = Vec::new();
+    for plugin in plugins.get_project_plugins(canonical).await.plugins {
+        if plugin.mcp_servers.is_empty() || !enabled_plugins.contains(&plugin.id) {
+            continue;
+        }
+        if let Some(path) = &plugin.path {
+            servers.extend(mcp_manager::discover_plugin_servers(
+                &plugin.id,
+                Path::new(path),
+            ));
+        }
+    }
+    servers
 }
 
 /// Sets the enabled MCP server names for a specific session.
@@ -146,18 +225,13 @@ pub async fn save_project_mcp_defaults(
     project_path: String,
     enabled_servers: Vec<String>,
 ) -> Result<(), String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     store.set("enabled_mcp_servers", serde_json::json!(enabled_servers));
     store.save().map_err(|e| e.to_string())?;
 
-    log::debug!("Saved MCP server defaults for project: {}", canonical);
+    log::debug!("Saved MCP server defaults for project: {}", project_path);
     Ok(())
 }
 
@@ -169,12 +243,7 @@ pub async fn load_project_mcp_defaults(
     app: AppHandle,
     project_path: String,
 ) -> Result<Option<Vec<String>>, String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     let result = store
@@ -189,6 +258,24 @@ pub async fn load_project_mcp_defaults(
     Ok(result)
 }
 
+/// Loads a project's default MCP servers from every layer.
+///
+/// Layers any `.maestro/mcp-defaults.json` files between the project and its
+/// git root under the selection saved by `save_project_mcp_defaults` (see
+/// `mcp_manager::resolve_mcp_defaults`). Returns None if neither exists yet.
+#[tauri::command]
+pub async fn load_project_mcp_defaults_resolved(
+    app: AppHandle,
+    project_path: String,
+) -> Result<Option<ResolvedMcpDefaults>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?;
+
+    let saved = load_project_mcp_defaults(app, project_path).await?;
+    let resolved = mcp_manager::resolve_mcp_defaults(&canonical, saved.as_deref());
+    Ok((resolved.saved || !resolved.sources.is_empty()).then_some(resolved))
+}
+
 /// Registers a project with the status server.
 ///
 /// This is a no-op in the new HTTP-based architecture since we don't need
@@ -225,6 +312,37 @@ pub async fn remove_mcp_project(project_path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Registers a session with the status server under `project_path`.
+///
+/// Call this after relaunching or restoring a session so its status reports
+/// route to the right project. Replaces any previous mapping for the ID and
+/// flushes a status that was buffered while the session was unregistered.
+#[tauri::command]
+pub async fn register_session_status(
+    status_server: State<'_, Arc<StatusServer>>,
+    session_id: u32,
+    project_path: String,
+) -> Result<(), String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    status_server.register_session(session_id, &canonical).await;
+    Ok(())
+}
+
+/// Unregisters a session from the status server, dropping any buffered
+/// status and history. Later reports for the ID are buffered again.
+#[tauri::command]
+pub async fn unregister_session_status(
+    status_server: State<'_, Arc<StatusServer>>,
+    session_id: u32,
+) -> Result<(), String> {
+    status_server.unregister_session(session_id).await;
+    Ok(())
+}
+
 /// Removes a session's status from tracking.
 ///
 /// In the new HTTP-based architecture, this unregisters the session from
@@ -261,6 +379,36 @@ pub async fn get_status_server_info(
     })
 }
 
+/// Returns the most recent statuses reported for a session, oldest first.
+///
+/// The buffer is bounded (20 by default, `MAESTRO_STATUS_HISTORY_LEN` to
+/// override) and cleared when the session is unregistered.
+#[tauri::command]
+pub async fn get_session_status_history(
+    status_server: State<'_, Arc<StatusServer>>,
+    session_id: u32,
+) -> Result<Vec<SessionStatusPayload>, String> {
+    Ok(status_server.status_history(session_id))
+}
+
+/// Whether the status server is accepting reports. Changes are also pushed
+/// as `status-server-heartbeat` events.
+#[tauri::command]
+pub async fn status_server_alive(
+    status_server: State<'_, Arc<StatusServer>>,
+) -> Result<bool, String> {
+    Ok(status_server.is_alive())
+}
+
+/// Returns the `maestro-mcp-server` binary that sessions would be configured
+/// with, or `None` if it cannot be found.
+///
+/// Honors the `MAESTRO_MCP_SERVER_PATH` override.
+#[tauri::command]
+pub fn get_maestro_mcp_path() -> Option<String> {
+    mcp_config_writer::find_maestro_mcp_path().map(|p| p.to_string_lossy().into_owned())
+}
+
 /// Writes a session-specific `.mcp.json` file to the working directory.
 ///
 /// This must be called BEFORE launching the Claude CLI so it can discover
@@ -269,20 +417,26 @@ pub async fn get_status_server_info(
 /// The written config includes:
 /// - The `maestro` MCP server with HTTP-based status reporting
 /// - All enabled servers from the project's `.mcp.json`
-/// - All enabled custom servers (user-defined, global)
+/// - All enabled custom servers (user-defined, global) that apply to the
+///   session's tags
 ///
 /// Existing user-defined servers in the working directory's `.mcp.json` are
 /// preserved (only Maestro-managed servers are replaced).
+///
+/// The returned status reports whether maestro-mcp-server was found; when it
+/// wasn't, the config is still written but without status reporting.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn write_session_mcp_config(
     app: AppHandle,
     mcp_state: State<'_, McpManager>,
+    session_state: State<'_, SessionManager>,
     status_server: State<'_, Arc<StatusServer>>,
     working_dir: String,
     session_id: u32,
     project_path: String,
     enabled_server_names: Vec<String>,
-) -> Result<(), String> {
+) -> Result<MaestroMcpStatus, String> {
     let canonical = std::fs::canonicalize(&project_path)
         .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
         .to_string_lossy()
@@ -297,19 +451,13 @@ pub async fn write_session_mcp_config(
     let status_url = status_server.status_url();
     let instance_id = status_server.instance_id();
 
-    // Get full server configs for enabled discovered servers
-    let all_discovered = mcp_state.get_project_servers(&canonical);
-    let enabled_discovered: Vec<_> = all_discovered
-        .into_iter()
-        .filter(|s| enabled_server_names.contains(&s.name))
-        .collect();
-
-    // Get enabled custom servers
-    let custom_servers = get_custom_mcp_servers_internal(&app)?;
-    let enabled_custom: Vec<_> = custom_servers
-        .into_iter()
-        .filter(|s| s.is_enabled)
-        .collect();
+    let (enabled_discovered, enabled_custom) = enabled_session_servers(
+        &app,
+        &mcp_state,
+        &canonical,
+        &enabled_server_names,
+        &session_tags(&session_state, session_id),
+    )?;
 
     log::info!(
         "Writing MCP config for session {} to {} ({} discovered + {} custom servers), status_url={}",
@@ -325,12 +473,97 @@ pub async fn write_session_mcp_config(
         session_id,
         &status_url,
         instance_id,
+        &StatusServer::generate_project_hash(&canonical),
         &enabled_discovered,
         &enabled_custom,
     )
     .await
 }
 
+/// Lists the MCP servers a session will receive once its config is written.
+///
+/// Uses the session's enabled server selection, so the result matches what
+/// `write_session_mcp_config` emits for it. Commands, args, and env values
+/// are omitted, so no secrets reach the frontend.
+#[tauri::command]
+pub async fn resolve_effective_mcp_servers(
+    app: AppHandle,
+    mcp_state: State<'_, McpManager>,
+    plugin_state: State<'_, PluginManager>,
+    session_state: State<'_, SessionManager>,
+    status_server: State<'_, Arc<StatusServer>>,
+    project_path: String,
+    session_id: u32,
+) -> Result<Vec<EffectiveMcpServer>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    let enabled_names = mcp_state.get_session_enabled(&canonical, session_id);
+    let (enabled_discovered, enabled_custom) = enabled_session_servers(
+        &app,
+        &mcp_state,
+        &canonical,
+        &enabled_names,
+        &session_tags(&session_state, session_id),
+    )?;
+
+    Ok(mcp_config_writer::resolve_effective_mcp_servers(
+        mcp_config_writer::find_maestro_mcp_path().as_deref(),
+        session_id,
+        &status_server.status_url(),
+        status_server.instance_id(),
+        &StatusServer::generate_project_hash(&canonical),
+        &enabled_discovered,
+        &enabled_custom,
+        &enabled_plugin_servers(&plugin_state, &canonical, session_id).await,
+    ))
+}
+
+/// Tags of a registered session; unknown sessions have none, so they only
+/// get untagged custom servers.
+fn session_tags(sessions: &SessionManager, session_id: u32) -> BTreeSet<String> {
+    sessions
+        .get_session(session_id)
+        .map(|s| s.tags)
+        .unwrap_or_default()
+}
+
+/// Keeps the enabled custom servers that apply to a session with `tags`.
+fn custom_servers_for_session(
+    servers: Vec<McpCustomServer>,
+    tags: &BTreeSet<String>,
+) -> Vec<McpCustomServer> {
+    servers
+        .into_iter()
+        .filter(|s| s.is_enabled && s.applies_to(tags))
+        .collect()
+}
+
+/// Resolves the discovered servers named in `enabled_names` and the enabled
+/// custom servers that apply to a session with `session_tags`.
+fn enabled_session_servers(
+    app: &AppHandle,
+    mcp_state: &McpManager,
+    canonical: &str,
+    enabled_names: &[String],
+    session_tags: &BTreeSet<String>,
+) -> Result<(Vec<McpServerConfig>, Vec<McpCustomServer>), String> {
+    // Get full server configs for enabled discovered servers
+    let enabled_discovered = mcp_state
+        .get_project_servers(canonical)
+        .into_iter()
+        .filter(|s| enabled_names.contains(&s.name))
+        .collect();
+
+    // Get enabled custom servers meant for this session
+    let enabled_custom =
+        custom_servers_for_session(get_custom_mcp_servers_internal(app)?, session_tags);
+
+    Ok((enabled_discovered, enabled_custom))
+}
+
 /// Internal helper to get custom MCP servers (non-async for use within commands).
 fn get_custom_mcp_servers_internal(app: &AppHandle) -> Result<Vec<McpCustomServer>, String> {
     let store = app
@@ -355,6 +588,22 @@ pub async fn remove_session_mcp_config(working_dir: String, session_id: u32) ->
     mcp_config_writer::remove_session_mcp_config(&path, session_id).await
 }
 
+/// Reports whether the working directory's `.mcp.json` can be merged into,
+/// with the parse error when it can't.
+#[tauri::command]
+pub async fn inspect_mcp_json(working_dir: String) -> Result<McpJsonHealth, String> {
+    mcp_config_writer::inspect_mcp_json(Path::new(&working_dir)).await
+}
+
+/// Backs up an invalid `.mcp.json` and replaces it with an empty config so
+/// sessions can launch again. Returns the backup path, or `None` if the file
+/// was already valid.
+#[tauri::command]
+pub async fn repair_mcp_json(working_dir: String) -> Result<Option<String>, String> {
+    let backup = mcp_config_writer::repair_mcp_json(Path::new(&working_dir)).await?;
+    Ok(backup.map(|p| p.to_string_lossy().into_owned()))
+}
+
 /// Generates a project hash for the given path.
 ///
 /// This hash is used for identification purposes. In the new HTTP-based
@@ -391,9 +640,14 @@ pub async fn get_custom_mcp_servers(app: AppHandle) -> Result<Vec<McpCustomServe
 /// Saves a custom MCP server configuration.
 ///
 /// If a server with the same ID already exists, it will be updated.
-/// Otherwise, the new server is added to the list.
+/// Otherwise, the new server is added to the list. Tags are normalized as in
+/// `set_custom_mcp_server_tags`.
 #[tauri::command]
-pub async fn save_custom_mcp_server(app: AppHandle, server: McpCustomServer) -> Result<(), String> {
+pub async fn save_custom_mcp_server(
+    app: AppHandle,
+    mut server: McpCustomServer,
+) -> Result<(), String> {
+    server.tags = normalize_tags(&server.tags).into_iter().collect();
     let store = app
         .store(CUSTOM_MCP_SERVERS_STORE)
         .map_err(|e| e.to_string())?;
@@ -423,6 +677,43 @@ pub async fn save_custom_mcp_server(app: AppHandle, server: McpCustomServer) ->
     Ok(())
 }
 
+/// Replaces the tags of a custom MCP server, limiting it to sessions with a
+/// matching tag (or, with no tags, giving it to every session).
+#[tauri::command]
+pub async fn set_custom_mcp_server_tags(
+    app: AppHandle,
+    server_id: String,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let store = app
+        .store(CUSTOM_MCP_SERVERS_STORE)
+        .map_err(|e| e.to_string())?;
+
+    let mut servers: Vec<McpCustomServer> = store
+        .get("servers")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+
+    let server = servers
+        .iter_mut()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("Custom MCP server '{}' not found", server_id))?;
+    server.tags = normalize_tags(tags).into_iter().collect();
+    log::debug!(
+        "Set tags of custom MCP server {}: {:?}",
+        server.name,
+        server.tags
+    );
+
+    store.set(
+        "servers",
+        serde_json::to_value(&servers).map_err(|e| e.to_string())?,
+    );
+    store.save().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 /// Deletes a custom MCP server by ID.
 #[tauri::command]
 pub async fn delete_custom_mcp_server(app: AppHandle, server_id: String) -> Result<(), String> {
@@ -453,3 +744,91 @@ pub async fn delete_custom_mcp_server(app: AppHandle, server_id: String) -> Resu
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session_manager::AiMode;
+    use crate::git::Git;
+
+    fn custom_server(name: &str, tags: &[&str]) -> McpCustomServer {
+        McpCustomServer {
+            id: name.to_string(),
+            name: name.to_string(),
+            command: "npx".to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            working_directory: None,
+            is_enabled: true,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_tagged_custom_server_only_reaches_matching_sessions() {
+        let sessions = SessionManager::new();
+        for id in [1, 2] {
+            sessions
+                .create_session(id, AiMode::Claude, "/project/a".to_string())
+                .unwrap();
+        }
+        sessions.set_tags(1, vec![" frontend ".to_string(), String::new()]);
+        sessions.set_tags(2, vec!["backend".to_string()]);
+
+        let servers = || {
+            vec![
+                custom_server("browser", &[" frontend", "e2e"]),
+                custom_server("everywhere", &[]),
+                // Blank tags stored before saving normalized them
+                custom_server("blank", &["  "]),
+            ]
+        };
+        let names_for = |session_id| -> Vec<String> {
+            custom_servers_for_session(servers(), &session_tags(&sessions, session_id))
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+
+        assert_eq!(names_for(1), vec!["browser", "everywhere", "blank"]);
+        assert_eq!(names_for(2), vec!["everywhere", "blank"]);
+        // Unregistered sessions have no tags
+        assert_eq!(names_for(9), vec!["everywhere", "blank"]);
+
+        assert_eq!(
+            normalize_tags([" e2e ", "", "e2e", "frontend"]),
+            BTreeSet::from(["e2e".to_string(), "frontend".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_worktree_shares_the_project_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        let git = Git::new(&project);
+        git.run(&["init"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        std::fs::write(project.join("README.md"), "# Test").unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        let worktree = dir.path().join("wt");
+        git.worktree_add(&worktree, Some("feature"), None, false)
+            .await
+            .unwrap();
+
+        let project_store = project_store_name(&project.to_string_lossy()).unwrap();
+        assert_eq!(
+            project_store_name(&worktree.to_string_lossy()).unwrap(),
+            project_store
+        );
+        assert_ne!(
+            project_store_name(&dir.path().to_string_lossy()).unwrap(),
+            project_store
+        );
+    }
+}
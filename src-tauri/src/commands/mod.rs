@@ -1,11 +1,16 @@
 pub mod claudemd;
+pub mod diagnostics;
 pub mod fonts;
 pub mod git;
 pub mod github;
+pub mod links;
 pub mod marketplace;
 pub mod mcp;
+pub mod permissions;
 pub mod plugin;
+pub mod reveal;
 pub mod session;
+pub mod session_config;
 pub mod terminal;
 pub mod update;
 pub mod usage;
@@ -0,0 +1,13 @@
+//! IPC commands for Claude project permission rules.
+
+use crate::core::project_permissions::{self, ProjectPermissions};
+
+/// Returns the tool permission rules from the project's `.claude/settings.json`
+/// merged with `.claude/settings.local.json` (local wins on conflicts).
+#[tauri::command]
+pub async fn read_project_permissions(project_path: String) -> Result<ProjectPermissions, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?;
+
+    project_permissions::read_project_permissions(&canonical)
+}
@@ -3,7 +3,6 @@
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use tauri::{AppHandle, State};
 use tauri_plugin_store::StoreExt;
 
@@ -16,17 +15,12 @@ pub struct BranchConfig {
     pub enabled_mcp_servers: Vec<String>,
 }
 
+use crate::commands::mcp::project_store_name;
 use crate::core::plugin_config_writer;
-use crate::core::plugin_manager::{PluginManager, ProjectPlugins};
-
-/// Creates a stable hash of a project path for use in store filenames.
-fn hash_project_path(path: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(path.as_bytes());
-    let result = hasher.finalize();
-    // Take first 12 hex characters for a reasonably short but unique filename
-    format!("{:x}", &result)[..12].to_string()
-}
+use crate::core::plugin_manager::{
+    LegacyPluginsReport, PluginManager, ProjectPlugins, SkillPreview, SkillResolution,
+};
+use crate::core::session_manager::{SessionConfig, SessionManager};
 
 /// Discovers and returns plugins/skills configured in the project's `.plugins.json`.
 ///
@@ -41,7 +35,7 @@ pub async fn get_project_plugins(
         .to_string_lossy()
         .into_owned();
 
-    Ok(state.get_project_plugins(&canonical))
+    Ok(state.get_project_plugins(&canonical).await)
 }
 
 /// Re-parses the `.plugins.json` file for a project, updating the cache.
@@ -55,7 +49,7 @@ pub async fn refresh_project_plugins(
         .to_string_lossy()
         .into_owned();
 
-    Ok(state.refresh_project_plugins(&canonical))
+    Ok(state.refresh_project_plugins(&canonical).await)
 }
 
 /// Gets the enabled skill IDs for a specific session.
@@ -72,7 +66,75 @@ pub async fn get_session_skills(
         .to_string_lossy()
         .into_owned();
 
-    Ok(state.get_session_skills(&canonical, session_id))
+    Ok(state.get_session_skills(&canonical, session_id).await)
+}
+
+/// Gets the enabled skill IDs for a session, dropping file-based skills whose
+/// file has been deleted since discovery.
+///
+/// A stale entry also triggers a background refresh of the project's cache.
+#[tauri::command]
+pub async fn get_session_skills_validated(
+    state: State<'_, PluginManager>,
+    project_path: String,
+    session_id: u32,
+) -> Result<Vec<String>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(state
+        .get_session_skills_validated(&canonical, session_id)
+        .await)
+}
+
+/// Resolves a typed slash-command name to one of the session's enabled skills,
+/// or returns ranked near matches when there is no exact hit.
+#[tauri::command]
+pub async fn resolve_skill(
+    state: State<'_, PluginManager>,
+    project_path: String,
+    session_id: u32,
+    query: String,
+) -> Result<SkillResolution, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(state.resolve_skill(&query, &canonical, session_id).await)
+}
+
+/// Returns preview text for a skill (frontmatter description, first body
+/// paragraph, and argument hint) for the skill picker.
+#[tauri::command]
+pub async fn get_skill_preview(
+    state: State<'_, PluginManager>,
+    project_path: String,
+    skill_id: String,
+) -> Result<SkillPreview, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    state
+        .get_skill_preview(&skill_id, &canonical)
+        .await
+        .ok_or_else(|| format!("Skill '{}' not found", skill_id))
+}
+
+/// Reports which skills in the project's legacy `.plugins.json` are skipped
+/// (and why), with suggested `.claude/` equivalents for the rest.
+#[tauri::command]
+pub async fn validate_legacy_plugins(project_path: String) -> Result<LegacyPluginsReport, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    PluginManager::validate_legacy_plugins(&canonical)
 }
 
 /// Sets the enabled skill IDs for a specific session.
@@ -92,6 +154,26 @@ pub async fn set_session_skills(
     Ok(())
 }
 
+/// Enables every discovered skill for a session, or disables them all.
+///
+/// Returns the resulting enabled skill IDs.
+#[tauri::command]
+pub async fn set_all_session_skills(
+    state: State<'_, PluginManager>,
+    project_path: String,
+    session_id: u32,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(state
+        .set_all_session_skills(&canonical, session_id, enabled)
+        .await)
+}
+
 /// Gets the enabled plugin IDs for a specific session.
 ///
 /// If not explicitly set, returns plugins where enabled_by_default is true.
@@ -106,7 +188,7 @@ pub async fn get_session_plugins(
         .to_string_lossy()
         .into_owned();
 
-    Ok(state.get_session_plugins(&canonical, session_id))
+    Ok(state.get_session_plugins(&canonical, session_id).await)
 }
 
 /// Sets the enabled plugin IDs for a specific session.
@@ -126,6 +208,26 @@ pub async fn set_session_plugins(
     Ok(())
 }
 
+/// Enables every discovered plugin for a session, or disables them all.
+///
+/// Returns the resulting enabled plugin IDs.
+#[tauri::command]
+pub async fn set_all_session_plugins(
+    state: State<'_, PluginManager>,
+    project_path: String,
+    session_id: u32,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(state
+        .set_all_session_plugins(&canonical, session_id, enabled)
+        .await)
+}
+
 /// Returns the count of enabled skills for a session.
 #[tauri::command]
 pub async fn get_session_skills_count(
@@ -138,7 +240,7 @@ pub async fn get_session_skills_count(
         .to_string_lossy()
         .into_owned();
 
-    Ok(state.get_skills_count(&canonical, session_id))
+    Ok(state.get_skills_count(&canonical, session_id).await)
 }
 
 /// Returns the count of enabled plugins for a session.
@@ -153,7 +255,7 @@ pub async fn get_session_plugins_count(
         .to_string_lossy()
         .into_owned();
 
-    Ok(state.get_plugins_count(&canonical, session_id))
+    Ok(state.get_plugins_count(&canonical, session_id).await)
 }
 
 /// Saves the default enabled skills for a project.
@@ -166,18 +268,13 @@ pub async fn save_project_skill_defaults(
     project_path: String,
     enabled_skills: Vec<String>,
 ) -> Result<(), String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     store.set("enabled_skills", serde_json::json!(enabled_skills));
     store.save().map_err(|e| e.to_string())?;
 
-    log::debug!("Saved skill defaults for project: {}", canonical);
+    log::debug!("Saved skill defaults for project: {}", project_path);
     Ok(())
 }
 
@@ -189,12 +286,7 @@ pub async fn load_project_skill_defaults(
     app: AppHandle,
     project_path: String,
 ) -> Result<Option<Vec<String>>, String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     let result = store
@@ -219,18 +311,13 @@ pub async fn save_project_plugin_defaults(
     project_path: String,
     enabled_plugins: Vec<String>,
 ) -> Result<(), String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     store.set("enabled_plugins", serde_json::json!(enabled_plugins));
     store.save().map_err(|e| e.to_string())?;
 
-    log::debug!("Saved plugin defaults for project: {}", canonical);
+    log::debug!("Saved plugin defaults for project: {}", project_path);
     Ok(())
 }
 
@@ -242,12 +329,7 @@ pub async fn load_project_plugin_defaults(
     app: AppHandle,
     project_path: String,
 ) -> Result<Option<Vec<String>>, String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     let result = store
@@ -279,12 +361,88 @@ pub async fn write_session_plugin_config(
         .into_owned();
 
     // Resolve Maestro plugin IDs to CLI enabledPlugins map
-    let enabled_plugins_map = state.resolve_enabled_plugins_map(&canonical, &enabled_plugin_ids);
+    let enabled_plugins_map = state
+        .resolve_enabled_plugins_map(&canonical, &enabled_plugin_ids)
+        .await;
 
     plugin_config_writer::write_session_plugin_config(Path::new(&working_dir), &enabled_plugins_map)
         .await
 }
 
+/// Directory a session's CLI runs in: its worktree, or the project itself.
+fn session_working_dir(session: &SessionConfig) -> PathBuf {
+    PathBuf::from(
+        session
+            .worktree_path
+            .as_ref()
+            .unwrap_or(&session.project_path),
+    )
+}
+
+/// Merges the session's resolved `enabledPlugins` map into the
+/// `.claude/settings.local.json` of its worktree (or the project, if it has
+/// none), so plugin toggles in Maestro take effect in Claude CLI.
+///
+/// Goes through the same writer as `write_session_plugin_config`; the entries
+/// are removed again when the session is killed.
+#[tauri::command]
+pub async fn write_enabled_plugins(
+    state: State<'_, PluginManager>,
+    session_mgr: State<'_, SessionManager>,
+    project_path: String,
+    session_id: u32,
+) -> Result<(), String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    let working_dir = session_mgr
+        .get_session(session_id)
+        .map(|s| session_working_dir(&s))
+        .unwrap_or_else(|| PathBuf::from(&canonical));
+    let enabled_ids = state.get_session_plugins(&canonical, session_id).await;
+    let enabled_plugins_map = state
+        .resolve_enabled_plugins_map(&canonical, &enabled_ids)
+        .await;
+
+    plugin_config_writer::write_session_plugin_config(&working_dir, &enabled_plugins_map).await
+}
+
+/// Removes the plugin config `write_enabled_plugins` wrote for a session.
+/// Does nothing for unknown sessions. Runs automatically on `kill_session`.
+#[tauri::command]
+pub async fn clear_enabled_plugins(
+    session_mgr: State<'_, SessionManager>,
+    session_id: u32,
+) -> Result<(), String> {
+    match session_mgr.get_session(session_id) {
+        Some(session) => clear_session_plugins(&session_mgr, &session).await,
+        None => Ok(()),
+    }
+}
+
+/// Removes `session`'s plugin config from its working directory, unless
+/// another session still runs there and relies on the same file.
+pub(crate) async fn clear_session_plugins(
+    session_mgr: &SessionManager,
+    session: &SessionConfig,
+) -> Result<(), String> {
+    let working_dir = session_working_dir(session);
+    let shared = session_mgr
+        .all_sessions()
+        .iter()
+        .any(|s| s.id != session.id && session_working_dir(s) == working_dir);
+    if shared {
+        log::debug!(
+            "Keeping plugin config in {:?}; another session still uses it",
+            working_dir
+        );
+        return Ok(());
+    }
+    plugin_config_writer::remove_session_plugin_config(&working_dir).await
+}
+
 /// Removes the plugins array from the session's .claude/settings.local.json.
 ///
 /// This should be called when a session is killed to clean up.
@@ -421,12 +579,7 @@ pub async fn save_branch_config(
     enabled_skills: Vec<String>,
     enabled_mcp_servers: Vec<String>,
 ) -> Result<(), String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     let config = BranchConfig {
@@ -439,7 +592,7 @@ pub async fn save_branch_config(
     store.set(&key, serde_json::json!(config));
     store.save().map_err(|e| e.to_string())?;
 
-    log::debug!("Saved branch config for {}/{}", canonical, branch);
+    log::debug!("Saved branch config for {}/{}", project_path, branch);
     Ok(())
 }
 
@@ -452,12 +605,7 @@ pub async fn load_branch_config(
     project_path: String,
     branch: String,
 ) -> Result<Option<BranchConfig>, String> {
-    let canonical = std::fs::canonicalize(&project_path)
-        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
-        .to_string_lossy()
-        .into_owned();
-
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    let store_name = project_store_name(&project_path)?;
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     let key = format!("branch_config:{}", branch);
@@ -467,3 +615,36 @@ pub async fn load_branch_config(
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session_manager::AiMode;
+
+    #[tokio::test]
+    async fn test_clear_session_plugins_skips_shared_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_string_lossy().into_owned();
+        let settings_path = dir.path().join(".claude/settings.local.json");
+        let mut plugins = std::collections::HashMap::new();
+        plugins.insert("lint@official".to_string(), true);
+        plugin_config_writer::write_session_plugin_config(dir.path(), &plugins)
+            .await
+            .unwrap();
+
+        let session_mgr = SessionManager::new();
+        let first = session_mgr
+            .create_session(1, AiMode::Plain, project.clone())
+            .unwrap();
+        let second = session_mgr
+            .create_session(2, AiMode::Plain, project)
+            .unwrap();
+
+        clear_session_plugins(&session_mgr, &first).await.unwrap();
+        assert!(settings_path.exists());
+
+        session_mgr.remove_session(1);
+        clear_session_plugins(&session_mgr, &second).await.unwrap();
+        assert!(!settings_path.exists());
+    }
+}
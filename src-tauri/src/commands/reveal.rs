@@ -0,0 +1,149 @@
+//! Tauri commands for revealing plugin, skill, worktree and config files in
+//! the OS file manager.
+
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+
+use crate::core::plugin_manager::PluginManager;
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_manager::worktree_base_dir;
+
+/// Returns true if `path` lies inside one of the `allowed` roots.
+///
+/// Both sides are expected to be canonicalized so `..` segments and symlinks
+/// cannot be used to escape a root.
+pub(crate) fn is_revealable(path: &Path, allowed: &[PathBuf]) -> bool {
+    allowed.iter().any(|root| path.starts_with(root))
+}
+
+/// Directories Maestro is willing to reveal: `~/.claude`, the managed
+/// worktree directory, every project whose plugins were loaded, and every
+/// project and worktree with an open session.
+fn allowed_roots(session_mgr: &SessionManager, plugin_mgr: &PluginManager) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(base_dirs) = directories::BaseDirs::new() {
+        roots.push(base_dirs.home_dir().join(".claude"));
+    }
+    roots.push(worktree_base_dir());
+    roots.extend(plugin_mgr.known_projects().into_iter().map(PathBuf::from));
+    for session in session_mgr.all_sessions() {
+        roots.push(PathBuf::from(session.project_path));
+        roots.extend(session.worktree_path.map(PathBuf::from));
+    }
+    // Roots may themselves be symlinked (e.g. /tmp on macOS)
+    roots
+        .into_iter()
+        .map(|root| root.canonicalize().unwrap_or(root))
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn open_file_manager(path: &Path) -> std::io::Result<()> {
+    std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+fn open_file_manager(path: &Path) -> std::io::Result<()> {
+    use crate::core::windows_process::StdCommandExt;
+
+    // explorer doesn't understand the \\?\ prefix canonicalize adds, and
+    // wants "/select,<path>" as a single argument
+    let display = path.to_string_lossy();
+    let plain = display.strip_prefix(r"\\?\").unwrap_or(&display);
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(plain);
+    std::process::Command::new("explorer")
+        .arg(select)
+        .hide_console_window()
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn open_file_manager(path: &Path) -> std::io::Result<()> {
+    // Prefer the FileManager1 D-Bus interface, which highlights the item.
+    // Not every desktop implements it, so fall back to opening the parent.
+    let uri = format!("file://{}", path.display());
+    let shown = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", uri))
+        .arg("string:")
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if shown {
+        return Ok(());
+    }
+
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    std::process::Command::new("xdg-open")
+        .arg(dir)
+        .spawn()
+        .map(|_| ())
+}
+
+/// Opens the OS file manager with `path` selected.
+///
+/// Only paths inside `~/.claude`, the worktree directory, or an open project
+/// or worktree are revealed, so the frontend cannot be used to probe
+/// arbitrary locations.
+#[tauri::command]
+pub async fn reveal_path(
+    session_mgr: State<'_, SessionManager>,
+    plugin_mgr: State<'_, PluginManager>,
+    path: String,
+) -> Result<(), String> {
+    let canonical = Path::new(&path)
+        .canonicalize()
+        .map_err(|e| format!("Invalid path '{}': {}", path, e))?;
+
+    if !is_revealable(&canonical, &allowed_roots(&session_mgr, &plugin_mgr)) {
+        return Err(format!(
+            "Cannot reveal '{}': path is not within ~/.claude or an open project",
+            path
+        ));
+    }
+
+    open_file_manager(&canonical)
+        .map_err(|e| format!("Failed to open file manager for '{}': {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_revealable_requires_path_inside_a_root() {
+        let roots = vec![
+            PathBuf::from("/home/me/.claude"),
+            PathBuf::from("/work/app"),
+        ];
+
+        assert!(is_revealable(
+            Path::new("/home/me/.claude/plugins/lint"),
+            &roots
+        ));
+        assert!(is_revealable(Path::new("/work/app/.mcp.json"), &roots));
+        assert!(is_revealable(Path::new("/work/app"), &roots));
+
+        // Component-wise, so sibling prefixes don't match
+        assert!(!is_revealable(Path::new("/work/app-secrets/key"), &roots));
+        assert!(!is_revealable(Path::new("/home/me/.ssh/id_rsa"), &roots));
+        assert!(!is_revealable(Path::new("/etc/passwd"), &[]));
+    }
+}
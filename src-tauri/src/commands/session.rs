@@ -1,8 +1,12 @@
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use tauri::State;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
 
+use crate::commands::mcp::project_store_name;
+use crate::commands::plugin;
 use crate::core::mcp_config_writer;
 use crate::core::mcp_manager::McpManager;
 use crate::core::plugin_manager::PluginManager;
@@ -48,18 +52,114 @@ pub async fn update_session_status(
     Ok(state.update_status(session_id, status))
 }
 
+/// Project store key holding the branches sessions were recently launched on.
+const RECENT_BRANCHES_KEY: &str = "recent_branches";
+
+/// How many branches a project's recents list keeps.
+pub const MAX_RECENT_BRANCHES: usize = 20;
+
+/// Serializes the load-update-save of recent branches, so concurrent branch
+/// assignments can't overwrite each other's entries.
+static RECENT_BRANCHES_LOCK: Mutex<()> = Mutex::new(());
+
+/// A branch a session of the project was assigned to, and when.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentBranch {
+    pub branch: String,
+    /// RFC 3339 timestamp of the latest assignment.
+    pub last_used_at: String,
+}
+
+/// Moves `branch` to the front of `recents` (adding it if new) and caps the
+/// list at [`MAX_RECENT_BRANCHES`].
+fn push_recent_branch(recents: &mut Vec<RecentBranch>, branch: &str, at: String) {
+    recents.retain(|r| r.branch != branch);
+    recents.insert(
+        0,
+        RecentBranch {
+            branch: branch.to_string(),
+            last_used_at: at,
+        },
+    );
+    recents.truncate(MAX_RECENT_BRANCHES);
+}
+
+fn load_recent_branches(app: &AppHandle, store_name: &str) -> Result<Vec<RecentBranch>, String> {
+    let store = app.store(store_name).map_err(|e| e.to_string())?;
+    store
+        .get(RECENT_BRANCHES_KEY)
+        .map(serde_json::from_value)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|e| e.to_string())
+}
+
+/// Records `branch` as the most recently used branch of `project_path`.
+fn record_recent_branch(app: &AppHandle, project_path: &str, branch: &str) -> Result<(), String> {
+    let store_name = project_store_name(project_path)?;
+    let _guard = RECENT_BRANCHES_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut recents = load_recent_branches(app, &store_name)?;
+    push_recent_branch(&mut recents, branch, chrono::Utc::now().to_rfc3339());
+
+    let store = app.store(&store_name).map_err(|e| e.to_string())?;
+    store.set(
+        RECENT_BRANCHES_KEY,
+        serde_json::to_value(&recents).map_err(|e| e.to_string())?,
+    );
+    store.save().map_err(|e| e.to_string())
+}
+
 /// Exposes `SessionManager::assign_branch` to the frontend.
-/// Links a session to a branch and optional worktree path. Returns an error
-/// string if the session does not exist.
+/// Links a session to a branch and optional worktree path, and records the
+/// branch in the project's recent branches. Returns an error string if the
+/// session does not exist.
 #[tauri::command]
 pub async fn assign_session_branch(
+    app: AppHandle,
     state: State<'_, SessionManager>,
     session_id: u32,
     branch: String,
     worktree_path: Option<String>,
+) -> Result<SessionConfig, String> {
+    let config = state
+        .assign_branch(session_id, branch.clone(), worktree_path)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+    // Recents are a convenience; failing to save them must not fail the launch
+    if let Err(e) = record_recent_branch(&app, &config.project_path, &branch) {
+        log::warn!("Failed to record recent branch '{}': {}", branch, e);
+    }
+    Ok(config)
+}
+
+/// Returns up to `limit` (default and at most [`MAX_RECENT_BRANCHES`])
+/// branches the project recently launched sessions on, most recent first.
+#[tauri::command]
+pub async fn get_recent_branches(
+    app: AppHandle,
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<RecentBranch>, String> {
+    let store_name = project_store_name(&project_path)?;
+
+    let mut recents = load_recent_branches(&app, &store_name)?;
+    recents.truncate(limit.unwrap_or(MAX_RECENT_BRANCHES));
+    Ok(recents)
+}
+
+/// Exposes `SessionManager::set_tags` to the frontend.
+/// Replaces the session's tags, which decide which tagged custom MCP servers
+/// its next MCP config includes. Returns an error string if the session does
+/// not exist.
+#[tauri::command]
+pub async fn set_session_tags(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    tags: Vec<String>,
 ) -> Result<SessionConfig, String> {
     state
-        .assign_branch(session_id, branch, worktree_path)
+        .set_tags(session_id, tags)
         .ok_or_else(|| format!("Session {} not found", session_id))
 }
 
@@ -87,6 +187,22 @@ pub async fn get_sessions_for_project(
     Ok(state.get_sessions_for_project(&canonical))
 }
 
+/// Gets the sessions in a project that are on `branch`, so the UI can offer
+/// to reuse one instead of creating a duplicate worktree.
+#[tauri::command]
+pub async fn get_sessions_for_branch(
+    state: State<'_, SessionManager>,
+    project_path: String,
+    branch: String,
+) -> Result<Vec<SessionConfig>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(state.get_sessions_for_branch(&canonical, &branch))
+}
+
 /// Removes all sessions for a project (used when closing a project tab).
 /// Also kills the associated PTY sessions and cleans up MCP/plugin state.
 #[tauri::command]
@@ -128,6 +244,13 @@ pub async fn remove_sessions_for_project(
                 e
             );
         }
+        if let Err(e) = plugin::clear_session_plugins(&state, session).await {
+            log::warn!(
+                "Failed to remove plugin config for session {}: {}",
+                session.id,
+                e
+            );
+        }
 
         // Fire-and-forget kill -- log errors but don't fail the removal
         if let Err(e) = process_manager.kill_session(session.id).await {
@@ -143,3 +266,38 @@ pub async fn remove_sessions_for_project(
 
     Ok(removed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branches(recents: &[RecentBranch]) -> Vec<&str> {
+        recents.iter().map(|r| r.branch.as_str()).collect()
+    }
+
+    #[test]
+    fn test_recent_branches_are_most_recent_first_and_deduped() {
+        let mut recents = Vec::new();
+        for (i, branch) in ["main", "feature/a", "main", "fix/b", "feature/a"]
+            .into_iter()
+            .enumerate()
+        {
+            push_recent_branch(
+                &mut recents,
+                branch,
+                format!("2024-05-0{}T00:00:00Z", i + 1),
+            );
+        }
+
+        assert_eq!(branches(&recents), vec!["feature/a", "fix/b", "main"]);
+        assert_eq!(recents[0].last_used_at, "2024-05-05T00:00:00Z");
+        assert_eq!(recents[2].last_used_at, "2024-05-03T00:00:00Z");
+
+        for i in 0..MAX_RECENT_BRANCHES {
+            push_recent_branch(&mut recents, &format!("b{i}"), String::new());
+        }
+        assert_eq!(recents.len(), MAX_RECENT_BRANCHES);
+        assert_eq!(recents[0].branch, format!("b{}", MAX_RECENT_BRANCHES - 1));
+        assert!(!branches(&recents).contains(&"main"));
+    }
+}
@@ -0,0 +1,489 @@
+//! IPC commands for copying a session's skill/plugin/MCP selection between projects.
+//!
+//! A [`SessionConfigBundle`] captures everything a user toggles per session.
+//! Importing it into another project applies only the items that project
+//! actually has and reports the rest, so a bundle never enables something
+//! that cannot be launched.
+//!
+//! A [`LaunchProfile`] is a named, per-project setup (branch, env, and
+//! selection) that new sessions can be launched with. Applying one follows
+//! the same rule: missing items are skipped and reported.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::commands::mcp::project_store_name;
+use crate::core::marketplace_manager::MarketplaceManager;
+use crate::core::marketplace_models::SessionMarketplaceConfig;
+use crate::core::mcp_manager::McpManager;
+use crate::core::plugin_manager::PluginManager;
+
+/// Portable snapshot of a session's enabled skills, plugins, MCP servers,
+/// and marketplace plugin toggles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionConfigBundle {
+    pub enabled_skills: Vec<String>,
+    pub enabled_plugins: Vec<String>,
+    pub enabled_mcp_servers: Vec<String>,
+    #[serde(default)]
+    pub marketplace: SessionMarketplaceConfig,
+}
+
+/// Items from an imported bundle that the target project doesn't have.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionConfigImportReport {
+    pub skipped_skills: Vec<String>,
+    pub skipped_plugins: Vec<String>,
+    pub skipped_mcp_servers: Vec<String>,
+    pub skipped_marketplace_plugins: Vec<String>,
+}
+
+/// Store key of a project's launch profiles.
+const LAUNCH_PROFILES_KEY: &str = "launch_profiles";
+
+/// A named session setup saved per project, so users don't recreate the
+/// same branch, env, and selection for every session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProfile {
+    pub name: String,
+    /// Branch, or pattern like `feature/*`, the session should check out.
+    #[serde(default)]
+    pub branch_pattern: Option<String>,
+    /// Environment overrides for the session's shell.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub enabled_skills: Vec<String>,
+    #[serde(default)]
+    pub enabled_plugins: Vec<String>,
+    #[serde(default)]
+    pub enabled_mcp_servers: Vec<String>,
+}
+
+/// Result of applying a launch profile to a session.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProfileApplied {
+    pub branch_pattern: Option<String>,
+    /// Env to pass to `spawn_shell` for the session.
+    pub env: BTreeMap<String, String>,
+    pub skipped_skills: Vec<String>,
+    pub skipped_plugins: Vec<String>,
+    pub skipped_mcp_servers: Vec<String>,
+}
+
+/// Splits `wanted` into the IDs present in `available` and those that are not.
+fn partition_known(wanted: Vec<String>, available: &HashSet<String>) -> (Vec<String>, Vec<String>) {
+    wanted.into_iter().partition(|id| available.contains(id))
+}
+
+pub(crate) async fn export_session_config_inner(
+    plugins: &PluginManager,
+    mcp: &McpManager,
+    marketplace: &MarketplaceManager,
+    project_path: &str,
+    session_id: u32,
+) -> SessionConfigBundle {
+    SessionConfigBundle {
+        enabled_skills: plugins.get_session_skills(project_path, session_id).await,
+        enabled_plugins: plugins.get_session_plugins(project_path, session_id).await,
+        enabled_mcp_servers: mcp.get_session_enabled(project_path, session_id),
+        marketplace: marketplace.get_session_config(project_path, session_id),
+    }
+}
+
+pub(crate) async fn import_session_config_inner(
+    plugins: &PluginManager,
+    mcp: &McpManager,
+    marketplace: &MarketplaceManager,
+    project_path: &str,
+    session_id: u32,
+    bundle: SessionConfigBundle,
+) -> SessionConfigImportReport {
+    let project = plugins.get_project_plugins(project_path).await;
+    let known_skills: HashSet<String> = project.skills.into_iter().map(|s| s.id).collect();
+    let known_plugins: HashSet<String> = project.plugins.into_iter().map(|p| p.id).collect();
+    let known_servers: HashSet<String> = mcp
+        .get_project_servers(project_path)
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    let installed: HashSet<String> = marketplace
+        .get_installed_plugins()
+        .into_iter()
+        .map(|p| p.id)
+        .collect();
+
+    let (skills, skipped_skills) = partition_known(bundle.enabled_skills, &known_skills);
+    let (plugin_ids, skipped_plugins) = partition_known(bundle.enabled_plugins, &known_plugins);
+    let (servers, skipped_mcp_servers) =
+        partition_known(bundle.enabled_mcp_servers, &known_servers);
+    let (enabled_mp, mut skipped_marketplace_plugins) =
+        partition_known(bundle.marketplace.enabled_plugins, &installed);
+    let (disabled_mp, skipped_disabled) =
+        partition_known(bundle.marketplace.disabled_plugins, &installed);
+    skipped_marketplace_plugins.extend(skipped_disabled);
+
+    plugins.set_session_skills(project_path, session_id, skills);
+    plugins.set_session_plugins(project_path, session_id, plugin_ids);
+    mcp.set_session_enabled(project_path, session_id, servers);
+
+    marketplace.clear_session(project_path, session_id);
+    for id in &enabled_mp {
+        marketplace.set_plugin_enabled_for_session(project_path, session_id, id, true);
+    }
+    for id in &disabled_mp {
+        marketplace.set_plugin_enabled_for_session(project_path, session_id, id, false);
+    }
+
+    let report = SessionConfigImportReport {
+        skipped_skills,
+        skipped_plugins,
+        skipped_mcp_servers,
+        skipped_marketplace_plugins,
+    };
+    log::info!(
+        "Imported session config into {} session {} (skipped: {:?})",
+        project_path,
+        session_id,
+        report
+    );
+    report
+}
+
+/// Adds `profile`, replacing any existing profile with the same name.
+fn upsert_launch_profile(profiles: &mut Vec<LaunchProfile>, profile: LaunchProfile) {
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+}
+
+pub(crate) async fn apply_launch_profile_inner(
+    plugins: &PluginManager,
+    mcp: &McpManager,
+    project_path: &str,
+    session_id: u32,
+    profile: LaunchProfile,
+) -> LaunchProfileApplied {
+    let project = plugins.get_project_plugins(project_path).await;
+    let known_skills: HashSet<String> = project.skills.into_iter().map(|s| s.id).collect();
+    let known_plugins: HashSet<String> = project.plugins.into_iter().map(|p| p.id).collect();
+    let known_servers: HashSet<String> = mcp
+        .get_project_servers(project_path)
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+
+    let (skills, skipped_skills) = partition_known(profile.enabled_skills, &known_skills);
+    let (plugin_ids, skipped_plugins) = partition_known(profile.enabled_plugins, &known_plugins);
+    let (servers, skipped_mcp_servers) =
+        partition_known(profile.enabled_mcp_servers, &known_servers);
+
+    plugins.set_session_skills(project_path, session_id, skills);
+    plugins.set_session_plugins(project_path, session_id, plugin_ids);
+    mcp.set_session_enabled(project_path, session_id, servers);
+
+    let applied = LaunchProfileApplied {
+        branch_pattern: profile.branch_pattern,
+        env: profile.env,
+        skipped_skills,
+        skipped_plugins,
+        skipped_mcp_servers,
+    };
+    log::info!(
+        "Applied launch profile '{}' to {} session {} \
+         (skipped skills: {:?}, plugins: {:?}, MCP servers: {:?})",
+        profile.name,
+        project_path,
+        session_id,
+        applied.skipped_skills,
+        applied.skipped_plugins,
+        applied.skipped_mcp_servers
+    );
+    applied
+}
+
+fn load_launch_profiles(app: &AppHandle, store_name: &str) -> Result<Vec<LaunchProfile>, String> {
+    let store = app.store(store_name).map_err(|e| e.to_string())?;
+    store
+        .get(LAUNCH_PROFILES_KEY)
+        .map(serde_json::from_value)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|e| e.to_string())
+}
+
+/// Lists the project's launch profiles in the order they were first saved.
+#[tauri::command]
+pub async fn list_launch_profiles(
+    app: AppHandle,
+    project_path: String,
+) -> Result<Vec<LaunchProfile>, String> {
+    let store_name = project_store_name(&project_path)?;
+    load_launch_profiles(&app, &store_name)
+}
+
+/// Saves a launch profile for the project, replacing one with the same name.
+#[tauri::command]
+pub async fn save_launch_profile(
+    app: AppHandle,
+    project_path: String,
+    mut profile: LaunchProfile,
+) -> Result<(), String> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("Launch profile name must not be empty".to_string());
+    }
+
+    let store_name = project_store_name(&project_path)?;
+    let mut profiles = load_launch_profiles(&app, &store_name)?;
+    let name = profile.name.clone();
+    upsert_launch_profile(&mut profiles, profile);
+
+    let store = app.store(&store_name).map_err(|e| e.to_string())?;
+    store.set(LAUNCH_PROFILES_KEY, serde_json::json!(profiles));
+    store.save().map_err(|e| e.to_string())?;
+
+    log::debug!(
+        "Saved launch profile '{}' for project: {}",
+        name,
+        project_path
+    );
+    Ok(())
+}
+
+/// Applies the project's launch profile `name` to a session: sets its enabled
+/// skills, plugins, and MCP servers, skipping those the project no longer has.
+///
+/// Returns the profile's branch pattern and env for launching the session,
+/// along with the skipped items.
+#[tauri::command]
+pub async fn apply_launch_profile(
+    app: AppHandle,
+    plugin_state: State<'_, PluginManager>,
+    mcp_state: State<'_, McpManager>,
+    project_path: String,
+    session_id: u32,
+    name: String,
+) -> Result<LaunchProfileApplied, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+    let store_name = project_store_name(&project_path)?;
+    let profile = load_launch_profiles(&app, &store_name)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("No launch profile named '{}'", name))?;
+
+    Ok(
+        apply_launch_profile_inner(&plugin_state, &mcp_state, &canonical, session_id, profile)
+            .await,
+    )
+}
+
+/// Exports a session's enabled skills, plugins, MCP servers, and marketplace
+/// toggles as a bundle that can be imported into another project.
+#[tauri::command]
+pub async fn export_session_config(
+    plugin_state: State<'_, PluginManager>,
+    mcp_state: State<'_, McpManager>,
+    marketplace_state: State<'_, MarketplaceManager>,
+    project_path: String,
+    session_id: u32,
+) -> Result<SessionConfigBundle, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(export_session_config_inner(
+        &plugin_state,
+        &mcp_state,
+        &marketplace_state,
+        &canonical,
+        session_id,
+    )
+    .await)
+}
+
+/// Applies a bundle to a session, skipping items the project doesn't have.
+///
+/// Returns the skipped items so the UI can tell the user what didn't carry over.
+#[tauri::command]
+pub async fn import_session_config(
+    plugin_state: State<'_, PluginManager>,
+    mcp_state: State<'_, McpManager>,
+    marketplace_state: State<'_, MarketplaceManager>,
+    project_path: String,
+    session_id: u32,
+    bundle: SessionConfigBundle,
+) -> Result<SessionConfigImportReport, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(import_session_config_inner(
+        &plugin_state,
+        &mcp_state,
+        &marketplace_state,
+        &canonical,
+        session_id,
+        bundle,
+    )
+    .await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    /// Creates a project with the given skill directories and `.mcp.json` servers.
+    fn make_project(root: &Path, skills: &[&str], servers: &[&str]) -> String {
+        for skill in skills {
+            let dir = root.join(".claude").join("skills").join(skill);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("SKILL.md"), format!("---\nname: {}\n---\n", skill)).unwrap();
+        }
+        let servers: serde_json::Map<String, serde_json::Value> = servers
+            .iter()
+            .map(|name| {
+                let entry = serde_json::json!({ "type": "stdio", "command": name });
+                (name.to_string(), entry)
+            })
+            .collect();
+        std::fs::write(
+            root.join(".mcp.json"),
+            serde_json::json!({ "mcpServers": servers }).to_string(),
+        )
+        .unwrap();
+        std::fs::canonicalize(root)
+            .unwrap()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let dir = tempdir().unwrap();
+        let project = make_project(dir.path(), &["lint", "deploy"], &["db", "search"]);
+        let plugins = PluginManager::new();
+        let mcp = McpManager::new();
+        let marketplace = MarketplaceManager::new();
+        plugins.set_session_skills(&project, 1, vec!["project:deploy".to_string()]);
+        mcp.set_session_enabled(&project, 1, vec!["search".to_string()]);
+
+        let exported = export_session_config_inner(&plugins, &mcp, &marketplace, &project, 1).await;
+        let json = serde_json::to_string(&exported).unwrap();
+        let bundle: SessionConfigBundle = serde_json::from_str(&json).unwrap();
+
+        let report =
+            import_session_config_inner(&plugins, &mcp, &marketplace, &project, 2, bundle).await;
+
+        assert!(report.skipped_skills.is_empty());
+        assert!(report.skipped_mcp_servers.is_empty());
+        assert_eq!(
+            export_session_config_inner(&plugins, &mcp, &marketplace, &project, 2).await,
+            exported
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_items_missing_from_target_project() {
+        let source_dir = tempdir().unwrap();
+        let target_dir = tempdir().unwrap();
+        let source = make_project(source_dir.path(), &["lint", "deploy"], &["db", "search"]);
+        let target = make_project(target_dir.path(), &["lint"], &["db"]);
+        let plugins = PluginManager::new();
+        let mcp = McpManager::new();
+        let marketplace = MarketplaceManager::new();
+        plugins.set_session_skills(
+            &source,
+            1,
+            vec!["project:lint".to_string(), "project:deploy".to_string()],
+        );
+        mcp.set_session_enabled(&source, 1, vec!["db".to_string(), "search".to_string()]);
+        marketplace.set_plugin_enabled_for_session(&source, 1, "not-installed", true);
+
+        let bundle = export_session_config_inner(&plugins, &mcp, &marketplace, &source, 1).await;
+        let report =
+            import_session_config_inner(&plugins, &mcp, &marketplace, &target, 7, bundle).await;
+
+        assert_eq!(report.skipped_skills, vec!["project:deploy"]);
+        assert_eq!(report.skipped_mcp_servers, vec!["search"]);
+        assert_eq!(report.skipped_marketplace_plugins, vec!["not-installed"]);
+        assert_eq!(
+            plugins.get_session_skills(&target, 7).await,
+            vec!["project:lint"]
+        );
+        assert_eq!(mcp.get_session_enabled(&target, 7), vec!["db"]);
+        let target_marketplace = marketplace.get_session_config(&target, 7);
+        assert!(target_marketplace.enabled_plugins.is_empty());
+    }
+
+    fn profile(name: &str) -> LaunchProfile {
+        LaunchProfile {
+            name: name.to_string(),
+            branch_pattern: Some("feature/*".to_string()),
+            env: BTreeMap::from([("RUST_LOG".to_string(), "debug".to_string())]),
+            enabled_skills: vec!["project:lint".to_string(), "project:gone".to_string()],
+            enabled_plugins: vec!["old-plugin".to_string()],
+            enabled_mcp_servers: vec!["db".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_launch_profiles_round_trip_and_replace_by_name() {
+        let mut profiles = Vec::new();
+        upsert_launch_profile(&mut profiles, profile("backend"));
+        upsert_launch_profile(&mut profiles, profile("frontend"));
+        let mut updated = profile("backend");
+        updated.branch_pattern = None;
+        upsert_launch_profile(&mut profiles, updated.clone());
+
+        let stored = serde_json::json!(profiles);
+        let loaded: Vec<LaunchProfile> = serde_json::from_value(stored).unwrap();
+        assert_eq!(loaded, vec![updated, profile("frontend")]);
+
+        // Profiles saved with only a name still load
+        let minimal: LaunchProfile =
+            serde_json::from_value(serde_json::json!({ "name": "bare" })).unwrap();
+        assert!(minimal.env.is_empty() && minimal.enabled_skills.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_launch_profile_sets_enabled_sets_and_reports_missing() {
+        let dir = tempdir().unwrap();
+        let project = make_project(dir.path(), &["lint", "deploy"], &["db", "search"]);
+        let plugins = PluginManager::new();
+        let mcp = McpManager::new();
+
+        let applied =
+            apply_launch_profile_inner(&plugins, &mcp, &project, 3, profile("backend")).await;
+
+        assert_eq!(
+            plugins.get_session_skills(&project, 3).await,
+            vec!["project:lint"]
+        );
+        assert!(plugins.get_session_plugins(&project, 3).await.is_empty());
+        assert_eq!(mcp.get_session_enabled(&project, 3), vec!["db"]);
+        assert_eq!(applied.skipped_skills, vec!["project:gone"]);
+        assert_eq!(applied.skipped_plugins, vec!["old-plugin"]);
+        assert!(applied.skipped_mcp_servers.is_empty());
+        assert_eq!(applied.branch_pattern.as_deref(), Some("feature/*"));
+        assert_eq!(
+            applied.env.get("RUST_LOG").map(String::as_str),
+            Some("debug")
+        );
+    }
+}
@@ -1,13 +1,22 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use serde::Serialize;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 
+use crate::commands::{marketplace, plugin};
+use crate::core::dotenv;
+use crate::core::process_manager::DEFAULT_KILL_GRACE;
 use crate::core::session_manager::SessionManager;
+use crate::core::session_recorder::DEFAULT_MAX_RECORDING_BYTES;
 use crate::core::status_server::StatusServer;
 use crate::core::windows_process::TokioCommandExt;
-use crate::core::{BackendCapabilities, BackendType, ProcessManager, PtyError, SessionProcessTree};
+use crate::core::{
+    BackendCapabilities, BackendType, EnvMode, MarketplaceManager, ProcessManager, PtyError,
+    SessionProcessTree, SpawnOptions, TerminalState,
+};
 
 /// Backend information returned to the frontend.
 #[derive(Debug, Clone, Serialize)]
@@ -42,35 +51,33 @@ impl From<BackendCapabilities> for BackendCapabilitiesDto {
     }
 }
 
+impl From<BackendType> for BackendInfo {
+    fn from(backend_type: BackendType) -> Self {
+        Self {
+            backend_type,
+            capabilities: backend_type.capabilities().into(),
+        }
+    }
+}
+
 /// Returns information about the active terminal backend.
 ///
 /// The frontend can use this to enable/disable features based on
 /// backend capabilities (e.g., enhanced terminal state queries).
 #[tauri::command]
 pub fn get_backend_info() -> BackendInfo {
-    let backend_type = BackendType::platform_default();
-
-    let capabilities = match backend_type {
-        BackendType::XtermPassthrough => BackendCapabilities {
-            enhanced_state: false,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "xterm-passthrough",
-        },
-        BackendType::VteParser => BackendCapabilities {
-            enhanced_state: true,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "vte-parser",
-        },
-    };
+    BackendType::platform_default().into()
+}
 
-    BackendInfo {
-        backend_type,
-        capabilities: capabilities.into(),
-    }
+/// Lists the terminal backends compiled into this build, default first.
+///
+/// Any of these can be passed as `backend` to `spawn_shell`.
+#[tauri::command]
+pub fn list_backends() -> Vec<BackendInfo> {
+    BackendType::compiled()
+        .into_iter()
+        .map(BackendInfo::from)
+        .collect()
 }
 
 /// Exposes `ProcessManager::spawn_shell` to the frontend.
@@ -84,12 +91,31 @@ pub fn get_backend_info() -> BackendInfo {
 /// These are inherited by all child processes (including Claude CLI → MCP server).
 /// Common usage: `{ "MAESTRO_PROJECT_HASH": "<hash>" }` for MCP status identification.
 /// Note: `MAESTRO_SESSION_ID` is automatically set by the process manager.
+///
+/// `shell` picks a specific shell binary instead of `$SHELL`; like `$SHELL`,
+/// it is replaced by a fallback if missing or not executable.
+/// `backend` selects the terminal backend for this session (see
+/// `list_backends`); when omitted or not compiled in, the default is used.
+///
+/// With `load_dotenv`, variables from `<cwd>/.env` (or `dotenv_path`, relative
+/// to `cwd`) are added too. Explicit `env` entries win over the file, and file
+/// entries never override app variables the shell inherits.
+///
+/// With `env_allowlist`, the shell starts from an empty environment and
+/// inherits only the listed variables plus `PATH`, `HOME`, `TERM` and the
+/// locale; `env` and `.env` entries are still added.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_shell(
     app_handle: AppHandle,
     state: State<'_, ProcessManager>,
     cwd: Option<String>,
     env: Option<HashMap<String, String>>,
+    shell: Option<String>,
+    backend: Option<BackendType>,
+    load_dotenv: Option<bool>,
+    dotenv_path: Option<String>,
+    env_allowlist: Option<Vec<String>>,
 ) -> Result<u32, PtyError> {
     // Validate cwd if provided: must exist and be a directory
     let canonical_cwd = if let Some(ref dir) = cwd {
@@ -119,8 +145,63 @@ pub async fn spawn_shell(
     } else {
         None
     };
+    let env_mode = env_allowlist.map_or(EnvMode::Inherit, EnvMode::Allowlist);
+    let env = if load_dotenv.unwrap_or(false) {
+        Some(resolve_dotenv_env(
+            canonical_cwd.as_deref(),
+            dotenv_path.as_deref(),
+            env,
+            &env_mode,
+        )?)
+    } else {
+        env
+    };
     let pm = state.inner().clone();
-    pm.spawn_shell(app_handle, canonical_cwd, env)
+    let options = SpawnOptions::from_env()
+        .with_backend(BackendType::resolve(backend))
+        .with_env_mode(env_mode);
+    pm.spawn_shell(app_handle, canonical_cwd, env, shell, options)
+        .await
+}
+
+/// Merges the `.env` file for a `spawn_shell` call into its explicit `env`.
+///
+/// A missing default `<cwd>/.env` is not an error; a missing `dotenv_path`
+/// the caller asked for is. File entries only yield to app variables the
+/// shell will inherit under `env_mode`.
+fn resolve_dotenv_env(
+    cwd: Option<&str>,
+    dotenv_path: Option<&str>,
+    env: Option<HashMap<String, String>>,
+    env_mode: &EnvMode,
+) -> Result<HashMap<String, String>, PtyError> {
+    let path = match (dotenv_path, cwd) {
+        (Some(p), _) if Path::new(p).is_absolute() => PathBuf::from(p),
+        (Some(p), Some(cwd)) => Path::new(cwd).join(p),
+        (None, Some(cwd)) => Path::new(cwd).join(".env"),
+        (_, None) => {
+            return Err(PtyError::spawn_failed(
+                "load_dotenv requires a cwd or an absolute dotenv_path",
+            ))
+        }
+    };
+
+    let file_vars = match dotenv::load_dotenv(&path) {
+        Ok(vars) => vars,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && dotenv_path.is_none() => {
+            log::debug!("No .env at {}, skipping", path.display());
+            Vec::new()
+        }
+        Err(e) => {
+            return Err(PtyError::spawn_failed(format!(
+                "Failed to read '{}': {e}",
+                path.display()
+            )))
+        }
+    };
+    Ok(dotenv::merge_dotenv(file_vars, env, |key| {
+        env_mode.inherits(key) && std::env::var_os(key).is_some()
+    }))
 }
 
 /// Exposes `ProcessManager::write_stdin` to the frontend.
@@ -135,6 +216,17 @@ pub async fn write_stdin(
     pm.write_stdin(session_id, &data)
 }
 
+/// Exposes `ProcessManager::interrupt_session` to the frontend.
+/// Sends Ctrl-C to the session's foreground command without killing the shell.
+#[tauri::command]
+pub async fn interrupt_session(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<(), PtyError> {
+    let pm = state.inner().clone();
+    pm.interrupt_session(session_id)
+}
+
 /// Exposes `ProcessManager::resize_pty` to the frontend.
 /// Rejects dimensions that are zero or exceed 500 to prevent misuse.
 #[tauri::command]
@@ -151,33 +243,172 @@ pub async fn resize_pty(
     pm.resize_pty(session_id, rows, cols)
 }
 
-/// Exposes `ProcessManager::kill_session` to the frontend.
-/// Gracefully terminates the PTY session (SIGTERM, then SIGKILL after 3s).
-/// Also unregisters the session from the status server.
+/// Dimensions currently applied to a session's PTY.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PtySize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+/// Exposes `ProcessManager::pty_size` to the frontend.
+/// Reports the size the PTY actually has, which lags a coalesced resize
+/// until it is flushed.
+#[tauri::command]
+pub async fn get_pty_size(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<PtySize, PtyError> {
+    let (rows, cols) = state.pty_size(session_id)?;
+    Ok(PtySize { rows, cols })
+}
+
+/// Exposes `ProcessManager::kill_session_with_grace` to the frontend.
+/// Gracefully terminates the PTY session: SIGTERM, then SIGKILL if it is
+/// still running after `grace_ms` (default 2s).
+/// Also unregisters the session from the status server and removes the
+/// plugin config written to its working directory.
 #[tauri::command]
 pub async fn kill_session(
     state: State<'_, ProcessManager>,
     session_mgr: State<'_, SessionManager>,
     status_server: State<'_, Arc<StatusServer>>,
     session_id: u32,
+    grace_ms: Option<u64>,
 ) -> Result<(), PtyError> {
+    // Look the session up first; the frontend may drop it while we kill
+    let session = session_mgr.get_session(session_id);
+
     // Kill the PTY session
     let pm = state.inner().clone();
-    let result = pm.kill_session(session_id).await;
+    let grace = grace_ms.map_or(DEFAULT_KILL_GRACE, Duration::from_millis);
+    let result = pm.kill_session_with_grace(session_id, grace).await;
 
     // Unregister the session from the status server so it stops accepting updates
     status_server.unregister_session(session_id).await;
 
-    // Log for debugging
-    let _project_path = session_mgr
-        .all_sessions()
-        .into_iter()
-        .find(|s| s.id == session_id)
-        .map(|s| s.project_path);
+    if let Some(session) = session {
+        if let Err(e) = plugin::clear_session_plugins(&session_mgr, &session).await {
+            log::warn!("Failed to clear plugin config of session {session_id}: {e}");
+        }
+    }
 
     result
 }
 
+/// Exposes `ProcessManager::detach_session` to the frontend.
+/// Lets go of the session's PTY without killing it and returns the shell's
+/// PID. See `ProcessManager::detach_session` for what survives the hangup.
+/// Also unregisters the session from the status server.
+#[tauri::command]
+pub async fn detach_session(
+    state: State<'_, ProcessManager>,
+    status_server: State<'_, Arc<StatusServer>>,
+    session_id: u32,
+) -> Result<i32, PtyError> {
+    let pm = state.inner().clone();
+    let pid = pm.detach_session(session_id).await?;
+    status_server.unregister_session(session_id).await;
+    Ok(pid)
+}
+
+/// Exposes `ProcessManager::get_terminal_state` to the frontend.
+/// Returns cursor, title, color, and scroll-region state for the session, or
+/// `None` when the active backend does not parse output (xterm passthrough).
+#[tauri::command]
+pub async fn get_terminal_state(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<Option<TerminalState>, PtyError> {
+    get_terminal_state_inner(state.inner(), session_id)
+}
+
+/// Returns the capabilities of the backend a session runs on.
+///
+/// The frontend checks `enhancedState` before showing UI that depends on
+/// `get_terminal_state`, which is always `None` for passthrough sessions.
+#[tauri::command]
+pub async fn backend_capabilities(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<BackendCapabilitiesDto, PtyError> {
+    backend_capabilities_inner(state.inner(), session_id)
+}
+
+pub(crate) fn backend_capabilities_inner(
+    pm: &ProcessManager,
+    session_id: u32,
+) -> Result<BackendCapabilitiesDto, PtyError> {
+    let mut caps = pm.session_backend(session_id)?.capabilities();
+    // Shell integration depends on the shell, so it turns on once it emits OSC 133
+    caps.shell_integration = pm
+        .get_terminal_state(session_id)?
+        .is_some_and(|s| s.shell_integration);
+    Ok(caps.into())
+}
+
+pub(crate) fn get_terminal_state_inner(
+    pm: &ProcessManager,
+    session_id: u32,
+) -> Result<Option<TerminalState>, PtyError> {
+    pm.get_terminal_state(session_id)
+}
+
+/// Exposes `ProcessManager::restart_session` to the frontend.
+/// Replaces the session's shell with a fresh one under the same ID, cwd, and env.
+/// Emits `session-restarted-{id}` once the new shell is running, or
+/// `session-restart-failed-{id}` with a `RestartFailed` error if it couldn't
+/// start (the session is gone by then). On success it also re-registers the session with the status server so reports from the new
+/// shell route to its project.
+#[tauri::command]
+pub async fn restart_session(
+    state: State<'_, ProcessManager>,
+    session_mgr: State<'_, SessionManager>,
+    status_server: State<'_, Arc<StatusServer>>,
+    session_id: u32,
+) -> Result<(), PtyError> {
+    let pm = state.inner().clone();
+    pm.restart_session(session_id).await?;
+
+    if let Some(session) = session_mgr.get_session(session_id) {
+        status_server
+            .register_session(session_id, &session.project_path)
+            .await;
+    }
+    Ok(())
+}
+
+/// Exposes `ProcessManager::record_session` to the frontend.
+/// Tees the session's raw PTY output (and its input, if `include_input`) into
+/// `path` until `stop_recording` is called. The file rotates to `path.1`..
+/// once it would exceed `max_bytes` (16 MiB by default).
+#[tauri::command]
+pub async fn record_session(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+    path: String,
+    include_input: Option<bool>,
+    max_bytes: Option<u64>,
+) -> Result<(), PtyError> {
+    let pm = state.inner().clone();
+    pm.record_session(
+        session_id,
+        path.into(),
+        include_input.unwrap_or(false),
+        max_bytes.unwrap_or(DEFAULT_MAX_RECORDING_BYTES),
+    )
+}
+
+/// Exposes `ProcessManager::stop_recording` to the frontend.
+/// Returns whether the session was being recorded.
+#[tauri::command]
+pub async fn stop_recording(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<bool, PtyError> {
+    let pm = state.inner().clone();
+    pm.stop_recording(session_id)
+}
+
 /// Returns the process tree for a specific session.
 ///
 /// The tree includes the root shell process and all its descendants.
@@ -240,6 +471,99 @@ pub async fn kill_all_sessions(state: State<'_, ProcessManager>) -> Result<u32,
     pm.kill_all_sessions().await
 }
 
+/// What [`shutdown_app`] cleaned up.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownReport {
+    /// PTY sessions terminated.
+    pub sessions_killed: u32,
+    /// Sessions dropped from the session registry.
+    pub sessions_removed: u32,
+    /// Sessions unregistered from the status server.
+    pub statuses_unregistered: u32,
+    /// Whether marketplace data was written to the store.
+    pub marketplace_flushed: bool,
+}
+
+/// Terminates all PTY sessions with `grace` between SIGTERM and SIGKILL,
+/// then clears the session registry and status server registrations.
+///
+/// The session registry is in-memory only, so there is nothing to write for
+/// it; clearing it tells the frontend every session is gone.
+pub(crate) async fn shutdown_sessions(
+    pm: &ProcessManager,
+    session_mgr: &SessionManager,
+    status_server: Option<&StatusServer>,
+    grace: Duration,
+) -> ShutdownReport {
+    let sessions_killed = pm.shutdown_all_sessions(grace).await;
+
+    let mut sessions_removed = 0;
+    for session in session_mgr.all_sessions() {
+        if session_mgr.remove_session(session.id).is_some() {
+            sessions_removed += 1;
+        }
+    }
+
+    let mut statuses_unregistered = 0;
+    if let Some(server) = status_server {
+        for id in server.registered_sessions().await {
+            server.unregister_session(id).await;
+            statuses_unregistered += 1;
+        }
+    }
+
+    ShutdownReport {
+        sessions_killed,
+        sessions_removed,
+        statuses_unregistered,
+        marketplace_flushed: false,
+    }
+}
+
+/// Shuts down everything that must not outlive the app: sessions, status
+/// server registrations, and pending marketplace writes. Safe to call more
+/// than once; later calls find nothing left to clean up.
+pub(crate) async fn shutdown(app: &AppHandle, grace: Duration) -> ShutdownReport {
+    let pm = app.state::<ProcessManager>().inner().clone();
+    let status_server = app.try_state::<Arc<StatusServer>>();
+    let mut report = shutdown_sessions(
+        &pm,
+        &app.state::<SessionManager>(),
+        status_server.as_deref().map(Arc::as_ref),
+        grace,
+    )
+    .await;
+
+    match marketplace::flush(app, &app.state::<MarketplaceManager>()) {
+        Ok(()) => report.marketplace_flushed = true,
+        Err(e) => log::warn!("Failed to flush marketplace data on shutdown: {}", e),
+    }
+    log::info!("Shutdown complete: {:?}", report);
+    report
+}
+
+/// Gracefully shuts down all sessions and flushes state before quitting.
+/// Sessions get `grace_ms` (default 2s) between SIGTERM and SIGKILL. Also
+/// runs automatically when the app is asked to exit.
+#[tauri::command]
+pub async fn shutdown_app(app: AppHandle, grace_ms: Option<u64>) -> ShutdownReport {
+    let grace = grace_ms.map_or(DEFAULT_KILL_GRACE, Duration::from_millis);
+    shutdown(&app, grace).await
+}
+
+/// Sets how many shells may spawn at once (e.g. from the user's settings);
+/// further `spawn_shell` calls queue until a spawn finishes setting up.
+/// Returns the applied limit, which is at least 1.
+#[tauri::command]
+pub async fn set_spawn_limit(
+    state: State<'_, ProcessManager>,
+    limit: usize,
+) -> Result<usize, PtyError> {
+    state.set_spawn_limit(limit);
+    Ok(state.spawn_limit())
+}
+
 /// Checks if a command is available in the user's PATH.
 ///
 /// On macOS/Linux, when the app is launched from GUI launchers (Raycast, Spotlight),
@@ -310,3 +634,172 @@ pub async fn check_cli_available(command: String) -> Result<bool, String> {
         Ok(output.status.success())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::process_manager::PtyEmitFn;
+    use crate::core::session_manager::AiMode;
+    use crate::core::{Color, CursorShape};
+
+    #[test]
+    fn test_terminal_state_round_trips_through_json() {
+        let state = TerminalState {
+            cursor_row: 3,
+            cursor_col: 7,
+            cursor_shape: CursorShape::Bar,
+            cursor_visible: true,
+            title: Some("build".to_string()),
+            fg: Color::Rgb(1, 2, 3),
+            bg: Color::Indexed(17),
+            scroll_bottom: 23,
+            ..TerminalState::default()
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains("\"cursorRow\":3"));
+        let parsed: TerminalState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, state);
+    }
+
+    #[cfg(all(unix, feature = "vte-backend"))]
+    #[tokio::test]
+    async fn test_terminal_state_reflects_osc_title() {
+        let pm = ProcessManager::new();
+        let emit: PtyEmitFn = Arc::new(|_, _| {});
+        let id = pm
+            .spawn_shell_with_emitter(emit, None, None, None, SpawnOptions::default())
+            .unwrap();
+
+        pm.write_stdin(id, "printf '\\033]2;maestro-title\\007'\n")
+            .unwrap();
+
+        let mut title = None;
+        for _ in 0..200 {
+            title = get_terminal_state_inner(&pm, id)
+                .unwrap()
+                .and_then(|state| state.title);
+            if title.as_deref() == Some("maestro-title") {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        pm.kill_session(id).await.unwrap();
+        assert_eq!(title.as_deref(), Some("maestro-title"));
+    }
+
+    #[test]
+    fn test_list_backends_reflects_compiled_features() {
+        let backends: Vec<BackendType> = list_backends().iter().map(|b| b.backend_type).collect();
+
+        assert_eq!(backends[0], get_backend_info().backend_type);
+        assert!(backends.contains(&BackendType::XtermPassthrough));
+        assert_eq!(
+            backends.contains(&BackendType::VteParser),
+            cfg!(feature = "vte-backend")
+        );
+        let vte_requested = BackendType::resolve(Some(BackendType::VteParser));
+        if !cfg!(feature = "vte-backend") {
+            assert_eq!(vte_requested, BackendType::platform_default());
+        }
+    }
+
+    #[cfg(all(unix, feature = "vte-backend"))]
+    #[tokio::test]
+    async fn test_passthrough_session_has_no_terminal_state() {
+        let pm = ProcessManager::new();
+        let emit: PtyEmitFn = Arc::new(|_, _| {});
+        let options = SpawnOptions::default().with_backend(BackendType::XtermPassthrough);
+        let id = pm
+            .spawn_shell_with_emitter(emit, None, None, None, options)
+            .unwrap();
+
+        let state = get_terminal_state_inner(&pm, id).unwrap();
+        let caps = backend_capabilities_inner(&pm, id).unwrap();
+
+        pm.kill_session(id).await.unwrap();
+        assert!(state.is_none());
+        assert!(!caps.enhanced_state);
+        assert_eq!(caps.backend_name, "xterm-passthrough");
+    }
+
+    #[test]
+    fn test_backends_report_accurate_capabilities() {
+        use crate::core::{TerminalBackend, XtermPassthroughBackend};
+
+        let passthrough = XtermPassthroughBackend::new().capabilities();
+        assert!(!passthrough.enhanced_state);
+        assert_eq!(passthrough.backend_name, "xterm-passthrough");
+
+        #[cfg(feature = "vte-backend")]
+        {
+            let vte = crate::core::VteBackend::new().capabilities();
+            assert!(vte.enhanced_state);
+            assert_eq!(vte.backend_name, "vte-parser");
+        }
+    }
+
+    #[test]
+    fn test_terminal_state_unknown_session() {
+        let pm = ProcessManager::new();
+        assert!(get_terminal_state_inner(&pm, 99).is_err());
+        assert!(backend_capabilities_inner(&pm, 99).is_err());
+    }
+
+    #[test]
+    fn test_dotenv_keeps_parent_var_the_allowlist_drops() {
+        // Set once for the whole test binary; other tests don't read it
+        std::env::set_var("MAESTRO_TEST_DOTENV_PARENT", "from-app");
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".env"),
+            "MAESTRO_TEST_DOTENV_PARENT=from-file\n",
+        )
+        .unwrap();
+        let cwd = dir.path().to_str();
+
+        let inherited = resolve_dotenv_env(cwd, None, None, &EnvMode::Inherit).unwrap();
+        assert!(!inherited.contains_key("MAESTRO_TEST_DOTENV_PARENT"));
+
+        let allowlist = EnvMode::Allowlist(vec!["OTHER".to_string()]);
+        let env = resolve_dotenv_env(cwd, None, None, &allowlist).unwrap();
+        assert_eq!(
+            env.get("MAESTRO_TEST_DOTENV_PARENT").map(String::as_str),
+            Some("from-file")
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shutdown_sessions_kills_tracked_sessions() {
+        let pm = ProcessManager::new();
+        let session_mgr = SessionManager::new();
+        let emit: PtyEmitFn = Arc::new(|_, _| {});
+        for _ in 0..3 {
+            let id = pm
+                .spawn_shell_with_emitter(emit.clone(), None, None, None, SpawnOptions::default())
+                .unwrap();
+            session_mgr
+                .create_session(id, AiMode::Plain, "/tmp/project".to_string())
+                .unwrap();
+        }
+
+        let report = shutdown_sessions(&pm, &session_mgr, None, Duration::from_millis(200)).await;
+
+        assert_eq!(
+            report,
+            ShutdownReport {
+                sessions_killed: 3,
+                sessions_removed: 3,
+                statuses_unregistered: 0,
+                marketplace_flushed: false,
+            }
+        );
+        assert!(pm.get_all_session_pids().is_empty());
+        assert!(session_mgr.all_sessions().is_empty());
+
+        let again = shutdown_sessions(&pm, &session_mgr, None, Duration::ZERO).await;
+        assert_eq!(again, ShutdownReport::default());
+    }
+}
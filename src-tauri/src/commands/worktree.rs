@@ -1,10 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::Serialize;
 use tauri::State;
 
-use crate::core::worktree_manager::WorktreeManager;
-use crate::git::{BranchInfo, Git};
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_manager::{
+    CheckoutOptions, WorktreeIssue, WorktreeManager, WorktreeUsage,
+};
+use crate::git::{BranchInfo, Git, GitError, HeadState};
 
 /// Result of preparing a worktree for a session.
 #[derive(Debug, Clone, Serialize)]
@@ -30,13 +33,23 @@ pub struct WorktreePreparationResult {
 ///
 /// On any failure, falls back to the project path so sessions always launch.
 /// The caller is responsible for updating the session with the worktree path.
+///
+/// With `no_checkout`, a newly created worktree starts with an empty working
+/// tree; only `checkout_paths` (if any) are checked out. A reused worktree is
+/// left as it is.
 #[tauri::command]
 pub async fn prepare_session_worktree(
     worktree_manager: State<'_, WorktreeManager>,
     project_path: String,
     branch: Option<String>,
+    no_checkout: Option<bool>,
+    checkout_paths: Option<Vec<String>>,
 ) -> Result<WorktreePreparationResult, String> {
-    prepare_worktree_inner(&worktree_manager, project_path, branch).await
+    let checkout = CheckoutOptions {
+        no_checkout: no_checkout.unwrap_or(false),
+        paths: checkout_paths.unwrap_or_default(),
+    };
+    prepare_worktree_inner(&worktree_manager, project_path, branch, &checkout).await
 }
 
 /// Inner implementation extracted from the Tauri command for testability.
@@ -47,6 +60,7 @@ pub(crate) async fn prepare_worktree_inner(
     worktree_manager: &WorktreeManager,
     project_path: String,
     branch: Option<String>,
+    checkout: &CheckoutOptions,
 ) -> Result<WorktreePreparationResult, String> {
     // No branch specified - just use the project path
     let branch = match branch {
@@ -105,11 +119,15 @@ pub(crate) async fn prepare_worktree_inner(
         }
     }
 
-    // Check if the branch is checked out in the main repo and needs to be switched
-    let current_branch = git.current_branch().await.ok();
+    // Check if the branch is checked out in the main repo and needs to be switched.
+    // A detached main repo holds no branch, so there is nothing to free.
+    let on_target_branch = matches!(
+        git.head_state().await,
+        Ok(HeadState::Branch(ref name)) if name == &local_branch
+    );
     let mut warning = None;
 
-    if current_branch.as_ref() == Some(&local_branch) {
+    if on_target_branch {
         log::info!(
             "Target branch {} is checked out in main repo, switching to default",
             local_branch
@@ -159,7 +177,10 @@ pub(crate) async fn prepare_worktree_inner(
     }
 
     // Create the worktree
-    match worktree_manager.create(&local_branch, &repo_path).await {
+    match worktree_manager
+        .create(&local_branch, &repo_path, checkout)
+        .await
+    {
         Ok(wt_path) => {
             let wt_path_str = wt_path.to_string_lossy().to_string();
             log::info!(
@@ -187,6 +208,201 @@ pub(crate) async fn prepare_worktree_inner(
     }
 }
 
+/// Prepares a worktree pinned to a specific commit, tag, or branch tip with a
+/// detached HEAD, e.g. for reproducing a bug at a release.
+///
+/// No branch is created or checked out, so the main repo is never switched.
+/// Unlike `prepare_session_worktree`, an unresolvable ref is an error rather
+/// than a silent fallback to the project path.
+#[tauri::command]
+pub async fn prepare_worktree_at_ref(
+    worktree_manager: State<'_, WorktreeManager>,
+    project_path: String,
+    git_ref: String,
+) -> Result<WorktreePreparationResult, String> {
+    prepare_worktree_at_ref_inner(&worktree_manager, project_path, git_ref).await
+}
+
+/// Inner implementation for detached worktrees, extracted for testability.
+///
+/// Reuses a managed worktree that is already detached at the same commit.
+pub(crate) async fn prepare_worktree_at_ref_inner(
+    worktree_manager: &WorktreeManager,
+    project_path: String,
+    git_ref: String,
+) -> Result<WorktreePreparationResult, String> {
+    let repo_path = PathBuf::from(&project_path);
+    let git = Git::new(&repo_path);
+    let commit = git
+        .resolve_commit(&git_ref)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let managed = worktree_manager
+        .list_managed(&repo_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(wt) = managed
+        .iter()
+        .find(|wt| wt.branch.is_none() && wt.head == commit)
+    {
+        log::info!("Reusing detached worktree at {} for {}", wt.path, git_ref);
+        return Ok(WorktreePreparationResult {
+            working_directory: wt.path.clone(),
+            worktree_path: Some(wt.path.clone()),
+            created: false,
+            warning: None,
+        });
+    }
+
+    let wt_path = worktree_manager
+        .create_detached(&commit, &repo_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    let wt_path_str = wt_path.to_string_lossy().to_string();
+    log::info!(
+        "Created detached worktree at {} for {} ({})",
+        wt_path_str,
+        git_ref,
+        commit
+    );
+
+    Ok(WorktreePreparationResult {
+        working_directory: wt_path_str.clone(),
+        worktree_path: Some(wt_path_str),
+        created: true,
+        warning: None,
+    })
+}
+
+/// Attaches a session to a worktree that already exists on disk.
+///
+/// Unlike `prepare_session_worktree`, this never creates branches, never
+/// switches the main repo, and never creates a worktree. The path must be
+/// registered in `git worktree list` for the project's repository.
+#[tauri::command]
+pub async fn prepare_existing_worktree(
+    project_path: String,
+    worktree_path: String,
+) -> Result<WorktreePreparationResult, String> {
+    prepare_worktree_for_existing_path(project_path, worktree_path).await
+}
+
+/// Inner implementation for attaching to an existing worktree, extracted for testability.
+///
+/// Paths are canonicalized before comparison so symlinked temp dirs (e.g.
+/// `/tmp` vs `/private/tmp` on macOS) still match. If the path is the main
+/// worktree, `worktree_path` is left `None` so session cleanup never tries
+/// to remove the main checkout.
+pub(crate) async fn prepare_worktree_for_existing_path(
+    project_path: String,
+    worktree_path: String,
+) -> Result<WorktreePreparationResult, String> {
+    if worktree_path.is_empty() {
+        return Err(GitError::WorktreeNotFound(worktree_path).to_string());
+    }
+
+    let git = Git::new(&project_path);
+    let worktrees = git.worktree_list().await.map_err(|e| e.to_string())?;
+
+    let target = canonicalize_or_raw(Path::new(&worktree_path)).await;
+    for wt in &worktrees {
+        if canonicalize_or_raw(Path::new(&wt.path)).await != target {
+            continue;
+        }
+
+        log::info!(
+            "Attaching to existing worktree at {} (branch: {})",
+            wt.path,
+            wt.branch.as_deref().unwrap_or("detached")
+        );
+
+        return Ok(WorktreePreparationResult {
+            working_directory: wt.path.clone(),
+            worktree_path: if wt.is_main_worktree {
+                None
+            } else {
+                Some(wt.path.clone())
+            },
+            created: false,
+            warning: None,
+        });
+    }
+
+    Err(GitError::WorktreeNotFound(worktree_path).to_string())
+}
+
+/// Switches an existing worktree to another branch, keeping its directory.
+///
+/// The branch is checked out (or created from the worktree's HEAD) inside
+/// the worktree itself, so the main repo is never touched. Sessions running
+/// in the worktree are reassigned to the new branch. Returns the local
+/// branch name that was checked out.
+#[tauri::command]
+pub async fn switch_worktree_branch(
+    session_mgr: State<'_, SessionManager>,
+    worktree_path: String,
+    new_branch: String,
+) -> Result<String, String> {
+    let local_branch = switch_worktree_branch_inner(&worktree_path, &new_branch).await?;
+    session_mgr.reassign_worktree_branch(&worktree_path, &local_branch);
+    Ok(local_branch)
+}
+
+/// Inner implementation for branch switching, extracted for testability.
+///
+/// A branch already checked out in another worktree (including the main
+/// repo) is rejected up front with `BranchAlreadyCheckedOut`, since git
+/// would refuse the checkout anyway.
+pub(crate) async fn switch_worktree_branch_inner(
+    worktree_path: &str,
+    new_branch: &str,
+) -> Result<String, String> {
+    if new_branch.is_empty() {
+        return Err("Branch name must not be empty".to_string());
+    }
+
+    let git = Git::new(worktree_path);
+    let branches = git.list_branches().await.map_err(|e| e.to_string())?;
+    let local_branch = resolve_local_branch_name(new_branch, &branches);
+
+    let target = canonicalize_or_raw(Path::new(worktree_path)).await;
+    let worktrees = git.worktree_list().await.map_err(|e| e.to_string())?;
+    for wt in worktrees {
+        if wt.branch.as_deref() != Some(local_branch.as_str()) {
+            continue;
+        }
+        if canonicalize_or_raw(Path::new(&wt.path)).await == target {
+            // Already on the requested branch
+            return Ok(local_branch);
+        }
+        return Err(GitError::BranchAlreadyCheckedOut {
+            branch: local_branch,
+            path: wt.path,
+        }
+        .to_string());
+    }
+
+    ensure_local_branch(&git, new_branch, &local_branch, &branches).await?;
+    git.run(&["checkout", &local_branch, "--"])
+        .await
+        .map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Switched worktree {} to branch {}",
+        worktree_path,
+        local_branch
+    );
+    Ok(local_branch)
+}
+
+/// Canonicalizes a path, falling back to the raw path if it does not exist.
+async fn canonicalize_or_raw(path: &Path) -> PathBuf {
+    tokio::fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
 /// Cleans up a worktree when a session ends.
 ///
 /// Removes the worktree from the filesystem and prunes git refs.
@@ -225,11 +441,59 @@ pub(crate) async fn cleanup_worktree_inner(
     }
 }
 
+/// Reports the disk space used by each Maestro-managed worktree of a project.
+#[tauri::command]
+pub async fn get_worktree_disk_usage(
+    worktree_manager: State<'_, WorktreeManager>,
+    project_path: String,
+) -> Result<Vec<WorktreeUsage>, String> {
+    worktree_manager
+        .disk_usage(Path::new(&project_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Prunes stale worktree refs and orphaned worktree directories of a project.
+///
+/// Returns the number of bytes freed.
+#[tauri::command]
+pub async fn prune_worktrees(
+    worktree_manager: State<'_, WorktreeManager>,
+    project_path: String,
+) -> Result<u64, String> {
+    worktree_manager
+        .prune(Path::new(&project_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Flags managed worktrees of a project whose branch was deleted, whose
+/// directory is missing, or that are stuck behind a stale lock, each with a
+/// suggested remedy.
+#[tauri::command]
+pub async fn audit_worktrees(
+    worktree_manager: State<'_, WorktreeManager>,
+    project_path: String,
+) -> Result<Vec<WorktreeIssue>, String> {
+    worktree_manager
+        .audit(Path::new(&project_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Gets a fallback branch to switch to when the target branch is checked out.
 ///
-/// Tries init.defaultBranch config, then looks for main/master.
+/// Tries the branch `origin/HEAD` points at, then init.defaultBranch config,
+/// then looks for main/master.
 /// Returns None if no suitable fallback branch exists (e.g., single-branch repo).
 pub(crate) async fn get_fallback_branch(git: &Git, avoid_branch: &str) -> Option<String> {
+    // Prefer the remote's actual default branch
+    if let Ok(Some(default)) = git.remote_default_branch("origin").await {
+        if default != avoid_branch {
+            return Some(default);
+        }
+    }
+
     // Try configured default branch
     if let Ok(Some(default)) = git.get_default_branch().await {
         if default != avoid_branch {
@@ -369,6 +633,8 @@ mod tests {
             name: name.to_string(),
             is_remote: false,
             is_current: false,
+            upstream: None,
+            has_worktree: false,
         }
     }
 
@@ -413,9 +679,14 @@ mod tests {
     async fn test_prepare_no_branch_returns_project_path() {
         let (_dir, path) = create_test_repo().await;
         let wm = WorktreeManager::new();
-        let result = prepare_worktree_inner(&wm, path.to_string_lossy().to_string(), None)
-            .await
-            .unwrap();
+        let result = prepare_worktree_inner(
+            &wm,
+            path.to_string_lossy().to_string(),
+            None,
+            &CheckoutOptions::default(),
+        )
+        .await
+        .unwrap();
 
         assert_eq!(result.working_directory, path.to_string_lossy().to_string());
         assert!(result.worktree_path.is_none());
@@ -431,6 +702,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("".to_string()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -455,6 +727,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some(current.clone()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -495,6 +768,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some(current.clone()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -510,6 +784,38 @@ mod tests {
         let _ = wm.remove(&path, &wt_path).await;
     }
 
+    #[tokio::test]
+    async fn test_prepare_leaves_detached_main_repo_alone() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        git.detach_head().await.unwrap();
+
+        // A branch whose name equals the detached short hash used to be
+        // mistaken for the main repo's current branch.
+        let short = git.current_branch().await.unwrap();
+        create_branch(&git, &short).await;
+        let head_before = git.head_state().await.unwrap();
+        assert!(matches!(head_before, HeadState::Detached(_)));
+
+        let wm = WorktreeManager::new();
+        let result = prepare_worktree_inner(
+            &wm,
+            path.to_string_lossy().to_string(),
+            Some(short.clone()),
+            &CheckoutOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert!(result.created);
+        assert!(result.warning.is_none());
+        assert_eq!(git.head_state().await.unwrap(), head_before);
+
+        // Cleanup
+        let wt_path = PathBuf::from(result.worktree_path.unwrap());
+        let _ = wm.remove(&path, &wt_path).await;
+    }
+
     #[tokio::test]
     async fn test_prepare_different_branch_creates_worktree() {
         let (_dir, path) = create_test_repo().await;
@@ -522,6 +828,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("feature-test".to_string()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -552,6 +859,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("reuse-test".to_string()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -562,6 +870,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("reuse-test".to_string()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -582,6 +891,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("brand-new-branch".to_string()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -609,6 +919,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("main".to_string()),
+            &CheckoutOptions::default(),
         )
         .await
         .unwrap();
@@ -620,6 +931,57 @@ mod tests {
         assert!(result.warning.is_some());
     }
 
+    #[tokio::test]
+    async fn test_prepare_at_ref_detaches_at_initial_commit() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        let initial = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        let initial = initial.trimmed().to_string();
+
+        tokio::fs::write(path.join("CHANGELOG.md"), "v2")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "second"]).await.unwrap();
+
+        let wm = WorktreeManager::new();
+        let result = prepare_worktree_at_ref_inner(
+            &wm,
+            path.to_string_lossy().to_string(),
+            initial[..8].to_string(),
+        )
+        .await
+        .unwrap();
+        assert!(result.created);
+        let wt_path = PathBuf::from(result.worktree_path.clone().unwrap());
+
+        let wt_git = Git::new(&wt_path);
+        let head = wt_git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        assert_eq!(head.trimmed(), initial);
+        assert!(
+            wt_git.run(&["symbolic-ref", "-q", "HEAD"]).await.is_err(),
+            "HEAD should be detached"
+        );
+
+        // Same commit again reuses the worktree
+        let again =
+            prepare_worktree_at_ref_inner(&wm, path.to_string_lossy().to_string(), initial.clone())
+                .await
+                .unwrap();
+        assert!(!again.created);
+        assert_eq!(again.worktree_path, result.worktree_path);
+
+        let missing = prepare_worktree_at_ref_inner(
+            &wm,
+            path.to_string_lossy().to_string(),
+            "no-such-tag".to_string(),
+        )
+        .await;
+        assert!(missing.unwrap_err().contains("no-such-tag"));
+
+        let _ = wm.remove(&path, &wt_path).await;
+    }
+
     #[tokio::test]
     async fn test_get_fallback_branch_avoids_target() {
         let (_dir, path) = create_test_repo().await;
@@ -644,6 +1006,136 @@ mod tests {
         assert!(fallback.is_none(), "Single-branch repo should have no fallback");
     }
 
+    #[tokio::test]
+    async fn test_prepare_existing_worktree_attaches_without_touching_main() {
+        let (dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        create_branch(&git, "out-of-band").await;
+
+        let main_branch = git.current_branch().await.unwrap();
+        let main_head = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+
+        // Create the worktree out-of-band, bypassing WorktreeManager
+        let wt_path = dir.path().join("oob-wt");
+        git.run(&["worktree", "add", &wt_path.to_string_lossy(), "out-of-band"])
+            .await
+            .unwrap();
+
+        let result = prepare_worktree_for_existing_path(
+            path.to_string_lossy().to_string(),
+            wt_path.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert!(!result.created);
+        assert!(result.warning.is_none());
+        assert!(result.worktree_path.is_some());
+        assert_eq!(
+            std::fs::canonicalize(&result.working_directory).unwrap(),
+            std::fs::canonicalize(&wt_path).unwrap()
+        );
+
+        // Main repo HEAD must be untouched
+        assert_eq!(git.current_branch().await.unwrap(), main_branch);
+        assert_eq!(
+            git.run(&["rev-parse", "HEAD"]).await.unwrap().trimmed(),
+            main_head.trimmed()
+        );
+
+        // Cleanup
+        let _ = git.worktree_remove(&wt_path, true).await;
+    }
+
+    #[tokio::test]
+    async fn test_prepare_existing_worktree_rejects_unregistered_path() {
+        let (_dir, path) = create_test_repo().await;
+        let other = tempdir().unwrap();
+
+        let result = prepare_worktree_for_existing_path(
+            path.to_string_lossy().to_string(),
+            other.path().to_string_lossy().to_string(),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_switch_worktree_branch_creates_branch_inside_worktree() {
+        let (dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        create_branch(&git, "wrong-branch").await;
+
+        let main_branch = git.current_branch().await.unwrap();
+        let main_head = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+
+        let wt_path = dir.path().join("switch-wt");
+        let wt_str = wt_path.to_string_lossy().to_string();
+        git.run(&["worktree", "add", &wt_str, "wrong-branch"])
+            .await
+            .unwrap();
+
+        let switched = switch_worktree_branch_inner(&wt_str, "right-branch")
+            .await
+            .unwrap();
+        assert_eq!(switched, "right-branch");
+
+        // The worktree is on the new branch, in the same directory
+        let wt_git = Git::new(&wt_path);
+        assert_eq!(wt_git.current_branch().await.unwrap(), "right-branch");
+        assert!(check_branch_exists(&git, "right-branch").await);
+
+        // Main repo HEAD must be untouched
+        assert_eq!(git.current_branch().await.unwrap(), main_branch);
+        assert_eq!(
+            git.run(&["rev-parse", "HEAD"]).await.unwrap().trimmed(),
+            main_head.trimmed()
+        );
+
+        // The branch it left is free again
+        let worktrees = git.worktree_list().await.unwrap();
+        assert!(!worktrees
+            .iter()
+            .any(|wt| wt.branch.as_deref() == Some("wrong-branch")));
+
+        let _ = git.worktree_remove(&wt_path, true).await;
+    }
+
+    #[tokio::test]
+    async fn test_switch_worktree_branch_rejects_branch_checked_out_elsewhere() {
+        let (dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        create_branch(&git, "feature").await;
+        let main_branch = git.current_branch().await.unwrap();
+
+        let wt_path = dir.path().join("busy-wt");
+        let wt_str = wt_path.to_string_lossy().to_string();
+        git.run(&["worktree", "add", &wt_str, "feature"])
+            .await
+            .unwrap();
+
+        let err = switch_worktree_branch_inner(&wt_str, &main_branch)
+            .await
+            .unwrap_err();
+        assert!(
+            err.contains("already checked out"),
+            "unexpected error: {err}"
+        );
+        assert_eq!(
+            Git::new(&wt_path).current_branch().await.unwrap(),
+            "feature"
+        );
+
+        // Switching to the branch it is already on is a no-op
+        let same = switch_worktree_branch_inner(&wt_str, "feature")
+            .await
+            .unwrap();
+        assert_eq!(same, "feature");
+
+        let _ = git.worktree_remove(&wt_path, true).await;
+    }
+
     #[tokio::test]
     async fn test_cleanup_empty_path_is_noop() {
         let wm = WorktreeManager::new();
@@ -0,0 +1,177 @@
+//! Minimal `.env` parsing for seeding a session's environment.
+//!
+//! Supports the common subset of the format: `KEY=value` lines, an optional
+//! `export ` prefix, `#` comments, single-quoted (literal) values, and
+//! double-quoted values with `\n`, `\t`, `\"` and `\\` escapes that may span
+//! several lines. Variable interpolation (`${OTHER}`) is not performed.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Parses `.env` contents into `(key, value)` pairs in file order.
+///
+/// Lines that aren't assignments or whose key isn't a valid variable name
+/// are skipped with a warning rather than failing the whole file.
+pub fn parse_dotenv(contents: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    let mut lines = contents.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, raw)) = line.split_once('=') else {
+            log::warn!(".env line {}: expected KEY=value", index + 1);
+            continue;
+        };
+        let key = key.trim();
+        if !is_valid_key(key) {
+            log::warn!(".env line {}: invalid variable name '{}'", index + 1, key);
+            continue;
+        }
+
+        let raw = raw.trim_start();
+        let value = if let Some(rest) = raw.strip_prefix('"') {
+            // Double-quoted values continue onto following lines until closed
+            let mut quoted = rest.to_string();
+            loop {
+                if let Some(value) = unescape_double_quoted(&quoted) {
+                    break Some(value);
+                }
+                match lines.next() {
+                    Some((_, next)) => {
+                        quoted.push('\n');
+                        quoted.push_str(next);
+                    }
+                    None => break None,
+                }
+            }
+        } else if let Some(rest) = raw.strip_prefix('\'') {
+            rest.find('\'').map(|end| rest[..end].to_string())
+        } else {
+            // Unquoted: a `#` preceded by whitespace starts a comment
+            let end = raw
+                .find(" #")
+                .or_else(|| raw.find("\t#"))
+                .unwrap_or(raw.len());
+            Some(raw[..end].trim_end().to_string())
+        };
+
+        match value {
+            Some(value) => vars.push((key.to_string(), value)),
+            None => log::warn!(".env line {}: unterminated quote for '{}'", index + 1, key),
+        }
+    }
+
+    vars
+}
+
+/// Returns the value of a double-quoted string whose opening quote has been
+/// stripped, or `None` if the closing quote hasn't been reached yet.
+fn unescape_double_quoted(quoted: &str) -> Option<String> {
+    let mut value = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(value),
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some(other @ ('"' | '\\')) => value.push(other),
+                Some(other) => {
+                    value.push('\\');
+                    value.push(other);
+                }
+                None => return None,
+            },
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Builds a session's spawn env from a parsed `.env` file and the caller's
+/// explicit variables.
+///
+/// Explicit variables always win. File values are dropped for any variable
+/// for which `is_set` returns true (the app's own process env), so a `.env`
+/// can't silently shadow what the user's environment already provides.
+pub fn merge_dotenv(
+    file_vars: Vec<(String, String)>,
+    explicit: Option<HashMap<String, String>>,
+    is_set: impl Fn(&str) -> bool,
+) -> HashMap<String, String> {
+    let mut env: HashMap<String, String> = file_vars
+        .into_iter()
+        .filter(|(key, _)| !is_set(key))
+        .collect();
+    env.extend(explicit.unwrap_or_default());
+    env
+}
+
+/// Reads and parses the `.env` file at `path`.
+pub fn load_dotenv(path: &Path) -> std::io::Result<Vec<(String, String)>> {
+    Ok(parse_dotenv(&std::fs::read_to_string(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotenv_handles_quotes_comments_and_export() {
+        let contents = r#"
+# Database settings
+DB_HOST=localhost
+export DB_PORT=5432
+DB_NAME = app_dev # trailing comment
+PASSWORD="p@ss #not a comment"
+GREETING="line one\nline \"two\""
+LITERAL='$HOME\n stays'
+URL=https://example.com/#anchor
+EMPTY=
+MULTI="first
+second"
+not a variable line
+1BAD=skipped
+UNTERMINATED='oops
+"#;
+
+        let vars: HashMap<String, String> = parse_dotenv(contents).into_iter().collect();
+        let expected: HashMap<String, String> = [
+            ("DB_HOST", "localhost"),
+            ("DB_PORT", "5432"),
+            ("DB_NAME", "app_dev"),
+            ("PASSWORD", "p@ss #not a comment"),
+            ("GREETING", "line one\nline \"two\""),
+            ("LITERAL", "$HOME\\n stays"),
+            ("URL", "https://example.com/#anchor"),
+            ("EMPTY", ""),
+            ("MULTI", "first\nsecond"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        assert_eq!(vars, expected);
+    }
+
+    #[test]
+    fn test_merge_dotenv_precedence() {
+        let file_vars = parse_dotenv("A=file\nB=file\nHOME=/from/dotenv\n");
+        let explicit = HashMap::from([("B".to_string(), "explicit".to_string())]);
+
+        let env = merge_dotenv(file_vars, Some(explicit), |key| key == "HOME");
+        assert_eq!(env.get("A").map(String::as_str), Some("file"));
+        assert_eq!(env.get("B").map(String::as_str), Some("explicit"));
+        assert!(!env.contains_key("HOME"));
+    }
+}
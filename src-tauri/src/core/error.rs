@@ -11,6 +11,8 @@ pub enum PtyErrorCode {
     ResizeFailed,
     KillFailed,
     IdOverflow,
+    RecordFailed,
+    RestartFailed,
 }
 
 /// Structured PTY error with a machine-readable code and human-readable message.
@@ -80,4 +82,21 @@ impl PtyError {
             message: "Session ID counter overflowed u32::MAX".to_string(),
         }
     }
+
+    /// Session recording could not be started or its file written.
+    pub fn record_failed(msg: impl Into<String>) -> Self {
+        Self {
+            code: PtyErrorCode::RecordFailed,
+            message: msg.into(),
+        }
+    }
+
+    /// A restarted session's old shell is gone but the new one failed to
+    /// spawn, so the session no longer exists.
+    pub fn restart_failed(id: u32, msg: impl Into<String>) -> Self {
+        Self {
+            code: PtyErrorCode::RestartFailed,
+            message: format!("Session {} could not be restarted: {}", id, msg.into()),
+        }
+    }
 }
@@ -29,6 +29,8 @@ pub enum MarketplaceError {
     NetworkError(String),
     /// Store error.
     StoreError(String),
+    /// Operation was cancelled by the user.
+    Cancelled,
 }
 
 impl fmt::Display for MarketplaceError {
@@ -46,6 +48,7 @@ impl fmt::Display for MarketplaceError {
             Self::SerdeError(e) => write!(f, "Serialization error: {e}"),
             Self::NetworkError(msg) => write!(f, "Network error: {msg}"),
             Self::StoreError(msg) => write!(f, "Store error: {msg}"),
+            Self::Cancelled => write!(f, "Operation cancelled"),
         }
     }
 }
@@ -9,9 +9,12 @@
 
 use dashmap::DashMap;
 use directories::BaseDirs;
+use std::collections::HashSet;
+use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 use tokio::process::Command;
+use tokio::sync::watch;
 
 use super::marketplace_error::{MarketplaceError, MarketplaceResult};
 use super::marketplace_models::*;
@@ -22,6 +25,34 @@ const OFFICIAL_MARKETPLACE_NAME: &str = "Claude Code Official";
 const OFFICIAL_MARKETPLACE_URL: &str = "https://github.com/anthropics/claude-code";
 const OFFICIAL_MARKETPLACE_ID: &str = "official-anthropic-claude-code";
 
+/// Event emitted with a [`MarketplaceRefreshProgress`] as each source is refreshed.
+pub const REFRESH_PROGRESS_EVENT: &str = "marketplace-refresh-progress";
+/// Event emitted once a refresh of all sources has finished.
+pub const REFRESH_DONE_EVENT: &str = "marketplace-refresh-done";
+
+/// Store key holding the persisted `MarketplaceData::sources`.
+const STORE_SOURCES_KEY: &str = "sources";
+/// Store key holding the persisted `MarketplaceData::installed_plugins`.
+const STORE_INSTALLED_PLUGINS_KEY: &str = "installed_plugins";
+
+/// Cancellation signal for one in-flight install, fired by
+/// [`MarketplaceManager::cancel_install`].
+struct InstallCancel(watch::Receiver<bool>);
+
+impl InstallCancel {
+    fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the install is cancelled; never resolves otherwise.
+    async fn cancelled(&self) {
+        let mut rx = self.0.clone();
+        if rx.wait_for(|cancelled| *cancelled).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
 /// Session key for per-session configuration: (project_path, session_id).
 type SessionKey = (String, u32);
 
@@ -37,6 +68,8 @@ pub struct MarketplaceManager {
     installed_plugins: RwLock<Vec<InstalledPlugin>>,
     /// Per-session marketplace configuration.
     session_configs: DashMap<SessionKey, SessionMarketplaceConfig>,
+    /// Cancellation senders for installs in progress, keyed by operation ID.
+    in_flight_installs: DashMap<String, watch::Sender<bool>>,
 }
 
 impl MarketplaceManager {
@@ -50,6 +83,7 @@ impl MarketplaceManager {
             is_enabled: true,
             last_fetched: None,
             last_error: None,
+            priority: 0,
         };
 
         Self {
@@ -57,6 +91,7 @@ impl MarketplaceManager {
             available_plugins: DashMap::new(),
             installed_plugins: RwLock::new(Vec::new()),
             session_configs: DashMap::new(),
+            in_flight_installs: DashMap::new(),
         }
     }
 
@@ -116,7 +151,10 @@ impl MarketplaceManager {
     }
 
     /// Adds a new marketplace source.
+    ///
+    /// New sources get the lowest precedence.
     pub fn add_source(&self, name: String, repository_url: String, is_official: bool) -> MarketplaceSource {
+        let mut sources = self.sources.write().unwrap();
         let source = MarketplaceSource {
             id: Self::generate_source_id(),
             name,
@@ -125,12 +163,47 @@ impl MarketplaceManager {
             is_enabled: true,
             last_fetched: None,
             last_error: None,
+            priority: sources.len() as u32,
         };
 
-        self.sources.write().unwrap().push(source.clone());
+        sources.push(source.clone());
         source
     }
 
+    /// Reorders sources to control which marketplace wins on name conflicts.
+    ///
+    /// `ordered_ids` lists source IDs from highest to lowest precedence.
+    /// Sources not mentioned keep their relative order after the listed ones.
+    /// Fails without changing anything if an ID is unknown.
+    pub fn reorder_sources(&self, ordered_ids: Vec<String>) -> MarketplaceResult<()> {
+        let mut sources = self.sources.write().unwrap();
+
+        if let Some(unknown) = ordered_ids
+            .iter()
+            .find(|id| !sources.iter().any(|s| &s.id == *id))
+        {
+            return Err(MarketplaceError::SourceNotFound(unknown.clone()));
+        }
+
+        let rank = |id: &str| {
+            ordered_ids
+                .iter()
+                .position(|o| o == id)
+                .unwrap_or(ordered_ids.len())
+        };
+        sources.sort_by_key(|s| rank(&s.id));
+        Self::renumber_priorities(&mut sources);
+
+        Ok(())
+    }
+
+    /// Sets each source's `priority` to its position in the list.
+    fn renumber_priorities(sources: &mut [MarketplaceSource]) {
+        for (i, source) in sources.iter_mut().enumerate() {
+            source.priority = i as u32;
+        }
+    }
+
     /// Removes a marketplace source by ID.
     pub fn remove_source(&self, source_id: &str) -> MarketplaceResult<()> {
         let mut sources = self.sources.write().unwrap();
@@ -140,6 +213,7 @@ impl MarketplaceManager {
         if sources.len() == initial_len {
             return Err(MarketplaceError::SourceNotFound(source_id.to_string()));
         }
+        Self::renumber_priorities(&mut sources);
 
         // Also remove cached plugins for this source
         self.available_plugins.remove(source_id);
@@ -245,36 +319,44 @@ impl MarketplaceManager {
         }
     }
 
-    /// Refreshes all enabled marketplace sources.
-    pub async fn refresh_all_marketplaces(&self) -> Vec<(String, MarketplaceResult<Vec<MarketplacePlugin>>)> {
-        let sources = self.get_sources();
-        let enabled_sources: Vec<_> = sources.into_iter()
+    /// Refreshes all enabled marketplace sources, reporting progress through
+    /// `emit`: a [`REFRESH_PROGRESS_EVENT`] per source as it completes, then a
+    /// [`REFRESH_DONE_EVENT`].
+    pub async fn refresh_all_marketplaces(
+        &self,
+        emit: impl Fn(&str, serde_json::Value),
+    ) -> Vec<(String, MarketplaceResult<Vec<MarketplacePlugin>>)> {
+        let enabled_sources: Vec<_> = self
+            .get_sources()
+            .into_iter()
             .filter(|s| s.is_enabled)
             .collect();
 
-        let mut results = Vec::new();
-
-        for source in enabled_sources {
-            let result = self.fetch_marketplace(&source.id).await;
-            results.push((source.id, result));
-        }
-
-        results
+        refresh_sources(
+            enabled_sources,
+            |id| async move { self.fetch_marketplace(&id).await },
+            emit,
+        )
+        .await
     }
 
     /// Gets all available plugins from enabled marketplaces.
+    ///
+    /// Sources are visited in priority order; when several offer a plugin
+    /// with the same name, only the highest-priority one is returned.
     pub fn get_available_plugins(&self) -> Vec<MarketplacePlugin> {
         let sources = self.get_sources();
-        let enabled_ids: Vec<_> = sources.iter()
-            .filter(|s| s.is_enabled)
-            .map(|s| &s.id)
-            .collect();
-
+        let mut seen_names = HashSet::new();
         let mut all_plugins = Vec::new();
 
-        for entry in self.available_plugins.iter() {
-            if enabled_ids.contains(&entry.key()) {
-                all_plugins.extend(entry.value().clone());
+        for source in sources.iter().filter(|s| s.is_enabled) {
+            let Some(plugins) = self.available_plugins.get(&source.id) else {
+                continue;
+            };
+            for plugin in plugins.iter() {
+                if seen_names.insert(plugin.name.clone()) {
+                    all_plugins.push(plugin.clone());
+                }
             }
         }
 
@@ -306,35 +388,88 @@ impl MarketplaceManager {
     /// Clones a repository using git.
     ///
     /// If `source_path` is provided, uses sparse checkout to clone only the
-    /// specified subdirectory (for monorepo plugins).
+    /// specified subdirectory (for monorepo plugins). When `cancel` fires, the
+    /// running git process is killed and any partially cloned directories are
+    /// removed before returning [`MarketplaceError::Cancelled`].
     async fn clone_repository(
         repo_url: &str,
         target_dir: &Path,
         source_path: Option<&str>,
+        cancel: &InstallCancel,
     ) -> MarketplaceResult<()> {
         // Ensure parent directory exists
         if let Some(parent) = target_dir.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        if let Some(subpath) = source_path {
+        let result = if let Some(subpath) = source_path {
             // Sparse checkout for subdirectory within a monorepo
-            Self::clone_sparse(repo_url, target_dir, subpath).await
+            Self::clone_sparse(repo_url, target_dir, subpath, cancel).await
         } else {
             // Simple shallow clone for standalone repos
-            Self::clone_shallow(repo_url, target_dir).await
+            Self::clone_shallow(repo_url, target_dir, cancel).await
+        };
+
+        if matches!(result, Err(MarketplaceError::Cancelled)) {
+            for dir in [target_dir.to_path_buf(), Self::sparse_temp_dir(target_dir)] {
+                if dir.exists() {
+                    let _ = tokio::fs::remove_dir_all(&dir).await;
+                }
+            }
+        }
+        result
+    }
+
+    /// Runs a git command to completion, or kills it once `cancel` fires.
+    ///
+    /// On Unix git runs in its own process group so that helpers it spawns
+    /// (`git-remote-https`, `index-pack`) are killed along with it.
+    async fn run_git(
+        cmd: &mut Command,
+        cancel: &InstallCancel,
+        action: &str,
+    ) -> MarketplaceResult<std::process::Output> {
+        cmd.stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let child = cmd.spawn().map_err(|e| {
+            MarketplaceError::CloneError(format!("Failed to run {}: {}", action, e))
+        })?;
+        #[cfg(unix)]
+        let pgid = child.id();
+
+        tokio::select! {
+            output = child.wait_with_output() => output
+                .map_err(|e| MarketplaceError::CloneError(format!("Failed to run {}: {}", action, e))),
+            _ = cancel.cancelled() => {
+                #[cfg(unix)]
+                if let Some(pgid) = pgid {
+                    // SAFETY: killpg has no memory-safety preconditions
+                    unsafe { libc::killpg(pgid as i32, libc::SIGKILL) };
+                }
+                Err(MarketplaceError::Cancelled)
+            }
         }
     }
 
     /// Performs a shallow clone of the entire repository.
-    async fn clone_shallow(repo_url: &str, target_dir: &Path) -> MarketplaceResult<()> {
-        let output = Command::new("git")
-            .args(["clone", "--depth", "1", repo_url])
-            .arg(target_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| MarketplaceError::CloneError(format!("Failed to run git: {}", e)))?;
+    async fn clone_shallow(
+        repo_url: &str,
+        target_dir: &Path,
+        cancel: &InstallCancel,
+    ) -> MarketplaceResult<()> {
+        let output = Self::run_git(
+            Command::new("git")
+                .args(["clone", "--depth", "1", repo_url])
+                .arg(target_dir)
+                .hide_console_window(),
+            cancel,
+            "git",
+        )
+        .await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -344,16 +479,26 @@ impl MarketplaceManager {
         Ok(())
     }
 
+    /// Scratch directory a sparse checkout of `target_dir` is cloned into.
+    fn sparse_temp_dir(target_dir: &Path) -> PathBuf {
+        target_dir.with_file_name(format!(
+            ".{}-sparse-temp",
+            target_dir.file_name().unwrap_or_default().to_string_lossy()
+        ))
+    }
+
     /// Performs a sparse checkout to clone only a specific subdirectory.
     ///
     /// This is used for plugins that are subdirectories within a larger monorepo
     /// (e.g., anthropics/claude-code/plugins/frontend-design).
-    async fn clone_sparse(repo_url: &str, target_dir: &Path, subpath: &str) -> MarketplaceResult<()> {
+    async fn clone_sparse(
+        repo_url: &str,
+        target_dir: &Path,
+        subpath: &str,
+        cancel: &InstallCancel,
+    ) -> MarketplaceResult<()> {
         // Create a temporary directory for the sparse checkout
-        let temp_dir = target_dir.with_file_name(format!(
-            ".{}-sparse-temp",
-            target_dir.file_name().unwrap_or_default().to_string_lossy()
-        ));
+        let temp_dir = Self::sparse_temp_dir(target_dir);
 
         // Clean up any existing temp directory
         if temp_dir.exists() {
@@ -361,20 +506,22 @@ impl MarketplaceManager {
         }
 
         // Step 1: Clone with no checkout and blob filter for efficiency
-        let output = Command::new("git")
-            .args([
-                "clone",
-                "--filter=blob:none",
-                "--no-checkout",
-                "--depth",
-                "1",
-                repo_url,
-            ])
-            .arg(&temp_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| MarketplaceError::CloneError(format!("Failed to run git clone: {}", e)))?;
+        let output = Self::run_git(
+            Command::new("git")
+                .args([
+                    "clone",
+                    "--filter=blob:none",
+                    "--no-checkout",
+                    "--depth",
+                    "1",
+                    repo_url,
+                ])
+                .arg(&temp_dir)
+                .hide_console_window(),
+            cancel,
+            "git clone",
+        )
+        .await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -386,15 +533,15 @@ impl MarketplaceManager {
         }
 
         // Step 2: Set up sparse checkout for the specific subdirectory
-        let output = Command::new("git")
-            .args(["sparse-checkout", "set", "--no-cone", subpath])
-            .current_dir(&temp_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| {
-                MarketplaceError::CloneError(format!("Failed to run git sparse-checkout: {}", e))
-            })?;
+        let output = Self::run_git(
+            Command::new("git")
+                .args(["sparse-checkout", "set", "--no-cone", subpath])
+                .current_dir(&temp_dir)
+                .hide_console_window(),
+            cancel,
+            "git sparse-checkout",
+        )
+        .await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -406,13 +553,15 @@ impl MarketplaceManager {
         }
 
         // Step 3: Checkout the files
-        let output = Command::new("git")
-            .args(["checkout"])
-            .current_dir(&temp_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| MarketplaceError::CloneError(format!("Failed to run git checkout: {}", e)))?;
+        let output = Self::run_git(
+            Command::new("git")
+                .args(["checkout"])
+                .current_dir(&temp_dir)
+                .hide_console_window(),
+            cancel,
+            "git checkout",
+        )
+        .await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -529,12 +678,57 @@ impl MarketplaceManager {
         (skills, commands, mcp_servers, agents, hooks)
     }
 
+    /// Generates an ID for tracking an install until it finishes.
+    pub fn new_operation_id() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    /// Cancels the in-flight install tracked as `operation_id`.
+    ///
+    /// Returns `false` if no such install is running (it may have finished).
+    pub fn cancel_install(&self, operation_id: &str) -> bool {
+        match self.in_flight_installs.get(operation_id) {
+            Some(sender) => {
+                sender.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Installs a plugin from a marketplace.
+    ///
+    /// The install is tracked as `operation_id` until it returns, so
+    /// [`cancel_install`](Self::cancel_install) can abort the clone; a
+    /// cancelled install leaves nothing behind and fails with
+    /// [`MarketplaceError::Cancelled`].
     pub async fn install_plugin(
         &self,
         marketplace_plugin_id: &str,
         scope: InstallScope,
         project_path: Option<&str>,
+        operation_id: &str,
+    ) -> MarketplaceResult<InstalledPlugin> {
+        let (sender, receiver) = watch::channel(false);
+        self.in_flight_installs.insert(operation_id.to_string(), sender);
+        let result = self
+            .install_plugin_with_cancel(
+                marketplace_plugin_id,
+                scope,
+                project_path,
+                &InstallCancel(receiver),
+            )
+            .await;
+        self.in_flight_installs.remove(operation_id);
+        result
+    }
+
+    async fn install_plugin_with_cancel(
+        &self,
+        marketplace_plugin_id: &str,
+        scope: InstallScope,
+        project_path: Option<&str>,
+        cancel: &InstallCancel,
     ) -> MarketplaceResult<InstalledPlugin> {
         // Find the plugin in available plugins
         let plugin = self.get_available_plugins()
@@ -567,7 +761,13 @@ impl MarketplaceManager {
         let plugin_dir = install_base.join(&plugin_dir_name);
 
         // Clone the repository (with sparse checkout for monorepo plugins)
-        Self::clone_repository(repo_url, &plugin_dir, plugin.source_path.as_deref()).await?;
+        Self::clone_repository(repo_url, &plugin_dir, plugin.source_path.as_deref(), cancel)
+            .await?;
+        // Cancelled just as the clone finished: still honour it
+        if cancel.is_cancelled() {
+            let _ = tokio::fs::remove_dir_all(&plugin_dir).await;
+            return Err(MarketplaceError::Cancelled);
+        }
 
         // Create plugin manifest directory
         let manifest_dir = plugin_dir.join(".claude-plugin");
@@ -642,6 +842,77 @@ impl MarketplaceManager {
         self.installed_plugins.read().unwrap().clone()
     }
 
+    /// Lists installed marketplace plugins whose catalog entry has a newer version.
+    ///
+    /// Only plugins from marketplaces that have been fetched this session can
+    /// be checked; the rest are skipped. Comparison is by [`is_newer_version`].
+    pub fn check_updates(&self) -> Vec<PluginUpdate> {
+        let installed = self.installed_plugins.read().unwrap();
+
+        installed
+            .iter()
+            .filter_map(|p| {
+                let InstalledPluginSource::Marketplace {
+                    marketplace_id,
+                    plugin_id,
+                } = &p.source
+                else {
+                    return None;
+                };
+                let catalog = self.available_plugins.get(marketplace_id)?;
+                let latest = catalog.iter().find(|m| &m.id == plugin_id)?;
+
+                is_newer_version(&p.version, &latest.version).then(|| PluginUpdate {
+                    installed_id: p.id.clone(),
+                    current_version: p.version.clone(),
+                    latest_version: latest.version.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Checks an installed plugin's marketplace source and catalog entry
+    /// against the current sources, so removed or edited marketplaces show up.
+    ///
+    /// The catalog is only known for marketplaces fetched this session; for
+    /// the rest `catalog_entry_present` is `false`.
+    pub fn plugin_provenance(&self, installed_plugin_id: &str) -> MarketplaceResult<Provenance> {
+        let installed = self.installed_plugins.read().unwrap();
+        let plugin = installed
+            .iter()
+            .find(|p| p.id == installed_plugin_id)
+            .ok_or_else(|| MarketplaceError::NotInstalled(installed_plugin_id.to_string()))?;
+
+        let InstalledPluginSource::Marketplace {
+            marketplace_id,
+            plugin_id,
+        } = &plugin.source
+        else {
+            return Ok(Provenance {
+                source_present: false,
+                source_name: None,
+                catalog_entry_present: false,
+                version_drift: None,
+            });
+        };
+
+        let source = self.get_source(marketplace_id);
+        let latest_version = source.as_ref().and_then(|_| {
+            let catalog = self.available_plugins.get(marketplace_id)?;
+            let entry = catalog.iter().find(|m| &m.id == plugin_id)?;
+            Some(entry.version.clone())
+        });
+
+        Ok(Provenance {
+            source_present: source.is_some(),
+            source_name: source.map(|s| s.name),
+            catalog_entry_present: latest_version.is_some(),
+            version_drift: latest_version
+                .filter(|latest| *latest != plugin.version)
+                .map(|latest| (plugin.version.clone(), latest)),
+        })
+    }
+
     /// Checks if a marketplace plugin is installed.
     pub fn is_plugin_installed(&self, marketplace_plugin_id: &str) -> bool {
         self.installed_plugins.read().unwrap()
@@ -697,21 +968,90 @@ impl MarketplaceManager {
     /// Loads marketplace data from a JSON string.
     pub fn load_from_json(&self, json: &str) -> MarketplaceResult<()> {
         let data: MarketplaceData = serde_json::from_str(json)?;
-
-        *self.sources.write().unwrap() = data.sources;
-        *self.installed_plugins.write().unwrap() = data.installed_plugins;
-
+        self.load_data(data);
         Ok(())
     }
 
     /// Exports marketplace data to a JSON string.
     pub fn export_to_json(&self) -> MarketplaceResult<String> {
-        let data = MarketplaceData {
+        Ok(serde_json::to_string_pretty(&self.export_data())?)
+    }
+
+    /// Exports marketplace data in the form persisted to the store: one
+    /// entry per `MarketplaceData` field.
+    pub fn to_store_entries(&self) -> MarketplaceResult<Vec<(&'static str, serde_json::Value)>> {
+        let data = self.export_data();
+        Ok(vec![
+            (STORE_SOURCES_KEY, serde_json::to_value(data.sources)?),
+            (
+                STORE_INSTALLED_PLUGINS_KEY,
+                serde_json::to_value(data.installed_plugins)?,
+            ),
+        ])
+    }
+
+    /// Loads marketplace data from store entries looked up with `get`.
+    ///
+    /// A missing or unreadable entry is treated as empty, so one corrupt key
+    /// doesn't discard the other.
+    pub fn load_store_entries(&self, get: impl Fn(&str) -> Option<serde_json::Value>) {
+        fn field<T: serde::de::DeserializeOwned + Default>(
+            value: Option<serde_json::Value>,
+            key: &str,
+        ) -> T {
+            let Some(value) = value else {
+                return T::default();
+            };
+            serde_json::from_value(value).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable marketplace store entry {}: {}", key, e);
+                T::default()
+            })
+        }
+
+        self.load_data(MarketplaceData {
+            sources: field(get(STORE_SOURCES_KEY), STORE_SOURCES_KEY),
+            installed_plugins: field(
+                get(STORE_INSTALLED_PLUGINS_KEY),
+                STORE_INSTALLED_PLUGINS_KEY,
+            ),
+        });
+    }
+
+    fn export_data(&self) -> MarketplaceData {
+        MarketplaceData {
             sources: self.sources.read().unwrap().clone(),
             installed_plugins: self.installed_plugins.read().unwrap().clone(),
-        };
+        }
+    }
 
-        Ok(serde_json::to_string_pretty(&data)?)
+    fn load_data(&self, mut data: MarketplaceData) {
+        // Data saved before priorities existed has them all at 0; the stable
+        // sort then keeps the stored order.
+        data.sources.sort_by_key(|s| s.priority);
+        Self::renumber_priorities(&mut data.sources);
+        *self.sources.write().unwrap() = data.sources;
+        *self.installed_plugins.write().unwrap() = data.installed_plugins;
+    }
+}
+
+/// Returns true if `latest` is a newer version than `current`.
+///
+/// Versions are compared as semver (a leading `v` is ignored). If either side
+/// is not valid semver, falls back to a plain string comparison and logs a
+/// warning, since e.g. "1.10" vs "1.9" may then be misordered.
+fn is_newer_version(current: &str, latest: &str) -> bool {
+    let parse = |v: &str| semver::Version::parse(v.trim().trim_start_matches('v'));
+
+    match (parse(current), parse(latest)) {
+        (Ok(current), Ok(latest)) => latest > current,
+        _ => {
+            log::warn!(
+                "Non-semver plugin version ({} vs {}), comparing lexically",
+                current,
+                latest
+            );
+            latest.trim() > current.trim()
+        }
     }
 }
 
@@ -721,9 +1061,51 @@ impl Default for MarketplaceManager {
     }
 }
 
+/// Fetches each source in turn with `fetch`, emitting its progress as it
+/// completes and a summary once all are done.
+async fn refresh_sources<F, Fut>(
+    sources: Vec<MarketplaceSource>,
+    fetch: F,
+    emit: impl Fn(&str, serde_json::Value),
+) -> Vec<(String, MarketplaceResult<Vec<MarketplacePlugin>>)>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = MarketplaceResult<Vec<MarketplacePlugin>>>,
+{
+    let mut results = Vec::with_capacity(sources.len());
+    let mut failed = 0;
+
+    for source in sources {
+        let result = fetch(source.id.clone()).await;
+        let (status, plugin_count, error) = match &result {
+            Ok(plugins) => (RefreshStatus::Ok, plugins.len(), None),
+            Err(e) => {
+                failed += 1;
+                (RefreshStatus::Error, 0, Some(e.to_string()))
+            }
+        };
+        let progress = MarketplaceRefreshProgress {
+            source_id: source.id.clone(),
+            name: source.name,
+            status,
+            plugin_count,
+            error,
+        };
+        emit(REFRESH_PROGRESS_EVENT, serde_json::json!(progress));
+        results.push((source.id, result));
+    }
+
+    emit(
+        REFRESH_DONE_EVENT,
+        serde_json::json!({ "total": results.len(), "failed": failed }),
+    );
+    results
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn test_add_remove_source() {
@@ -785,4 +1167,411 @@ mod tests {
         let url_trailing = MarketplaceManager::get_marketplace_json_url("https://github.com/owner/repo/");
         assert_eq!(url_trailing, "https://raw.githubusercontent.com/owner/repo/main/.claude-plugin/marketplace.json");
     }
+
+    #[test]
+    fn test_catalog_entry_keeps_featured_category_and_tags() {
+        let catalog: MarketplaceCatalog = serde_json::from_str(
+            r#"{
+                "name": "test-market",
+                "plugins": [
+                    {
+                        "name": "widget",
+                        "source": "./plugins/widget",
+                        "category": "productivity",
+                        "tags": ["git", "review"],
+                        "featured": true
+                    },
+                    { "name": "plain" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let plugins: Vec<MarketplacePlugin> = catalog
+            .plugins
+            .into_iter()
+            .map(|p| p.into_marketplace_plugin("test-market", "https://github.com/owner/repo"))
+            .collect();
+
+        assert!(plugins[0].featured);
+        assert_eq!(plugins[0].category, PluginCategory::Productivity);
+        assert_eq!(plugins[0].tags, vec!["git", "review"]);
+        assert!(!plugins[1].featured);
+        assert!(plugins[1].tags.is_empty());
+    }
+
+    /// Registers an installed marketplace plugin at `installed` and a catalog
+    /// entry for it at `available`.
+    fn manager_with_versions(installed: &str, available: &str) -> MarketplaceManager {
+        let manager = MarketplaceManager::new();
+        manager.available_plugins.insert(
+            "test-market".to_string(),
+            vec![MarketplacePlugin {
+                id: "test-market/widget".to_string(),
+                name: "widget".to_string(),
+                description: String::new(),
+                version: available.to_string(),
+                author: String::new(),
+                category: PluginCategory::default(),
+                types: Vec::new(),
+                download_url: None,
+                repository_url: None,
+                source_path: None,
+                tags: Vec::new(),
+                marketplace_id: "test-market".to_string(),
+                icon_url: None,
+                homepage_url: None,
+                min_version: None,
+                license: None,
+                downloads: None,
+                stars: None,
+                featured: false,
+            }],
+        );
+        let plugin = InstalledPlugin {
+            id: "installed-1".to_string(),
+            name: "widget".to_string(),
+            version: installed.to_string(),
+            source: InstalledPluginSource::Marketplace {
+                marketplace_id: "test-market".to_string(),
+                plugin_id: "test-market/widget".to_string(),
+            },
+            install_scope: InstallScope::User,
+            path: String::new(),
+            installed_at: String::new(),
+            updated_at: None,
+            skills: Vec::new(),
+            commands: Vec::new(),
+            mcp_servers: Vec::new(),
+            agents: Vec::new(),
+            hooks: Vec::new(),
+            is_enabled: true,
+        };
+        manager.installed_plugins.write().unwrap().push(plugin);
+        manager
+    }
+
+    #[test]
+    fn test_plugin_provenance_reports_drift_and_removed_source() {
+        let manager = manager_with_versions("1.0.0", "1.1.0");
+        let source = manager.add_source(
+            "Test Market".to_string(),
+            "https://github.com/test/market".to_string(),
+            false,
+        );
+        let catalog = manager.available_plugins.remove("test-market").unwrap().1;
+        manager.available_plugins.insert(source.id.clone(), catalog);
+        if let InstalledPluginSource::Marketplace { marketplace_id, .. } =
+            &mut manager.installed_plugins.write().unwrap()[0].source
+        {
+            *marketplace_id = source.id.clone();
+        }
+
+        assert_eq!(
+            manager.plugin_provenance("installed-1").unwrap(),
+            Provenance {
+                source_present: true,
+                source_name: Some("Test Market".to_string()),
+                catalog_entry_present: true,
+                version_drift: Some(("1.0.0".to_string(), "1.1.0".to_string())),
+            }
+        );
+
+        manager.remove_source(&source.id).unwrap();
+        let provenance = manager.plugin_provenance("installed-1").unwrap();
+        assert!(!provenance.source_present);
+        assert_eq!(provenance.source_name, None);
+        assert!(!provenance.catalog_entry_present);
+        assert_eq!(provenance.version_drift, None);
+
+        assert!(matches!(
+            manager.plugin_provenance("missing"),
+            Err(MarketplaceError::NotInstalled(_))
+        ));
+    }
+
+    #[test]
+    fn test_store_entries_round_trip_sources_and_installed_plugins() {
+        let manager = manager_with_versions("1.0.0", "1.0.0");
+        let source = manager.add_source(
+            "Team".to_string(),
+            "https://github.com/team/plugins".to_string(),
+            false,
+        );
+        manager.toggle_source(&source.id).unwrap();
+
+        // Through JSON values, as the store keeps them
+        let stored: HashMap<&str, serde_json::Value> =
+            manager.to_store_entries().unwrap().into_iter().collect();
+
+        let restored = MarketplaceManager::new();
+        restored.load_store_entries(|key| stored.get(key).cloned());
+
+        assert_eq!(
+            restored.export_to_json().unwrap(),
+            manager.export_to_json().unwrap()
+        );
+        let team = restored.get_source(&source.id).unwrap();
+        assert!(!team.is_enabled);
+        assert_eq!(restored.get_installed_plugins().len(), 1);
+        assert_eq!(restored.get_installed_plugins()[0].name, "widget");
+    }
+
+    #[test]
+    fn test_json_export_load_round_trip() {
+        let manager = manager_with_versions("1.0.0", "1.0.0");
+        let source = manager.add_source(
+            "Team".to_string(),
+            "https://github.com/team/plugins".to_string(),
+            false,
+        );
+        manager.toggle_source(&source.id).unwrap();
+        let json = manager.export_to_json().unwrap();
+
+        let restored = MarketplaceManager::new();
+        restored.load_from_json(&json).unwrap();
+
+        assert_eq!(restored.export_to_json().unwrap(), json);
+        assert!(!restored.get_source(&source.id).unwrap().is_enabled);
+        assert_eq!(restored.get_installed_plugins()[0].name, "widget");
+        assert!(restored.load_from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_store_entries_tolerate_missing_and_corrupt_keys() {
+        let manager = MarketplaceManager::new();
+        manager.load_store_entries(|key| {
+            (key == "installed_plugins").then(|| serde_json::json!("not a list"))
+        });
+        assert!(manager.get_sources().is_empty());
+        assert!(manager.get_installed_plugins().is_empty());
+    }
+
+    #[test]
+    fn test_check_updates_newer_version() {
+        let updates = manager_with_versions("1.2.0", "1.10.0").check_updates();
+        assert_eq!(
+            updates,
+            vec![PluginUpdate {
+                installed_id: "installed-1".to_string(),
+                current_version: "1.2.0".to_string(),
+                latest_version: "1.10.0".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_updates_equal_and_downgrade() {
+        for latest in ["v2.0.0", "1.9.9", "2.0.0-beta.1"] {
+            let updates = manager_with_versions("2.0.0", latest).check_updates();
+            assert!(updates.is_empty(), "2.0.0 -> {} is not an update", latest);
+        }
+    }
+
+    #[test]
+    fn test_check_updates_non_semver_falls_back_to_lexical() {
+        assert!(is_newer_version("2024-01-05", "2024-02-01"));
+        assert!(!is_newer_version("2024-02-01", "2024-02-01"));
+        assert!(!is_newer_version("beta", "alpha"));
+        let updates = manager_with_versions("2024-01-05", "2024-02-01").check_updates();
+        assert_eq!(updates.len(), 1);
+    }
+
+    /// Builds a catalog entry named `name` from the given marketplace.
+    fn catalog_plugin(name: &str, marketplace_id: &str) -> MarketplacePlugin {
+        MarketplacePlugin {
+            id: format!("{}/{}", marketplace_id, name),
+            name: name.to_string(),
+            description: String::new(),
+            version: "1.0.0".to_string(),
+            author: String::new(),
+            category: PluginCategory::default(),
+            types: Vec::new(),
+            download_url: None,
+            repository_url: None,
+            source_path: None,
+            tags: Vec::new(),
+            marketplace_id: marketplace_id.to_string(),
+            icon_url: None,
+            homepage_url: None,
+            min_version: None,
+            license: None,
+            downloads: None,
+            stars: None,
+            featured: false,
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancel_install_kills_clone_and_removes_partial_dir() {
+        // A "remote" that accepts connections but never answers, so git
+        // creates the target directory and then blocks
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/slow.git", listener.local_addr().unwrap());
+
+        let manager = std::sync::Arc::new(MarketplaceManager::new());
+        let source = manager.add_source(
+            "Slow".to_string(),
+            "https://github.com/test/slow".to_string(),
+            false,
+        );
+        let mut plugin = catalog_plugin("slow", &source.id);
+        plugin.repository_url = Some(url);
+        manager
+            .available_plugins
+            .insert(source.id.clone(), vec![plugin.clone()]);
+
+        let project = tempfile::tempdir().unwrap();
+        let plugin_dir = project
+            .path()
+            .join(".claude")
+            .join("plugins")
+            .join(plugin.id.replace('/', "-"));
+        let install = {
+            let manager = manager.clone();
+            let project_path = project.path().to_string_lossy().to_string();
+            tokio::spawn(async move {
+                manager
+                    .install_plugin(
+                        &plugin.id,
+                        InstallScope::Project,
+                        Some(&project_path),
+                        "op-1",
+                    )
+                    .await
+            })
+        };
+
+        for _ in 0..200 {
+            if plugin_dir.exists() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(25)).await;
+        }
+        assert!(plugin_dir.exists(), "git should have started the clone");
+
+        assert!(manager.cancel_install("op-1"));
+        let result = tokio::time::timeout(std::time::Duration::from_secs(10), install)
+            .await
+            .expect("cancelled install should return promptly")
+            .unwrap();
+        assert!(matches!(result, Err(MarketplaceError::Cancelled)));
+        assert!(!plugin_dir.exists(), "partial clone should be removed");
+        assert!(manager.get_installed_plugins().is_empty());
+        assert!(!manager.cancel_install("op-1"));
+    }
+
+    #[test]
+    fn test_reorder_sources_controls_duplicate_precedence() {
+        let manager = MarketplaceManager::new();
+        let first = manager.add_source(
+            "First".to_string(),
+            "https://github.com/a/a".to_string(),
+            false,
+        );
+        let second = manager.add_source(
+            "Second".to_string(),
+            "https://github.com/b/b".to_string(),
+            false,
+        );
+        for source in [&first, &second] {
+            let plugins = vec![
+                catalog_plugin("shared", &source.id),
+                catalog_plugin(&source.name, &source.id),
+            ];
+            manager.available_plugins.insert(source.id.clone(), plugins);
+        }
+
+        let winner = |manager: &MarketplaceManager| {
+            let plugins = manager.get_available_plugins();
+            assert_eq!(plugins.len(), 3, "duplicate name should appear once");
+            plugins
+                .into_iter()
+                .find(|p| p.name == "shared")
+                .unwrap()
+                .marketplace_id
+        };
+        assert_eq!(winner(&manager), first.id);
+
+        manager
+            .reorder_sources(vec![second.id.clone(), first.id.clone()])
+            .unwrap();
+        assert_eq!(winner(&manager), second.id);
+
+        let sources = manager.get_sources();
+        assert_eq!(sources[0].id, second.id);
+        assert_eq!(sources[0].priority, 0);
+        // The official source was not listed, so it moves after the listed ones
+        assert_eq!(sources[2].id, OFFICIAL_MARKETPLACE_ID);
+        assert_eq!(sources[2].priority, 2);
+
+        // Priorities survive a save/load round trip
+        let stored: HashMap<&str, serde_json::Value> =
+            manager.to_store_entries().unwrap().into_iter().collect();
+        let reloaded = MarketplaceManager::new();
+        reloaded.load_store_entries(|key| stored.get(key).cloned());
+        assert_eq!(reloaded.get_sources()[0].id, second.id);
+    }
+
+    #[test]
+    fn test_reorder_sources_rejects_unknown_id() {
+        let manager = MarketplaceManager::new();
+        let result = manager.reorder_sources(vec!["missing".to_string()]);
+        assert!(matches!(result, Err(MarketplaceError::SourceNotFound(_))));
+        assert_eq!(manager.get_sources()[0].id, OFFICIAL_MARKETPLACE_ID);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_emits_progress_per_source_then_done() {
+        let manager = MarketplaceManager::new();
+        let good = manager.add_source(
+            "Good".to_string(),
+            "https://github.com/a/a".to_string(),
+            false,
+        );
+        let bad = manager.add_source(
+            "Bad".to_string(),
+            "https://github.com/b/b".to_string(),
+            false,
+        );
+        let sources: Vec<_> = manager
+            .get_sources()
+            .into_iter()
+            .filter(|s| s.id != OFFICIAL_MARKETPLACE_ID)
+            .collect();
+
+        let events = std::sync::Mutex::new(Vec::new());
+        let results = refresh_sources(
+            sources,
+            |id| {
+                let good_id = good.id.clone();
+                async move {
+                    if id == good_id {
+                        Ok(vec![catalog_plugin("one", &id), catalog_plugin("two", &id)])
+                    } else {
+                        Err(MarketplaceError::FetchError("HTTP 404".to_string()))
+                    }
+                }
+            },
+            |event: &str, payload| events.lock().unwrap().push((event.to_string(), payload)),
+        )
+        .await;
+
+        assert_eq!(results.len(), 2);
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events[..2].iter().all(|(e, _)| e == REFRESH_PROGRESS_EVENT));
+        let progress: MarketplaceRefreshProgress =
+            serde_json::from_value(events[0].1.clone()).unwrap();
+        assert_eq!(progress.source_id, good.id);
+        assert_eq!(progress.name, "Good");
+        assert_eq!(progress.status, RefreshStatus::Ok);
+        assert_eq!(progress.plugin_count, 2);
+        assert_eq!(events[1].1["source_id"], bad.id);
+        assert_eq!(events[1].1["status"], "error");
+        assert_eq!(events[1].1["plugin_count"], 0);
+        assert_eq!(events[2].0, REFRESH_DONE_EVENT);
+        assert_eq!(events[2].1, serde_json::json!({ "total": 2, "failed": 1 }));
+    }
 }
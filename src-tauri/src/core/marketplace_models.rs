@@ -93,6 +93,10 @@ pub struct MarketplaceSource {
     pub last_fetched: Option<String>,
     /// Error message from last fetch attempt (if any).
     pub last_error: Option<String>,
+    /// Precedence when several sources offer a plugin with the same name
+    /// (lower wins). Kept equal to the source's position in the list.
+    #[serde(default)]
+    pub priority: u32,
 }
 
 /// A plugin available for download from a marketplace.
@@ -135,6 +139,9 @@ pub struct MarketplacePlugin {
     pub downloads: Option<u64>,
     /// Star/rating count (if tracked by marketplace).
     pub stars: Option<u64>,
+    /// Whether the catalog marks this plugin as featured/curated.
+    #[serde(default)]
+    pub featured: bool,
 }
 
 /// Source of an installed plugin.
@@ -194,10 +201,59 @@ pub struct InstalledPlugin {
     pub is_enabled: bool,
 }
 
+/// An installed plugin whose marketplace entry offers a newer version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginUpdate {
+    /// ID of the installed plugin record.
+    pub installed_id: String,
+    /// Version currently installed.
+    pub current_version: String,
+    /// Version advertised by the marketplace.
+    pub latest_version: String,
+}
+
+/// Outcome of fetching one source's catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefreshStatus {
+    Ok,
+    Error,
+}
+
+/// Payload of a `marketplace-refresh-progress` event, sent as each source
+/// finishes during a refresh of all sources.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketplaceRefreshProgress {
+    pub source_id: String,
+    /// Human-readable name of the source.
+    pub name: String,
+    pub status: RefreshStatus,
+    /// Plugins in the fetched catalog; 0 when the fetch failed.
+    pub plugin_count: usize,
+    /// Why the fetch failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Where an installed plugin came from, checked against the current sources.
+///
+/// Plugins installed from Git or a local directory have no marketplace, so
+/// every field is `false`/`None` for them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Whether the marketplace the plugin was installed from is still configured.
+    pub source_present: bool,
+    /// Name of that marketplace, if it is still configured.
+    pub source_name: Option<String>,
+    /// Whether the marketplace's fetched catalog still lists the plugin.
+    pub catalog_entry_present: bool,
+    /// `(installed, latest)` versions when the catalog entry's version differs.
+    pub version_drift: Option<(String, String)>,
+}
+
 /// Session-specific marketplace plugin configuration.
 ///
 /// Tracks which marketplace plugins are enabled for a specific session.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SessionMarketplaceConfig {
     /// IDs of enabled installed plugins.
     pub enabled_plugins: Vec<String>,
@@ -297,6 +353,9 @@ pub struct CatalogPlugin {
     /// Stars count.
     #[serde(default)]
     pub stars: Option<u64>,
+    /// Featured/curated flag.
+    #[serde(default)]
+    pub featured: bool,
 }
 
 impl CatalogPlugin {
@@ -348,6 +407,7 @@ impl CatalogPlugin {
             license: self.license,
             downloads: self.downloads,
             stars: self.stars,
+            featured: self.featured,
         }
     }
 }
@@ -6,10 +6,14 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use dashmap::DashMap;
+use serde::Serialize;
 use serde_json::{json, Value};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 
 use super::mcp_manager::{McpServerConfig, McpServerSource, McpServerType};
@@ -19,7 +23,7 @@ use crate::commands::mcp::McpCustomServer;
 static DIR_LOCKS: LazyLock<DashMap<PathBuf, Arc<Mutex<()>>>> = LazyLock::new(DashMap::new);
 
 /// Acquire a per-directory lock for atomic .mcp.json operations.
-fn dir_lock(dir: &Path) -> Arc<Mutex<()>> {
+pub(crate) fn dir_lock(dir: &Path) -> Arc<Mutex<()>> {
     DIR_LOCKS
         .entry(dir.to_path_buf())
         .or_insert_with(|| Arc::new(Mutex::new(())))
@@ -27,17 +31,45 @@ fn dir_lock(dir: &Path) -> Arc<Mutex<()>> {
         .clone()
 }
 
+/// Filename prefix for in-flight `.mcp.json` writes.
+const TEMP_PREFIX: &str = ".mcp.json.tmp.";
+
+/// Temp files younger than this may belong to another process mid-write.
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60);
+
+/// Distinguishes temp files from concurrent writes within this process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Builds a temp filename unique across processes, runs, and concurrent writes.
+fn unique_temp_name() -> String {
+    format!(
+        "{}{}.{:08x}.{}",
+        TEMP_PREFIX,
+        std::process::id(),
+        uuid::Uuid::new_v4().as_u128() as u32,
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
 /// Write content to a file atomically: write to a temp file in the same directory, then rename.
-async fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
+///
+/// The temp file is fsynced before the rename, and on Unix the parent directory
+/// is fsynced afterwards, so a crash leaves either the old or the new file,
+/// never a truncated one.
+pub(crate) async fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
     let parent = path.parent().ok_or("No parent directory")?;
-    let temp_path = parent.join(format!(
-        ".mcp.json.tmp.{}",
-        std::process::id()
-    ));
+    let temp_path = parent.join(unique_temp_name());
 
-    tokio::fs::write(&temp_path, content)
-        .await
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    let write_result = async {
+        let mut file = tokio::fs::File::create(&temp_path).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await
+    }
+    .await;
+    if let Err(e) = write_result {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(format!("Failed to write temp file: {}", e));
+    }
 
     tokio::fs::rename(&temp_path, path)
         .await
@@ -47,18 +79,78 @@ async fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
             format!("Failed to rename temp file: {}", e)
         })?;
 
+    // Persist the rename itself. Directories can't be opened this way on Windows.
+    #[cfg(unix)]
+    if let Err(e) = sync_dir(parent).await {
+        log::warn!(
+            "Failed to fsync {:?} after writing .mcp.json: {}",
+            parent,
+            e
+        );
+    }
+
     Ok(())
 }
 
-/// Finds the maestro-mcp-server binary in common installation locations.
+#[cfg(unix)]
+async fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    tokio::fs::File::open(dir).await?.sync_all().await
+}
+
+/// Removes temp files left behind by crashed or killed writes.
+///
+/// Must be called with the directory lock held. Files younger than `min_age`
+/// are kept since another Maestro instance may still be writing them.
+async fn cleanup_orphaned_temps(dir: &Path, min_age: Duration) {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if !entry.file_name().to_string_lossy().starts_with(TEMP_PREFIX) {
+            continue;
+        }
+        let age = entry
+            .metadata()
+            .await
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+        if age.is_some_and(|age| age >= min_age) {
+            match tokio::fs::remove_file(entry.path()).await {
+                Ok(()) => log::debug!("Removed orphaned temp file {:?}", entry.path()),
+                Err(e) => log::warn!(
+                    "Failed to remove orphaned temp file {:?}: {}",
+                    entry.path(),
+                    e
+                ),
+            }
+        }
+    }
+}
+
+/// Environment variable that points at a specific maestro-mcp-server binary,
+/// bypassing the install-location probe (e.g. for custom installs).
+pub const MCP_SERVER_PATH_ENV: &str = "MAESTRO_MCP_SERVER_PATH";
+
+/// Finds the maestro-mcp-server binary.
 ///
-/// Searches in order:
+/// If `MAESTRO_MCP_SERVER_PATH` is set and points at an existing file it is
+/// used as-is. Otherwise searches in order:
 /// 1. Next to the current executable (development and installed)
 /// 2. Inside Resources for macOS app bundle
 /// 3. Development: relative to src-tauri/target/debug or release
 /// 4. macOS Application Support (~Library/Application Support/Claude Maestro/)
 /// 5. Linux local share (~/.local/share/maestro/)
-fn find_maestro_mcp_path() -> Option<PathBuf> {
+pub(crate) fn find_maestro_mcp_path() -> Option<PathBuf> {
+    let override_path = std::env::var_os(MCP_SERVER_PATH_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from);
+    select_mcp_path(override_path.as_deref(), &mcp_path_candidates())
+}
+
+/// Builds the list of probe locations for the maestro-mcp-server binary.
+fn mcp_path_candidates() -> Vec<Option<PathBuf>> {
     // Determine the binary name based on platform
     #[cfg(target_os = "windows")]
     let binary_name = "maestro-mcp-server.exe";
@@ -71,7 +163,7 @@ fn find_maestro_mcp_path() -> Option<PathBuf> {
         current_exe
     );
 
-    let candidates: Vec<Option<PathBuf>> = vec![
+    vec![
         // Next to the executable (most common for development and installed)
         current_exe
             .as_ref()
@@ -123,7 +215,30 @@ fn find_maestro_mcp_path() -> Option<PathBuf> {
         #[cfg(target_os = "windows")]
         directories::BaseDirs::new()
             .map(|d| d.data_local_dir().join("Maestro").join(binary_name)),
-    ];
+    ]
+}
+
+/// Picks the MCP server binary: the override if it exists, else the first
+/// existing candidate.
+fn select_mcp_path(
+    override_path: Option<&Path>,
+    candidates: &[Option<PathBuf>],
+) -> Option<PathBuf> {
+    if let Some(path) = override_path {
+        if path.exists() {
+            log::info!(
+                "find_maestro_mcp_path: using {} override {:?}",
+                MCP_SERVER_PATH_ENV,
+                path
+            );
+            return Some(path.to_path_buf());
+        }
+        log::warn!(
+            "find_maestro_mcp_path: {} override {:?} does not exist, falling back to search",
+            MCP_SERVER_PATH_ENV,
+            path
+        );
+    }
 
     for (i, candidate) in candidates.iter().enumerate() {
         if let Some(path) = candidate {
@@ -135,7 +250,11 @@ fn find_maestro_mcp_path() -> Option<PathBuf> {
                 exists
             );
             if exists {
-                log::info!("find_maestro_mcp_path: found at {:?}", path);
+                log::info!(
+                    "find_maestro_mcp_path: candidate[{}] matched at {:?}",
+                    i,
+                    path
+                );
                 return Some(path.clone());
             }
         }
@@ -145,7 +264,71 @@ fn find_maestro_mcp_path() -> Option<PathBuf> {
     None
 }
 
+/// Whether a session's `.mcp.json` got the `maestro-status` entry.
+///
+/// `available` is false when maestro-mcp-server could not be located, in
+/// which case status reporting won't work until Maestro is reinstalled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MaestroMcpStatus {
+    pub available: bool,
+    pub path: Option<String>,
+}
+
+impl MaestroMcpStatus {
+    fn from_path(path: Option<&Path>) -> Self {
+        Self {
+            available: path.is_some(),
+            path: path.map(|p| p.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+/// Whether a working directory's `.mcp.json` can be merged into.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct McpJsonHealth {
+    /// False when the file exists but isn't a JSON object whose `mcpServers`
+    /// (if present) is an object. A missing file is valid.
+    pub valid: bool,
+    /// Why the file is invalid, including the parse error's line and column.
+    pub error: Option<String>,
+    /// Number of entries under `mcpServers`; 0 when invalid.
+    pub server_count: usize,
+}
+
+impl McpJsonHealth {
+    fn invalid(error: String) -> Self {
+        Self {
+            valid: false,
+            error: Some(error),
+            server_count: 0,
+        }
+    }
+}
+
+/// Checks `.mcp.json` contents the way `merge_with_existing` will read them.
+fn check_mcp_json(content: &str) -> McpJsonHealth {
+    let config: Value = match serde_json::from_str(content) {
+        Ok(config) => config,
+        Err(e) => return McpJsonHealth::invalid(e.to_string()),
+    };
+    let Some(config) = config.as_object() else {
+        return McpJsonHealth::invalid("top level is not a JSON object".to_string());
+    };
+    let server_count = match config.get("mcpServers") {
+        None => 0,
+        Some(Value::Object(servers)) => servers.len(),
+        Some(_) => return McpJsonHealth::invalid("`mcpServers` is not an object".to_string()),
+    };
+    McpJsonHealth {
+        valid: true,
+        error: None,
+        server_count,
+    }
+}
+
 /// Converts an McpServerConfig to the JSON format expected by `.mcp.json`.
+///
+/// The server's `source` is metadata for the UI and is never written.
 fn server_config_to_json(config: &McpServerConfig) -> Value {
     match &config.server_type {
         McpServerType::Stdio { command, args, env } => {
@@ -181,6 +364,170 @@ fn custom_server_to_json(server: &McpCustomServer) -> Value {
     obj
 }
 
+/// Where a server a session receives comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectiveMcpSource {
+    /// The `maestro-status` entry pointing at maestro-mcp-server.
+    Maestro,
+    /// A server discovered from `.mcp.json` or `~/.claude.json`.
+    Discovered,
+    /// A custom server defined in Maestro.
+    Custom,
+    /// Bundled in one of the session's enabled plugins. Claude loads these
+    /// through the plugin, so they never appear in the session's `.mcp.json`.
+    Plugin { plugin_id: String },
+}
+
+/// How Claude CLI connects to an MCP server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    Stdio,
+    Http,
+}
+
+/// A server a session will receive, without its command, args, or env.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectiveMcpServer {
+    pub name: String,
+    pub source: EffectiveMcpSource,
+    pub transport: McpTransport,
+}
+
+/// Builds the servers Maestro writes for a session, keyed by name.
+///
+/// Later sources win on a name clash: custom over discovered over the
+/// `maestro-status` entry, which is only added when `maestro_mcp_path` is known.
+fn build_session_servers(
+    maestro_mcp_path: Option<&Path>,
+    session_id: u32,
+    status_url: &str,
+    instance_id: &str,
+    project_hash: &str,
+    enabled_servers: &[McpServerConfig],
+    custom_servers: &[McpCustomServer],
+) -> HashMap<String, (EffectiveMcpSource, Value)> {
+    let mut mcp_servers = HashMap::new();
+
+    // Add Maestro MCP server with HTTP-based status reporting.
+    // Uses a SINGLE "maestro-status" entry with session ID in env vars (Swift pattern).
+    // Each Claude instance spawns its own MCP server process with the env vars from when
+    // it read the config. This avoids memory bloat from loading N servers per project.
+    if let Some(mcp_path) = maestro_mcp_path {
+        log::info!(
+            "Found maestro-mcp-server at {:?}, adding single maestro-status entry for session {} with status_url={}",
+            mcp_path,
+            session_id,
+            status_url
+        );
+
+        // Use fixed name "maestro-status" - session ID is in env vars
+        mcp_servers.insert(
+            "maestro-status".to_string(),
+            (
+                EffectiveMcpSource::Maestro,
+                json!({
+                    "type": "stdio",
+                    "command": mcp_path.to_string_lossy(),
+                    "args": [],
+                    "env": {
+                        "MAESTRO_SESSION_ID": session_id.to_string(),
+                        "MAESTRO_STATUS_URL": status_url,
+                        "MAESTRO_INSTANCE_ID": instance_id,
+                        "MAESTRO_PROJECT_HASH": project_hash
+                    }
+                }),
+            ),
+        );
+    } else {
+        log::warn!(
+            "maestro-mcp-server binary not found, maestro_status tool will not be available"
+        );
+    }
+
+    // Add enabled discovered servers from project .mcp.json
+    for server in enabled_servers {
+        mcp_servers.insert(
+            server.name.clone(),
+            (
+                EffectiveMcpSource::Discovered,
+                server_config_to_json(server),
+            ),
+        );
+    }
+
+    // Add enabled custom servers (user-defined, global)
+    for server in custom_servers {
+        mcp_servers.insert(
+            server.name.clone(),
+            (EffectiveMcpSource::Custom, custom_server_to_json(server)),
+        );
+    }
+
+    mcp_servers
+}
+
+/// Lists the servers `write_session_mcp_config` would emit for a session,
+/// plus the servers of its enabled plugins, tagged by origin and sorted by
+/// name.
+///
+/// User-defined entries already present in the working directory's
+/// `.mcp.json` are not included; only what Maestro itself contributes is.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_effective_mcp_servers(
+    maestro_mcp_path: Option<&Path>,
+    session_id: u32,
+    status_url: &str,
+    instance_id: &str,
+    project_hash: &str,
+    enabled_servers: &[McpServerConfig],
+    custom_servers: &[McpCustomServer],
+    plugin_servers: &[McpServerConfig],
+) -> Vec<EffectiveMcpServer> {
+    let servers = build_session_servers(
+        maestro_mcp_path,
+        session_id,
+        status_url,
+        instance_id,
+        project_hash,
+        enabled_servers,
+        custom_servers,
+    );
+
+    let mut effective: Vec<EffectiveMcpServer> = servers
+        .into_iter()
+        .map(|(name, (source, config))| EffectiveMcpServer {
+            name,
+            source,
+            transport: if config["type"] == "http" {
+                McpTransport::Http
+            } else {
+                McpTransport::Stdio
+            },
+        })
+        .collect();
+
+    // Plugin servers are namespaced by Claude, so they can't clash with the above
+    for server in plugin_servers {
+        let McpServerSource::Plugin { plugin_id } = &server.source else {
+            continue;
+        };
+        effective.push(EffectiveMcpServer {
+            name: server.name.clone(),
+            source: EffectiveMcpSource::Plugin {
+                plugin_id: plugin_id.clone(),
+            },
+            transport: match server.server_type {
+                McpServerType::Stdio { .. } => McpTransport::Stdio,
+                McpServerType::Http { .. } => McpTransport::Http,
+            },
+        });
+    }
+    effective.sort_by(|a, b| a.name.cmp(&b.name));
+    effective
+}
+
 /// Checks if a server entry should be removed when updating the MCP config.
 ///
 /// Removes:
@@ -295,63 +642,40 @@ fn merge_with_existing(
 /// * `session_id` - Session identifier for the Maestro MCP server
 /// * `status_url` - HTTP URL for the status server endpoint
 /// * `instance_id` - UUID for this Maestro instance (prevents cross-instance pollution)
+/// * `project_hash` - Hash of the canonical project path, lets the status server
+///   route reports from sessions that aren't registered
 /// * `enabled_servers` - List of discovered MCP server configs enabled for this session
 /// * `custom_servers` - List of custom MCP servers that are enabled
+///
+/// Returns whether the `maestro-status` entry could be written, so callers
+/// can tell the user when maestro-mcp-server is missing.
 pub async fn write_session_mcp_config(
     working_dir: &Path,
     session_id: u32,
     status_url: &str,
     instance_id: &str,
+    project_hash: &str,
     enabled_servers: &[McpServerConfig],
     custom_servers: &[McpCustomServer],
-) -> Result<(), String> {
-    let mut mcp_servers: HashMap<String, Value> = HashMap::new();
-
-    // Add Maestro MCP server with HTTP-based status reporting.
-    // Uses a SINGLE "maestro-status" entry with session ID in env vars (Swift pattern).
-    // Each Claude instance spawns its own MCP server process with the env vars from when
-    // it read the config. This avoids memory bloat from loading N servers per project.
-    if let Some(mcp_path) = find_maestro_mcp_path() {
-        log::info!(
-            "Found maestro-mcp-server at {:?}, adding single maestro-status entry for session {} with status_url={}",
-            mcp_path,
-            session_id,
-            status_url
-        );
-
-        // Use fixed name "maestro-status" - session ID is in env vars
-        mcp_servers.insert(
-            "maestro-status".to_string(),
-            json!({
-                "type": "stdio",
-                "command": mcp_path.to_string_lossy(),
-                "args": [],
-                "env": {
-                    "MAESTRO_SESSION_ID": session_id.to_string(),
-                    "MAESTRO_STATUS_URL": status_url,
-                    "MAESTRO_INSTANCE_ID": instance_id
-                }
-            }),
-        );
-    } else {
-        log::warn!(
-            "maestro-mcp-server binary not found, maestro_status tool will not be available"
-        );
-    }
-
-    // Add enabled discovered servers from project .mcp.json
-    for server in enabled_servers {
-        mcp_servers.insert(server.name.clone(), server_config_to_json(server));
-    }
-
-    // Add enabled custom servers (user-defined, global)
-    for server in custom_servers {
-        mcp_servers.insert(server.name.clone(), custom_server_to_json(server));
-    }
+) -> Result<MaestroMcpStatus, String> {
+    let maestro_mcp_path = find_maestro_mcp_path();
+    let mcp_servers: HashMap<String, Value> = build_session_servers(
+        maestro_mcp_path.as_deref(),
+        session_id,
+        status_url,
+        instance_id,
+        project_hash,
+        enabled_servers,
+        custom_servers,
+    )
+    .into_iter()
+    .map(|(name, (_, config))| (name, config))
+    .collect();
 
     // Acquire per-directory lock to serialize concurrent read-modify-write
     let lock = dir_lock(working_dir);
     let _guard = lock.lock().await;
+    cleanup_orphaned_temps(working_dir, STALE_TEMP_AGE).await;
 
     // Merge with existing .mcp.json if present (preserve user servers AND other sessions)
     let mcp_path = working_dir.join(".mcp.json");
@@ -369,7 +693,7 @@ pub async fn write_session_mcp_config(
         mcp_path
     );
 
-    Ok(())
+    Ok(MaestroMcpStatus::from_path(maestro_mcp_path.as_deref()))
 }
 
 /// Removes Maestro server entries from `.mcp.json`.
@@ -394,6 +718,7 @@ pub async fn remove_session_mcp_config(working_dir: &Path, session_id: u32) -> R
     // Acquire per-directory lock to serialize concurrent read-modify-write
     let lock = dir_lock(working_dir);
     let _guard = lock.lock().await;
+    cleanup_orphaned_temps(working_dir, STALE_TEMP_AGE).await;
 
     let content = tokio::fs::read_to_string(&mcp_path)
         .await
@@ -430,12 +755,100 @@ pub async fn remove_session_mcp_config(working_dir: &Path, session_id: u32) -> R
     Ok(())
 }
 
+/// Reports whether `working_dir/.mcp.json` is usable, so a hand-edited file
+/// that would make `write_session_mcp_config` fail can be caught up front.
+pub async fn inspect_mcp_json(working_dir: &Path) -> Result<McpJsonHealth, String> {
+    match tokio::fs::read_to_string(working_dir.join(".mcp.json")).await {
+        Ok(content) => Ok(check_mcp_json(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(McpJsonHealth {
+            valid: true,
+            error: None,
+            server_count: 0,
+        }),
+        Err(e) => Err(format!("Failed to read .mcp.json: {}", e)),
+    }
+}
+
+/// Replaces an invalid `working_dir/.mcp.json` with `{"mcpServers": {}}` so
+/// sessions can launch again, after copying it to `.mcp.json.bak` (or
+/// `.mcp.json.bak.N` if earlier backups exist).
+///
+/// Valid or missing files are left alone. Returns the backup's path when a
+/// repair was made.
+pub async fn repair_mcp_json(working_dir: &Path) -> Result<Option<PathBuf>, String> {
+    let lock = dir_lock(working_dir);
+    let _guard = lock.lock().await;
+
+    if inspect_mcp_json(working_dir).await?.valid {
+        return Ok(None);
+    }
+
+    let mcp_path = working_dir.join(".mcp.json");
+    let backup = (0..)
+        .map(|n| match n {
+            0 => working_dir.join(".mcp.json.bak"),
+            n => working_dir.join(format!(".mcp.json.bak.{}", n)),
+        })
+        .find(|path| !path.exists())
+        .expect("unbounded candidate list");
+    tokio::fs::copy(&mcp_path, &backup)
+        .await
+        .map_err(|e| format!("Failed to back up .mcp.json: {}", e))?;
+
+    let content = serde_json::to_string_pretty(&json!({ "mcpServers": {} }))
+        .map_err(|e| format!("Failed to serialize MCP config: {}", e))?;
+    atomic_write(&mcp_path, &content).await?;
+
+    log::warn!(
+        "Replaced invalid {:?}; original kept at {:?}",
+        mcp_path,
+        backup
+    );
+    Ok(Some(backup))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashMap;
     use tempfile::tempdir;
 
+    #[tokio::test]
+    async fn test_inspect_and_repair_broken_mcp_json() {
+        let dir = tempdir().unwrap();
+        let mcp_path = dir.path().join(".mcp.json");
+        let broken = "{\n  \"mcpServers\": {\n    \"docs\": { \"type\": \"http\", }\n  }\n";
+        std::fs::write(&mcp_path, broken).unwrap();
+
+        let health = inspect_mcp_json(dir.path()).await.unwrap();
+        assert!(!health.valid);
+        assert_eq!(health.server_count, 0);
+        let error = health.error.unwrap();
+        assert!(error.contains("line 3"), "unexpected error: {}", error);
+
+        let backup = repair_mcp_json(dir.path()).await.unwrap().unwrap();
+        assert_eq!(backup, dir.path().join(".mcp.json.bak"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), broken);
+        let repaired: Value =
+            serde_json::from_str(&std::fs::read_to_string(&mcp_path).unwrap()).unwrap();
+        assert_eq!(repaired, json!({ "mcpServers": {} }));
+        assert_eq!(
+            inspect_mcp_json(dir.path()).await.unwrap(),
+            McpJsonHealth {
+                valid: true,
+                error: None,
+                server_count: 0,
+            }
+        );
+
+        // A valid file is never touched; a second breakage gets its own backup
+        assert_eq!(repair_mcp_json(dir.path()).await.unwrap(), None);
+        std::fs::write(&mcp_path, "[]").unwrap();
+        let second = repair_mcp_json(dir.path()).await.unwrap().unwrap();
+        assert_eq!(second, dir.path().join(".mcp.json.bak.1"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), broken);
+    }
+
     #[test]
     fn test_server_config_to_json_stdio() {
         let config = McpServerConfig {
@@ -474,6 +887,25 @@ mod tests {
         assert_eq!(json["url"], "http://localhost:3000");
     }
 
+    #[test]
+    fn test_server_config_to_json_omits_plugin_source() {
+        let config = McpServerConfig {
+            name: "search".to_string(),
+            server_type: McpServerType::Http {
+                url: "http://localhost:4000".to_string(),
+            },
+            source: McpServerSource::Plugin {
+                plugin_id: "plugin:toolbox".to_string(),
+            },
+        };
+
+        let json = server_config_to_json(&config);
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "http", "url": "http://localhost:4000"})
+        );
+    }
+
     #[tokio::test]
     async fn test_write_session_mcp_config_creates_file() {
         let dir = tempdir().unwrap();
@@ -482,15 +914,104 @@ mod tests {
             1,
             "http://127.0.0.1:9900/status",
             "test-instance-id",
+            "0123456789ab",
             &[],
             &[],
         )
         .await;
 
-        assert!(result.is_ok());
+        let status = result.unwrap();
+        assert_eq!(status.available, find_maestro_mcp_path().is_some());
         assert!(dir.path().join(".mcp.json").exists());
     }
 
+    #[test]
+    fn test_resolve_effective_servers_tags_sources() {
+        let discovered = McpServerConfig {
+            name: "docs".to_string(),
+            server_type: McpServerType::Http {
+                url: "http://localhost:3000".to_string(),
+            },
+            source: McpServerSource::Project,
+        };
+        let custom = McpCustomServer {
+            id: "c1".to_string(),
+            name: "my-tool".to_string(),
+            command: "npx".to_string(),
+            args: vec!["my-tool".to_string()],
+            env: HashMap::from([("TOKEN".to_string(), "secret".to_string())]),
+            working_directory: None,
+            is_enabled: true,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            tags: Vec::new(),
+        };
+
+        let servers = resolve_effective_mcp_servers(
+            Some(Path::new("/opt/maestro/maestro-mcp-server")),
+            1,
+            "http://127.0.0.1:9900/status",
+            "test-instance-id",
+            "0123456789ab",
+            &[discovered],
+            &[custom],
+            &[],
+        );
+
+        assert_eq!(
+            servers,
+            vec![
+                EffectiveMcpServer {
+                    name: "docs".to_string(),
+                    source: EffectiveMcpSource::Discovered,
+                    transport: McpTransport::Http,
+                },
+                EffectiveMcpServer {
+                    name: "maestro-status".to_string(),
+                    source: EffectiveMcpSource::Maestro,
+                    transport: McpTransport::Stdio,
+                },
+                EffectiveMcpServer {
+                    name: "my-tool".to_string(),
+                    source: EffectiveMcpSource::Custom,
+                    transport: McpTransport::Stdio,
+                },
+            ]
+        );
+
+        // Without the binary there is no maestro-status entry
+        let servers = resolve_effective_mcp_servers(None, 1, "", "", "", &[], &[], &[]);
+        assert!(servers.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_effective_servers_lists_plugin_servers() {
+        let plugin_dir = tempdir().unwrap();
+        std::fs::write(
+            plugin_dir.path().join(".mcp.json"),
+            r#"{"mcpServers": {"search": {"type": "http", "url": "http://localhost:4000"}}}"#,
+        )
+        .unwrap();
+        let plugin_servers =
+            crate::core::mcp_manager::discover_plugin_servers("plugin:toolbox", plugin_dir.path());
+
+        let servers = resolve_effective_mcp_servers(None, 1, "", "", "", &[], &[], &plugin_servers);
+
+        assert_eq!(
+            servers,
+            vec![EffectiveMcpServer {
+                name: "search".to_string(),
+                source: EffectiveMcpSource::Plugin {
+                    plugin_id: "plugin:toolbox".to_string(),
+                },
+                transport: McpTransport::Http,
+            }]
+        );
+        assert_eq!(
+            serde_json::to_value(&servers[0].source).unwrap(),
+            json!({"plugin": {"plugin_id": "plugin:toolbox"}})
+        );
+    }
+
     #[test]
     fn test_merge_preserves_user_servers_removes_all_maestro() {
         let dir = tempdir().unwrap();
@@ -593,6 +1114,35 @@ mod tests {
         assert!(parsed["mcpServers"]["test"].is_object());
     }
 
+    #[tokio::test]
+    async fn test_leftover_temp_file_does_not_corrupt_write() {
+        let dir = tempdir().unwrap();
+        let mcp_path = dir.path().join(".mcp.json");
+
+        // A crashed earlier run with the same PID left a half-written temp file
+        let leftover = dir
+            .path()
+            .join(format!("{}{}", TEMP_PREFIX, std::process::id()));
+        std::fs::write(&leftover, "{ \"mcpServers\": { \"trunc").unwrap();
+
+        let content = serde_json::to_string_pretty(&json!({
+            "mcpServers": { "test": { "type": "stdio", "command": "test" } }
+        }))
+        .unwrap();
+        atomic_write(&mcp_path, &content).await.unwrap();
+
+        let parsed: Value =
+            serde_json::from_str(&std::fs::read_to_string(&mcp_path).unwrap()).unwrap();
+        assert!(parsed["mcpServers"]["test"].is_object());
+
+        // Fresh temp files are left alone; stale ones are swept under the lock
+        cleanup_orphaned_temps(dir.path(), STALE_TEMP_AGE).await;
+        assert!(leftover.exists());
+        cleanup_orphaned_temps(dir.path(), Duration::ZERO).await;
+        assert!(!leftover.exists());
+        assert!(mcp_path.exists());
+    }
+
     #[tokio::test]
     async fn test_concurrent_writes_produce_valid_json() {
         let dir = tempdir().unwrap();
@@ -704,4 +1254,45 @@ mod tests {
         // New entry should be present
         assert!(servers.contains_key("maestro-status"), "new maestro-status entry should be present");
     }
+
+    #[test]
+    fn test_select_mcp_path_override_wins_over_probe_order() {
+        let dir = tempdir().unwrap();
+        let probed = dir.path().join("probed-mcp-server");
+        let custom = dir.path().join("custom-mcp-server");
+        std::fs::write(&probed, "").unwrap();
+        std::fs::write(&custom, "").unwrap();
+        let candidates = vec![None, Some(probed.clone())];
+
+        assert_eq!(select_mcp_path(None, &candidates), Some(probed.clone()));
+        assert_eq!(
+            select_mcp_path(Some(&custom), &candidates),
+            Some(custom.clone())
+        );
+
+        // A stale override falls back to the probe instead of failing
+        let missing = dir.path().join("missing");
+        assert_eq!(select_mcp_path(Some(&missing), &candidates), Some(probed));
+    }
+
+    #[test]
+    fn test_maestro_mcp_status_unavailable_without_binary() {
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("maestro-mcp-server");
+        let path = select_mcp_path(Some(&missing), &[None, Some(missing.clone())]);
+
+        let status = MaestroMcpStatus::from_path(path.as_deref());
+        assert_eq!(
+            status,
+            MaestroMcpStatus {
+                available: false,
+                path: None,
+            }
+        );
+
+        std::fs::write(&missing, "").unwrap();
+        let status = MaestroMcpStatus::from_path(Some(&missing));
+        assert!(status.available);
+        assert_eq!(status.path, Some(missing.to_string_lossy().into_owned()));
+    }
 }
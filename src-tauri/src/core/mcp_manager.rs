@@ -3,14 +3,16 @@
 //! This module discovers MCP servers from multiple sources:
 //! - Project `.mcp.json` files
 //! - User/local scope servers from `~/.claude.json`
+//! - Plugin-bundled `.mcp.json` files (resolved per plugin)
 //!
-//! It also tracks which servers are enabled per session.
+//! It also tracks which servers are enabled per session, and resolves the
+//! `.maestro/mcp-defaults.json` chain a project inherits from its ancestors.
 
 use dashmap::DashMap;
 use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// The source/origin of an MCP server.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -24,6 +26,9 @@ pub enum McpServerSource {
     Local,
     /// Custom server defined in Maestro.
     Custom,
+    /// Bundled in a plugin's `.mcp.json`. Claude loads these itself through
+    /// the plugin, so Maestro only reports them.
+    Plugin { plugin_id: String },
 }
 
 /// Configuration for an MCP server as read from `.mcp.json`.
@@ -143,22 +148,10 @@ impl McpManager {
     ///
     /// Returns an empty vec if the file doesn't exist or can't be parsed.
     fn parse_project_mcp_config(project_path: &str) -> Vec<McpServerConfig> {
-        let mcp_path = Path::new(project_path).join(".mcp.json");
-
-        let content = match std::fs::read_to_string(&mcp_path) {
-            Ok(c) => c,
-            Err(_) => return Vec::new(),
-        };
-
-        let parsed: McpJsonFile = match serde_json::from_str(&content) {
-            Ok(p) => p,
-            Err(e) => {
-                log::warn!("Failed to parse .mcp.json at {:?}: {}", mcp_path, e);
-                return Vec::new();
-            }
-        };
-
-        parse_mcp_entries(parsed.mcp_servers, McpServerSource::Project)
+        read_mcp_json(
+            &Path::new(project_path).join(".mcp.json"),
+            McpServerSource::Project,
+        )
     }
 
     /// Parses MCP servers from ~/.claude.json for a given project.
@@ -305,6 +298,121 @@ impl McpManager {
     }
 }
 
+/// Reads the servers of a `.mcp.json` file, tagging each with `source`.
+///
+/// Returns an empty vec if the file doesn't exist or can't be parsed.
+fn read_mcp_json(mcp_path: &Path, source: McpServerSource) -> Vec<McpServerConfig> {
+    let content = match std::fs::read_to_string(mcp_path) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+
+    let parsed: McpJsonFile = match serde_json::from_str(&content) {
+        Ok(p) => p,
+        Err(e) => {
+            log::warn!("Failed to parse .mcp.json at {:?}: {}", mcp_path, e);
+            return Vec::new();
+        }
+    };
+
+    parse_mcp_entries(parsed.mcp_servers, source)
+}
+
+/// Resolves the MCP servers bundled in a plugin's `.mcp.json`, sorted by name.
+///
+/// Each server is attributed to `plugin_id` via `McpServerSource::Plugin`.
+pub fn discover_plugin_servers(plugin_id: &str, plugin_dir: &Path) -> Vec<McpServerConfig> {
+    let source = McpServerSource::Plugin {
+        plugin_id: plugin_id.to_string(),
+    };
+    let mut servers = read_mcp_json(&plugin_dir.join(".mcp.json"), source);
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    servers
+}
+
+/// Path of a directory's MCP defaults file, relative to the directory.
+pub const MCP_DEFAULTS_FILE: &str = ".maestro/mcp-defaults.json";
+
+/// Raw structure of `.maestro/mcp-defaults.json`: server name to whether it
+/// is enabled by default.
+#[derive(Debug, Deserialize)]
+struct McpDefaultsFile {
+    #[serde(default)]
+    servers: BTreeMap<String, bool>,
+}
+
+/// MCP defaults merged from a project and its ancestors up to the git root.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResolvedMcpDefaults {
+    /// Servers enabled after merging, sorted by name.
+    pub enabled: Vec<String>,
+    /// Defaults files that contributed, outermost first. Empty when the
+    /// saved selection applied, since it replaces what the files say.
+    pub sources: Vec<String>,
+    /// Whether the selection saved in Maestro for the project applied.
+    pub saved: bool,
+}
+
+/// Merges every `.maestro/mcp-defaults.json` from the git root down to
+/// `project_path`; a nearer file's setting for a server wins over an
+/// ancestor's.
+///
+/// `saved` is the selection saved for the project itself and is the
+/// innermost layer. It lists every server the user left enabled, so it
+/// replaces the files' result rather than merging into it, and no file is
+/// reported as a source.
+///
+/// Outside a git repository only `project_path` itself is consulted. Files
+/// that can't be parsed are skipped with a warning.
+pub fn resolve_mcp_defaults(project_path: &Path, saved: Option<&[String]>) -> ResolvedMcpDefaults {
+    if let Some(names) = saved {
+        let names: BTreeSet<&String> = names.iter().collect();
+        return ResolvedMcpDefaults {
+            enabled: names.into_iter().cloned().collect(),
+            sources: Vec::new(),
+            saved: true,
+        };
+    }
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let mut reached_root = false;
+    for dir in project_path.ancestors() {
+        dirs.push(dir.to_path_buf());
+        if dir.join(".git").exists() {
+            reached_root = true;
+            break;
+        }
+    }
+    if !reached_root {
+        dirs.truncate(1);
+    }
+
+    let mut servers = BTreeMap::new();
+    let mut sources = Vec::new();
+    for dir in dirs.iter().rev() {
+        let path = dir.join(MCP_DEFAULTS_FILE);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        match serde_json::from_str::<McpDefaultsFile>(&content) {
+            Ok(file) => {
+                servers.extend(file.servers);
+                sources.push(path.to_string_lossy().into_owned());
+            }
+            Err(e) => log::warn!("Failed to parse MCP defaults at {:?}: {}", path, e),
+        }
+    }
+
+    ResolvedMcpDefaults {
+        enabled: servers
+            .into_iter()
+            .filter_map(|(name, enabled)| enabled.then_some(name))
+            .collect(),
+        sources,
+        saved: false,
+    }
+}
+
 /// Parses a single MCP server entry from a serde_json::Value.
 fn parse_mcp_value_entry(
     name: &str,
@@ -394,4 +502,72 @@ mod tests {
         assert_eq!(servers[0].name, "test-server");
         assert_eq!(servers[0].source, McpServerSource::Project);
     }
+
+    #[test]
+    fn test_plugin_servers_report_plugin_id() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".mcp.json"),
+            r#"{"mcpServers": {
+                "search": {"type": "http", "url": "http://localhost:4000"},
+                "db": {"type": "stdio", "command": "db-mcp"}
+            }}"#,
+        )
+        .unwrap();
+
+        let servers = discover_plugin_servers("plugin:toolbox", dir.path());
+        let names: Vec<&str> = servers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["db", "search"]);
+        for server in &servers {
+            assert_eq!(
+                server.source,
+                McpServerSource::Plugin {
+                    plugin_id: "plugin:toolbox".to_string()
+                }
+            );
+        }
+
+        let json = serde_json::to_value(&servers[0]).unwrap();
+        assert_eq!(json["source"]["plugin"]["plugin_id"], "plugin:toolbox");
+
+        // Plugins without a bundled .mcp.json contribute nothing
+        let empty = tempfile::tempdir().unwrap();
+        assert!(discover_plugin_servers("plugin:bare", empty.path()).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_mcp_defaults_nearest_file_wins() {
+        let repo = tempfile::tempdir().unwrap();
+        let sub = repo.path().join("packages/web");
+        std::fs::create_dir_all(repo.path().join(".git")).unwrap();
+        std::fs::create_dir_all(sub.join(".maestro")).unwrap();
+        std::fs::create_dir_all(repo.path().join(".maestro")).unwrap();
+        std::fs::write(
+            repo.path().join(MCP_DEFAULTS_FILE),
+            r#"{"servers": {"github": true, "sentry": true, "db": false}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            sub.join(MCP_DEFAULTS_FILE),
+            r#"{"servers": {"sentry": false, "db": true, "browser": true}}"#,
+        )
+        .unwrap();
+
+        let resolved = resolve_mcp_defaults(&sub, None);
+        assert_eq!(resolved.enabled, vec!["browser", "db", "github"]);
+        let source = |dir: &Path| dir.join(MCP_DEFAULTS_FILE).to_string_lossy().into_owned();
+        assert_eq!(resolved.sources, vec![source(repo.path()), source(&sub)]);
+
+        // The root only sees its own file
+        let root = resolve_mcp_defaults(repo.path(), None);
+        assert_eq!(root.enabled, vec!["github", "sentry"]);
+        assert_eq!(root.sources.len(), 1);
+
+        // A selection saved in Maestro is the innermost layer
+        let saved = ["sentry".to_string(), "browser".to_string()];
+        let resolved = resolve_mcp_defaults(&sub, Some(&saved));
+        assert_eq!(resolved.enabled, vec!["browser", "sentry"]);
+        assert!(resolved.saved);
+        assert!(resolved.sources.is_empty());
+    }
 }
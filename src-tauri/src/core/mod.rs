@@ -1,3 +1,4 @@
+pub mod dotenv;
 pub mod error;
 pub mod font_detector;
 pub mod marketplace_error;
@@ -9,7 +10,9 @@ pub mod plugin_config_writer;
 pub mod plugin_manager;
 pub mod process_manager;
 pub mod process_tree;
+pub mod project_permissions;
 pub mod session_manager;
+pub mod session_recorder;
 pub mod status_server;
 pub mod terminal_backend;
 pub mod windows_process;
@@ -24,12 +27,12 @@ pub use font_detector::{detect_available_fonts, is_font_available, AvailableFont
 pub use marketplace_manager::MarketplaceManager;
 pub use mcp_manager::McpManager;
 pub use plugin_manager::PluginManager;
-pub use process_manager::ProcessManager;
+pub use process_manager::{EnvMode, ProcessManager, SpawnOptions};
 pub use session_manager::SessionManager;
 pub use status_server::StatusServer;
 pub use terminal_backend::{
-    BackendCapabilities, BackendType, SubscriptionHandle, TerminalBackend, TerminalConfig,
-    TerminalError, TerminalState,
+    BackendCapabilities, BackendType, Color, CommandMarks, CursorShape, SubscriptionHandle,
+    TerminalBackend, TerminalConfig, TerminalError, TerminalState,
 };
 pub use worktree_manager::WorktreeManager;
 pub use xterm_backend::XtermPassthroughBackend;
@@ -17,47 +17,42 @@ use std::path::Path;
 
 use serde_json::{json, Value};
 
-/// Merges `enabledPlugins` into an existing settings.local.json file.
-///
-/// Preserves user-defined settings while replacing the `enabledPlugins` object.
+use super::mcp_config_writer::{atomic_write, dir_lock};
+
+/// Merges `enabled_plugins` into the `enabledPlugins` object of a settings
+/// value, keeping entries for plugins not in the map and all other keys.
 /// Also removes the legacy `plugins` array if present.
-fn merge_with_existing(
-    settings_path: &Path,
+fn merge_enabled_plugins(
+    config: &mut Value,
     enabled_plugins: &HashMap<String, bool>,
-) -> Result<Value, String> {
-    let mut config: Value = if settings_path.exists() {
-        let content = std::fs::read_to_string(settings_path)
-            .map_err(|e| format!("Failed to read existing settings.local.json: {}", e))?;
-
-        serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse existing settings.local.json: {}", e))?
-    } else {
-        json!({})
-    };
+) -> Result<(), String> {
+    let obj = config
+        .as_object_mut()
+        .ok_or("settings.local.json is not a JSON object")?;
 
     // Remove legacy plugins array if present
-    if let Some(obj) = config.as_object_mut() {
-        obj.remove("plugins");
-    }
-
-    // Build the enabledPlugins object
-    let plugins_obj: serde_json::Map<String, Value> = enabled_plugins
-        .iter()
-        .map(|(id, enabled)| (id.clone(), json!(*enabled)))
-        .collect();
+    obj.remove("plugins");
 
-    config["enabledPlugins"] = Value::Object(plugins_obj);
-
-    Ok(config)
+    let mut merged = match obj.remove("enabledPlugins") {
+        Some(Value::Object(existing)) => existing,
+        _ => serde_json::Map::new(),
+    };
+    for (id, enabled) in enabled_plugins {
+        merged.insert(id.clone(), json!(*enabled));
+    }
+    obj.insert("enabledPlugins".to_string(), Value::Object(merged));
+    Ok(())
 }
 
 /// Writes plugin enabled/disabled state to the session's .claude/settings.local.json.
 ///
 /// This function:
 /// 1. Creates the .claude directory if it doesn't exist
-/// 2. Builds the `enabledPlugins` object from the provided map
-/// 3. Merges with any existing settings.local.json (preserving other settings)
-/// 4. Writes the final config
+/// 2. Merges the provided map into the existing `enabledPlugins` object, so
+///    entries written by other sessions sharing the directory are kept
+/// 3. Preserves all other settings in settings.local.json
+/// 4. Writes the final config atomically, serialized with other writers of
+///    the same directory
 ///
 /// # Arguments
 ///
@@ -69,23 +64,21 @@ pub async fn write_session_plugin_config(
 ) -> Result<(), String> {
     // Create .claude directory if needed
     let claude_dir = working_dir.join(".claude");
-    if !claude_dir.exists() {
-        tokio::fs::create_dir_all(&claude_dir)
-            .await
-            .map_err(|e| format!("Failed to create .claude directory: {}", e))?;
-    }
+    tokio::fs::create_dir_all(&claude_dir)
+        .await
+        .map_err(|e| format!("Failed to create .claude directory: {}", e))?;
+
+    let lock = dir_lock(&claude_dir);
+    let _guard = lock.lock().await;
 
     // Merge with existing settings
     let settings_path = claude_dir.join("settings.local.json");
-    let final_config = merge_with_existing(&settings_path, enabled_plugins)?;
+    let mut config = read_settings(&settings_path).await?;
+    merge_enabled_plugins(&mut config, enabled_plugins)?;
 
-    // Write the file
-    let content = serde_json::to_string_pretty(&final_config)
+    let content = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Failed to serialize plugin config: {}", e))?;
-
-    tokio::fs::write(&settings_path, content)
-        .await
-        .map_err(|e| format!("Failed to write settings.local.json: {}", e))?;
+    atomic_write(&settings_path, &content).await?;
 
     let enabled_count = enabled_plugins.values().filter(|v| **v).count();
     let disabled_count = enabled_plugins.len() - enabled_count;
@@ -108,17 +101,16 @@ pub async fn write_session_plugin_config(
 ///
 /// * `working_dir` - Directory containing the `.claude/settings.local.json` file
 pub async fn remove_session_plugin_config(working_dir: &Path) -> Result<(), String> {
-    let settings_path = working_dir.join(".claude/settings.local.json");
+    let claude_dir = working_dir.join(".claude");
+    let settings_path = claude_dir.join("settings.local.json");
     if !settings_path.exists() {
         return Ok(());
     }
 
-    let content = tokio::fs::read_to_string(&settings_path)
-        .await
-        .map_err(|e| format!("Failed to read settings.local.json: {}", e))?;
+    let lock = dir_lock(&claude_dir);
+    let _guard = lock.lock().await;
 
-    let mut config: Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse settings.local.json: {}", e))?;
+    let mut config = read_settings(&settings_path).await?;
 
     // Remove both enabledPlugins and legacy plugins array
     if let Some(obj) = config.as_object_mut() {
@@ -139,15 +131,22 @@ pub async fn remove_session_plugin_config(working_dir: &Path) -> Result<(), Stri
         // Otherwise, write the updated config
         let output = serde_json::to_string_pretty(&config)
             .map_err(|e| format!("Failed to serialize config: {}", e))?;
-
-        tokio::fs::write(&settings_path, output)
-            .await
-            .map_err(|e| format!("Failed to write settings.local.json: {}", e))?;
+        atomic_write(&settings_path, &output).await?;
     }
 
     Ok(())
 }
 
+/// Reads a settings file, treating a missing file as an empty object.
+async fn read_settings(settings_path: &Path) -> Result<Value, String> {
+    match tokio::fs::read_to_string(settings_path).await {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse settings.local.json: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(json!({})),
+        Err(e) => Err(format!("Failed to read settings.local.json: {}", e)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,4 +304,39 @@ mod tests {
         let result = remove_session_plugin_config(dir.path()).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_write_merges_into_existing_enabled_plugins() {
+        let dir = tempdir().unwrap();
+        let claude_dir = dir.path().join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        let existing = json!({
+            "permissions": { "allow": ["Bash(ls)"] },
+            "enabledPlugins": { "user-pick@official": true, "lint@official": true }
+        });
+        std::fs::write(
+            claude_dir.join("settings.local.json"),
+            serde_json::to_string(&existing).unwrap(),
+        )
+        .unwrap();
+
+        let mut plugins = HashMap::new();
+        plugins.insert("lint@official".to_string(), false);
+        plugins.insert("deploy@official".to_string(), true);
+        write_session_plugin_config(dir.path(), &plugins)
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(claude_dir.join("settings.local.json")).unwrap();
+        let config: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(config["permissions"], existing["permissions"]);
+        assert_eq!(
+            config["enabledPlugins"],
+            json!({
+                "user-pick@official": true,
+                "lint@official": false,
+                "deploy@official": true
+            })
+        );
+    }
 }
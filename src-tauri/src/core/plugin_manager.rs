@@ -16,7 +16,10 @@ use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::mcp_manager;
 
 /// The source/origin of a skill.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +85,10 @@ pub struct SkillConfig {
     pub context: Option<String>,
     /// Subagent type when context="fork".
     pub agent: Option<String>,
+    /// Author-defined frontmatter keys Maestro doesn't interpret
+    /// (e.g. `category`, `tags`), for grouping and filtering in the UI.
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
 }
 
 /// The source/origin of a plugin bundle.
@@ -101,6 +108,38 @@ pub enum PluginSource {
     CliInstalled,
 }
 
+/// Scope an entry in installed_plugins.json was installed under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallScope {
+    /// Installed for the user across all projects.
+    User,
+    /// Installed for a project and shared via its checked-in settings.
+    Project,
+    /// Installed for a project but kept out of version control.
+    Local,
+}
+
+impl InstallScope {
+    fn parse(scope: &str) -> Option<Self> {
+        match scope {
+            "user" => Some(Self::User),
+            "project" => Some(Self::Project),
+            "local" => Some(Self::Local),
+            _ => None,
+        }
+    }
+
+    /// Higher wins when the same plugin is installed under several scopes.
+    fn priority(self) -> u8 {
+        match self {
+            Self::Project => 2,
+            Self::Local => 1,
+            Self::User => 0,
+        }
+    }
+}
+
 /// Hook configuration (simplified for now).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookConfig {
@@ -132,6 +171,10 @@ pub struct PluginConfig {
     /// Claude CLI plugin ID (e.g. "name@marketplace") for enabledPlugins config.
     /// None for legacy or builtin plugins.
     pub cli_id: Option<String>,
+    /// Scope the CLI installed this plugin under. None for plugins not
+    /// discovered through installed_plugins.json.
+    #[serde(default)]
+    pub install_scope: Option<InstallScope>,
     /// IDs of skills this plugin provides.
     pub skills: Vec<String>,
     /// Names of MCP servers this plugin references.
@@ -199,6 +242,78 @@ struct RawSkillEntry {
     args: Option<Vec<String>>,
 }
 
+impl RawSkillEntry {
+    /// Builds the executable skill type, or the reason the entry is skipped.
+    fn to_skill_type(&self) -> Result<SkillType, String> {
+        match self.skill_type.as_str() {
+            "prompt" => self
+                .prompt
+                .clone()
+                .map(|prompt| SkillType::Prompt { prompt })
+                .ok_or_else(|| "prompt skill has no 'prompt' field".to_string()),
+            "file" => self
+                .path
+                .clone()
+                .map(|path| SkillType::File { path })
+                .ok_or_else(|| "file skill has no 'path' field".to_string()),
+            "command" => self
+                .command
+                .clone()
+                .map(|command| SkillType::Command {
+                    command,
+                    args: self.args.clone().unwrap_or_default(),
+                })
+                .ok_or_else(|| "command skill has no 'command' field".to_string()),
+            other => Err(format!(
+                "unknown skill type '{}' (expected prompt, file, or command)",
+                other
+            )),
+        }
+    }
+}
+
+/// A `.plugins.json` skill entry that is ignored when loading.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedLegacyEntry {
+    pub id: String,
+    pub reason: String,
+}
+
+/// What a project's legacy `.plugins.json` contributes, as reported by
+/// `PluginManager::validate_legacy_plugins`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LegacyPluginsReport {
+    /// IDs of skills that load, sorted.
+    pub valid_skills: Vec<String>,
+    /// Skills that are dropped, with why.
+    pub skipped: Vec<SkippedLegacyEntry>,
+    /// One step per valid skill describing its `.claude/` equivalent.
+    pub suggested_migration: Vec<String>,
+}
+
+/// Describes where a legacy skill lives in the modern `.claude/` layout.
+fn legacy_migration_step(id: &str, skill_type: &SkillType) -> String {
+    match skill_type {
+        SkillType::Prompt { .. } => {
+            format!("{}: move the prompt into .claude/commands/{}.md", id, id)
+        }
+        SkillType::File { path } => {
+            format!("{}: move {} to .claude/skills/{}/SKILL.md", id, path, id)
+        }
+        SkillType::Command { command, args } => {
+            let mut line = command.clone();
+            for arg in args {
+                line.push(' ');
+                line.push_str(arg);
+            }
+            format!(
+                "{}: create .claude/commands/{}.md that runs `{}` via a `!` bash line",
+                id, id, line
+            )
+        }
+    }
+}
+
 /// Raw plugin entry from JSON.
 #[derive(Debug, Deserialize)]
 struct RawPluginEntry {
@@ -258,6 +373,18 @@ struct InstalledPluginEntry {
     install_path: String,
     #[serde(default)]
     version: Option<String>,
+    /// Project the entry belongs to, for "project" and "local" scopes.
+    #[serde(default)]
+    project_path: Option<String>,
+}
+
+/// A CLI-installed plugin selected from installed_plugins.json.
+#[derive(Debug)]
+struct InstalledPlugin {
+    cli_id: String,
+    install_path: String,
+    version: String,
+    scope: InstallScope,
 }
 
 /// Parsed YAML frontmatter from a skill/command markdown file.
@@ -272,6 +399,8 @@ struct Frontmatter {
     model: Option<String>,
     context: Option<String>,
     agent: Option<String>,
+    /// Unrecognized top-level keys with scalar values.
+    extra: HashMap<String, String>,
 }
 
 impl Frontmatter {
@@ -297,8 +426,8 @@ impl Frontmatter {
         let yaml_content = &after_first[..end_idx];
 
         // Parse line by line (simple key: value parsing)
-        for line in yaml_content.lines() {
-            let line = line.trim();
+        for raw_line in yaml_content.lines() {
+            let line = raw_line.trim();
             if line.is_empty() || line.starts_with('#') {
                 continue;
             }
@@ -324,6 +453,14 @@ impl Frontmatter {
                 "model" => fm.model = Some(value.to_string()),
                 "context" => fm.context = Some(value.to_string()),
                 "agent" => fm.agent = Some(value.to_string()),
+                // Skip nested keys, list items, and block or flow collections
+                _ if !raw_line.starts_with(char::is_whitespace)
+                    && !key.starts_with('-')
+                    && !value.is_empty()
+                    && !value.starts_with(['[', '{', '|', '>']) =>
+                {
+                    fm.extra.insert(key.to_string(), value.to_string());
+                }
                 _ => {}
             }
         }
@@ -332,6 +469,47 @@ impl Frontmatter {
     }
 }
 
+/// Returns the markdown body after the frontmatter block (or all of it if
+/// there is no frontmatter).
+fn strip_frontmatter(content: &str) -> &str {
+    let trimmed = content.trim_start();
+    let Some(after_first) = trimmed.strip_prefix("---") else {
+        return content;
+    };
+    let Some(end_idx) = after_first.find("\n---") else {
+        return content;
+    };
+    // Skip the rest of the closing `---` line
+    let after_close = &after_first[end_idx + 4..];
+    match after_close.find('\n') {
+        Some(newline) => &after_close[newline + 1..],
+        None => "",
+    }
+}
+
+/// Extracts the first prose paragraph of a markdown body, skipping leading
+/// headings, and truncates it to `max_chars` (appending `…`).
+fn first_paragraph(body: &str, max_chars: usize) -> Option<String> {
+    let paragraph = body
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with('#'))
+        .take_while(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if paragraph.is_empty() {
+        return None;
+    }
+    if paragraph.chars().count() <= max_chars {
+        return Some(paragraph);
+    }
+    let mut truncated: String = paragraph.chars().take(max_chars).collect();
+    truncated.truncate(truncated.trim_end().len());
+    truncated.push('…');
+    Some(truncated)
+}
+
 /// Scans a skills directory for SKILL.md files in subdirectories.
 /// Pattern: `dir/*/SKILL.md`
 fn scan_skills_directory(dir: &Path, source: SkillSource) -> Vec<SkillConfig> {
@@ -387,6 +565,7 @@ fn scan_skills_directory(dir: &Path, source: SkillSource) -> Vec<SkillConfig> {
             model: fm.model,
             context: fm.context,
             agent: fm.agent,
+            metadata: fm.extra,
         });
     }
 
@@ -450,6 +629,7 @@ fn scan_commands_directory(dir: &Path, source: SkillSource) -> Vec<SkillConfig>
             model: fm.model,
             context: fm.context,
             agent: fm.agent,
+            metadata: fm.extra,
         });
     }
 
@@ -512,18 +692,21 @@ fn scan_plugins_directory(dir: &Path) -> Vec<(PluginConfig, Vec<SkillConfig>)> {
 
         // Derive CLI ID from manifest marketplace_id + plugin_id/name
         let cli_id = derive_cli_id_from_manifest(&manifest, &plugin_name);
+        let id = format!("plugin:{}", plugin_name);
+        let mcp_servers = plugin_mcp_server_names(&id, &plugin_dir);
 
         let plugin = PluginConfig {
-            id: format!("plugin:{}", plugin_name),
+            id,
             name: manifest.name.clone(),
             version: manifest.version.unwrap_or_else(|| "0.0.0".to_string()),
             description: manifest.description.unwrap_or_default(),
             icon: manifest.icon,
             plugin_source: PluginSource::Installed,
             cli_id,
+            install_scope: None,
             skills: skill_ids,
-            mcp_servers: Vec::new(), // TODO: parse .mcp.json if present
-            hooks: Vec::new(),       // TODO: parse hooks.json if present
+            mcp_servers,
+            hooks: Vec::new(), // TODO: parse hooks.json if present
             enabled_by_default: true,
             path: Some(plugin_dir.to_string_lossy().to_string()),
         };
@@ -534,6 +717,14 @@ fn scan_plugins_directory(dir: &Path) -> Vec<(PluginConfig, Vec<SkillConfig>)> {
     results
 }
 
+/// Names of the MCP servers bundled in a plugin's `.mcp.json`.
+fn plugin_mcp_server_names(plugin_id: &str, plugin_dir: &Path) -> Vec<String> {
+    mcp_manager::discover_plugin_servers(plugin_id, plugin_dir)
+        .into_iter()
+        .map(|s| s.name)
+        .collect()
+}
+
 /// Derives a Claude CLI plugin ID from a plugin manifest.
 ///
 /// If the manifest has marketplace_id, constructs "name@marketplace-short-name".
@@ -565,8 +756,10 @@ fn marketplace_id_to_short(marketplace_id: &str) -> &str {
 
 /// Parses ~/.claude/plugins/installed_plugins.json to discover CLI-installed plugins.
 ///
-/// Returns tuples of (cli_id, install_path, version).
-fn parse_installed_plugins_json(plugins_dir: &Path) -> Vec<(String, String, String)> {
+/// Entries of every scope are considered. "project" and "local" entries only
+/// apply when their `projectPath` matches `project_path`. When a plugin has
+/// several applicable entries, project wins over local, which wins over user.
+fn parse_installed_plugins_json(plugins_dir: &Path, project_path: &Path) -> Vec<InstalledPlugin> {
     let json_path = plugins_dir.join("installed_plugins.json");
     let content = match fs::read_to_string(&json_path) {
         Ok(c) => c,
@@ -583,10 +776,26 @@ fn parse_installed_plugins_json(plugins_dir: &Path) -> Vec<(String, String, Stri
 
     let mut results = Vec::new();
     for (cli_id, entries) in parsed.plugins {
-        // Use the first user-scope entry (or any entry)
-        if let Some(entry) = entries.into_iter().find(|e| e.scope == "user").or_else(|| None) {
-            let version = entry.version.unwrap_or_else(|| "0.0.0".to_string());
-            results.push((cli_id, entry.install_path, version));
+        let best = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let scope = InstallScope::parse(&entry.scope)?;
+                let applies = scope == InstallScope::User
+                    || entry
+                        .project_path
+                        .as_deref()
+                        .is_none_or(|p| Path::new(p) == project_path);
+                applies.then_some((scope, entry))
+            })
+            .max_by_key(|(scope, _)| scope.priority());
+
+        if let Some((scope, entry)) = best {
+            results.push(InstalledPlugin {
+                cli_id,
+                install_path: entry.install_path,
+                version: entry.version.unwrap_or_else(|| "0.0.0".to_string()),
+                scope,
+            });
         }
     }
 
@@ -601,6 +810,7 @@ fn scan_cli_installed_plugin(
     cli_id: &str,
     install_path: &str,
     version: &str,
+    scope: InstallScope,
 ) -> Option<(PluginConfig, Vec<SkillConfig>)> {
     let plugin_dir = Path::new(install_path);
     if !plugin_dir.exists() {
@@ -635,9 +845,11 @@ fn scan_cli_installed_plugin(
         .and_then(|content| serde_json::from_str(&content).ok());
 
     let skill_ids: Vec<String> = plugin_skills.iter().map(|s| s.id.clone()).collect();
+    let id = format!("plugin:{}", plugin_name);
+    let mcp_servers = plugin_mcp_server_names(&id, plugin_dir);
 
     let plugin = PluginConfig {
-        id: format!("plugin:{}", plugin_name),
+        id,
         name: manifest
             .as_ref()
             .map(|m| m.name.clone())
@@ -650,8 +862,9 @@ fn scan_cli_installed_plugin(
         icon: manifest.as_ref().and_then(|m| m.icon.clone()),
         plugin_source: PluginSource::CliInstalled,
         cli_id: Some(cli_id.to_string()),
+        install_scope: Some(scope),
         skills: skill_ids,
-        mcp_servers: Vec::new(),
+        mcp_servers,
         hooks: Vec::new(),
         enabled_by_default: true,
         path: Some(install_path.to_string()),
@@ -670,6 +883,123 @@ fn source_prefix(source: &SkillSource) -> &'static str {
     }
 }
 
+/// The user's `~/.claude` directory, if a home directory can be determined.
+fn personal_claude_dir() -> Option<PathBuf> {
+    BaseDirs::new().map(|base_dirs| base_dirs.home_dir().join(".claude"))
+}
+
+/// Scans `<claude_dir>/skills/*/SKILL.md` then `<claude_dir>/commands/*.md`.
+fn scan_claude_dir(claude_dir: &Path, source: SkillSource) -> Vec<SkillConfig> {
+    let mut skills = Vec::new();
+
+    let skills_dir = claude_dir.join("skills");
+    if skills_dir.exists() {
+        skills.extend(scan_skills_directory(&skills_dir, source.clone()));
+    }
+
+    let commands_dir = claude_dir.join("commands");
+    if commands_dir.exists() {
+        skills.extend(scan_commands_directory(&commands_dir, source));
+    }
+
+    skills
+}
+
+/// Scans manually installed plugins in `plugins_dir`, then CLI-installed
+/// plugins from its `installed_plugins.json` that weren't already found.
+fn scan_installed_plugins(
+    plugins_dir: &Path,
+    project: &Path,
+) -> (Vec<PluginConfig>, Vec<SkillConfig>) {
+    let mut plugins: Vec<PluginConfig> = Vec::new();
+    let mut skills = Vec::new();
+    if !plugins_dir.exists() {
+        return (plugins, skills);
+    }
+
+    // Track which plugin names we've already seen from manual installs
+    let mut seen_plugin_names: HashSet<String> = HashSet::new();
+
+    for (plugin, plugin_skills) in scan_plugins_directory(plugins_dir) {
+        seen_plugin_names.insert(plugin.name.clone());
+        plugins.push(plugin);
+        skills.extend(plugin_skills);
+    }
+
+    // CLI-installed plugins from installed_plugins.json
+    // These live in cache/ subdirectories and aren't found by scan_plugins_directory
+    for installed in parse_installed_plugins_json(plugins_dir, project) {
+        let cli_id = installed.cli_id;
+        let plugin_name = cli_id.split('@').next().unwrap_or(&cli_id);
+
+        // Skip if already discovered via manual install
+        if seen_plugin_names.contains(plugin_name) {
+            // But update the existing plugin's cli_id if it doesn't have one
+            if let Some(existing) = plugins.iter_mut().find(|p| p.name == plugin_name) {
+                if existing.cli_id.is_none() {
+                    existing.cli_id = Some(cli_id.clone());
+                    existing.install_scope = Some(installed.scope);
+                }
+            }
+            continue;
+        }
+
+        if let Some((plugin, plugin_skills)) = scan_cli_installed_plugin(
+            &cli_id,
+            &installed.install_path,
+            &installed.version,
+            installed.scope,
+        ) {
+            seen_plugin_names.insert(plugin_name.to_string());
+            plugins.push(plugin);
+            skills.extend(plugin_skills);
+        }
+    }
+
+    (plugins, skills)
+}
+
+/// Unwraps a `spawn_blocking` scan, treating a panicked scan as empty.
+fn joined_scan<T: Default>(result: Result<T, tokio::task::JoinError>, what: &str) -> T {
+    result.unwrap_or_else(|e| {
+        log::warn!("Scanning {} failed: {}", what, e);
+        T::default()
+    })
+}
+
+/// Results of the independent scans behind plugin discovery, one field per
+/// scan so they can run in any order (or concurrently).
+struct DiscoveryScans {
+    /// `<project>/.claude` skills and commands.
+    project: Vec<SkillConfig>,
+    /// `~/.claude` skills and commands.
+    personal: Vec<SkillConfig>,
+    /// Installed and CLI-installed plugins with their skills.
+    plugins: (Vec<PluginConfig>, Vec<SkillConfig>),
+    /// Legacy `.plugins.json`.
+    legacy: ProjectPlugins,
+}
+
+impl DiscoveryScans {
+    /// Concatenates the scans in priority order and deduplicates skills, so
+    /// the result doesn't depend on which scan finished first.
+    fn merge(self) -> ProjectPlugins {
+        let (mut all_plugins, plugin_skills) = self.plugins;
+
+        let mut all_skills = self.project;
+        all_skills.extend(self.personal);
+        all_skills.extend(plugin_skills);
+        all_skills.extend(self.legacy.skills);
+        all_plugins.extend(self.legacy.plugins);
+
+        // Deduplicate skills (project > personal > plugin > legacy)
+        ProjectPlugins {
+            skills: deduplicate_skills(all_skills),
+            plugins: all_plugins,
+        }
+    }
+}
+
 /// Deduplicates skills, preferring project > personal > plugin > legacy.
 fn deduplicate_skills(skills: Vec<SkillConfig>) -> Vec<SkillConfig> {
     let mut seen_names: HashSet<String> = HashSet::new();
@@ -687,28 +1017,106 @@ fn deduplicate_skills(skills: Vec<SkillConfig>) -> Vec<SkillConfig> {
     result
 }
 
+/// Maximum length (in characters) of `SkillPreview::body_excerpt`.
+const SKILL_PREVIEW_MAX_CHARS: usize = 280;
+
+/// Preview text for the skill picker.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillPreview {
+    /// `description` from the skill's frontmatter, if any.
+    pub frontmatter_description: Option<String>,
+    /// First paragraph of the skill body, truncated for display.
+    pub body_excerpt: Option<String>,
+    /// Autocomplete hint for the skill's arguments.
+    pub argument_hint: Option<String>,
+}
+
+/// Maximum number of fuzzy candidates returned by `resolve_skill`.
+const MAX_SKILL_SUGGESTIONS: usize = 5;
+
+/// Result of resolving a typed slash-command name against a session's skills.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SkillResolution {
+    /// The query names exactly one enabled skill.
+    Exact { skill: Box<SkillConfig> },
+    /// No exact match; near matches ordered best first.
+    Suggestions { candidates: Vec<SkillConfig> },
+    /// Nothing enabled is close to the query.
+    NotFound,
+}
+
+/// Levenshtein edit distance between two strings, by `char`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Returns true if every char of `needle` appears in `haystack` in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut rest = haystack.chars();
+    needle.chars().all(|c| rest.any(|h| h == c))
+}
+
+/// Scores how well `query` matches `candidate` (both lowercase); lower is better.
+///
+/// Prefix matches rank first, then typos within a third of the query length
+/// (at least one edit), then abbreviations that appear as a subsequence.
+/// Returns `None` when the candidate is not a plausible match.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<(u8, usize)> {
+    if candidate.starts_with(query) {
+        return Some((0, candidate.len() - query.len()));
+    }
+
+    let distance = edit_distance(query, candidate);
+    if distance <= (query.chars().count() / 3).max(1) {
+        return Some((1, distance));
+    }
+
+    if query.chars().count() >= 2 && is_subsequence(query, candidate) {
+        return Some((2, candidate.len() - query.len()));
+    }
+
+    None
+}
+
 /// Session-specific key for enabled items lookup.
 type SessionKey = (String, u32); // (project_path, session_id)
 
 /// Manages plugin/skill discovery and per-session enabled state.
 ///
 /// Thread-safe via `DashMap` — can be accessed from multiple async tasks.
+/// Clones share the same state, so a stale cache entry can be re-discovered
+/// on a background task.
+#[derive(Clone)]
 pub struct PluginManager {
     /// Cached plugins/skills per project path (canonicalized).
-    project_plugins: DashMap<String, ProjectPlugins>,
+    project_plugins: Arc<DashMap<String, ProjectPlugins>>,
     /// Enabled skill IDs per (project_path, session_id).
-    session_enabled_skills: DashMap<SessionKey, Vec<String>>,
+    session_enabled_skills: Arc<DashMap<SessionKey, Vec<String>>>,
     /// Enabled plugin IDs per (project_path, session_id).
-    session_enabled_plugins: DashMap<SessionKey, Vec<String>>,
+    session_enabled_plugins: Arc<DashMap<SessionKey, Vec<String>>>,
 }
 
 impl PluginManager {
     /// Creates a new plugin manager with empty caches.
     pub fn new() -> Self {
         Self {
-            project_plugins: DashMap::new(),
-            session_enabled_skills: DashMap::new(),
-            session_enabled_plugins: DashMap::new(),
+            project_plugins: Arc::new(DashMap::new()),
+            session_enabled_skills: Arc::new(DashMap::new()),
+            session_enabled_plugins: Arc::new(DashMap::new()),
         }
     }
 
@@ -736,24 +1144,10 @@ impl PluginManager {
             .skills
             .into_iter()
             .filter_map(|(id, entry)| {
-                let skill_type = match entry.skill_type.as_str() {
-                    "prompt" => {
-                        let prompt = entry.prompt?;
-                        SkillType::Prompt { prompt }
-                    }
-                    "file" => {
-                        let path = entry.path.clone()?;
-                        SkillType::File { path }
-                    }
-                    "command" => {
-                        let command = entry.command?;
-                        SkillType::Command {
-                            command,
-                            args: entry.args.unwrap_or_default(),
-                        }
-                    }
-                    other => {
-                        log::warn!("Unknown skill type '{}' for skill '{}'", other, id);
+                let skill_type = match entry.to_skill_type() {
+                    Ok(skill_type) => skill_type,
+                    Err(reason) => {
+                        log::warn!("Skipping legacy skill '{}': {}", id, reason);
                         return None;
                     }
                 };
@@ -774,6 +1168,7 @@ impl PluginManager {
                     model: None,
                     context: None,
                     agent: None,
+                    metadata: HashMap::new(),
                 })
             })
             .collect();
@@ -799,6 +1194,7 @@ impl PluginManager {
                     icon: entry.icon,
                     plugin_source,
                     cli_id: None,
+                    install_scope: None,
                     skills: entry.skills,
                     mcp_servers: entry.mcp_servers,
                     hooks: entry.hooks,
@@ -811,6 +1207,59 @@ impl PluginManager {
         ProjectPlugins { skills, plugins }
     }
 
+    /// Checks the legacy `.plugins.json` at `project_path`, reporting every
+    /// skill entry that would be skipped and how the valid ones map onto the
+    /// `.claude/` layout.
+    ///
+    /// Unlike `parse_legacy_plugins_json`, a missing or malformed file is an
+    /// error rather than an empty result.
+    pub fn validate_legacy_plugins(project_path: &str) -> Result<LegacyPluginsReport, String> {
+        let plugins_path = Path::new(project_path).join(".plugins.json");
+        let content = fs::read_to_string(&plugins_path)
+            .map_err(|e| format!("Cannot read {}: {}", plugins_path.display(), e))?;
+        let parsed: PluginsJsonFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", plugins_path.display(), e))?;
+
+        let mut entries: Vec<(String, RawSkillEntry)> = parsed.skills.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut report = LegacyPluginsReport {
+            valid_skills: Vec::new(),
+            skipped: Vec::new(),
+            suggested_migration: Vec::new(),
+        };
+        for (id, entry) in entries {
+            match entry.to_skill_type() {
+                Ok(skill_type) => {
+                    report
+                        .suggested_migration
+                        .push(legacy_migration_step(&id, &skill_type));
+                    report.valid_skills.push(id);
+                }
+                Err(reason) => report.skipped.push(SkippedLegacyEntry { id, reason }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Discovers all skills and plugins from multiple sources, sequentially.
+    /// Only used to check `discover_all_concurrent_in` against.
+    #[cfg(test)]
+    fn discover_all_in(project_path: &str, claude_dir: Option<&Path>) -> ProjectPlugins {
+        let project = Path::new(project_path);
+        DiscoveryScans {
+            project: scan_claude_dir(&project.join(".claude"), SkillSource::Project),
+            personal: claude_dir
+                .map(|dir| scan_claude_dir(dir, SkillSource::Personal))
+                .unwrap_or_default(),
+            plugins: claude_dir
+                .map(|dir| scan_installed_plugins(&dir.join("plugins"), project))
+                .unwrap_or_default(),
+            legacy: Self::parse_legacy_plugins_json(project_path),
+        }
+        .merge()
+    }
+
     /// Discovers all skills and plugins from multiple sources.
     ///
     /// Sources are scanned in priority order:
@@ -822,127 +1271,82 @@ impl PluginManager {
     /// 5b. CLI-installed plugins: `~/.claude/plugins/installed_plugins.json`
     /// 6. Legacy .plugins.json
     ///
-    /// Skills are deduplicated, with earlier sources taking priority.
-    fn discover_all(project_path: &str) -> ProjectPlugins {
-        let mut all_skills = Vec::new();
-        let mut all_plugins = Vec::new();
-
-        let project = Path::new(project_path);
-
-        // 1. Project skills: <project>/.claude/skills/*/SKILL.md
-        let project_skills_dir = project.join(".claude").join("skills");
-        if project_skills_dir.exists() {
-            all_skills.extend(scan_skills_directory(
-                &project_skills_dir,
-                SkillSource::Project,
-            ));
-        }
-
-        // 2. Project commands: <project>/.claude/commands/*.md
-        let project_commands_dir = project.join(".claude").join("commands");
-        if project_commands_dir.exists() {
-            all_skills.extend(scan_commands_directory(
-                &project_commands_dir,
-                SkillSource::Project,
-            ));
-        }
-
-        // Get home directory for personal/plugin locations
-        if let Some(base_dirs) = BaseDirs::new() {
-            let home = base_dirs.home_dir();
-            let claude_dir = home.join(".claude");
-
-            // 3. Personal skills: ~/.claude/skills/*/SKILL.md
-            let personal_skills_dir = claude_dir.join("skills");
-            if personal_skills_dir.exists() {
-                all_skills.extend(scan_skills_directory(
-                    &personal_skills_dir,
-                    SkillSource::Personal,
-                ));
-            }
-
-            // 4. Personal commands: ~/.claude/commands/*.md
-            let personal_commands_dir = claude_dir.join("commands");
-            if personal_commands_dir.exists() {
-                all_skills.extend(scan_commands_directory(
-                    &personal_commands_dir,
-                    SkillSource::Personal,
-                ));
-            }
-
-            // 5. Installed plugins: ~/.claude/plugins/*/
-            let plugins_dir = claude_dir.join("plugins");
-            if plugins_dir.exists() {
-                // Track which plugin names we've already seen from manual installs
-                let mut seen_plugin_names: HashSet<String> = HashSet::new();
-
-                for (plugin, plugin_skills) in scan_plugins_directory(&plugins_dir) {
-                    seen_plugin_names.insert(plugin.name.clone());
-                    all_plugins.push(plugin);
-                    all_skills.extend(plugin_skills);
-                }
-
-                // 5b. CLI-installed plugins from installed_plugins.json
-                // These live in cache/ subdirectories and aren't found by scan_plugins_directory
-                for (cli_id, install_path, version) in parse_installed_plugins_json(&plugins_dir) {
-                    let plugin_name = cli_id.split('@').next().unwrap_or(&cli_id);
-
-                    // Skip if already discovered via manual install
-                    if seen_plugin_names.contains(plugin_name) {
-                        // But update the existing plugin's cli_id if it doesn't have one
-                        if let Some(existing) = all_plugins.iter_mut().find(|p| p.name == plugin_name) {
-                            if existing.cli_id.is_none() {
-                                existing.cli_id = Some(cli_id.clone());
-                            }
-                        }
-                        continue;
-                    }
+    /// Skills are deduplicated, with earlier sources taking priority. The
+    /// independent scans (project, personal, plugin directories, legacy file)
+    /// run in parallel on tokio's blocking pool so the calling task isn't
+    /// tied up by filesystem I/O.
+    async fn discover_all_concurrent(project_path: &str) -> ProjectPlugins {
+        Self::discover_all_concurrent_in(project_path, personal_claude_dir()).await
+    }
 
-                    if let Some((plugin, plugin_skills)) =
-                        scan_cli_installed_plugin(&cli_id, &install_path, &version)
-                    {
-                        seen_plugin_names.insert(plugin_name.to_string());
-                        all_plugins.push(plugin);
-                        all_skills.extend(plugin_skills);
-                    }
-                }
-            }
-        }
+    /// `discover_all_concurrent` with the personal `~/.claude` directory
+    /// supplied by the caller.
+    async fn discover_all_concurrent_in(
+        project_path: &str,
+        claude_dir: Option<PathBuf>,
+    ) -> ProjectPlugins {
+        let project = PathBuf::from(project_path);
 
-        // 6. Legacy .plugins.json
-        let legacy = Self::parse_legacy_plugins_json(project_path);
-        all_skills.extend(legacy.skills);
-        all_plugins.extend(legacy.plugins);
+        let project_task = {
+            let dir = project.join(".claude");
+            tokio::task::spawn_blocking(move || scan_claude_dir(&dir, SkillSource::Project))
+        };
+        let personal_task = {
+            let dir = claude_dir.clone();
+            tokio::task::spawn_blocking(move || {
+                dir.map(|dir| scan_claude_dir(&dir, SkillSource::Personal))
+                    .unwrap_or_default()
+            })
+        };
+        let plugins_task = {
+            let project = project.clone();
+            tokio::task::spawn_blocking(move || {
+                claude_dir
+                    .map(|dir| scan_installed_plugins(&dir.join("plugins"), &project))
+                    .unwrap_or_default()
+            })
+        };
+        let legacy_task = {
+            let project_path = project_path.to_string();
+            tokio::task::spawn_blocking(move || Self::parse_legacy_plugins_json(&project_path))
+        };
 
-        // Deduplicate skills (project > personal > plugin > legacy)
-        let deduped_skills = deduplicate_skills(all_skills);
+        let (project, personal, plugins, legacy) =
+            tokio::join!(project_task, personal_task, plugins_task, legacy_task);
 
-        ProjectPlugins {
-            skills: deduped_skills,
-            plugins: all_plugins,
+        DiscoveryScans {
+            project: joined_scan(project, "project skills"),
+            personal: joined_scan(personal, "personal skills"),
+            plugins: joined_scan(plugins, "installed plugins"),
+            legacy: joined_scan(legacy, "legacy .plugins.json"),
         }
+        .merge()
     }
 
-    /// Gets the plugins/skills for a project, discovering from all sources if not cached.
-    pub fn get_project_plugins(&self, project_path: &str) -> ProjectPlugins {
-        // Return cached if available
+    /// Gets the plugins/skills for a project, discovering from all sources
+    /// with concurrent scans if not cached.
+    pub async fn get_project_plugins(&self, project_path: &str) -> ProjectPlugins {
         if let Some(plugins) = self.project_plugins.get(project_path) {
             return plugins.clone();
         }
+        self.refresh_project_plugins(project_path).await
+    }
 
-        // Discover and cache
-        let plugins = Self::discover_all(project_path);
+    /// Refreshes the cached plugins for a project by re-discovering from all
+    /// sources with concurrent scans.
+    pub async fn refresh_project_plugins(&self, project_path: &str) -> ProjectPlugins {
+        let plugins = Self::discover_all_concurrent(project_path).await;
         self.project_plugins
             .insert(project_path.to_string(), plugins.clone());
         plugins
     }
 
-    /// Refreshes the cached plugins for a project by re-discovering from all sources.
-    pub fn refresh_project_plugins(&self, project_path: &str) -> ProjectPlugins {
-        let plugins = Self::discover_all(project_path);
+    /// Returns the project paths whose plugins have been discovered.
+    pub fn known_projects(&self) -> Vec<String> {
         self.project_plugins
-            .insert(project_path.to_string(), plugins.clone());
-        plugins
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect()
     }
 
     /// Resolves Maestro internal plugin IDs to Claude CLI `enabledPlugins` map.
@@ -950,12 +1354,12 @@ impl PluginManager {
     /// Takes the list of enabled Maestro plugin IDs and returns a HashMap
     /// mapping CLI plugin IDs to their enabled state (true/false).
     /// Only plugins with a `cli_id` are included (standalone/legacy plugins are excluded).
-    pub fn resolve_enabled_plugins_map(
+    pub async fn resolve_enabled_plugins_map(
         &self,
         project_path: &str,
         enabled_plugin_ids: &[String],
     ) -> HashMap<String, bool> {
-        let project_plugins = self.get_project_plugins(project_path);
+        let project_plugins = self.get_project_plugins(project_path).await;
         let enabled_set: HashSet<&str> = enabled_plugin_ids.iter().map(|s| s.as_str()).collect();
 
         let mut result = HashMap::new();
@@ -972,7 +1376,7 @@ impl PluginManager {
     /// Gets the enabled skill IDs for a session.
     ///
     /// If not explicitly set, returns all available skills as enabled by default.
-    pub fn get_session_skills(&self, project_path: &str, session_id: u32) -> Vec<String> {
+    pub async fn get_session_skills(&self, project_path: &str, session_id: u32) -> Vec<String> {
         let key = (project_path.to_string(), session_id);
 
         if let Some(enabled) = self.session_enabled_skills.get(&key) {
@@ -981,12 +1385,75 @@ impl PluginManager {
 
         // Default: all skills enabled
         self.get_project_plugins(project_path)
+            .await
             .skills
             .into_iter()
             .map(|s| s.id)
             .collect()
     }
 
+    /// Like `get_session_skills`, but drops file-based skills whose file was
+    /// deleted since discovery.
+    ///
+    /// Stale skills are logged and removed from the session's explicit
+    /// selection, and the project's cache is refreshed in the background.
+    pub async fn get_session_skills_validated(
+        &self,
+        project_path: &str,
+        session_id: u32,
+    ) -> Vec<String> {
+        self.validate_session_skills(project_path, session_id)
+            .await
+            .0
+    }
+
+    /// Implementation of `get_session_skills_validated`. Also returns the
+    /// background refresh, if one was started, so tests can wait for it.
+    async fn validate_session_skills(
+        &self,
+        project_path: &str,
+        session_id: u32,
+    ) -> (Vec<String>, Option<tauri::async_runtime::JoinHandle<()>>) {
+        let enabled = self.get_session_skills(project_path, session_id).await;
+        let missing: HashSet<String> = self
+            .get_project_plugins(project_path)
+            .await
+            .skills
+            .into_iter()
+            .filter(|s| match &s.skill_type {
+                SkillType::File { path } => !Path::new(path).exists(),
+                _ => false,
+            })
+            .map(|s| s.id)
+            .collect();
+        if missing.is_empty() {
+            return (enabled, None);
+        }
+
+        let (valid, stale): (Vec<String>, Vec<String>) =
+            enabled.into_iter().partition(|id| !missing.contains(id));
+        for id in &stale {
+            log::warn!(
+                "Dropping skill {} from session {}: its file no longer exists",
+                id,
+                session_id
+            );
+        }
+
+        let key = (project_path.to_string(), session_id);
+        if let Some(mut explicit) = self.session_enabled_skills.get_mut(&key) {
+            explicit.retain(|id| !missing.contains(id));
+        }
+
+        let manager = self.clone();
+        let project = project_path.to_string();
+        let refresh = tauri::async_runtime::spawn(async move {
+            manager.refresh_project_plugins(&project).await;
+        });
+
+        (valid, Some(refresh))
+    }
+
     /// Sets the enabled skill IDs for a session.
     pub fn set_session_skills(&self, project_path: &str, session_id: u32, enabled: Vec<String>) {
         let key = (project_path.to_string(), session_id);
@@ -996,7 +1463,7 @@ impl PluginManager {
     /// Gets the enabled plugin IDs for a session.
     ///
     /// If not explicitly set, returns plugins where enabled_by_default is true.
-    pub fn get_session_plugins(&self, project_path: &str, session_id: u32) -> Vec<String> {
+    pub async fn get_session_plugins(&self, project_path: &str, session_id: u32) -> Vec<String> {
         let key = (project_path.to_string(), session_id);
 
         if let Some(enabled) = self.session_enabled_plugins.get(&key) {
@@ -1005,6 +1472,7 @@ impl PluginManager {
 
         // Default: plugins with enabled_by_default = true
         self.get_project_plugins(project_path)
+            .await
             .plugins
             .into_iter()
             .filter(|p| p.enabled_by_default)
@@ -1018,6 +1486,56 @@ impl PluginManager {
         self.session_enabled_plugins.insert(key, enabled);
     }
 
+    /// Enables every discovered skill for a session, or disables them all.
+    ///
+    /// "Enable all" includes skills that are not `user_invocable`: that flag
+    /// only hides a skill from the slash-command menu, while the session list
+    /// controls what Claude can load. Returns the resulting enabled IDs.
+    pub async fn set_all_session_skills(
+        &self,
+        project_path: &str,
+        session_id: u32,
+        enabled: bool,
+    ) -> Vec<String> {
+        let ids: Vec<String> = if enabled {
+            self.get_project_plugins(project_path)
+                .await
+                .skills
+                .into_iter()
+                .map(|s| s.id)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.set_session_skills(project_path, session_id, ids.clone());
+        ids
+    }
+
+    /// Enables every discovered plugin for a session, or disables them all.
+    ///
+    /// `enabled_by_default` only picks the initial selection for sessions the
+    /// user hasn't touched, so "enable all" ignores it and includes every
+    /// plugin. Returns the resulting enabled IDs.
+    pub async fn set_all_session_plugins(
+        &self,
+        project_path: &str,
+        session_id: u32,
+        enabled: bool,
+    ) -> Vec<String> {
+        let ids: Vec<String> = if enabled {
+            self.get_project_plugins(project_path)
+                .await
+                .plugins
+                .into_iter()
+                .map(|p| p.id)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.set_session_plugins(project_path, session_id, ids.clone());
+        ids
+    }
+
     /// Removes session state when a session is closed.
     pub fn remove_session(&self, project_path: &str, session_id: u32) {
         let key = (project_path.to_string(), session_id);
@@ -1025,14 +1543,125 @@ impl PluginManager {
         self.session_enabled_plugins.remove(&key);
     }
 
+    /// Resolves a slash-command name typed by the user to an enabled skill.
+    ///
+    /// Matching is case-insensitive against the skill name and the tail of its
+    /// ID (after the source prefix); a leading `/` is ignored. Only skills that
+    /// are enabled for the session and `user_invocable` are considered.
+    /// `disable_model_invocation` does not exclude a skill here, since it only
+    /// restricts Claude's automatic invocation, not the user's.
+    pub async fn resolve_skill(
+        &self,
+        query: &str,
+        project_path: &str,
+        session_id: u32,
+    ) -> SkillResolution {
+        let query = query.trim().trim_start_matches('/').to_lowercase();
+        if query.is_empty() {
+            return SkillResolution::NotFound;
+        }
+
+        let enabled: HashSet<String> = self
+            .get_session_skills(project_path, session_id)
+            .await
+            .into_iter()
+            .collect();
+        let invocable: Vec<SkillConfig> = self
+            .get_project_plugins(project_path)
+            .await
+            .skills
+            .into_iter()
+            .filter(|skill| skill.user_invocable && enabled.contains(&skill.id))
+            .collect();
+
+        let keys = |skill: &SkillConfig| -> [String; 2] {
+            let id_tail = skill.id.rsplit(':').next().unwrap_or(&skill.id);
+            [skill.name.to_lowercase(), id_tail.to_lowercase()]
+        };
+
+        if let Some(skill) = invocable.iter().find(|skill| keys(skill).contains(&query)) {
+            return SkillResolution::Exact {
+                skill: Box::new(skill.clone()),
+            };
+        }
+
+        let mut scored: Vec<((u8, usize), SkillConfig)> = invocable
+            .into_iter()
+            .filter_map(|skill| {
+                let best = keys(&skill)
+                    .iter()
+                    .filter_map(|key| fuzzy_score(&query, key))
+                    .min()?;
+                Some((best, skill))
+            })
+            .collect();
+
+        if scored.is_empty() {
+            return SkillResolution::NotFound;
+        }
+
+        scored.sort_by(|(a, skill_a), (b, skill_b)| {
+            a.cmp(b).then_with(|| skill_a.name.cmp(&skill_b.name))
+        });
+        SkillResolution::Suggestions {
+            candidates: scored
+                .into_iter()
+                .take(MAX_SKILL_SUGGESTIONS)
+                .map(|(_, skill)| skill)
+                .collect(),
+        }
+    }
+
+    /// Builds a picker preview for a skill in the project.
+    ///
+    /// For file-based skills the markdown is re-read so the excerpt reflects
+    /// the current body; prompt skills use their inline prompt. Returns `None`
+    /// if the project has no skill with this ID.
+    pub async fn get_skill_preview(
+        &self,
+        skill_id: &str,
+        project_path: &str,
+    ) -> Option<SkillPreview> {
+        let skill = self
+            .get_project_plugins(project_path)
+            .await
+            .skills
+            .into_iter()
+            .find(|s| s.id == skill_id)?;
+
+        let body_excerpt = match &skill.skill_type {
+            SkillType::File { path } => match fs::read_to_string(path) {
+                Ok(content) => {
+                    first_paragraph(strip_frontmatter(&content), SKILL_PREVIEW_MAX_CHARS)
+                }
+                Err(e) => {
+                    log::warn!("Failed to read skill file {} for preview: {}", path, e);
+                    None
+                }
+            },
+            SkillType::Prompt { prompt } => first_paragraph(prompt, SKILL_PREVIEW_MAX_CHARS),
+            SkillType::Command { .. } => None,
+        };
+
+        Some(SkillPreview {
+            frontmatter_description: Some(skill.description).filter(|d| !d.is_empty()),
+            body_excerpt,
+            argument_hint: skill.argument_hint,
+        })
+    }
+
     /// Counts enabled skills for a session.
-    pub fn get_skills_count(&self, project_path: &str, session_id: u32) -> usize {
-        self.get_session_skills(project_path, session_id).len()
+    pub async fn get_skills_count(&self, project_path: &str, session_id: u32) -> usize {
+        self.get_session_skills(project_path, session_id)
+            .await
+            .len()
     }
 
     /// Counts enabled plugins for a session.
-    pub fn get_plugins_count(&self, project_path: &str, session_id: u32) -> usize {
-        self.get_session_plugins(project_path, session_id).len()
+    pub async fn get_plugins_count(&self, project_path: &str, session_id: u32) -> usize {
+        self.get_session_plugins(project_path, session_id)
+            .await
+            .len()
     }
 }
 
@@ -1046,10 +1675,10 @@ impl Default for PluginManager {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_empty_project() {
+    #[tokio::test]
+    async fn test_parse_empty_project() {
         let manager = PluginManager::new();
-        let plugins = manager.get_project_plugins("/nonexistent/path");
+        let plugins = manager.get_project_plugins("/nonexistent/path").await;
         assert!(plugins.skills.iter().all(|skill| {
             !matches!(skill.source, SkillSource::Project | SkillSource::Legacy)
         }));
@@ -1070,8 +1699,8 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_resolve_enabled_plugins_map() {
+    #[tokio::test]
+    async fn test_resolve_enabled_plugins_map() {
         let manager = PluginManager::new();
 
         // Manually insert some test plugins
@@ -1086,6 +1715,7 @@ mod tests {
                     icon: None,
                     plugin_source: PluginSource::Installed,
                     cli_id: Some("frontend-design@claude-plugins-official".to_string()),
+                    install_scope: None,
                     skills: Vec::new(),
                     mcp_servers: Vec::new(),
                     hooks: Vec::new(),
@@ -1100,6 +1730,7 @@ mod tests {
                     icon: None,
                     plugin_source: PluginSource::Installed,
                     cli_id: None, // No CLI ID (manually installed, no marketplace)
+                    install_scope: None,
                     skills: Vec::new(),
                     mcp_servers: Vec::new(),
                     hooks: Vec::new(),
@@ -1115,7 +1746,9 @@ mod tests {
 
         // Enable only frontend-design
         let enabled = vec!["plugin:frontend-design".to_string()];
-        let result = manager.resolve_enabled_plugins_map("/test/path", &enabled);
+        let result = manager
+            .resolve_enabled_plugins_map("/test/path", &enabled)
+            .await;
 
         // Only plugins with cli_id should be in the result
         assert_eq!(result.len(), 1);
@@ -1126,4 +1759,409 @@ mod tests {
         // stripe has no cli_id, so it's not in the result
         assert!(result.get("stripe").is_none());
     }
+
+    /// Builds a user-invocable project command skill with the given name.
+    fn test_skill(name: &str) -> SkillConfig {
+        SkillConfig {
+            id: format!("project:{}", name),
+            name: name.to_string(),
+            description: String::new(),
+            icon: None,
+            skill_type: SkillType::Prompt {
+                prompt: String::new(),
+            },
+            plugin_id: None,
+            source: SkillSource::Project,
+            path: None,
+            argument_hint: None,
+            disable_model_invocation: false,
+            user_invocable: true,
+            allowed_tools: None,
+            model: None,
+            context: None,
+            agent: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn manager_with_skills(skills: Vec<SkillConfig>) -> PluginManager {
+        let manager = PluginManager::new();
+        manager.project_plugins.insert(
+            "/test/path".to_string(),
+            ProjectPlugins {
+                skills,
+                plugins: Vec::new(),
+            },
+        );
+        manager
+    }
+
+    fn candidate_names(resolution: SkillResolution) -> Vec<String> {
+        match resolution {
+            SkillResolution::Suggestions { candidates } => {
+                candidates.into_iter().map(|s| s.name).collect()
+            }
+            other => panic!("expected suggestions, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_skill_exact_match() {
+        let manager = manager_with_skills(vec![test_skill("review-pr"), test_skill("review")]);
+
+        match manager.resolve_skill("/Review-PR", "/test/path", 1).await {
+            SkillResolution::Exact { skill } => assert_eq!(skill.id, "project:review-pr"),
+            other => panic!("expected exact match, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_skill_single_close_suggestion() {
+        let manager = manager_with_skills(vec![
+            test_skill("deploy"),
+            test_skill("review"),
+            test_skill("changelog"),
+        ]);
+
+        let names = candidate_names(manager.resolve_skill("reveiw", "/test/path", 1).await);
+        assert_eq!(names, vec!["review"]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_skill_ambiguous_candidates_are_ranked() {
+        let manager = manager_with_skills(vec![
+            test_skill("test-unit"),
+            test_skill("test-e2e"),
+            test_skill("text"),
+            test_skill("deploy"),
+        ]);
+
+        let names = candidate_names(manager.resolve_skill("test", "/test/path", 1).await);
+        // Prefix matches (shorter remainder first) outrank the one-typo match
+        assert_eq!(names, vec!["test-e2e", "test-unit", "text"]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_skill_respects_invocability_and_session() {
+        let mut hidden = test_skill("secret");
+        hidden.user_invocable = false;
+        let mut manual_only = test_skill("release");
+        manual_only.disable_model_invocation = true;
+        let manager = manager_with_skills(vec![hidden, manual_only, test_skill("lint")]);
+
+        assert!(matches!(
+            manager.resolve_skill("secret", "/test/path", 1).await,
+            SkillResolution::NotFound
+        ));
+        assert!(matches!(
+            manager.resolve_skill("release", "/test/path", 1).await,
+            SkillResolution::Exact { .. }
+        ));
+
+        // Skills disabled for the session are not resolvable
+        manager.set_session_skills("/test/path", 2, vec!["project:release".to_string()]);
+        assert!(matches!(
+            manager.resolve_skill("lint", "/test/path", 2).await,
+            SkillResolution::NotFound
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_skill_preview_returns_first_body_paragraph() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_file = dir.path().join("SKILL.md");
+        std::fs::write(
+            &skill_file,
+            "---\nname: review\ndescription: Review a PR\nargument-hint: [pr-number]\n---\n\n\
+             # Review\n\n\
+             Checks out the PR and reviews\nthe diff for bugs.\n\n\
+             Second paragraph with details.\n",
+        )
+        .unwrap();
+        let mut skill = test_skill("review");
+        skill.description = "Review a PR".to_string();
+        skill.argument_hint = Some("[pr-number]".to_string());
+        skill.skill_type = SkillType::File {
+            path: skill_file.to_string_lossy().into_owned(),
+        };
+        let manager = manager_with_skills(vec![skill]);
+
+        let preview = manager
+            .get_skill_preview("project:review", "/test/path")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            preview.body_excerpt.as_deref(),
+            Some("Checks out the PR and reviews the diff for bugs.")
+        );
+        assert_eq!(
+            preview.frontmatter_description.as_deref(),
+            Some("Review a PR")
+        );
+        assert_eq!(preview.argument_hint.as_deref(), Some("[pr-number]"));
+        let missing = manager
+            .get_skill_preview("project:missing", "/test/path")
+            .await;
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_all_session_skills_and_plugins() {
+        let mut hidden = test_skill("internal");
+        hidden.user_invocable = false;
+        let manager = manager_with_skills(vec![hidden, test_skill("lint"), test_skill("deploy")]);
+        let plugin = |name: &str, enabled_by_default: bool| PluginConfig {
+            id: format!("plugin:{}", name),
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: String::new(),
+            icon: None,
+            plugin_source: PluginSource::Project,
+            cli_id: None,
+            install_scope: None,
+            skills: Vec::new(),
+            mcp_servers: Vec::new(),
+            hooks: Vec::new(),
+            enabled_by_default,
+            path: None,
+        };
+        manager
+            .project_plugins
+            .get_mut("/test/path")
+            .unwrap()
+            .plugins = vec![plugin("opt-in", false), plugin("standard", true)];
+        assert_eq!(manager.get_plugins_count("/test/path", 1).await, 1);
+
+        let skills = manager.set_all_session_skills("/test/path", 1, true).await;
+        let plugins = manager.set_all_session_plugins("/test/path", 1, true).await;
+        assert_eq!(skills.len(), 3);
+        assert_eq!(manager.get_skills_count("/test/path", 1).await, 3);
+        assert_eq!(plugins, vec!["plugin:opt-in", "plugin:standard"]);
+        assert_eq!(manager.get_plugins_count("/test/path", 1).await, 2);
+
+        manager.set_all_session_skills("/test/path", 1, false).await;
+        manager
+            .set_all_session_plugins("/test/path", 1, false)
+            .await;
+        assert_eq!(manager.get_skills_count("/test/path", 1).await, 0);
+        assert_eq!(manager.get_plugins_count("/test/path", 1).await, 0);
+        // Other sessions keep their defaults
+        assert_eq!(manager.get_skills_count("/test/path", 2).await, 3);
+    }
+
+    #[test]
+    fn test_first_paragraph_truncates_on_char_boundary() {
+        let excerpt = first_paragraph("héllo wörld again", 11).unwrap();
+        assert_eq!(excerpt, "héllo wörld…");
+    }
+
+    #[tokio::test]
+    async fn test_validated_session_skills_drop_deleted_files_and_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_string_lossy().to_string();
+        for name in ["keep", "gone"] {
+            let skill_dir = dir.path().join(".claude").join("skills").join(name);
+            fs::create_dir_all(&skill_dir).unwrap();
+            fs::write(skill_dir.join("SKILL.md"), "Body").unwrap();
+        }
+
+        let manager = PluginManager::new();
+        manager.get_project_plugins(&project).await;
+        manager.set_session_skills(
+            &project,
+            1,
+            vec!["project:keep".to_string(), "project:gone".to_string()],
+        );
+
+        fs::remove_file(dir.path().join(".claude/skills/gone/SKILL.md")).unwrap();
+
+        let (valid, refresh) = manager.validate_session_skills(&project, 1).await;
+        assert_eq!(valid, vec!["project:keep"]);
+        assert_eq!(
+            manager.get_session_skills(&project, 1).await,
+            vec!["project:keep"]
+        );
+
+        refresh
+            .expect("stale cache should be refreshed")
+            .await
+            .unwrap();
+        let cached: Vec<String> = manager
+            .get_project_plugins(&project)
+            .await
+            .skills
+            .into_iter()
+            .map(|s| s.id)
+            .filter(|id| id.starts_with("project:"))
+            .collect();
+        assert_eq!(cached, vec!["project:keep"]);
+
+        // Nothing stale left, so no further refresh
+        assert!(manager
+            .validate_session_skills(&project, 1)
+            .await
+            .1
+            .is_none());
+    }
+
+    #[test]
+    fn test_installed_plugins_prefer_project_scope_for_matching_project() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("installed_plugins.json"),
+            r#"{
+                "version": 2,
+                "plugins": {
+                    "lint@market": [
+                        { "scope": "user", "installPath": "/cache/lint/1.0.0", "version": "1.0.0" },
+                        {
+                            "scope": "project",
+                            "installPath": "/cache/lint/2.0.0",
+                            "version": "2.0.0",
+                            "projectPath": "/work/app"
+                        }
+                    ],
+                    "deploy@market": [
+                        { "scope": "local", "installPath": "/cache/deploy", "projectPath": "/work/app" }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let mut installed = parse_installed_plugins_json(dir.path(), Path::new("/work/app"));
+        installed.sort_by(|a, b| a.cli_id.cmp(&b.cli_id));
+        assert_eq!(installed.len(), 2);
+        assert_eq!(installed[0].cli_id, "deploy@market");
+        assert_eq!(installed[0].scope, InstallScope::Local);
+        assert_eq!(installed[0].version, "0.0.0");
+        assert_eq!(installed[1].cli_id, "lint@market");
+        assert_eq!(installed[1].scope, InstallScope::Project);
+        assert_eq!(installed[1].install_path, "/cache/lint/2.0.0");
+
+        // Another project only sees the user-scope install
+        let installed = parse_installed_plugins_json(dir.path(), Path::new("/work/other"));
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].cli_id, "lint@market");
+        assert_eq!(installed[0].scope, InstallScope::User);
+        assert_eq!(installed[0].install_path, "/cache/lint/1.0.0");
+    }
+
+    #[test]
+    fn test_skill_frontmatter_extra_keys_land_in_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().join("ship");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: ship\ndescription: Ship it\ncategory: deploy\ntags: \"release, ci\"\n\
+             owners:\n  - infra\nlinks: [a, b]\n---\n\nBody\n",
+        )
+        .unwrap();
+
+        let skills = scan_skills_directory(dir.path(), SkillSource::Project);
+        assert_eq!(skills.len(), 1);
+        let metadata = &skills[0].metadata;
+        assert_eq!(metadata.get("category").map(String::as_str), Some("deploy"));
+        assert_eq!(
+            metadata.get("tags").map(String::as_str),
+            Some("release, ci")
+        );
+        // Known keys and non-scalar values are not duplicated into metadata
+        assert_eq!(metadata.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_discovery_matches_sequential() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        let claude_dir = dir.path().join("home").join(".claude");
+
+        let write = |path: PathBuf, content: &str| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        // `ship` exists in both project and personal skills; project must win
+        write(
+            project.join(".claude/skills/ship/SKILL.md"),
+            "---\nname: ship\ndescription: project ship\n---\n",
+        );
+        write(project.join(".claude/commands/lint.md"), "Lint it\n");
+        write(
+            claude_dir.join("skills/ship/SKILL.md"),
+            "---\nname: ship\ndescription: personal ship\n---\n",
+        );
+        write(claude_dir.join("commands/notes.md"), "Take notes\n");
+        write(
+            claude_dir.join("plugins/widget/.claude-plugin/plugin.json"),
+            r#"{ "name": "widget", "version": "1.0.0" }"#,
+        );
+        write(claude_dir.join("plugins/widget/commands/spin.md"), "Spin\n");
+        write(
+            project.join(".plugins.json"),
+            r#"{ "skills": { "old": { "type": "prompt", "name": "old", "prompt": "Old" } } }"#,
+        );
+
+        let project_path = project.to_string_lossy();
+        let sequential = PluginManager::discover_all_in(&project_path, Some(&claude_dir));
+        let concurrent =
+            PluginManager::discover_all_concurrent_in(&project_path, Some(claude_dir.clone()))
+                .await;
+
+        assert_eq!(
+            serde_json::to_value(&sequential).unwrap(),
+            serde_json::to_value(&concurrent).unwrap()
+        );
+
+        let ship: Vec<_> = concurrent
+            .skills
+            .iter()
+            .filter(|s| s.name == "ship")
+            .collect();
+        assert_eq!(ship.len(), 1);
+        assert_eq!(ship[0].description, "project ship");
+        for name in ["lint", "notes", "spin", "old"] {
+            assert!(
+                concurrent.skills.iter().any(|s| s.name == name),
+                "missing skill {}",
+                name
+            );
+        }
+        assert_eq!(concurrent.plugins.len(), 1);
+        assert_eq!(concurrent.plugins[0].name, "widget");
+    }
+
+    #[test]
+    fn test_validate_legacy_plugins_reports_unknown_skill_type() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(".plugins.json"),
+            r#"{
+                "skills": {
+                    "review": { "type": "prompt", "name": "Review", "prompt": "Review this" },
+                    "deploy": { "type": "webhook", "name": "Deploy" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let project = dir.path().to_string_lossy();
+        let report = PluginManager::validate_legacy_plugins(&project).unwrap();
+        assert_eq!(report.valid_skills, vec!["review"]);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].id, "deploy");
+        assert!(report.skipped[0]
+            .reason
+            .contains("unknown skill type 'webhook'"));
+        assert_eq!(
+            report.suggested_migration,
+            vec!["review: move the prompt into .claude/commands/review.md"]
+        );
+
+        // Loading drops the same entry
+        let loaded = PluginManager::parse_legacy_plugins_json(&project);
+        assert_eq!(loaded.skills.len(), 1);
+        assert_eq!(loaded.skills[0].id, "legacy:review");
+    }
 }
@@ -1,22 +1,28 @@
 use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use tauri::{AppHandle, Emitter};
-use tokio::sync::Notify;
+use tokio::sync::{Notify, Semaphore, SemaphorePermit};
 
 #[cfg(unix)]
 use libc;
 
 use super::error::PtyError;
+use super::session_recorder::SessionRecorder;
+use super::terminal_backend::{BackendType, TerminalState};
+#[cfg(feature = "vte-backend")]
+use super::vte_backend::VteStateTracker;
 
 /// Stateful UTF-8 decoder that handles split multi-byte sequences.
 ///
-/// When reading from a PTY in 4096-byte chunks, a multi-byte UTF-8 character
+/// When reading from a PTY in fixed-size chunks, a multi-byte UTF-8 character
 /// (e.g., emoji, Nerd Font icon, CJK character) can be split across chunk
 /// boundaries. Using `String::from_utf8_lossy` replaces incomplete sequences
 /// with U+FFFD (�), causing garbled output.
@@ -58,6 +64,13 @@ impl Utf8Decoder {
             .unwrap_or_else(|_| String::from_utf8_lossy(&data[..valid_up_to]).into_owned())
     }
 
+    /// Emits any buffered incomplete sequence lossily (as U+FFFD) and
+    /// clears the buffer. Call once the input has ended, so a stream cut off
+    /// mid-character still shows that something was there.
+    pub fn flush(&mut self) -> String {
+        String::from_utf8_lossy(&std::mem::take(&mut self.incomplete)).into_owned()
+    }
+
     /// Finds the byte index up to which the data is valid UTF-8.
     fn find_valid_boundary(data: &[u8]) -> usize {
         match std::str::from_utf8(data) {
@@ -76,6 +89,288 @@ impl Utf8Decoder {
     }
 }
 
+/// Per-session tuning for the PTY reader pipeline.
+///
+/// `read_buf` is the size of each `read()` from the PTY, and `channel_cap` is
+/// how many such chunks may queue between the reader thread and the event
+/// emitter. Worst-case buffered memory per session is therefore roughly
+/// `channel_cap × read_buf` (1 MB with the defaults). Larger values help
+/// high-throughput sessions such as big builds; smaller values suit
+/// memory-constrained setups. Both are clamped to sane bounds.
+///
+/// `backend` decides whether output is also parsed for terminal state
+/// (`VteParser`) or only forwarded to xterm.js (`XtermPassthrough`), and
+/// `env_mode` how much of Maestro's own environment the shell inherits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnOptions {
+    pub read_buf: usize,
+    pub channel_cap: usize,
+    pub backend: BackendType,
+    pub env_mode: EnvMode,
+}
+
+/// Which of Maestro's environment variables a spawned shell inherits.
+///
+/// Variables passed explicitly to the spawn are set either way.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EnvMode {
+    /// Inherit Maestro's full environment.
+    #[default]
+    Inherit,
+    /// Start from an empty environment and inherit only the named variables,
+    /// plus those in [`ALWAYS_INHERITED_ENV`].
+    Allowlist(Vec<String>),
+}
+
+impl EnvMode {
+    /// Whether a shell spawned with this mode inherits Maestro's `name`.
+    pub fn inherits(&self, name: &str) -> bool {
+        match self {
+            EnvMode::Inherit => true,
+            EnvMode::Allowlist(names) => {
+                ALWAYS_INHERITED_ENV.contains(&name) || names.iter().any(|n| n == name)
+            }
+        }
+    }
+}
+
+/// Variables an [`EnvMode::Allowlist`] shell inherits even when unlisted,
+/// since a login shell can't find programs or its home without them, and
+/// without the locale it garbles multi-byte input (see `open_session`).
+/// `MAESTRO_SESSION_ID` is always set for the session itself.
+#[cfg(unix)]
+pub const ALWAYS_INHERITED_ENV: &[&str] = &["PATH", "HOME", "TERM", "LANG", "LC_ALL", "LC_CTYPE"];
+/// Variables an [`EnvMode::Allowlist`] shell inherits even when unlisted.
+/// Windows shells also need their system directories to start at all.
+#[cfg(windows)]
+pub const ALWAYS_INHERITED_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "TERM",
+    "SYSTEMROOT",
+    "COMSPEC",
+    "PATHEXT",
+    "USERPROFILE",
+];
+
+impl SpawnOptions {
+    pub const DEFAULT_READ_BUF: usize = 4096;
+    pub const DEFAULT_CHANNEL_CAP: usize = 256;
+    pub const MIN_READ_BUF: usize = 64;
+    pub const MAX_READ_BUF: usize = 64 * 1024;
+    pub const MIN_CHANNEL_CAP: usize = 4;
+    pub const MAX_CHANNEL_CAP: usize = 4096;
+
+    /// Creates options with both values clamped to their bounds.
+    pub fn new(read_buf: usize, channel_cap: usize) -> Self {
+        Self {
+            read_buf: read_buf.clamp(Self::MIN_READ_BUF, Self::MAX_READ_BUF),
+            channel_cap: channel_cap.clamp(Self::MIN_CHANNEL_CAP, Self::MAX_CHANNEL_CAP),
+            backend: BackendType::platform_default(),
+            env_mode: EnvMode::Inherit,
+        }
+    }
+
+    /// Selects the terminal backend, falling back to the platform default
+    /// if `backend` is not compiled in.
+    pub fn with_backend(mut self, backend: BackendType) -> Self {
+        self.backend = BackendType::resolve(Some(backend));
+        self
+    }
+
+    /// Sets which of Maestro's environment variables the shell inherits.
+    pub fn with_env_mode(mut self, env_mode: EnvMode) -> Self {
+        self.env_mode = env_mode;
+        self
+    }
+
+    /// Reads `MAESTRO_PTY_READ_BUF` and `MAESTRO_PTY_CHANNEL_CAP`, falling back
+    /// to the defaults for unset or unparseable values.
+    pub fn from_env() -> Self {
+        Self::new(
+            env_usize("MAESTRO_PTY_READ_BUF", Self::DEFAULT_READ_BUF),
+            env_usize("MAESTRO_PTY_CHANNEL_CAP", Self::DEFAULT_CHANNEL_CAP),
+        )
+    }
+}
+
+impl Default for SpawnOptions {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_READ_BUF, Self::DEFAULT_CHANNEL_CAP)
+    }
+}
+
+/// Reads a `usize` from the environment variable `key`, falling back to
+/// `default` when it is unset or unparseable.
+fn env_usize(key: &str, default: usize) -> usize {
+    match std::env::var(key) {
+        Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+            log::warn!("Ignoring invalid {key}={value:?}, using {default}");
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// How many shells may be spawning at once unless `MAESTRO_MAX_CONCURRENT_SPAWNS`
+/// or [`ProcessManager::set_spawn_limit`] says otherwise.
+pub const DEFAULT_SPAWN_LIMIT: usize = 8;
+
+/// Bounds concurrent spawns so launching a session per branch doesn't open
+/// dozens of PTYs at once. Spawns beyond the limit wait for a free slot.
+struct SpawnLimiter {
+    /// One permit per slot; resized in place when the limit changes, so
+    /// queued spawns keep waiting on the same semaphore.
+    slots: Semaphore,
+    sizing: Mutex<SpawnSizing>,
+    /// Spawns currently holding a slot.
+    in_flight: AtomicUsize,
+    /// Most spawns ever in flight at once.
+    peak: AtomicUsize,
+}
+
+/// The current spawn limit, and how many permits a lowered limit still has
+/// to retire. Permits held by in-flight spawns can't be forgotten until they
+/// are returned.
+struct SpawnSizing {
+    limit: usize,
+    owed: usize,
+}
+
+impl SpawnLimiter {
+    fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            slots: Semaphore::new(limit),
+            sizing: Mutex::new(SpawnSizing { limit, owed: 0 }),
+            in_flight: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    fn limit(&self) -> usize {
+        self.sizing.lock().unwrap().limit
+    }
+
+    /// Grows or shrinks the semaphore to `limit` (at least 1). Shrinking
+    /// below the number of in-flight spawns retires their permits as they
+    /// are released.
+    fn set_limit(&self, limit: usize) {
+        let limit = limit.max(1);
+        let mut sizing = self.sizing.lock().unwrap();
+        if limit >= sizing.limit {
+            let grow = limit - sizing.limit;
+            let repaid = grow.min(sizing.owed);
+            sizing.owed -= repaid;
+            self.slots.add_permits(grow - repaid);
+        } else {
+            let shrink = sizing.limit - limit;
+            let forgotten = self.slots.forget_permits(shrink);
+            sizing.owed += shrink - forgotten;
+        }
+        sizing.limit = limit;
+    }
+
+    /// Returns a spawn's slot, or retires it if a lowered limit still owes
+    /// permits.
+    fn release(&self, permit: SemaphorePermit<'_>) {
+        let mut sizing = self.sizing.lock().unwrap();
+        if sizing.owed > 0 {
+            sizing.owed -= 1;
+            permit.forget();
+        }
+    }
+
+    /// Records a spawn taking its slot.
+    fn enter(&self) {
+        let now = self.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+        if now > self.peak.fetch_max(now, Ordering::Relaxed) {
+            log::debug!("{now} shells spawning at once");
+        }
+    }
+
+    /// Records a spawn releasing its slot.
+    fn leave(&self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Callback for emitting PTY events (event name, payload). In production this
+/// wraps `AppHandle::emit`; in tests it captures events into a `Vec`.
+pub(crate) type PtyEmitFn = Arc<dyn Fn(&str, serde_json::Value) + Send + Sync>;
+
+/// Create a `PtyEmitFn` from a Tauri `AppHandle`.
+fn emit_fn_from_app_handle(app_handle: AppHandle) -> PtyEmitFn {
+    Arc::new(move |event: &str, payload: serde_json::Value| {
+        let _ = app_handle.emit(event, payload);
+    })
+}
+
+/// A session's active recording, if any. Shared with the reader thread and
+/// carried across restarts so a recording survives `restart_session`.
+type RecorderSlot = Arc<Mutex<Option<SessionRecorder>>>;
+
+/// Everything needed to (re)spawn a session's shell, retained so that
+/// `restart_session` can recreate it with identical settings.
+#[derive(Clone)]
+struct SpawnSpec {
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    /// Shell requested by the caller; resolved again on every (re)spawn.
+    shell: Option<String>,
+    options: SpawnOptions,
+    emit: PtyEmitFn,
+}
+
+/// How long a killed session gets between SIGTERM and SIGKILL by default.
+/// Long enough for programs that save state on SIGTERM to finish.
+pub const DEFAULT_KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// How often the kill grace window checks whether the process has exited.
+#[cfg(unix)]
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Resizes arriving within this long of the last applied one are coalesced,
+/// so a window drag produces a few SIGWINCHs instead of dozens.
+pub const RESIZE_COALESCE_WINDOW: Duration = Duration::from_millis(50);
+
+/// Resize bookkeeping for one session (see [`ProcessManager::resize_pty`]).
+struct ResizeState {
+    /// Size most recently applied to the PTY as `(rows, cols)`.
+    applied: (u16, u16),
+    /// Latest requested size still waiting for the deferred flush.
+    pending: Option<(u16, u16)>,
+    /// When `applied` was last written to the PTY.
+    applied_at: Option<Instant>,
+    /// Number of times the PTY was actually resized.
+    resize_count: u32,
+    /// When `resize_pty` was last called, whether or not it applied.
+    requested_at: Option<Instant>,
+    /// Whether a task is waiting to report the burst as settled.
+    settle_scheduled: bool,
+}
+
+impl ResizeState {
+    fn new(rows: u16, cols: u16) -> Self {
+        Self {
+            applied: (rows, cols),
+            pending: None,
+            applied_at: None,
+            resize_count: 0,
+            requested_at: None,
+            settle_scheduled: false,
+        }
+    }
+}
+
+/// Emits `pty-resized-{id}` with the size a resize burst settled on.
+fn emit_resized(emit: &PtyEmitFn, session_id: u32, (rows, cols): (u16, u16)) {
+    emit(
+        &format!("pty-resized-{session_id}"),
+        serde_json::json!({ "rows": rows, "cols": cols }),
+    );
+}
+
 /// A single PTY session with its associated resources.
 struct PtySession {
     /// Writer half of the PTY master — used for stdin.
@@ -93,6 +388,50 @@ struct PtySession {
     shutdown: Arc<Notify>,
     /// Handle to the dedicated reader OS thread.
     reader_handle: Mutex<Option<JoinHandle<()>>>,
+    /// Handle to the tokio task that batches output into events.
+    emitter_handle: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Parsed terminal state, fed by the event emitter. `None` for sessions
+    /// spawned with the passthrough backend.
+    #[cfg(feature = "vte-backend")]
+    tracker: Option<Arc<Mutex<VteStateTracker>>>,
+    /// Settings the session was spawned with.
+    spec: SpawnSpec,
+    /// Applied and pending PTY sizes, used to coalesce resize bursts.
+    resize: Mutex<ResizeState>,
+    /// Raw output recording started by [`ProcessManager::record_session`].
+    recorder: RecorderSlot,
+    /// Set by [`ProcessManager::detach_session`]; the reader thread then keeps
+    /// draining output instead of exiting once nobody forwards it.
+    detached: Arc<AtomicBool>,
+}
+
+impl PtySession {
+    /// Resizes the PTY (and the state tracker, if any) right away.
+    fn apply_resize(&self, state: &mut ResizeState, rows: u16, cols: u16) -> Result<(), PtyError> {
+        let master = self
+            .master
+            .lock()
+            .map_err(|e| PtyError::resize_failed(format!("Master lock poisoned: {e}")))?;
+
+        master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| PtyError::resize_failed(format!("Resize failed: {e}")))?;
+
+        #[cfg(feature = "vte-backend")]
+        if let Some(Ok(mut t)) = self.tracker.as_ref().map(|t| t.lock()) {
+            t.resize(rows, cols);
+        }
+
+        state.applied = (rows, cols);
+        state.applied_at = Some(Instant::now());
+        state.resize_count += 1;
+        Ok(())
+    }
 }
 
 struct Inner {
@@ -102,6 +441,7 @@ struct Inner {
     /// that may cause terminal spawning loops (Bug #76).
     #[cfg(windows)]
     last_spawn_time: Mutex<std::time::Instant>,
+    spawn_limiter: SpawnLimiter,
 }
 
 /// Owns and manages all PTY sessions for the application lifetime.
@@ -122,27 +462,52 @@ impl Default for ProcessManager {
 
 impl ProcessManager {
     /// Creates a new manager with no active sessions.
-    /// Session IDs start at 1 and increment atomically.
+    /// Session IDs start at 1 and increment atomically. At most
+    /// `MAESTRO_MAX_CONCURRENT_SPAWNS` (default [`DEFAULT_SPAWN_LIMIT`]) shells
+    /// spawn at once.
     pub fn new() -> Self {
+        Self::with_spawn_limit(env_usize(
+            "MAESTRO_MAX_CONCURRENT_SPAWNS",
+            DEFAULT_SPAWN_LIMIT,
+        ))
+    }
+
+    /// Creates a new manager that spawns at most `limit` shells at once.
+    pub fn with_spawn_limit(limit: usize) -> Self {
         Self {
             inner: Arc::new(Inner {
                 sessions: DashMap::new(),
                 next_id: AtomicU32::new(1),
                 #[cfg(windows)]
                 last_spawn_time: Mutex::new(std::time::Instant::now()),
+                spawn_limiter: SpawnLimiter::new(limit),
             }),
         }
     }
 
+    /// Changes how many shells may spawn at once (at least 1). Spawns already
+    /// in progress finish, and queued ones are admitted under the new limit.
+    pub fn set_spawn_limit(&self, limit: usize) {
+        self.inner.spawn_limiter.set_limit(limit);
+    }
+
+    /// Returns the current concurrent spawn limit.
+    pub fn spawn_limit(&self) -> usize {
+        self.inner.spawn_limiter.limit()
+    }
+
     /// Spawns a login shell in a new PTY and returns its session ID.
     ///
-    /// Uses `$SHELL` (falling back to `/bin/sh`) with `-l` for a login environment.
+    /// Uses `shell` if given, else `$SHELL`, with `-l` for a login environment.
+    /// On Unix a shell that is missing or not executable is replaced by the
+    /// first usable entry of [`FALLBACK_SHELLS`] (see [`resolve_shell`]).
     /// The child process calls `setsid()` via portable-pty, making it a session
     /// leader so `kill_session` can signal the entire process group.
-    /// A dedicated OS thread reads PTY output into a bounded 256-slot channel
-    /// (~1 MB of 4 KB chunks), and a tokio task drains it into Tauri events
-    /// named `pty-output-{id}`. If the channel fills, output is dropped and a
-    /// log message is emitted to make the loss visible.
+    /// A dedicated OS thread reads PTY output in `options.read_buf`-sized chunks
+    /// into a bounded channel of `options.channel_cap` slots, and a tokio task
+    /// drains it into Tauri events named `pty-output-{id}`. If the channel
+    /// closes, output is dropped and a log message is emitted to make the loss
+    /// visible.
     ///
     /// # Environment Variables
     /// - `MAESTRO_SESSION_ID` is automatically set to the session ID
@@ -151,11 +516,70 @@ impl ProcessManager {
     /// # Windows Debouncing
     /// On Windows, rapid consecutive spawn calls (within 500ms) are rejected to prevent
     /// terminal spawning loops (Bug #76).
-    pub fn spawn_shell(
+    ///
+    /// # Concurrency
+    /// At most [`spawn_limit`](Self::spawn_limit) shells spawn at once; further
+    /// calls wait for a slot. A slot is held only until the PTY, reader thread
+    /// and emitter are set up, not for the session's lifetime.
+    pub async fn spawn_shell(
         &self,
         app_handle: AppHandle,
         cwd: Option<String>,
         env: Option<HashMap<String, String>>,
+        shell: Option<String>,
+        options: SpawnOptions,
+    ) -> Result<u32, PtyError> {
+        let emit = emit_fn_from_app_handle(app_handle);
+        self.spawn_shell_queued(emit, cwd, env, shell, options)
+            .await
+    }
+
+    /// Waits for a spawn slot, then runs
+    /// [`spawn_shell_with_emitter`](Self::spawn_shell_with_emitter) on a
+    /// blocking thread.
+    pub(crate) async fn spawn_shell_queued(
+        &self,
+        emit: PtyEmitFn,
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+        shell: Option<String>,
+        options: SpawnOptions,
+    ) -> Result<u32, PtyError> {
+        let pm = self.clone();
+        self.in_spawn_slot(move || pm.spawn_shell_with_emitter(emit, cwd, env, shell, options))
+            .await?
+    }
+
+    /// Runs a blocking shell spawn on the blocking pool once the spawn
+    /// limiter has a free slot.
+    async fn in_spawn_slot<T: Send + 'static>(
+        &self,
+        spawn: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, PtyError> {
+        let limiter = &self.inner.spawn_limiter;
+        let permit = limiter
+            .slots
+            .acquire()
+            .await
+            .map_err(|e| PtyError::spawn_failed(format!("Spawn queue closed: {e}")))?;
+
+        limiter.enter();
+        let result = tokio::task::spawn_blocking(spawn).await;
+        limiter.leave();
+        limiter.release(permit);
+
+        result.map_err(|e| PtyError::spawn_failed(format!("Spawn task failed: {e}")))
+    }
+
+    /// Same as [`spawn_shell`](Self::spawn_shell), but emits through `emit`
+    /// instead of an `AppHandle`.
+    pub(crate) fn spawn_shell_with_emitter(
+        &self,
+        emit: PtyEmitFn,
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+        shell: Option<String>,
+        options: SpawnOptions,
     ) -> Result<u32, PtyError> {
         // Windows spawn debounce: prevent rapid consecutive spawns (Bug #76)
         #[cfg(windows)]
@@ -186,6 +610,32 @@ impl ProcessManager {
             })
             .map_err(|_| PtyError::id_overflow())?;
 
+        let session = self.open_session(
+            id,
+            SpawnSpec {
+                cwd,
+                env,
+                shell,
+                options,
+                emit,
+            },
+            RecorderSlot::default(),
+        )?;
+        self.inner.sessions.insert(id, session);
+
+        Ok(id)
+    }
+
+    /// Opens a PTY, spawns the shell described by `spec`, and starts its
+    /// reader thread and event emitter, teeing output into `recorder`. The
+    /// caller inserts the returned session under `id`.
+    fn open_session(
+        &self,
+        id: u32,
+        spec: SpawnSpec,
+        recorder: RecorderSlot,
+    ) -> Result<PtySession, PtyError> {
+        let options = spec.options.clone();
         let pty_system = native_pty_system();
 
         let pair = pty_system
@@ -197,16 +647,36 @@ impl ProcessManager {
             })
             .map_err(|e| PtyError::spawn_failed(format!("Failed to open PTY: {e}")))?;
 
-        // Determine the user's shell (platform-specific)
+        // Determine the shell (platform-specific)
         #[cfg(unix)]
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        let shell = resolve_shell(
+            spec.shell.as_deref(),
+            std::env::var("SHELL").ok().as_deref(),
+        );
         #[cfg(windows)]
-        let shell = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
+        let shell = spec
+            .shell
+            .clone()
+            .or_else(|| std::env::var("COMSPEC").ok())
+            .unwrap_or_else(|| "cmd.exe".to_string());
 
         let mut cmd = CommandBuilder::new(&shell);
         #[cfg(unix)]
         cmd.arg("-l"); // Login shell for proper env on Unix
 
+        if let EnvMode::Allowlist(names) = &options.env_mode {
+            cmd.env_clear();
+            let kept = ALWAYS_INHERITED_ENV
+                .iter()
+                .copied()
+                .chain(names.iter().map(String::as_str));
+            for name in kept {
+                if let Some(value) = std::env::var_os(name) {
+                    cmd.env(name, value);
+                }
+            }
+        }
+
         // Set TERM for proper terminal emulation on Unix.
         // xterm-256color is the standard for modern terminal emulators and enables:
         // - Proper cursor positioning and line editing
@@ -234,13 +704,13 @@ impl ProcessManager {
         cmd.env("MAESTRO_SESSION_ID", id.to_string());
 
         // Apply any additional environment variables from caller
-        if let Some(envs) = env {
+        if let Some(ref envs) = spec.env {
             for (key, value) in envs {
-                cmd.env(&key, &value);
+                cmd.env(key, value);
             }
         }
 
-        if let Some(ref dir) = cwd {
+        if let Some(ref dir) = spec.cwd {
             cmd.cwd(dir);
         }
 
@@ -267,7 +737,7 @@ impl ProcessManager {
             .map_err(|e| PtyError::spawn_failed(format!("Failed to take PTY writer: {e}")))?;
 
         // Get reader from master
-        let mut reader = pair
+        let reader = pair
             .master
             .try_clone_reader()
             .map_err(|e| PtyError::spawn_failed(format!("Failed to clone PTY reader: {e}")))?;
@@ -276,49 +746,19 @@ impl ProcessManager {
         let shutdown_clone = shutdown.clone();
 
         // Dedicated OS thread for reading PTY output.
-        // Sends data through a bounded mpsc channel (~1 MB of 4 KB chunks) to a
-        // tokio task that emits Tauri events.
-        let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
-
-        // Shutdown mechanism: dropping the master/writer FDs closes the PTY
-        // file descriptor, which causes the blocking `reader.read()` call
-        // below to return `Ok(0)` (EOF). This is the primary way the reader
-        // thread terminates — no explicit signal is needed.
-        let reader_handle = std::thread::Builder::new()
-            .name(format!("pty-reader-{id}"))
-            .spawn(move || {
-                let mut buf = [0u8; 4096];
-                loop {
-                    match reader.read(&mut buf) {
-                        Ok(0) => break, // EOF — shell exited
-                        Ok(n) => {
-                            // blocking_send is used because this is an OS thread, not async.
-                            // If the channel is full or closed, we break out of the loop.
-                            if tx.blocking_send(buf[..n].to_vec()).is_err() {
-                                log::warn!(
-                                    "PTY reader {id}: channel send failed, dropping {} bytes",
-                                    n
-                                );
-                                break; // Channel full or receiver dropped
-                            }
-                        }
-                        Err(e) => {
-                            // EAGAIN/EINTR are retriable on Unix; anything else is fatal
-                            #[cfg(unix)]
-                            {
-                                let raw = e.raw_os_error().unwrap_or(0);
-                                if raw == libc::EAGAIN || raw == libc::EINTR {
-                                    continue;
-                                }
-                            }
-                            log::debug!("PTY reader {id} error: {e}");
-                            break;
-                        }
-                    }
-                }
-                log::debug!("PTY reader {id} exited");
-            })
-            .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn reader thread: {e}")))?;
+        // Sends data through a bounded mpsc channel to a tokio task that emits
+        // Tauri events.
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<u8>>(options.channel_cap);
+        let detached = Arc::new(AtomicBool::new(false));
+        let reader_handle = spawn_reader_thread(
+            id,
+            reader,
+            tx,
+            options.read_buf,
+            recorder.clone(),
+            detached.clone(),
+        )
+        .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn reader thread: {e}")))?;
 
         // Tokio task: drain the channel and emit Tauri events with time-based batching.
         // Accumulates decoded text and flushes every 16ms (aligned with 60fps) or when
@@ -326,10 +766,19 @@ impl ProcessManager {
         // PTY chunks (e.g. during `npm install` or `cargo build`) into fewer IPC events,
         // dramatically reducing frontend overhead while remaining imperceptible for typing.
         let event_name = format!("pty-output-{id}");
-        let app = app_handle.clone();
+        #[cfg(feature = "vte-backend")]
+        let cwd_event_name = format!("session-cwd-changed-{id}");
+        #[cfg(feature = "vte-backend")]
+        let finished_event_name = format!("command-finished-{id}");
+        let emit = spec.emit.clone();
         #[cfg(windows)]
         let inner_ref = self.inner.clone();
-        tokio::spawn(async move {
+        #[cfg(feature = "vte-backend")]
+        let tracker = (options.backend == BackendType::VteParser)
+            .then(|| Arc::new(Mutex::new(VteStateTracker::new(24, 80))));
+        #[cfg(feature = "vte-backend")]
+        let tracker_ref = tracker.clone();
+        let emitter_handle = tokio::spawn(async move {
             let mut decoder = Utf8Decoder::new();
             let mut batch_buf = String::new();
             const FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
@@ -368,13 +817,22 @@ impl ProcessManager {
                                             }
                                         }
                                     }
+                                    #[cfg(feature = "vte-backend")]
+                                    if let Some(Ok(mut t)) = tracker_ref.as_ref().map(|t| t.lock()) {
+                                        if let Some(cwd) = t.advance(&bytes) {
+                                            emit(&cwd_event_name, cwd.into());
+                                        }
+                                        for exit_code in t.take_finished_commands() {
+                                            emit(&finished_event_name, exit_code.into());
+                                        }
+                                    }
                                     let text = decoder.decode(&bytes);
                                     if !text.is_empty() {
                                         batch_buf.push_str(&text);
                                     }
                                     // Flush immediately if buffer exceeds safety valve
                                     if batch_buf.len() >= MAX_BATCH_BYTES {
-                                        let _ = app.emit(&event_name, std::mem::take(&mut batch_buf));
+                                        emit(&event_name, std::mem::take(&mut batch_buf).into());
                                     }
                                 }
                                 None => break, // Channel closed
@@ -415,34 +873,37 @@ impl ProcessManager {
                                             }
                                         }
                                     }
+                                    #[cfg(feature = "vte-backend")]
+                                    if let Some(Ok(mut t)) = tracker_ref.as_ref().map(|t| t.lock()) {
+                                        if let Some(cwd) = t.advance(&bytes) {
+                                            emit(&cwd_event_name, cwd.into());
+                                        }
+                                        for exit_code in t.take_finished_commands() {
+                                            emit(&finished_event_name, exit_code.into());
+                                        }
+                                    }
                                     let text = decoder.decode(&bytes);
                                     if !text.is_empty() {
                                         batch_buf.push_str(&text);
                                     }
                                     // Flush immediately if buffer exceeds safety valve
                                     if batch_buf.len() >= MAX_BATCH_BYTES {
-                                        let _ = app.emit(&event_name, std::mem::take(&mut batch_buf));
-                                    }
-                                }
-                                None => {
-                                    // Channel closed — flush remaining data and exit
-                                    if !batch_buf.is_empty() {
-                                        let _ = app.emit(&event_name, std::mem::take(&mut batch_buf));
+                                        emit(&event_name, std::mem::take(&mut batch_buf).into());
                                     }
-                                    break;
                                 }
+                                None => break, // Channel closed; flushed below
                             }
                         }
                         _ = tokio::time::sleep(FLUSH_INTERVAL) => {
                             // Timer fired — flush accumulated data
                             if !batch_buf.is_empty() {
-                                let _ = app.emit(&event_name, std::mem::take(&mut batch_buf));
+                                emit(&event_name, std::mem::take(&mut batch_buf).into());
                             }
                         }
                         _ = shutdown_clone.notified() => {
                             // Flush remaining data before shutdown
                             if !batch_buf.is_empty() {
-                                let _ = app.emit(&event_name, std::mem::take(&mut batch_buf));
+                                emit(&event_name, std::mem::take(&mut batch_buf).into());
                             }
                             break;
                         }
@@ -450,9 +911,11 @@ impl ProcessManager {
                 }
             }
 
-            // Final flush for any remaining buffered data
+            // Final flush for any remaining buffered data, including a partial
+            // character the decoder was still waiting to complete
+            batch_buf.push_str(&decoder.flush());
             if !batch_buf.is_empty() {
-                let _ = app.emit(&event_name, batch_buf);
+                emit(&event_name, batch_buf.into());
             }
             log::debug!("PTY event emitter {id} exited");
         });
@@ -468,15 +931,31 @@ impl ProcessManager {
             pgid,
             shutdown,
             reader_handle: Mutex::new(Some(reader_handle)),
+            emitter_handle: Mutex::new(Some(emitter_handle)),
+            #[cfg(feature = "vte-backend")]
+            tracker,
+            spec,
+            resize: Mutex::new(ResizeState::new(24, 80)),
+            recorder,
+            detached,
         };
 
-        self.inner.sessions.insert(id, session);
         #[cfg(unix)]
-        log::info!("Spawned PTY session {id} (pid={child_pid}, pgid={pgid}, shell={shell})");
+        log::info!(
+            "Spawned PTY session {id} (pid={child_pid}, pgid={pgid}, shell={shell}, read_buf={}, channel_cap={}, backend={:?})",
+            options.read_buf,
+            options.channel_cap,
+            options.backend
+        );
         #[cfg(windows)]
-        log::info!("Spawned PTY session {id} (pid={child_pid}, shell={shell})");
-
-        Ok(id)
+        log::info!(
+            "Spawned PTY session {id} (pid={child_pid}, shell={shell}, read_buf={}, channel_cap={}, backend={:?})",
+            options.read_buf,
+            options.channel_cap,
+            options.backend
+        );
+
+        Ok(session)
     }
 
     /// Writes raw bytes to a session's PTY stdin and flushes immediately.
@@ -503,11 +982,96 @@ impl ProcessManager {
             .flush()
             .map_err(|e| PtyError::write_failed(format!("Flush failed: {e}")))?;
 
+        if let Ok(mut slot) = session.recorder.lock() {
+            if let Some(recorder) = slot.as_mut().filter(|r| r.includes_input()) {
+                if let Err(e) = recorder.write(data.as_bytes()) {
+                    log::warn!("Stopping recording of PTY session {session_id}: {e}");
+                    *slot = None;
+                }
+            }
+        }
+
         Ok(())
     }
 
+    /// Cancels the session's foreground command by writing Ctrl-C (ETX,
+    /// `0x03`) to its PTY stdin.
+    ///
+    /// The line discipline turns it into SIGINT for the foreground process
+    /// group only, so the shell itself survives, unlike signaling the
+    /// session's process group directly.
+    pub fn interrupt_session(&self, session_id: u32) -> Result<(), PtyError> {
+        self.write_stdin(session_id, "\x03")
+    }
+
+    /// Starts teeing a session's raw PTY output into `path`, replacing any
+    /// recording already in progress. With `include_input`, data passed to
+    /// [`write_stdin`](Self::write_stdin) is recorded too. The file rotates
+    /// once it would exceed `max_bytes` (see [`SessionRecorder`]).
+    pub fn record_session(
+        &self,
+        session_id: u32,
+        path: PathBuf,
+        include_input: bool,
+        max_bytes: u64,
+    ) -> Result<(), PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        let recorder =
+            SessionRecorder::open(path.clone(), include_input, max_bytes).map_err(|e| {
+                PtyError::record_failed(format!("Failed to open '{}': {e}", path.display()))
+            })?;
+
+        *session
+            .recorder
+            .lock()
+            .map_err(|e| PtyError::record_failed(format!("Recorder lock poisoned: {e}")))? =
+            Some(recorder);
+
+        log::info!("Recording PTY session {session_id} to {}", path.display());
+        Ok(())
+    }
+
+    /// Stops a session's recording. Returns whether one was in progress.
+    pub fn stop_recording(&self, session_id: u32) -> Result<bool, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        let stopped = session
+            .recorder
+            .lock()
+            .map_err(|e| PtyError::record_failed(format!("Recorder lock poisoned: {e}")))?
+            .take();
+
+        if let Some(recorder) = &stopped {
+            log::info!(
+                "Stopped recording PTY session {session_id} to {}",
+                recorder.path().display()
+            );
+        }
+        Ok(stopped.is_some())
+    }
+
     /// Resizes the PTY to the given dimensions, propagating SIGWINCH to the child.
     ///
+    /// A resize is applied immediately if none was applied in the last
+    /// [`RESIZE_COALESCE_WINDOW`]. Otherwise only the latest requested size is
+    /// kept and applied once the window has passed, so a burst of calls
+    /// during a window drag collapses into one trailing resize. Without a
+    /// tokio runtime every call is applied immediately.
+    ///
+    /// Once no resize has been requested for a full window and nothing is
+    /// pending, `pty-resized-{id}` is emitted once with the final
+    /// `{ rows, cols }`, so the frontend can redraw a single time per burst.
+    /// Without a runtime it is emitted with every resize.
+    ///
     /// Pixel dimensions are always set to 0 (unused by terminal emulators).
     /// Callers should validate that rows/cols are non-zero before calling.
     pub fn resize_pty(&self, session_id: u32, rows: u16, cols: u16) -> Result<(), PtyError> {
@@ -517,28 +1081,91 @@ impl ProcessManager {
             .get(&session_id)
             .ok_or_else(|| PtyError::session_not_found(session_id))?;
 
-        let master = session
-            .master
+        let mut state = session
+            .resize
             .lock()
-            .map_err(|e| PtyError::resize_failed(format!("Master lock poisoned: {e}")))?;
+            .map_err(|e| PtyError::resize_failed(format!("Resize lock poisoned: {e}")))?;
 
-        master
-            .resize(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| PtyError::resize_failed(format!("Resize failed: {e}")))?;
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            state.pending = None;
+            session.apply_resize(&mut state, rows, cols)?;
+            emit_resized(&session.spec.emit, session_id, (rows, cols));
+            return Ok(());
+        };
+
+        state.requested_at = Some(Instant::now());
+        if !state.settle_scheduled {
+            state.settle_scheduled = true;
+            runtime.spawn(settle_resize(self.inner.clone(), session_id));
+        }
+
+        let since_last = state.applied_at.map(|at| at.elapsed());
+        let delay = match since_last {
+            Some(elapsed) if elapsed < RESIZE_COALESCE_WINDOW => RESIZE_COALESCE_WINDOW - elapsed,
+            _ => {
+                state.pending = None;
+                return session.apply_resize(&mut state, rows, cols);
+            }
+        };
 
+        // A flush is already scheduled if something is pending; just update it.
+        if state.pending.replace((rows, cols)).is_none() {
+            let inner = self.inner.clone();
+            runtime.spawn(async move {
+                tokio::time::sleep(delay).await;
+                flush_pending_resize(&inner, session_id);
+            });
+        }
         Ok(())
     }
 
+    /// Returns the size most recently applied to a session's PTY as
+    /// `(rows, cols)`. A coalesced resize shows up here once it is flushed.
+    pub fn pty_size(&self, session_id: u32) -> Result<(u16, u16), PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        let state = session
+            .resize
+            .lock()
+            .map_err(|e| PtyError::resize_failed(format!("Resize lock poisoned: {e}")))?;
+        Ok(state.applied)
+    }
+
+    /// Returns a snapshot of the session's parsed terminal state.
+    ///
+    /// Only sessions on the VTE backend track state; for xterm.js passthrough
+    /// sessions (or builds without `vte-backend`) this is always `Ok(None)`.
+    pub fn get_terminal_state(&self, session_id: u32) -> Result<Option<TerminalState>, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        #[cfg(feature = "vte-backend")]
+        {
+            Ok(session
+                .tracker
+                .as_ref()
+                .and_then(|t| t.lock().ok())
+                .map(|t| t.state()))
+        }
+        #[cfg(not(feature = "vte-backend"))]
+        {
+            let _ = session;
+            Ok(None)
+        }
+    }
+
     /// Terminates a PTY session with graceful escalation.
     ///
     /// On Unix: Sends SIGTERM to the entire process group (via negative PGID),
-    /// waits up to 3 seconds for the lead process to exit, then escalates to
-    /// SIGKILL if it is still alive.
+    /// waits up to [`DEFAULT_KILL_GRACE`] for the lead process to exit, then
+    /// escalates to SIGKILL if it is still alive.
     ///
     /// On Windows: Uses taskkill to terminate the process tree.
     ///
@@ -548,6 +1175,96 @@ impl ProcessManager {
     /// The session is removed from the map before signaling, so concurrent
     /// calls with the same ID return `SessionNotFound`.
     pub async fn kill_session(&self, session_id: u32) -> Result<(), PtyError> {
+        self.kill_session_with_grace(session_id, DEFAULT_KILL_GRACE)
+            .await
+    }
+
+    /// Same as [`kill_session`](Self::kill_session), but waits up to `grace`
+    /// between SIGTERM and SIGKILL. A zero grace kills immediately unless the
+    /// process is already gone.
+    pub async fn kill_session_with_grace(
+        &self,
+        session_id: u32,
+        grace: Duration,
+    ) -> Result<(), PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .remove(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?
+            .1;
+
+        Self::terminate(session_id, session, grace).await;
+        log::info!("Killed PTY session {session_id}");
+        Ok(())
+    }
+
+    /// Restarts a session's shell in place, keeping its ID.
+    ///
+    /// The old PTY is torn down exactly like `kill_session`, including joining
+    /// the reader thread and awaiting the event emitter, so no output from the
+    /// old shell can arrive after the new one starts. A fresh shell is then
+    /// spawned with the same cwd, env, and spawn options, waiting for a slot
+    /// in the spawn limiter like any other spawn, and `session-restarted-{id}`
+    /// is emitted once it is live. The frontend keeps listening on the same
+    /// `pty-output-{id}` event.
+    ///
+    /// If the new shell can't be spawned the session is gone:
+    /// `session-restart-failed-{id}` is emitted with the error, which is also
+    /// returned, with code `RestartFailed`.
+    pub async fn restart_session(&self, session_id: u32) -> Result<(), PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .remove(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?
+            .1;
+        let spec = session.spec.clone();
+        let recorder = session.recorder.clone();
+
+        Self::terminate(session_id, session, DEFAULT_KILL_GRACE).await;
+
+        let emit = spec.emit.clone();
+        let pm = self.clone();
+        let opened = self
+            .in_spawn_slot(move || pm.open_session(session_id, spec, recorder))
+            .await
+            .and_then(|opened| opened);
+        let session = match opened {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("Failed to restart PTY session {session_id}: {e}");
+                let err = PtyError::restart_failed(session_id, e.message);
+                emit(
+                    &format!("session-restart-failed-{session_id}"),
+                    serde_json::to_value(&err).unwrap_or_default(),
+                );
+                return Err(err);
+            }
+        };
+        self.inner.sessions.insert(session_id, session);
+
+        emit(
+            &format!("session-restarted-{session_id}"),
+            session_id.into(),
+        );
+        log::info!("Restarted PTY session {session_id}");
+        Ok(())
+    }
+
+    /// Stops managing a session without killing it, like closing a terminal
+    /// tab whose job should keep running. Returns the shell's PID.
+    ///
+    /// Output stops being forwarded, any recording ends, and the session ID
+    /// becomes unknown. Maestro then drops its end of the PTY (the master FD,
+    /// writer, and reader), so no thread or descriptor outlives the call.
+    ///
+    /// Limits: a PTY can't be handed to another owner after spawn, so closing
+    /// the master hangs the terminal up. On Unix the session gets SIGHUP;
+    /// only processes that ignore it (`nohup`, `trap '' HUP`, `setsid`)
+    /// keep running, and reads from the terminal fail from then on. On
+    /// Windows, closing the pseudoconsole ends the processes attached to it.
+    pub async fn detach_session(&self, session_id: u32) -> Result<i32, PtyError> {
         let session = self
             .inner
             .sessions
@@ -555,44 +1272,74 @@ impl ProcessManager {
             .ok_or_else(|| PtyError::session_not_found(session_id))?
             .1;
 
+        // Must be set before the emitter stops, so the reader never sees a
+        // closed channel without it
+        session.detached.store(true, Ordering::Release);
+        session.shutdown.notify_one();
+        let emitter_handle = session
+            .emitter_handle
+            .lock()
+            .map_err(|e| log::warn!("Emitter handle lock poisoned during detach: {e}"))
+            .ok()
+            .and_then(|mut h| h.take());
+        if let Some(handle) = emitter_handle {
+            let _ = handle.await;
+        }
+
+        let reader_handle = session
+            .reader_handle
+            .lock()
+            .map_err(|e| log::warn!("Reader handle lock poisoned during detach: {e}"))
+            .ok()
+            .and_then(|mut h| h.take());
+        let PtySession {
+            writer,
+            master,
+            child_pid,
+            ..
+        } = session;
+
+        // The reader holds its own copy of the master FD, so the PTY only
+        // closes once it exits. It may be blocked in `read` on an idle shell;
+        // a byte written to the slave side wakes it to see `detached`.
+        #[cfg(unix)]
+        let tty_name = master.lock().ok().and_then(|m| m.tty_name());
+        drop(writer);
+        drop(master);
+        #[cfg(unix)]
+        if let Some(tty) = tty_name {
+            use std::os::unix::fs::OpenOptionsExt;
+
+            let woke = std::fs::OpenOptions::new()
+                .write(true)
+                .custom_flags(libc::O_NOCTTY)
+                .open(&tty)
+                .and_then(|mut slave| slave.write_all(b"\n"));
+            if let Err(e) = woke {
+                log::warn!("Failed to wake reader of detached PTY session {session_id}: {e}");
+            }
+        }
+        if let Some(handle) = reader_handle {
+            let _ = tokio::task::spawn_blocking(move || handle.join()).await;
+        }
+
+        log::info!("Detached PTY session {session_id} (pid={child_pid})");
+        Ok(child_pid)
+    }
+
+    /// Signals a removed session's process group and releases its resources,
+    /// waiting for the reader thread and event emitter to finish.
+    async fn terminate(session_id: u32, session: PtySession, grace: Duration) {
         let pid = session.child_pid;
 
         #[cfg(unix)]
         {
             let pgid = session.pgid;
-
-            // Send SIGTERM to the process group (negative pgid targets the group)
-            let term_result = unsafe { libc::kill(-pgid, libc::SIGTERM) };
-            if term_result != 0 {
-                log::warn!(
-                    "Failed to SIGTERM session {session_id} (pgid={pgid}): {}",
-                    std::io::Error::last_os_error()
-                );
-            }
-
-            // Wait up to 3 seconds for the lead process to exit
-            let exited = tokio::time::timeout(std::time::Duration::from_secs(3), async {
-                loop {
-                    let result = unsafe { libc::kill(pid, 0) };
-                    if result != 0 {
-                        return; // Process gone
-                    }
-                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                }
+            // The grace window blocks while polling, so keep it off the runtime
+            let _ = tokio::task::spawn_blocking(move || {
+                terminate_process_group(session_id, pid, pgid, grace)
             })
             .await;
-
-            if exited.is_err() {
-                // Still alive after grace period — SIGKILL the process group
-                let kill_result = unsafe { libc::kill(-pgid, libc::SIGKILL) };
-                if kill_result != 0 {
-                    log::warn!(
-                        "Failed to SIGKILL session {session_id} (pgid={pgid}): {}",
-                        std::io::Error::last_os_error()
-                    );
-                }
-                log::warn!("Session {session_id} (pid={pid}, pgid={pgid}) required SIGKILL");
-            }
         }
 
         #[cfg(windows)]
@@ -630,8 +1377,17 @@ impl ProcessManager {
             let _ = tokio::task::spawn_blocking(move || handle.join()).await;
         }
 
-        log::info!("Killed PTY session {session_id}");
-        Ok(())
+        // Wait for the emitter's final flush so no stale output trails behind
+        let emitter_handle = session
+            .emitter_handle
+            .lock()
+            .map_err(|e| log::warn!("Emitter handle lock poisoned during cleanup: {e}"))
+            .ok()
+            .and_then(|mut h| h.take());
+
+        if let Some(handle) = emitter_handle {
+            let _ = handle.await;
+        }
     }
 
     /// Returns the child PID for a specific session.
@@ -644,6 +1400,15 @@ impl ProcessManager {
             .map(|session| session.child_pid)
     }
 
+    /// Returns the terminal backend a session was spawned with.
+    pub fn session_backend(&self, session_id: u32) -> Result<BackendType, PtyError> {
+        self.inner
+            .sessions
+            .get(&session_id)
+            .map(|session| session.spec.options.backend)
+            .ok_or_else(|| PtyError::session_not_found(session_id))
+    }
+
     /// Returns all active session IDs with their root PIDs.
     ///
     /// Used for building process trees for all sessions at once.
@@ -678,4 +1443,771 @@ impl ProcessManager {
 
         Ok(count)
     }
+
+    /// Terminates every session at once, giving each up to `grace` between
+    /// SIGTERM and SIGKILL, and waits for their reader threads and emitters.
+    ///
+    /// Unlike [`kill_all_sessions`](Self::kill_all_sessions), the grace
+    /// windows overlap, so quitting with many sessions takes about `grace`
+    /// rather than `grace` per session. Returns the number of sessions killed.
+    pub async fn shutdown_all_sessions(&self, grace: Duration) -> u32 {
+        let session_ids: Vec<u32> = self
+            .inner
+            .sessions
+            .iter()
+            .map(|entry| *entry.key())
+            .collect();
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for id in session_ids {
+            if let Some((_, session)) = self.inner.sessions.remove(&id) {
+                tasks.spawn(Self::terminate(id, session, grace));
+            }
+        }
+
+        let mut killed = 0;
+        while let Some(result) = tasks.join_next().await {
+            match result {
+                Ok(()) => killed += 1,
+                Err(e) => log::warn!("Session shutdown task failed: {e}"),
+            }
+        }
+        log::info!("Shut down {killed} PTY sessions");
+        killed
+    }
+}
+
+/// Applies the size left pending by [`ProcessManager::resize_pty`], if the
+/// session still exists.
+fn flush_pending_resize(inner: &Inner, session_id: u32) {
+    let Some(session) = inner.sessions.get(&session_id) else {
+        return;
+    };
+    let Ok(mut state) = session.resize.lock() else {
+        return;
+    };
+    if let Some((rows, cols)) = state.pending.take() {
+        if let Err(e) = session.apply_resize(&mut state, rows, cols) {
+            log::warn!("Deferred resize of session {session_id} failed: {e}");
+        }
+    }
+}
+
+/// Waits until a session's resizes have been quiet for
+/// [`RESIZE_COALESCE_WINDOW`] with nothing left pending, then emits
+/// `pty-resized-{id}` with the applied size.
+async fn settle_resize(inner: Arc<Inner>, session_id: u32) {
+    let mut wait = RESIZE_COALESCE_WINDOW;
+    loop {
+        tokio::time::sleep(wait).await;
+        let Some(session) = inner.sessions.get(&session_id) else {
+            return;
+        };
+        let Ok(mut state) = session.resize.lock() else {
+            return;
+        };
+        let quiet_for = state
+            .requested_at
+            .map_or(RESIZE_COALESCE_WINDOW, |at| at.elapsed());
+        if quiet_for < RESIZE_COALESCE_WINDOW || state.pending.is_some() {
+            // A pending size is flushed no later than this; if the flush
+            // task hasn't run yet, check again shortly
+            wait = RESIZE_COALESCE_WINDOW
+                .saturating_sub(quiet_for)
+                .max(Duration::from_millis(1));
+            continue;
+        }
+        state.settle_scheduled = false;
+        let size = state.applied;
+        drop(state);
+        emit_resized(&session.spec.emit, session_id, size);
+        return;
+    }
+}
+
+/// Sends SIGTERM to process group `pgid`, polls for its lead process `pid`
+/// to exit for up to `grace`, then SIGKILLs the group if it is still alive.
+///
+/// Blocks the calling thread for at most `grace`. Reaps `pid` if it exits
+/// and is our child, so an exited shell is not mistaken for a live zombie.
+/// Returns `true` if SIGKILL was needed.
+#[cfg(unix)]
+pub(crate) fn terminate_process_group(
+    session_id: u32,
+    pid: i32,
+    pgid: i32,
+    grace: Duration,
+) -> bool {
+    // Send SIGTERM to the process group (negative pgid targets the group)
+    let term_result = unsafe { libc::kill(-pgid, libc::SIGTERM) };
+    if term_result != 0 {
+        log::warn!(
+            "Failed to SIGTERM session {session_id} (pgid={pgid}): {}",
+            std::io::Error::last_os_error()
+        );
+    }
+
+    let deadline = Instant::now() + grace;
+    loop {
+        if has_exited(pid) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL.min(deadline - now));
+    }
+
+    // Still alive after the grace period — SIGKILL the process group
+    let kill_result = unsafe { libc::kill(-pgid, libc::SIGKILL) };
+    if kill_result != 0 {
+        log::warn!(
+            "Failed to SIGKILL session {session_id} (pgid={pgid}): {}",
+            std::io::Error::last_os_error()
+        );
+    }
+    log::warn!(
+        "Session {session_id} (pid={pid}, pgid={pgid}) required SIGKILL after {}ms",
+        grace.as_millis()
+    );
+    true
+}
+
+/// Returns true once `pid` is gone. Reaps it when it is our exited child;
+/// otherwise probes with signal 0.
+#[cfg(unix)]
+fn has_exited(pid: i32) -> bool {
+    let mut status = 0;
+    match unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) } {
+        0 => false,
+        reaped if reaped == pid => true,
+        // Not our child (or already reaped elsewhere)
+        _ => (unsafe { libc::kill(pid, 0) }) != 0,
+    }
+}
+
+/// Shells tried in order when the requested shell and `$SHELL` are unusable.
+#[cfg(unix)]
+pub const FALLBACK_SHELLS: [&str; 3] = ["/bin/zsh", "/bin/bash", "/bin/sh"];
+
+/// Returns true if `path` is a regular file with an execute bit set.
+#[cfg(unix)]
+fn is_executable(path: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Picks the shell binary to spawn: `requested`, then `login_shell`
+/// (`$SHELL`), then [`FALLBACK_SHELLS`], taking the first that exists and is
+/// executable. Logs when a preferred shell had to be skipped.
+///
+/// If nothing is usable, returns `/bin/sh` and lets the spawn report the error.
+#[cfg(unix)]
+fn resolve_shell(requested: Option<&str>, login_shell: Option<&str>) -> String {
+    let preferred = requested.or(login_shell).filter(|s| !s.is_empty());
+    let candidates = requested
+        .into_iter()
+        .chain(login_shell)
+        .chain(FALLBACK_SHELLS)
+        .filter(|s| !s.is_empty());
+
+    for candidate in candidates {
+        if is_executable(candidate) {
+            if let Some(preferred) = preferred.filter(|p| *p != candidate) {
+                log::warn!(
+                    "Shell '{}' is missing or not executable, using '{}' instead",
+                    preferred,
+                    candidate
+                );
+            }
+            return candidate.to_string();
+        }
+    }
+
+    log::warn!("No usable shell found, trying /bin/sh");
+    "/bin/sh".to_string()
+}
+
+/// Starts the dedicated OS thread that reads PTY output in `read_buf`-sized
+/// chunks and forwards them to `tx`.
+///
+/// Shutdown mechanism: dropping the master/writer FDs closes the PTY file
+/// descriptor, which causes the blocking `reader.read()` call to return
+/// `Ok(0)` (EOF). This is the primary way the reader thread terminates — no
+/// explicit signal is needed.
+fn spawn_reader_thread(
+    id: u32,
+    mut reader: Box<dyn Read + Send>,
+    tx: tokio::sync::mpsc::Sender<Vec<u8>>,
+    read_buf: usize,
+    recorder: RecorderSlot,
+    detached: Arc<AtomicBool>,
+) -> std::io::Result<JoinHandle<()>> {
+    std::thread::Builder::new()
+        .name(format!("pty-reader-{id}"))
+        .spawn(move || {
+            let mut buf = vec![0u8; read_buf];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break, // EOF — shell exited
+                    // Detached: let go of the PTY instead of forwarding
+                    Ok(_) if detached.load(Ordering::Acquire) => break,
+                    Ok(n) => {
+                        // Record before sending so the file sees bytes exactly as read
+                        if let Ok(mut slot) = recorder.lock() {
+                            if let Some(Err(e)) = slot.as_mut().map(|r| r.write(&buf[..n])) {
+                                log::warn!("Stopping recording of PTY session {id}: {e}");
+                                *slot = None;
+                            }
+                        }
+                        // blocking_send is used because this is an OS thread, not async.
+                        // If the channel is full or closed, we break out of the loop.
+                        if tx.blocking_send(buf[..n].to_vec()).is_err() {
+                            log::warn!(
+                                "PTY reader {id}: channel send failed, dropping {} bytes",
+                                n
+                            );
+                            break; // Channel full or receiver dropped
+                        }
+                    }
+                    Err(e) => {
+                        // EAGAIN/EINTR are retriable on Unix; anything else is fatal
+                        #[cfg(unix)]
+                        {
+                            let raw = e.raw_os_error().unwrap_or(0);
+                            if raw == libc::EAGAIN || raw == libc::EINTR {
+                                continue;
+                            }
+                        }
+                        log::debug!("PTY reader {id} error: {e}");
+                        break;
+                    }
+                }
+            }
+            log::debug!("PTY reader {id} exited");
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spawns `sh -c script` as the leader of a new process group.
+    #[cfg(unix)]
+    fn spawn_group_leader(script: &str) -> std::process::Child {
+        use std::os::unix::process::CommandExt;
+
+        let child = std::process::Command::new("/bin/sh")
+            .args(["-c", script])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        // Give the shell time to install its traps
+        std::thread::sleep(Duration::from_millis(100));
+        child
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_escalates_to_sigkill_after_grace() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let mut child = spawn_group_leader("trap '' TERM; while :; do sleep 1; done");
+        let pid = child.id() as i32;
+        let grace = Duration::from_millis(300);
+
+        let started = Instant::now();
+        let killed = terminate_process_group(0, pid, pid, grace);
+
+        assert!(killed);
+        assert!(started.elapsed() >= grace);
+        let status = child.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_returns_early_when_process_exits() {
+        let mut child = spawn_group_leader("sleep 30");
+        let pid = child.id() as i32;
+
+        let started = Instant::now();
+        let killed = terminate_process_group(0, pid, pid, Duration::from_secs(5));
+
+        assert!(!killed);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        // Already reaped by the grace loop, so this only satisfies the handle
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_utf8_decoder_flush_emits_truncated_sequence() {
+        let mut decoder = Utf8Decoder::new();
+        // "é" then the first two of the four bytes of "🦀"
+        assert_eq!(decoder.decode(&[b'o', 0xC3]), "o");
+        assert_eq!(decoder.decode(&[0xA9, 0xF0, 0x9F]), "é");
+        assert_eq!(decoder.flush(), "\u{FFFD}");
+        // The buffer is cleared, so nothing is emitted twice
+        assert_eq!(decoder.flush(), "");
+        assert_eq!(decoder.decode(b"ok"), "ok");
+    }
+
+    #[test]
+    fn test_spawn_options_are_clamped() {
+        let tiny = SpawnOptions::new(1, 0);
+        assert_eq!(tiny.read_buf, SpawnOptions::MIN_READ_BUF);
+        assert_eq!(tiny.channel_cap, SpawnOptions::MIN_CHANNEL_CAP);
+
+        let huge = SpawnOptions::new(usize::MAX, usize::MAX);
+        assert_eq!(huge.read_buf, SpawnOptions::MAX_READ_BUF);
+        assert_eq!(huge.channel_cap, SpawnOptions::MAX_CHANNEL_CAP);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_shell_falls_back_from_missing_binaries() {
+        assert_eq!(
+            resolve_shell(Some("/bin/sh"), Some("/nonexistent/zsh")),
+            "/bin/sh"
+        );
+        assert_eq!(resolve_shell(None, Some("/bin/sh")), "/bin/sh");
+
+        let fallback = resolve_shell(Some("/nonexistent/fish"), Some("/nonexistent/zsh"));
+        assert!(FALLBACK_SHELLS.contains(&fallback.as_str()));
+        assert!(is_executable(&fallback));
+
+        // A directory is not a shell
+        let fallback = resolve_shell(Some("/"), None);
+        assert_ne!(fallback, "/");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_tiny_read_buffer_delivers_multibyte_output_intact() {
+        let text = "héllo 世界 🎵 ".repeat(40);
+        let options = SpawnOptions::new(1, 1);
+
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .unwrap();
+        let mut cmd = CommandBuilder::new("/bin/sh");
+        cmd.args(["-c", "printf '%s' \"$MAESTRO_TEST_TEXT\""]);
+        cmd.env("MAESTRO_TEST_TEXT", &text);
+        cmd.env("LANG", "en_US.UTF-8");
+        let mut child = pair.slave.spawn_command(cmd).unwrap();
+        drop(pair.slave);
+
+        let reader = pair.master.try_clone_reader().unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<u8>>(options.channel_cap);
+        let handle = spawn_reader_thread(
+            1,
+            reader,
+            tx,
+            options.read_buf,
+            RecorderSlot::default(),
+            Arc::default(),
+        )
+        .unwrap();
+
+        let mut decoder = Utf8Decoder::new();
+        let mut output = String::new();
+        let mut max_chunk = 0;
+        while !output.contains(text.as_str()) {
+            match rx.blocking_recv() {
+                Some(chunk) => {
+                    max_chunk = max_chunk.max(chunk.len());
+                    output.push_str(&decoder.decode(&chunk));
+                }
+                None => break,
+            }
+        }
+
+        child.wait().unwrap();
+        drop(pair.master);
+        drop(rx);
+        handle.join().unwrap();
+
+        assert!(max_chunk <= SpawnOptions::MIN_READ_BUF);
+        assert!(!output.contains('\u{FFFD}'), "split sequences were garbled");
+        assert!(output.contains(text.as_str()));
+    }
+
+    type EventLog = Arc<Mutex<Vec<(String, serde_json::Value)>>>;
+
+    /// Create a test PtyEmitFn that captures events into a shared Vec.
+    fn test_emit_fn() -> (PtyEmitFn, EventLog) {
+        let events: EventLog = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let emit_fn: PtyEmitFn = Arc::new(move |event: &str, payload: serde_json::Value| {
+            sink.lock().unwrap().push((event.to_string(), payload));
+        });
+        (emit_fn, events)
+    }
+
+    /// Concatenates all `pty-output-{id}` payloads captured so far.
+    fn output_for(events: &EventLog, id: u32) -> String {
+        let name = format!("pty-output-{id}");
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(event, _)| *event == name)
+            .filter_map(|(_, payload)| payload.as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// Polls until `needle` shows up in the session's output, or 10s pass.
+    async fn wait_for_output(events: &EventLog, id: u32, needle: &str) -> bool {
+        for _ in 0..200 {
+            if output_for(events, id).contains(needle) {
+                return true;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        false
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_resize_burst_is_coalesced_to_last_size() {
+        let pm = ProcessManager::new();
+        let (emit_fn, _events) = test_emit_fn();
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, None, SpawnOptions::default())
+            .unwrap();
+
+        for i in 0..10u16 {
+            pm.resize_pty(id, 30 + i, 100 + i).unwrap();
+        }
+        // The first resize applies immediately
+        assert_eq!(pm.pty_size(id).unwrap(), (30, 100));
+
+        tokio::time::sleep(RESIZE_COALESCE_WINDOW * 3).await;
+
+        assert_eq!(pm.pty_size(id).unwrap(), (39, 109));
+        let (resize_count, master_size) = {
+            let session = pm.inner.sessions.get(&id).unwrap();
+            let count = session.resize.lock().unwrap().resize_count;
+            let size = session.master.lock().unwrap().get_size().unwrap();
+            (count, size)
+        };
+        assert!(
+            resize_count <= 2,
+            "expected coalescing, got {resize_count} resizes"
+        );
+        assert_eq!((master_size.rows, master_size.cols), (39, 109));
+
+        pm.kill_session(id).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_resize_burst_emits_one_settled_event() {
+        let pm = ProcessManager::new();
+        let (emit_fn, events) = test_emit_fn();
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, None, SpawnOptions::default())
+            .unwrap();
+        let resized = || -> Vec<serde_json::Value> {
+            let name = format!("pty-resized-{id}");
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(event, _)| *event == name)
+                .map(|(_, payload)| payload.clone())
+                .collect()
+        };
+
+        for i in 0..10u16 {
+            pm.resize_pty(id, 30 + i, 100 + i).unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(resized().is_empty(), "emitted before the burst settled");
+
+        tokio::time::sleep(RESIZE_COALESCE_WINDOW * 4).await;
+        assert_eq!(
+            resized(),
+            vec![serde_json::json!({ "rows": 39, "cols": 109 })]
+        );
+
+        pm.kill_session(id).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_spawn_with_missing_shell_falls_back() {
+        let pm = ProcessManager::new();
+        let (emit_fn, events) = test_emit_fn();
+        let shell = Some("/nonexistent/maestro-shell".to_string());
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, shell, SpawnOptions::default())
+            .unwrap();
+
+        pm.write_stdin(id, "echo fallback-$((40+2))\n").unwrap();
+        let flowed = wait_for_output(&events, id, "fallback-42").await;
+
+        pm.kill_session(id).await.unwrap();
+        assert!(flowed, "fallback shell should run commands");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_restart_session_preserves_id_and_output_flows() {
+        let pm = ProcessManager::new();
+        let (emit_fn, events) = test_emit_fn();
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, None, SpawnOptions::default())
+            .unwrap();
+        let old_pid = pm.get_session_pid(id).unwrap();
+
+        pm.write_stdin(id, "echo before-$((20+1))\n").unwrap();
+        assert!(wait_for_output(&events, id, "before-21").await);
+
+        pm.restart_session(id).await.unwrap();
+
+        let new_pid = pm.get_session_pid(id).expect("session should keep its ID");
+        assert_ne!(old_pid, new_pid);
+        assert!(events
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(event, payload)| *event == format!("session-restarted-{id}") && *payload == id));
+
+        pm.write_stdin(id, "echo after-$((40+2))\n").unwrap();
+        assert!(wait_for_output(&events, id, "after-42").await);
+
+        pm.kill_session(id).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_record_session_tees_raw_output() {
+        let pm = ProcessManager::new();
+        let (emit_fn, events) = test_emit_fn();
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, None, SpawnOptions::default())
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.raw");
+
+        pm.record_session(id, path.clone(), false, 1024 * 1024)
+            .unwrap();
+        pm.write_stdin(id, "echo rec-$((40+2))\n").unwrap();
+        assert!(wait_for_output(&events, id, "rec-42").await);
+
+        assert!(pm.stop_recording(id).unwrap());
+        assert!(!pm.stop_recording(id).unwrap());
+        let recorded = String::from_utf8_lossy(&std::fs::read(&path).unwrap()).into_owned();
+        assert!(recorded.contains("rec-42"), "recording was: {recorded:?}");
+
+        pm.kill_session(id).await.unwrap();
+        assert!(matches!(
+            pm.stop_recording(id).unwrap_err().code,
+            crate::core::error::PtyErrorCode::SessionNotFound
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_detach_session_leaves_child_running() {
+        let pm = ProcessManager::new();
+        let (emit_fn, events) = test_emit_fn();
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, None, SpawnOptions::default())
+            .unwrap();
+        // Detaching hangs the PTY up, so only a SIGHUP-immune child survives
+        pm.write_stdin(id, "trap '' HUP; echo ready-$((1+1)); exec sleep 30\n")
+            .unwrap();
+        assert!(wait_for_output(&events, id, "ready-2").await);
+
+        // Returns once the reader has let go, even though the child is idle
+        let pid = tokio::time::timeout(Duration::from_secs(5), pm.detach_session(id))
+            .await
+            .expect("detach should not wait for the child to exit")
+            .unwrap();
+        assert!(!pm.inner.sessions.contains_key(&id));
+        assert!(pm.get_session_pid(id).is_none());
+        assert!(matches!(
+            pm.write_stdin(id, "exit\n").unwrap_err().code,
+            crate::core::error::PtyErrorCode::SessionNotFound
+        ));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!has_exited(pid), "detached shell should keep running");
+
+        // Clean up the shell we let go of
+        terminate_process_group(id, pid, pid, Duration::ZERO);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_restart_waits_for_a_spawn_slot_and_reports_failure() {
+        let pm = ProcessManager::with_spawn_limit(1);
+        let (emit_fn, events) = test_emit_fn();
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, None, SpawnOptions::default())
+            .unwrap();
+
+        // The restart queues behind a spawn holding the only slot
+        let slot = pm.inner.spawn_limiter.slots.acquire().await.unwrap();
+        let restart = tokio::spawn({
+            let pm = pm.clone();
+            async move { pm.restart_session(id).await }
+        });
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(!restart.is_finished());
+        drop(slot);
+        restart.await.unwrap().unwrap();
+        assert!(pm.get_session_pid(id).is_some());
+
+        // With no way to spawn, the old shell is gone and so is the session
+        pm.inner.spawn_limiter.slots.close();
+        let err = pm.restart_session(id).await.unwrap_err();
+
+        assert!(matches!(
+            err.code,
+            crate::core::error::PtyErrorCode::RestartFailed
+        ));
+        assert!(pm.get_session_pid(id).is_none());
+        assert!(events
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(event, _)| *event == format!("session-restart-failed-{id}")));
+    }
+
+    #[tokio::test]
+    async fn test_restart_unknown_session_fails() {
+        let pm = ProcessManager::new();
+        let err = pm.restart_session(42).await.unwrap_err();
+        assert!(matches!(
+            err.code,
+            crate::core::error::PtyErrorCode::SessionNotFound
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_spawn_limit_queues_excess_spawns() {
+        let pm = ProcessManager::with_spawn_limit(4);
+        let (emit_fn, _events) = test_emit_fn();
+
+        let tasks: Vec<_> = (0..20)
+            .map(|_| {
+                let pm = pm.clone();
+                let emit_fn = emit_fn.clone();
+                tokio::spawn(async move {
+                    pm.spawn_shell_queued(emit_fn, None, None, None, SpawnOptions::default())
+                        .await
+                })
+            })
+            .collect();
+        let mut ids = Vec::new();
+        for task in tasks {
+            ids.push(task.await.unwrap().unwrap());
+        }
+
+        let limiter = &pm.inner.spawn_limiter;
+        let peak = limiter.peak.load(Ordering::Relaxed);
+        assert!((1..=4).contains(&peak), "peak of {peak} concurrent spawns");
+        // Slots are released once each PTY is set up, not held by live sessions
+        assert_eq!(limiter.in_flight.load(Ordering::Relaxed), 0);
+        assert_eq!(limiter.slots.available_permits(), 4);
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 20);
+
+        assert_eq!(pm.kill_all_sessions().await.unwrap(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_set_spawn_limit_resizes_the_semaphore_in_place() {
+        let limiter = SpawnLimiter::new(3);
+        let first = limiter.slots.acquire().await.unwrap();
+        let second = limiter.slots.acquire().await.unwrap();
+
+        // Only one permit is free, so the other must be retired on release
+        limiter.set_limit(1);
+        assert_eq!(limiter.limit(), 1);
+        assert_eq!(limiter.slots.available_permits(), 0);
+        limiter.release(first);
+        assert_eq!(limiter.slots.available_permits(), 0);
+        limiter.release(second);
+        assert_eq!(limiter.slots.available_permits(), 1);
+
+        limiter.set_limit(4);
+        assert_eq!(limiter.slots.available_permits(), 4);
+        limiter.set_limit(0);
+        assert_eq!(limiter.limit(), 1);
+        assert_eq!(limiter.slots.available_permits(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_interrupt_session_stops_foreground_command() {
+        let pm = ProcessManager::new();
+        let (emit_fn, events) = test_emit_fn();
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, None, SpawnOptions::default())
+            .unwrap();
+        let pid = pm.get_session_pid(id).unwrap();
+        pm.write_stdin(id, "echo start-$((1+1)); sleep 100\n")
+            .unwrap();
+        assert!(wait_for_output(&events, id, "start-2").await);
+
+        let started = std::time::Instant::now();
+        pm.interrupt_session(id).unwrap();
+        pm.write_stdin(id, "echo after-$((2+3))\n").unwrap();
+        assert!(wait_for_output(&events, id, "after-5").await);
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // The shell survives the interrupt
+        assert_eq!(pm.get_session_pid(id), Some(pid));
+        assert!(!has_exited(pid));
+
+        pm.kill_session(id).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_env_allowlist_drops_unlisted_vars() {
+        // Set once for the whole test binary; other tests don't read these
+        std::env::set_var("MAESTRO_TEST_LISTED", "kept");
+        std::env::set_var("MAESTRO_TEST_UNLISTED", "leaked");
+        let pm = ProcessManager::new();
+        let (emit_fn, events) = test_emit_fn();
+        let options = SpawnOptions::default()
+            .with_env_mode(EnvMode::Allowlist(vec!["MAESTRO_TEST_LISTED".to_string()]));
+        let id = pm
+            .spawn_shell_with_emitter(emit_fn, None, None, Some("/bin/sh".to_string()), options)
+            .unwrap();
+
+        pm.write_stdin(
+            id,
+            "echo \"listed=[$MAESTRO_TEST_LISTED] unlisted=[$MAESTRO_TEST_UNLISTED] \
+             path=[${PATH:+set}] lang=[${LANG:+set}] sid=[$MAESTRO_SESSION_ID] done-$((1+1))\"\n",
+        )
+        .unwrap();
+        assert!(wait_for_output(&events, id, "done-2").await);
+        let output = output_for(&events, id);
+        pm.kill_session(id).await.unwrap();
+
+        assert!(output.contains("listed=[kept]"), "output: {output:?}");
+        assert!(output.contains("unlisted=[]"), "output: {output:?}");
+        assert!(output.contains("path=[set]"), "output: {output:?}");
+        assert!(output.contains("lang=[set]"), "output: {output:?}");
+        assert!(
+            output.contains(&format!("sid=[{id}]")),
+            "output: {output:?}"
+        );
+    }
 }
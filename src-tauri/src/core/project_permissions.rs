@@ -0,0 +1,153 @@
+//! Reads tool permission rules from a project's Claude settings.
+//!
+//! Claude CLI reads `permissions` from `.claude/settings.json` (checked in)
+//! and `.claude/settings.local.json` (per-user). Both are merged here so the
+//! UI can show which tools are pre-approved, denied, or always prompted:
+//! ```json
+//! {
+//!   "permissions": {
+//!     "allow": ["Bash(npm run test:*)"],
+//!     "deny": ["WebFetch"],
+//!     "ask": ["Bash(git push:*)"]
+//!   }
+//! }
+//! ```
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Merged permission rules for a project.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectPermissions {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    #[serde(default)]
+    pub ask: Vec<String>,
+}
+
+impl ProjectPermissions {
+    /// Layers `overrides` on top of `self`.
+    ///
+    /// Rules from both files are kept, but a rule that `overrides` places in
+    /// one list is removed from the other lists, so the local file decides
+    /// which bucket a conflicting rule lands in.
+    fn merged_with(self, overrides: ProjectPermissions) -> ProjectPermissions {
+        let overridden = |rule: &String| {
+            overrides.allow.contains(rule)
+                || overrides.deny.contains(rule)
+                || overrides.ask.contains(rule)
+        };
+        let layer = |base: Vec<String>, top: &[String]| {
+            let mut merged: Vec<String> = base.into_iter().filter(|r| !overridden(r)).collect();
+            for rule in top {
+                if !merged.contains(rule) {
+                    merged.push(rule.clone());
+                }
+            }
+            merged
+        };
+
+        ProjectPermissions {
+            allow: layer(self.allow, &overrides.allow),
+            deny: layer(self.deny, &overrides.deny),
+            ask: layer(self.ask, &overrides.ask),
+        }
+    }
+}
+
+/// Top-level shape of a Claude settings file; other keys are ignored.
+#[derive(Debug, Default, Deserialize)]
+struct SettingsFile {
+    #[serde(default)]
+    permissions: ProjectPermissions,
+}
+
+/// Reads `permissions` from one settings file. A missing file yields no rules.
+fn read_settings_permissions(path: &Path) -> Result<ProjectPermissions, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(ProjectPermissions::default())
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let settings: SettingsFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    Ok(settings.permissions)
+}
+
+/// Reads and merges permission rules from `.claude/settings.json` and
+/// `.claude/settings.local.json`, with the local file taking precedence.
+pub fn read_project_permissions(project_path: &Path) -> Result<ProjectPermissions, String> {
+    let claude_dir = project_path.join(".claude");
+    let shared = read_settings_permissions(&claude_dir.join("settings.json"))?;
+    let local = read_settings_permissions(&claude_dir.join("settings.local.json"))?;
+    Ok(shared.merged_with(local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn write_settings(project: &Path, file: &str, value: serde_json::Value) {
+        let claude_dir = project.join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join(file), value.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_missing_files_yield_empty_permissions() {
+        let dir = tempdir().unwrap();
+        let perms = read_project_permissions(dir.path()).unwrap();
+        assert_eq!(perms, ProjectPermissions::default());
+
+        // A settings file without a permissions block is also empty
+        write_settings(dir.path(), "settings.json", json!({ "model": "opus" }));
+        let perms = read_project_permissions(dir.path()).unwrap();
+        assert_eq!(perms, ProjectPermissions::default());
+    }
+
+    #[test]
+    fn test_local_settings_override_project_settings() {
+        let dir = tempdir().unwrap();
+        write_settings(
+            dir.path(),
+            "settings.json",
+            json!({ "permissions": {
+                "allow": ["Bash(npm test)", "Bash(git push:*)"],
+                "deny": ["WebFetch"]
+            }}),
+        );
+        write_settings(
+            dir.path(),
+            "settings.local.json",
+            json!({ "permissions": {
+                "allow": ["WebFetch", "Read"],
+                "ask": ["Bash(git push:*)"]
+            }}),
+        );
+
+        let perms = read_project_permissions(dir.path()).unwrap();
+
+        assert_eq!(perms.allow, vec!["Bash(npm test)", "WebFetch", "Read"]);
+        assert!(perms.deny.is_empty());
+        assert_eq!(perms.ask, vec!["Bash(git push:*)"]);
+    }
+
+    #[test]
+    fn test_malformed_settings_reports_file() {
+        let dir = tempdir().unwrap();
+        let claude_dir = dir.path().join(".claude");
+        std::fs::create_dir_all(&claude_dir).unwrap();
+        std::fs::write(claude_dir.join("settings.local.json"), "{ nope").unwrap();
+
+        let err = read_project_permissions(dir.path()).unwrap_err();
+        assert!(err.contains("settings.local.json"));
+    }
+}
@@ -1,7 +1,17 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, OnceLock};
+
 use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 use serde::{Deserialize, Serialize};
 
+/// Event emitted after every successful session registry mutation.
+pub const SESSIONS_CHANGED_EVENT: &str = "sessions-changed";
+
+/// Callback for emitting registry events (event name, payload). In production
+/// this wraps `AppHandle::emit`; in tests it captures events.
+pub(crate) type SessionEmitFn = Arc<dyn Fn(&str, serde_json::Value) + Send + Sync>;
+
 /// Which AI backend a session is configured to use.
 ///
 /// `Plain` is a raw terminal with no AI agent attached, useful for
@@ -43,6 +53,39 @@ pub struct SessionConfig {
     /// The project directory this session belongs to.
     /// Canonicalized absolute path for reliable comparison.
     pub project_path: String,
+    /// Labels such as "frontend"; tagged custom MCP servers are only given
+    /// to sessions sharing one of their tags.
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+}
+
+/// Trims tags, drops empty ones, and dedupes the rest. Shared by session and
+/// custom MCP server tags so both sides match on the same form.
+pub fn normalize_tags<S: AsRef<str>>(tags: impl IntoIterator<Item = S>) -> BTreeSet<String> {
+    tags.into_iter()
+        .map(|t| t.as_ref().trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// What happened to the session named in a `sessions-changed` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SessionChangeKind {
+    Created,
+    StatusUpdated,
+    BranchAssigned,
+    TagsUpdated,
+    Removed,
+}
+
+/// Payload of `sessions-changed`: the mutation plus the affected project's
+/// sessions afterwards, ordered by ID, so listeners can replace their copy.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionsChangedPayload {
+    pub kind: SessionChangeKind,
+    pub session_id: u32,
+    pub project_path: String,
+    pub sessions: Vec<SessionConfig>,
 }
 
 /// Thread-safe session registry backed by `DashMap` for lock-free concurrent reads.
@@ -52,6 +95,8 @@ pub struct SessionConfig {
 /// multiple async command handlers.
 pub struct SessionManager {
     sessions: DashMap<u32, SessionConfig>,
+    /// Set once the app is running; mutations before then emit nothing.
+    emitter: OnceLock<SessionEmitFn>,
 }
 
 impl Default for SessionManager {
@@ -65,6 +110,37 @@ impl SessionManager {
     pub fn new() -> Self {
         Self {
             sessions: DashMap::new(),
+            emitter: OnceLock::new(),
+        }
+    }
+
+    /// Installs the callback used to publish `sessions-changed` events.
+    /// Only the first call takes effect.
+    pub(crate) fn set_emitter(&self, emit: SessionEmitFn) {
+        if self.emitter.set(emit).is_err() {
+            log::warn!("SessionManager emitter already set; ignoring replacement");
+        }
+    }
+
+    /// Publishes a `sessions-changed` event for one mutation.
+    ///
+    /// Must be called without holding a map guard, since building the
+    /// payload reads the project's sessions.
+    fn notify(&self, kind: SessionChangeKind, session_id: u32, project_path: &str) {
+        let Some(emit) = self.emitter.get() else {
+            return;
+        };
+        let mut sessions = self.get_sessions_for_project(project_path);
+        sessions.sort_by_key(|s| s.id);
+        let payload = SessionsChangedPayload {
+            kind,
+            session_id,
+            project_path: project_path.to_string(),
+            sessions,
+        };
+        match serde_json::to_value(&payload) {
+            Ok(value) => emit(SESSIONS_CHANGED_EVENT, value),
+            Err(e) => log::warn!("Failed to serialize sessions-changed payload: {}", e),
         }
     }
 
@@ -78,14 +154,16 @@ impl SessionManager {
             status: SessionStatus::Idle,
             worktree_path: None,
             project_path,
+            tags: BTreeSet::new(),
         };
         match self.sessions.entry(id) {
-            Entry::Occupied(e) => Err(e.get().clone()),
+            Entry::Occupied(e) => return Err(e.get().clone()),
             Entry::Vacant(e) => {
                 e.insert(config.clone());
-                Ok(config)
             }
         }
+        self.notify(SessionChangeKind::Created, id, &config.project_path);
+        Ok(config)
     }
 
     /// Returns a snapshot of the session config, or `None` if not found.
@@ -96,24 +174,62 @@ impl SessionManager {
     /// Updates the session's status in place. Returns `false` if the session
     /// does not exist (no error is raised).
     pub fn update_status(&self, id: u32, status: SessionStatus) -> bool {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
-            session.status = status;
-            true
-        } else {
-            false
-        }
+        let project_path = match self.sessions.get_mut(&id) {
+            Some(mut session) => {
+                session.status = status;
+                session.project_path.clone()
+            }
+            None => return false,
+        };
+        self.notify(SessionChangeKind::StatusUpdated, id, &project_path);
+        true
     }
 
     /// Associates a branch (and optional worktree path) with an existing session.
     /// Returns the updated config, or `None` if the session does not exist.
     pub fn assign_branch(&self, id: u32, branch: String, worktree_path: Option<String>) -> Option<SessionConfig> {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
+        let updated = {
+            let mut session = self.sessions.get_mut(&id)?;
             session.branch = Some(branch);
             session.worktree_path = worktree_path;
-            Some(session.clone())
-        } else {
-            None
+            session.clone()
+        };
+        self.notify(SessionChangeKind::BranchAssigned, id, &updated.project_path);
+        Some(updated)
+    }
+
+    /// Replaces the session's tags. Tags are trimmed, and empty ones dropped.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn set_tags(&self, id: u32, tags: Vec<String>) -> Option<SessionConfig> {
+        let updated = {
+            let mut session = self.sessions.get_mut(&id)?;
+            session.tags = normalize_tags(tags);
+            session.clone()
+        };
+        self.notify(SessionChangeKind::TagsUpdated, id, &updated.project_path);
+        Some(updated)
+    }
+
+    /// Points every session running in `worktree_path` at `branch`, e.g. after
+    /// the worktree was switched to another branch. Returns the updated configs.
+    pub fn reassign_worktree_branch(&self, worktree_path: &str, branch: &str) -> Vec<SessionConfig> {
+        let updated: Vec<SessionConfig> = self
+            .sessions
+            .iter_mut()
+            .filter(|s| s.worktree_path.as_deref() == Some(worktree_path))
+            .map(|mut s| {
+                s.branch = Some(branch.to_string());
+                s.clone()
+            })
+            .collect();
+        for session in &updated {
+            self.notify(
+                SessionChangeKind::BranchAssigned,
+                session.id,
+                &session.project_path,
+            );
         }
+        updated
     }
 
     /// Returns a snapshot of all active sessions. Order is not guaranteed.
@@ -123,7 +239,9 @@ impl SessionManager {
 
     /// Removes and returns a session. Returns `None` if not found.
     pub fn remove_session(&self, id: u32) -> Option<SessionConfig> {
-        self.sessions.remove(&id).map(|(_, v)| v)
+        let (_, removed) = self.sessions.remove(&id)?;
+        self.notify(SessionChangeKind::Removed, id, &removed.project_path);
+        Some(removed)
     }
 
     /// Returns all sessions for a specific project path.
@@ -136,8 +254,24 @@ impl SessionManager {
             .collect()
     }
 
+    /// Returns the sessions in a project that are assigned to `branch`,
+    /// ordered by session ID. Both comparisons are exact matches.
+    pub fn get_sessions_for_branch(&self, project_path: &str, branch: &str) -> Vec<SessionConfig> {
+        let mut sessions: Vec<SessionConfig> = self
+            .sessions
+            .iter()
+            .filter(|entry| {
+                let session = entry.value();
+                session.project_path == project_path && session.branch.as_deref() == Some(branch)
+            })
+            .map(|entry| entry.value().clone())
+            .collect();
+        sessions.sort_by_key(|s| s.id);
+        sessions
+    }
+
     /// Removes all sessions for a project. Returns the removed configs.
-    /// Useful when closing a project tab.
+    /// Useful when closing a project tab. Emits one `Removed` event per session.
     pub fn remove_sessions_for_project(&self, project_path: &str) -> Vec<SessionConfig> {
         let ids_to_remove: Vec<u32> = self.sessions
             .iter()
@@ -147,7 +281,128 @@ impl SessionManager {
 
         ids_to_remove
             .into_iter()
-            .filter_map(|id| self.sessions.remove(&id).map(|(_, v)| v))
+            .filter_map(|id| self.remove_session(id))
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    type EventLog = Arc<Mutex<Vec<(String, serde_json::Value)>>>;
+
+    /// Creates a manager whose emitter captures events into a shared Vec.
+    fn capturing_manager() -> (SessionManager, EventLog) {
+        let events: EventLog = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = SessionManager::new();
+        manager.set_emitter(Arc::new(move |event: &str, payload: serde_json::Value| {
+            sink.lock().unwrap().push((event.to_string(), payload));
+        }));
+        (manager, events)
+    }
+
+    #[test]
+    fn test_each_mutation_emits_one_sessions_changed_event() {
+        let (manager, events) = capturing_manager();
+        let kinds = |events: &EventLog| -> Vec<String> {
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, payload)| {
+                    assert_eq!(name, SESSIONS_CHANGED_EVENT);
+                    payload["kind"].as_str().unwrap().to_string()
+                })
+                .collect()
+        };
+
+        manager
+            .create_session(1, AiMode::Claude, "/project/a".to_string())
+            .unwrap();
+        manager
+            .create_session(2, AiMode::Plain, "/project/a".to_string())
+            .unwrap();
+        assert!(manager.update_status(1, SessionStatus::Working));
+        manager
+            .assign_branch(1, "feature-x".to_string(), None)
+            .unwrap();
+        manager.remove_session(2).unwrap();
+        assert_eq!(
+            kinds(&events),
+            vec![
+                "Created",
+                "Created",
+                "StatusUpdated",
+                "BranchAssigned",
+                "Removed"
+            ]
+        );
+
+        // The payload carries the project's sessions after the mutation
+        let last = events.lock().unwrap().last().unwrap().1.clone();
+        assert_eq!(last["session_id"], 2);
+        assert_eq!(last["project_path"], "/project/a");
+        assert_eq!(last["sessions"].as_array().unwrap().len(), 1);
+        assert_eq!(last["sessions"][0]["branch"], "feature-x");
+
+        // Failed mutations emit nothing
+        events.lock().unwrap().clear();
+        assert!(manager
+            .create_session(1, AiMode::Claude, "/project/a".to_string())
+            .is_err());
+        assert!(!manager.update_status(9, SessionStatus::Done));
+        assert!(manager.assign_branch(9, "x".to_string(), None).is_none());
+        assert!(manager.remove_session(9).is_none());
+        assert!(kinds(&events).is_empty());
+    }
+
+    #[test]
+    fn test_get_sessions_for_branch_matches_only_that_branch() {
+        let manager = SessionManager::new();
+        for id in 1..=3 {
+            manager
+                .create_session(id, AiMode::Claude, "/project/a".to_string())
+                .unwrap();
+        }
+        manager
+            .create_session(4, AiMode::Claude, "/project/b".to_string())
+            .unwrap();
+        manager.assign_branch(1, "feature-x".to_string(), None);
+        manager.assign_branch(2, "feature-y".to_string(), None);
+        manager.assign_branch(4, "feature-x".to_string(), None);
+
+        let found = manager.get_sessions_for_branch("/project/a", "feature-x");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, 1);
+
+        assert!(manager
+            .get_sessions_for_branch("/project/a", "missing")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_reassign_worktree_branch_updates_only_sessions_in_that_worktree() {
+        let manager = SessionManager::new();
+        for id in 1..=3 {
+            manager
+                .create_session(id, AiMode::Claude, "/project/a".to_string())
+                .unwrap();
+        }
+        manager.assign_branch(1, "old".to_string(), Some("/wt/a".to_string()));
+        manager.assign_branch(2, "old".to_string(), Some("/wt/a".to_string()));
+        manager.assign_branch(3, "other".to_string(), Some("/wt/b".to_string()));
+
+        let mut updated = manager.reassign_worktree_branch("/wt/a", "new");
+        updated.sort_by_key(|s| s.id);
+        assert_eq!(updated.iter().map(|s| s.id).collect::<Vec<_>>(), vec![1, 2]);
+
+        let branch_of = |id| manager.get_session(id).unwrap().branch;
+        assert_eq!(branch_of(1).as_deref(), Some("new"));
+        assert_eq!(branch_of(2).as_deref(), Some("new"));
+        assert_eq!(branch_of(3).as_deref(), Some("other"));
+        assert!(manager.reassign_worktree_branch("/wt/none", "x").is_empty());
+    }
+}
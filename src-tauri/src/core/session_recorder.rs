@@ -0,0 +1,138 @@
+//! Raw PTY output recording for debugging terminal issues.
+//!
+//! A recording is the exact byte stream read from the PTY (before UTF-8
+//! decoding), so escape sequences survive and the file can be replayed with
+//! e.g. `cat`. Files rotate by size: the active file is `<path>`, older data
+//! moves to `<path>.1` (newest) through `<path>.N`.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Size at which a recording rotates when the caller doesn't choose one.
+pub const DEFAULT_MAX_RECORDING_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Rotated files kept besides the active one.
+const ROTATED_FILES_KEPT: usize = 3;
+
+/// Appends a session's raw PTY bytes to a size-rotated file.
+pub struct SessionRecorder {
+    path: PathBuf,
+    file: File,
+    /// Bytes in the active file.
+    written: u64,
+    max_bytes: u64,
+    /// Whether stdin is interleaved with the output (breaks exact replay).
+    include_input: bool,
+}
+
+impl SessionRecorder {
+    /// Opens `path` for appending, creating parent directories as needed.
+    /// An existing file is continued, counting toward `max_bytes`.
+    pub fn open(path: PathBuf, include_input: bool, max_bytes: u64) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes: max_bytes.max(1),
+            include_input,
+        })
+    }
+
+    /// The active recording file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether input written to the session should be recorded too.
+    pub fn includes_input(&self) -> bool {
+        self.include_input
+    }
+
+    /// Appends `bytes` unchanged, rotating first if they would push a
+    /// non-empty file past the size limit.
+    pub fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.written > 0 && self.written + bytes.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(bytes)?;
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Shifts `<path>.N` up by one (dropping the oldest), moves the active
+    /// file to `<path>.1`, and starts a new active file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let rotated = |n: usize| {
+            let mut name = self.path.as_os_str().to_owned();
+            name.push(format!(".{}", n));
+            PathBuf::from(name)
+        };
+        match fs::remove_file(rotated(ROTATED_FILES_KEPT)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        for n in (1..ROTATED_FILES_KEPT).rev() {
+            let from = rotated(n);
+            if from.exists() {
+                fs::rename(&from, rotated(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_keeps_bounded_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("session.raw");
+        let mut recorder = SessionRecorder::open(path.clone(), false, 8).unwrap();
+
+        for chunk in [
+            &b"\x1b[31mred"[..],
+            b"\x1b[0m",
+            b"abcdef",
+            b"ghij",
+            b"klmn",
+            b"op",
+        ] {
+            recorder.write(chunk).unwrap();
+        }
+
+        // Chunks are never split; a write landing exactly on the limit stays
+        assert_eq!(fs::read(&path).unwrap(), b"op");
+        assert_eq!(
+            fs::read(dir.path().join("logs/session.raw.1")).unwrap(),
+            b"ghijklmn"
+        );
+        assert_eq!(
+            fs::read(dir.path().join("logs/session.raw.2")).unwrap(),
+            b"abcdef"
+        );
+        assert_eq!(
+            fs::read(dir.path().join("logs/session.raw.3")).unwrap(),
+            b"\x1b[0m"
+        );
+        // The oldest chunk rotated out
+        assert!(!dir.path().join("logs/session.raw.4").exists());
+    }
+}
@@ -4,8 +4,11 @@
 //! status updates from the Rust MCP server. Provides real-time updates
 //! and eliminates race conditions.
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::future::{Future, IntoFuture};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use axum::{
     extract::State,
@@ -21,10 +24,85 @@ use tokio::sync::RwLock;
 /// Maximum number of pending statuses to buffer (prevents memory leaks).
 const MAX_PENDING_STATUSES: usize = 100;
 
+/// Number of recent statuses kept per session unless overridden by
+/// `MAESTRO_STATUS_HISTORY_LEN`.
+const DEFAULT_STATUS_HISTORY_LEN: usize = 20;
+
+/// Silence after which a session's status is reported as `Unknown`, unless
+/// overridden by `MAESTRO_STALE_STATUS_SECS`.
+const DEFAULT_STALE_STATUS_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// MCP state strings and the UI statuses they map to. States not listed here
+/// are emitted as `UNKNOWN_STATUS`, with the original string in `raw_state`.
+const STATE_STATUSES: [(&str, &str); 5] = [
+    ("idle", "Idle"),
+    ("working", "Working"),
+    ("needs_input", "NeedsInput"),
+    ("finished", "Done"),
+    ("error", "Error"),
+];
+
+/// How often a `status-server-heartbeat` event is emitted.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Catch-all UI status for unrecognised states and silent sessions.
+const UNKNOWN_STATUS: &str = "Unknown";
+
+/// Statuses after which silence is expected, so they never go stale.
+const TERMINAL_STATUSES: [&str; 2] = ["Done", "Error"];
+
 /// Callback for emitting status events. In production this wraps `AppHandle::emit`;
 /// in tests it captures events into a `Vec`.
 type EmitFn = Arc<dyn Fn(SessionStatusPayload) + Send + Sync>;
 
+/// Callback for emitting `status-server-heartbeat` events.
+type HeartbeatFn = Arc<dyn Fn(HeartbeatPayload) + Send + Sync>;
+
+/// Liveness report emitted periodically and whenever the server dies or
+/// comes back, so the frontend can tell status reporting has stopped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeartbeatPayload {
+    pub instance_id: String,
+    pub port: u16,
+    pub alive: bool,
+}
+
+/// Whether the HTTP server is currently serving, and on which port.
+struct Liveness {
+    instance_id: String,
+    port: AtomicU16,
+    alive: AtomicBool,
+}
+
+impl Liveness {
+    fn new(instance_id: String, port: u16) -> Self {
+        Self {
+            instance_id,
+            port: AtomicU16::new(port),
+            alive: AtomicBool::new(true),
+        }
+    }
+
+    fn heartbeat(&self) -> HeartbeatPayload {
+        HeartbeatPayload {
+            instance_id: self.instance_id.clone(),
+            port: self.port.load(Ordering::Acquire),
+            alive: self.alive.load(Ordering::Acquire),
+        }
+    }
+
+    fn mark_dead(&self, heartbeat: &HeartbeatFn) {
+        self.alive.store(false, Ordering::Release);
+        heartbeat(self.heartbeat());
+    }
+
+    fn mark_alive(&self, port: u16, heartbeat: &HeartbeatFn) {
+        self.port.store(port, Ordering::Release);
+        self.alive.store(true, Ordering::Release);
+        heartbeat(self.heartbeat());
+    }
+}
+
 /// Status payload received from MCP server.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StatusRequest {
@@ -33,6 +111,10 @@ pub struct StatusRequest {
     pub state: String,
     pub message: String,
     pub needs_input_prompt: Option<String>,
+    /// Hash of the session's project path (`MAESTRO_PROJECT_HASH`), used to
+    /// route statuses from sessions that aren't registered.
+    #[serde(default)]
+    pub project_hash: Option<String>,
     #[allow(dead_code)]
     pub timestamp: String,
 }
@@ -45,6 +127,134 @@ pub struct SessionStatusPayload {
     pub status: String,
     pub message: String,
     pub needs_input_prompt: Option<String>,
+    /// The state string the MCP server reported, before mapping to `status`.
+    /// `None` for statuses Maestro synthesises itself.
+    pub raw_state: Option<String>,
+}
+
+/// Bounded per-session timeline of emitted statuses, oldest first.
+///
+/// Uses a std `Mutex` because it is only touched from the synchronous
+/// `emit_status` path and never held across an await.
+struct StatusHistory {
+    capacity: usize,
+    sessions: Mutex<HashMap<u32, VecDeque<SessionStatusPayload>>>,
+}
+
+impl StatusHistory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reads the capacity from `MAESTRO_STATUS_HISTORY_LEN`, falling back to the default.
+    fn from_env() -> Self {
+        let capacity = std::env::var("MAESTRO_STATUS_HISTORY_LEN")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_STATUS_HISTORY_LEN);
+        Self::new(capacity)
+    }
+
+    /// Appends a status, evicting the oldest once the session is at capacity.
+    fn record(&self, payload: &SessionStatusPayload) {
+        let mut sessions = self.sessions.lock().unwrap();
+        let history = sessions.entry(payload.session_id).or_default();
+        if history.len() == self.capacity {
+            history.pop_front();
+        }
+        history.push_back(payload.clone());
+    }
+
+    fn get(&self, session_id: u32) -> Vec<SessionStatusPayload> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions
+            .get(&session_id)
+            .map(|h| h.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn remove(&self, session_id: u32) {
+        self.sessions.lock().unwrap().remove(&session_id);
+    }
+}
+
+/// Last real status per session, for the stale-status sweep.
+///
+/// Like `StatusHistory`, only touched from synchronous code and never held
+/// across an await.
+#[derive(Default)]
+struct StatusActivity {
+    sessions: Mutex<HashMap<u32, LastStatus>>,
+}
+
+struct LastStatus {
+    at: Instant,
+    status: String,
+    /// Set once the silence has been reported, so it is reported only once.
+    reported_stale: bool,
+}
+
+impl StatusActivity {
+    /// Records a real status from the session, restarting its silence timer.
+    fn touch(&self, session_id: u32, status: &str, now: Instant) {
+        self.sessions.lock().unwrap().insert(
+            session_id,
+            LastStatus {
+                at: now,
+                status: status.to_string(),
+                reported_stale: false,
+            },
+        );
+    }
+
+    /// Returns the sessions among `candidates` that have been silent for at
+    /// least `timeout` as of `now` and whose last status is not terminal,
+    /// marking them so the same silence is not returned again.
+    fn take_stale(&self, candidates: &[u32], now: Instant, timeout: Duration) -> Vec<u32> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let mut stale = Vec::new();
+        for id in candidates {
+            let Some(last) = sessions.get_mut(id) else {
+                continue;
+            };
+            if last.reported_stale
+                || TERMINAL_STATUSES.contains(&last.status.as_str())
+                || now.saturating_duration_since(last.at) < timeout
+            {
+                continue;
+            }
+            last.reported_stale = true;
+            stale.push(*id);
+        }
+        stale
+    }
+
+    fn remove(&self, session_id: u32) {
+        self.sessions.lock().unwrap().remove(&session_id);
+    }
+}
+
+/// Reads the opt-in stale-status timeout from the environment.
+///
+/// The sweep is off unless `MAESTRO_STALE_STATUS` is `1` or `true`;
+/// `MAESTRO_STALE_STATUS_SECS` overrides the default timeout.
+fn stale_timeout_from_env() -> Option<Duration> {
+    let enabled = std::env::var("MAESTRO_STALE_STATUS")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    let timeout = std::env::var("MAESTRO_STALE_STATUS_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&secs: &u64| secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_STALE_STATUS_TIMEOUT);
+    Some(timeout)
 }
 
 /// State shared with the HTTP handler.
@@ -55,15 +265,24 @@ struct ServerState {
     session_projects: Arc<RwLock<HashMap<u32, String>>>,
     /// Buffers status requests that arrive before session registration
     pending_statuses: Arc<RwLock<HashMap<u32, StatusRequest>>>,
+    /// Maps project hash -> project_path for every project ever registered
+    project_hashes: Arc<RwLock<HashMap<String, String>>>,
+    /// Recent statuses per session
+    history: Arc<StatusHistory>,
+    /// Last real status per session
+    activity: Arc<StatusActivity>,
 }
 
 /// HTTP status server that receives status updates from MCP servers.
 pub struct StatusServer {
-    port: u16,
+    liveness: Arc<Liveness>,
     instance_id: String,
     emit_fn: EmitFn,
     session_projects: Arc<RwLock<HashMap<u32, String>>>,
     pending_statuses: Arc<RwLock<HashMap<u32, StatusRequest>>>,
+    project_hashes: Arc<RwLock<HashMap<String, String>>>,
+    history: Arc<StatusHistory>,
+    activity: Arc<StatusActivity>,
 }
 
 /// Build the axum router with the given shared state.
@@ -73,6 +292,62 @@ fn build_router(state: Arc<ServerState>) -> Router {
         .with_state(state)
 }
 
+/// Create a `HeartbeatFn` from a Tauri `AppHandle`.
+fn heartbeat_fn_from_app_handle(app_handle: AppHandle) -> HeartbeatFn {
+    Arc::new(move |payload: HeartbeatPayload| {
+        if let Err(e) = app_handle.emit("status-server-heartbeat", &payload) {
+            log::warn!("Failed to emit status-server-heartbeat: {}", e);
+        }
+    })
+}
+
+/// Runs `serve` on `listener` until it returns or panics, logging why.
+async fn serve_until_exit<F, Fut>(serve: &F, listener: tokio::net::TcpListener)
+where
+    F: Fn(tokio::net::TcpListener) -> Fut,
+    Fut: Future<Output = std::io::Result<()>> + Send + 'static,
+{
+    match tokio::spawn(serve(listener)).await {
+        Ok(Ok(())) => eprintln!("[STATUS SERVER] Stopped serving"),
+        Ok(Err(e)) => eprintln!("[STATUS SERVER] Error: {}", e),
+        Err(e) => eprintln!("[STATUS SERVER] Serve task failed: {}", e),
+    }
+}
+
+/// Serves on `listener` and, if serving stops, reports the server dead and
+/// makes one attempt to bind again and resume.
+///
+/// The old port is tried first because MCP configs already written for
+/// running sessions point at it; any port in the usual range is the
+/// fallback. A second exit is reported but not retried.
+async fn supervise_serve<F, Fut>(
+    listener: tokio::net::TcpListener,
+    serve: F,
+    liveness: Arc<Liveness>,
+    heartbeat: HeartbeatFn,
+) where
+    F: Fn(tokio::net::TcpListener) -> Fut,
+    Fut: Future<Output = std::io::Result<()>> + Send + 'static,
+{
+    serve_until_exit(&serve, listener).await;
+    liveness.mark_dead(&heartbeat);
+
+    let port = liveness.port.load(Ordering::Acquire);
+    let rebound = match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(listener) => Some((port, listener)),
+        Err(_) => StatusServer::find_and_bind_port(9900, 9999).await,
+    };
+    let Some((port, listener)) = rebound else {
+        eprintln!("[STATUS SERVER] Rebind failed; status reporting is unavailable");
+        return;
+    };
+    eprintln!("[STATUS SERVER] Rebound on port {}", port);
+    liveness.mark_alive(port, &heartbeat);
+
+    serve_until_exit(&serve, listener).await;
+    liveness.mark_dead(&heartbeat);
+}
+
 /// Create an `EmitFn` from a Tauri `AppHandle`.
 fn emit_fn_from_app_handle(app_handle: AppHandle) -> EmitFn {
     Arc::new(move |payload: SessionStatusPayload| {
@@ -113,42 +388,108 @@ impl StatusServer {
         // Find and bind in one step to avoid race conditions where another
         // process grabs the port between checking and binding
         let (port, listener) = Self::find_and_bind_port(9900, 9999).await?;
-        let session_projects = Arc::new(RwLock::new(HashMap::new()));
-        let pending_statuses = Arc::new(RwLock::new(HashMap::new()));
-        let emit_fn = emit_fn_from_app_handle(app_handle);
-
-        let state = Arc::new(ServerState {
-            emit_fn: emit_fn.clone(),
-            instance_id: instance_id.clone(),
-            session_projects: session_projects.clone(),
-            pending_statuses: pending_statuses.clone(),
-        });
+        let heartbeat = heartbeat_fn_from_app_handle(app_handle.clone());
+        let server = Self {
+            liveness: Arc::new(Liveness::new(instance_id.clone(), port)),
+            instance_id,
+            emit_fn: emit_fn_from_app_handle(app_handle),
+            session_projects: Arc::new(RwLock::new(HashMap::new())),
+            pending_statuses: Arc::new(RwLock::new(HashMap::new())),
+            project_hashes: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(StatusHistory::from_env()),
+            activity: Arc::new(StatusActivity::default()),
+        };
 
-        let app = build_router(state);
+        let app = build_router(server.handler_state());
 
         let addr = format!("127.0.0.1:{}", port);
         eprintln!("[STATUS SERVER] Started on http://{}", addr);
-        eprintln!("[STATUS SERVER] Instance ID: {}", instance_id);
+        eprintln!("[STATUS SERVER] Instance ID: {}", server.instance_id);
+
+        // Spawn the server in the background, supervised so that its death
+        // is reported to the frontend instead of going unnoticed
+        tokio::spawn(supervise_serve(
+            listener,
+            move |listener| axum::serve(listener, app.clone()).into_future(),
+            server.liveness.clone(),
+            heartbeat.clone(),
+        ));
+        server.start_heartbeat(heartbeat);
+
+        if let Some(timeout) = stale_timeout_from_env() {
+            server.start_stale_sweep(timeout);
+        }
+
+        Some(server)
+    }
+
+    /// Start a background task that reports a registered session as
+    /// `Unknown` once it has sent no status for `timeout`, e.g. because its
+    /// MCP server died. Sessions whose last status was Done or Error, or
+    /// that never reported at all, are left alone.
+    pub fn start_stale_sweep(&self, timeout: Duration) {
+        let emit_fn = self.emit_fn.clone();
+        let history = self.history.clone();
+        let activity = self.activity.clone();
+        let session_projects = self.session_projects.clone();
+        let period = (timeout / 10).max(Duration::from_secs(1));
+        eprintln!(
+            "[STATUS SERVER] Stale status sweep enabled (timeout {:?})",
+            timeout
+        );
 
-        // Spawn the server in the background
         tokio::spawn(async move {
-            if let Err(e) = axum::serve(listener, app).await {
-                eprintln!("[STATUS SERVER] Error: {}", e);
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                sweep_stale_statuses(
+                    &emit_fn,
+                    &history,
+                    &activity,
+                    &session_projects,
+                    Instant::now(),
+                    timeout,
+                )
+                .await;
             }
         });
+    }
 
-        Some(Self {
-            port,
-            instance_id,
-            emit_fn,
-            session_projects,
-            pending_statuses,
+    /// Start a background task emitting the server's liveness every
+    /// `HEARTBEAT_INTERVAL`.
+    fn start_heartbeat(&self, heartbeat: HeartbeatFn) {
+        let liveness = self.liveness.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                heartbeat(liveness.heartbeat());
+            }
+        });
+    }
+
+    /// Build the state shared with the HTTP handler.
+    fn handler_state(&self) -> Arc<ServerState> {
+        Arc::new(ServerState {
+            emit_fn: self.emit_fn.clone(),
+            instance_id: self.instance_id.clone(),
+            session_projects: self.session_projects.clone(),
+            pending_statuses: self.pending_statuses.clone(),
+            project_hashes: self.project_hashes.clone(),
+            history: self.history.clone(),
+            activity: self.activity.clone(),
         })
     }
 
-    /// Get the port the server is listening on.
+    /// Get the port the server is listening on. This can change if the
+    /// server had to rebind after dying.
     pub fn port(&self) -> u16 {
-        self.port
+        self.liveness.port.load(Ordering::Acquire)
+    }
+
+    /// Whether the HTTP server is currently accepting status reports.
+    pub fn is_alive(&self) -> bool {
+        self.liveness.alive.load(Ordering::Acquire)
     }
 
     /// Get the instance ID for this server.
@@ -158,17 +499,23 @@ impl StatusServer {
 
     /// Get the status URL for MCP servers to report to.
     pub fn status_url(&self) -> String {
-        format!("http://127.0.0.1:{}/status", self.port)
+        format!("http://127.0.0.1:{}/status", self.port())
     }
 
     /// Register a session with its project path.
     /// This allows routing status updates to the correct project.
     /// Also flushes any buffered status that arrived before registration.
+    /// Registering an already-known session replaces its project mapping,
+    /// which is how relaunched or restored sessions are re-routed.
     pub async fn register_session(&self, session_id: u32, project_path: &str) {
         {
             let mut projects = self.session_projects.write().await;
             projects.insert(session_id, project_path.to_string());
         }
+        self.project_hashes.write().await.insert(
+            Self::generate_project_hash(project_path),
+            project_path.to_string(),
+        );
         eprintln!(
             "[STATUS SERVER] Registered session {} for project '{}'",
             session_id,
@@ -186,7 +533,14 @@ impl StatusServer {
                 "[STATUS SERVER] Flushing buffered status for session {}: state={}",
                 session_id, payload.state
             );
-            emit_status(&self.emit_fn, session_id, project_path, &payload);
+            emit_status(
+                &self.emit_fn,
+                &self.history,
+                &self.activity,
+                session_id,
+                project_path,
+                &payload,
+            );
         }
     }
 
@@ -196,10 +550,17 @@ impl StatusServer {
         if projects.remove(&session_id).is_some() {
             log::debug!("Unregistered session {}", session_id);
         }
-        // Also clean up any buffered status
+        // Also clean up any buffered status and history
         drop(projects);
         let mut pending = self.pending_statuses.write().await;
         pending.remove(&session_id);
+        self.history.remove(session_id);
+        self.activity.remove(session_id);
+    }
+
+    /// Get the most recent statuses emitted for a session, oldest first.
+    pub fn status_history(&self, session_id: u32) -> Vec<SessionStatusPayload> {
+        self.history.get(session_id)
     }
 
     /// Get list of registered session IDs (for debugging).
@@ -209,24 +570,29 @@ impl StatusServer {
     }
 }
 
-/// Map MCP state string to session status string and call the emit function.
+/// Looks up the UI status for an MCP state, falling back to `UNKNOWN_STATUS`.
+fn map_state(state: &str) -> &'static str {
+    STATE_STATUSES
+        .iter()
+        .find(|(raw, _)| *raw == state)
+        .map(|(_, status)| *status)
+        .unwrap_or_else(|| {
+            log::warn!("Unknown status state: {}", state);
+            UNKNOWN_STATUS
+        })
+}
+
+/// Map MCP state string to session status string, record it in the session's
+/// history and activity, and call the emit function.
 fn emit_status(
     emit_fn: &EmitFn,
+    history: &StatusHistory,
+    activity: &StatusActivity,
     session_id: u32,
     project_path: &str,
     payload: &StatusRequest,
 ) {
-    let status = match payload.state.as_str() {
-        "idle" => "Idle",
-        "working" => "Working",
-        "needs_input" => "NeedsInput",
-        "finished" => "Done",
-        "error" => "Error",
-        other => {
-            log::warn!("Unknown status state: {}", other);
-            "Unknown"
-        }
-    };
+    let status = map_state(&payload.state);
 
     eprintln!(
         "[STATUS] EMITTING: session={} status={} project={}",
@@ -239,11 +605,47 @@ fn emit_status(
         status: status.to_string(),
         message: payload.message.clone(),
         needs_input_prompt: payload.needs_input_prompt.clone(),
+        raw_state: Some(payload.state.clone()),
     };
 
+    activity.touch(session_id, status, Instant::now());
+    history.record(&event_payload);
     (emit_fn)(event_payload);
 }
 
+/// Emit a synthetic `Unknown` status for every registered session that has
+/// been silent for `timeout` as of `now`. Returns how many were emitted.
+async fn sweep_stale_statuses(
+    emit_fn: &EmitFn,
+    history: &StatusHistory,
+    activity: &StatusActivity,
+    session_projects: &RwLock<HashMap<u32, String>>,
+    now: Instant,
+    timeout: Duration,
+) -> usize {
+    let projects = session_projects.read().await.clone();
+    let registered: Vec<u32> = projects.keys().copied().collect();
+    let stale = activity.take_stale(&registered, now, timeout);
+
+    for &session_id in &stale {
+        eprintln!(
+            "[STATUS] STALE: session={} silent for {:?}",
+            session_id, timeout
+        );
+        let event_payload = SessionStatusPayload {
+            session_id,
+            project_path: projects[&session_id].clone(),
+            status: UNKNOWN_STATUS.to_string(),
+            message: format!("No status reported for {} seconds", timeout.as_secs()),
+            needs_input_prompt: None,
+            raw_state: None,
+        };
+        history.record(&event_payload);
+        (emit_fn)(event_payload);
+    }
+    stale.len()
+}
+
 /// Handle incoming status POST requests.
 async fn handle_status(
     State(state): State<Arc<ServerState>>,
@@ -276,6 +678,15 @@ async fn handle_status(
         projects.get(&payload.session_id).cloned()
     };
 
+    // Unregistered session: fall back to the project hash from its MCP env
+    let project_path = match project_path {
+        Some(p) => Some(p),
+        None => match &payload.project_hash {
+            Some(hash) => state.project_hashes.read().await.get(hash).cloned(),
+            None => None,
+        },
+    };
+
     let project_path = match project_path {
         Some(p) => p,
         None => {
@@ -298,7 +709,14 @@ async fn handle_status(
         }
     };
 
-    emit_status(&state.emit_fn, payload.session_id, &project_path, &payload);
+    emit_status(
+        &state.emit_fn,
+        &state.history,
+        &state.activity,
+        payload.session_id,
+        &project_path,
+        &payload,
+    );
 
     StatusCode::OK
 }
@@ -323,14 +741,28 @@ mod tests {
     /// Create a test StatusServer (no real port, no AppHandle).
     fn test_server(instance_id: &str, emit_fn: EmitFn) -> StatusServer {
         StatusServer {
-            port: 0,
+            liveness: Arc::new(Liveness::new(instance_id.to_string(), 0)),
             instance_id: instance_id.to_string(),
             emit_fn,
             session_projects: Arc::new(RwLock::new(HashMap::new())),
             pending_statuses: Arc::new(RwLock::new(HashMap::new())),
+            project_hashes: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(StatusHistory::new(DEFAULT_STATUS_HISTORY_LEN)),
+            activity: Arc::new(StatusActivity::default()),
         }
     }
 
+    /// Serve HTTP for an existing test StatusServer, returning its address.
+    async fn serve(server: &StatusServer) -> std::net::SocketAddr {
+        let app = build_router(server.handler_state());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        addr
+    }
+
     /// Spin up a real HTTP server backed by our handler, returning its address.
     async fn start_test_http_server(
         instance_id: &str,
@@ -348,6 +780,9 @@ mod tests {
             instance_id: instance_id.to_string(),
             session_projects: session_projects.clone(),
             pending_statuses: pending_statuses.clone(),
+            project_hashes: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(StatusHistory::new(DEFAULT_STATUS_HISTORY_LEN)),
+            activity: Arc::new(StatusActivity::default()),
         });
 
         let app = build_router(state);
@@ -380,6 +815,7 @@ mod tests {
             state: state.to_string(),
             message: message.to_string(),
             needs_input_prompt: None,
+            project_hash: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
         }
     }
@@ -508,6 +944,189 @@ mod tests {
         assert_eq!(emitted[0].session_id, 2);
     }
 
+    #[tokio::test]
+    async fn test_unregistered_session_routes_by_project_hash() {
+        let (emit_fn, events) = test_emit_fn();
+        let server = test_server("inst-1", emit_fn);
+        let addr = serve(&server).await;
+
+        // Project is known from an earlier session, but session 9 never registered
+        server.register_session(1, "/path/project-h").await;
+        server.unregister_session(1).await;
+
+        let mut status = make_status(9, "inst-1", "working", "Routed by hash");
+        status.project_hash = Some(StatusServer::generate_project_hash("/path/project-h"));
+        assert_eq!(post_status(addr, &status).await, 200);
+
+        {
+            let emitted = events.lock().unwrap();
+            assert_eq!(emitted.len(), 1);
+            assert_eq!(emitted[0].session_id, 9);
+            assert_eq!(emitted[0].project_path, "/path/project-h");
+        }
+        assert!(server.pending_statuses.read().await.is_empty());
+
+        // An unknown hash still falls back to the pending buffer
+        let mut status = make_status(10, "inst-1", "idle", "Unknown project");
+        status.project_hash = Some("000000000000".to_string());
+        assert_eq!(post_status(addr, &status).await, 202);
+        assert!(server.pending_statuses.read().await.contains_key(&10));
+    }
+
+    #[tokio::test]
+    async fn test_status_history_is_ordered_and_capped() {
+        let (emit_fn, _events) = test_emit_fn();
+        let mut server = test_server("inst-1", emit_fn);
+        server.history = Arc::new(StatusHistory::new(3));
+        let addr = serve(&server).await;
+        server.register_session(1, "/path/p").await;
+
+        for (state, message) in [
+            ("working", "one"),
+            ("needs_input", "two"),
+            ("working", "three"),
+            ("finished", "four"),
+        ] {
+            let status = make_status(1, "inst-1", state, message);
+            assert_eq!(post_status(addr, &status).await, 200);
+        }
+
+        let history = server.status_history(1);
+        let timeline: Vec<(&str, &str)> = history
+            .iter()
+            .map(|p| (p.status.as_str(), p.message.as_str()))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                ("NeedsInput", "two"),
+                ("Working", "three"),
+                ("Done", "four")
+            ]
+        );
+        assert!(server.status_history(2).is_empty());
+
+        server.unregister_session(1).await;
+        assert!(server.status_history(1).is_empty());
+    }
+
+    // ── Stale status sweep ──────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_stale_sweep_emits_unknown_once_after_silence() {
+        let (emit_fn, events) = test_emit_fn();
+        let server = test_server("inst-1", emit_fn);
+        let timeout = DEFAULT_STALE_STATUS_TIMEOUT;
+        for id in 1..=3 {
+            server.register_session(id, "/path/p").await;
+        }
+
+        // Session 1 is working, session 2 finished, session 3 never reported
+        let report = |id: u32, state: &str| {
+            emit_status(
+                &server.emit_fn,
+                &server.history,
+                &server.activity,
+                id,
+                "/path/p",
+                &make_status(id, "inst-1", state, "msg"),
+            )
+        };
+        report(1, "working");
+        report(2, "finished");
+        let start = Instant::now();
+        events.lock().unwrap().clear();
+
+        // Fake clock: sweep at instants relative to the last real status
+        let sweep = |now: Instant| {
+            sweep_stale_statuses(
+                &server.emit_fn,
+                &server.history,
+                &server.activity,
+                &server.session_projects,
+                now,
+                timeout,
+            )
+        };
+        assert_eq!(sweep(start + timeout / 2).await, 0);
+        assert_eq!(sweep(start + timeout + Duration::from_secs(1)).await, 1);
+        assert_eq!(sweep(start + timeout * 3).await, 0);
+
+        {
+            let emitted = events.lock().unwrap();
+            assert_eq!(emitted.len(), 1);
+            assert_eq!(emitted[0].session_id, 1);
+            assert_eq!(emitted[0].project_path, "/path/p");
+            assert_eq!(emitted[0].status, "Unknown");
+        }
+        assert_eq!(server.status_history(1).last().unwrap().status, "Unknown");
+
+        // A real status resets the timer
+        report(1, "working");
+        let restart = Instant::now();
+        assert_eq!(sweep(restart + timeout / 2).await, 0);
+        assert_eq!(sweep(restart + timeout).await, 1);
+
+        // Unregistered sessions are no longer swept
+        report(1, "working");
+        server.unregister_session(1).await;
+        assert_eq!(sweep(Instant::now() + timeout * 2).await, 0);
+    }
+
+    // ── Supervision / heartbeat ─────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_serve_exit_reports_dead_and_rebinds_same_port() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let liveness = Arc::new(Liveness::new("inst-1".to_string(), port));
+
+        let beats = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = beats.clone();
+        let heartbeat: HeartbeatFn = Arc::new(move |payload| sink.lock().unwrap().push(payload));
+
+        // The first serve dies at once; the rebound one keeps serving
+        let served_on = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let serve = {
+            let served_on = served_on.clone();
+            move |listener: tokio::net::TcpListener| {
+                let mut served_on = served_on.lock().unwrap();
+                served_on.push(listener.local_addr().unwrap().port());
+                let first = served_on.len() == 1;
+                async move {
+                    if first {
+                        Err(std::io::Error::other("accept loop died"))
+                    } else {
+                        let _listener = listener;
+                        std::future::pending().await
+                    }
+                }
+            }
+        };
+        tokio::spawn(supervise_serve(
+            listener,
+            serve,
+            liveness.clone(),
+            heartbeat,
+        ));
+
+        for _ in 0..200 {
+            if beats.lock().unwrap().len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let beat = |alive| HeartbeatPayload {
+            instance_id: "inst-1".to_string(),
+            port,
+            alive,
+        };
+        assert_eq!(*beats.lock().unwrap(), vec![beat(false), beat(true)]);
+        assert_eq!(*served_on.lock().unwrap(), vec![port, port]);
+        assert_eq!(liveness.heartbeat(), beat(true));
+    }
+
     // ── StatusServer method tests (buffering / flushing) ────────────
 
     #[tokio::test]
@@ -535,6 +1154,38 @@ mod tests {
         assert!(server.pending_statuses.read().await.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_reregister_updates_project_and_flushes_buffer() {
+        let (emit_fn, events) = test_emit_fn();
+        let server = test_server("inst-1", emit_fn);
+
+        server.register_session(5, "/path/old").await;
+        // The relaunched session reported before it was re-registered
+        server.unregister_session(5).await;
+        server
+            .pending_statuses
+            .write()
+            .await
+            .insert(5, make_status(5, "inst-1", "working", "Relaunched"));
+
+        server.register_session(5, "/path/new").await;
+
+        assert_eq!(
+            server
+                .session_projects
+                .read()
+                .await
+                .get(&5)
+                .map(String::as_str),
+            Some("/path/new")
+        );
+        let emitted = events.lock().unwrap();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].project_path, "/path/new");
+        assert_eq!(emitted[0].message, "Relaunched");
+        assert!(server.pending_statuses.try_read().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_register_without_buffer_emits_nothing() {
         let (emit_fn, events) = test_emit_fn();
@@ -619,4 +1270,27 @@ mod tests {
 
         assert_eq!(events.lock().unwrap().len(), 5);
     }
+
+    #[tokio::test]
+    async fn test_unknown_state_keeps_raw_value() {
+        let (emit_fn, events) = test_emit_fn();
+        let (addr, projects, _) = start_test_http_server("inst-1", emit_fn).await;
+
+        projects.write().await.insert(1, "/path/p".to_string());
+
+        post_status(
+            addr,
+            &make_status(1, "inst-1", "compacting", "Compacting context"),
+        )
+        .await;
+        post_status(addr, &make_status(1, "inst-1", "working", "Back to work")).await;
+
+        let emitted = events.lock().unwrap();
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[0].status, "Unknown");
+        assert_eq!(emitted[0].raw_state.as_deref(), Some("compacting"));
+        assert_eq!(emitted[0].message, "Compacting context");
+        assert_eq!(emitted[1].status, "Working");
+        assert_eq!(emitted[1].raw_state.as_deref(), Some("working"));
+    }
 }
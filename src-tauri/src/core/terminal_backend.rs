@@ -23,7 +23,8 @@ pub struct TerminalConfig {
 }
 
 /// Terminal state information exposed by backends that support it.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TerminalState {
     /// Current cursor row position (0-indexed).
     pub cursor_row: u16,
@@ -39,10 +40,60 @@ pub struct TerminalState {
     pub scrollback_total: u32,
     /// Terminal title (set by shell escape sequences).
     pub title: Option<String>,
+    /// Shell working directory, as last reported via OSC 7.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Whether the shell has emitted OSC 133 shell integration markers.
+    #[serde(default)]
+    pub shell_integration: bool,
+    /// Marks of the command in progress, from its prompt up to its end marker.
+    #[serde(default)]
+    pub current_command: Option<CommandMarks>,
+    /// Marks and exit code of the most recently finished command.
+    #[serde(default)]
+    pub last_command: Option<CommandMarks>,
+    /// Current SGR foreground color.
+    pub fg: Color,
+    /// Current SGR background color.
+    pub bg: Color,
+    /// Top row of the scroll region set by DECSTBM (0-indexed, inclusive).
+    pub scroll_top: u16,
+    /// Bottom row of the scroll region set by DECSTBM (0-indexed, inclusive).
+    pub scroll_bottom: u16,
+}
+
+/// Boundaries of a shell command reported via OSC 133, as cursor rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMarks {
+    /// Row where the prompt started (`OSC 133 ; A`).
+    pub prompt_row: Option<u16>,
+    /// Row where command input started (`OSC 133 ; B`).
+    pub command_row: Option<u16>,
+    /// Row where command output started (`OSC 133 ; C`).
+    pub output_row: Option<u16>,
+    /// Row where the command finished (`OSC 133 ; D`).
+    pub end_row: Option<u16>,
+    /// Exit code from `OSC 133 ; D ; <code>`; `None` if the shell omitted it.
+    pub exit_code: Option<i32>,
+}
+
+/// A color set via SGR (`CSI ... m`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Color {
+    /// The terminal's default color (SGR 0, 39, or 49).
+    #[default]
+    Default,
+    /// Palette index: 0-7 standard, 8-15 bright, 16-255 from the 256-color cube.
+    Indexed(u8),
+    /// 24-bit truecolor.
+    Rgb(u8, u8, u8),
 }
 
 /// Cursor shape variants.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum CursorShape {
     #[default]
     Block,
@@ -159,7 +210,7 @@ pub trait TerminalBackend: Send + Sync {
 }
 
 /// Identifies the active backend type for the frontend.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum BackendType {
     /// xterm.js passthrough - raw PTY output sent directly to xterm.js.
@@ -178,4 +229,65 @@ impl BackendType {
         }
         BackendType::XtermPassthrough
     }
+
+    /// Returns every backend compiled into this build, default first.
+    pub fn compiled() -> Vec<Self> {
+        let default = Self::platform_default();
+        let mut backends = vec![default];
+        backends.extend(
+            [BackendType::XtermPassthrough, BackendType::VteParser]
+                .into_iter()
+                .filter(|b| *b != default && b.is_compiled()),
+        );
+        backends
+    }
+
+    /// Returns true if this backend is available in the current build.
+    pub fn is_compiled(self) -> bool {
+        match self {
+            BackendType::XtermPassthrough => true,
+            BackendType::VteParser => cfg!(feature = "vte-backend"),
+        }
+    }
+
+    /// Picks the backend for a new session: `requested` if it is compiled in,
+    /// otherwise the platform default (with a warning).
+    pub fn resolve(requested: Option<Self>) -> Self {
+        match requested {
+            Some(backend) if backend.is_compiled() => backend,
+            Some(backend) => {
+                let fallback = Self::platform_default();
+                log::warn!(
+                    "Terminal backend {:?} is not compiled in, using {:?}",
+                    backend,
+                    fallback
+                );
+                fallback
+            }
+            None => Self::platform_default(),
+        }
+    }
+
+    /// Returns the capabilities a session on this backend provides.
+    ///
+    /// This is the single source of truth; `TerminalBackend::capabilities`
+    /// implementations delegate here.
+    pub fn capabilities(self) -> BackendCapabilities {
+        match self {
+            BackendType::XtermPassthrough => BackendCapabilities {
+                enhanced_state: false,
+                text_reflow: false,
+                kitty_graphics: false,
+                shell_integration: false,
+                backend_name: "xterm-passthrough",
+            },
+            BackendType::VteParser => BackendCapabilities {
+                enhanced_state: true,
+                text_reflow: false,
+                kitty_graphics: false,
+                shell_integration: false,
+                backend_name: "vte-parser",
+            },
+        }
+    }
 }
@@ -23,9 +23,13 @@ use vte::{Parser, Perform};
 #[cfg(unix)]
 use libc;
 
+#[cfg(unix)]
+use super::process_manager::terminate_process_group;
+use super::process_manager::DEFAULT_KILL_GRACE;
+
 use super::terminal_backend::{
-    BackendCapabilities, BackendType, CursorShape, SubscriptionHandle, TerminalBackend,
-    TerminalConfig, TerminalError, TerminalState,
+    BackendCapabilities, BackendType, Color, CommandMarks, CursorShape, SubscriptionHandle,
+    TerminalBackend, TerminalConfig, TerminalError, TerminalState,
 };
 
 /// Stateful UTF-8 decoder that handles split multi-byte sequences.
@@ -95,14 +99,22 @@ struct VteHandler {
     state: Arc<RwLock<TerminalState>>,
     rows: u16,
     cols: u16,
+    /// Exit codes of commands finished since the last
+    /// [`VteStateTracker::take_finished_commands`].
+    finished: Vec<Option<i32>>,
 }
 
 impl VteHandler {
     fn new(rows: u16, cols: u16) -> Self {
+        let state = TerminalState {
+            scroll_bottom: rows.saturating_sub(1),
+            ..TerminalState::default()
+        };
         Self {
-            state: Arc::new(RwLock::new(TerminalState::default())),
+            state: Arc::new(RwLock::new(state)),
             rows,
             cols,
+            finished: Vec::new(),
         }
     }
 
@@ -113,6 +125,21 @@ impl VteHandler {
     fn resize(&mut self, rows: u16, cols: u16) {
         self.rows = rows;
         self.cols = cols;
+
+        // Like xterm, a resize resets the scroll region to the full screen
+        let mut state = self.state.write().unwrap();
+        state.scroll_top = 0;
+        state.scroll_bottom = rows.saturating_sub(1);
+        state.cursor_row = state.cursor_row.min(state.scroll_bottom);
+    }
+
+    /// Moves the cursor down one line. At the bottom of the scroll region the
+    /// content scrolls instead, so the cursor stays on that row.
+    fn line_feed(&self, state: &mut TerminalState) {
+        if state.cursor_row == state.scroll_bottom {
+            return;
+        }
+        state.cursor_row = state.cursor_row.saturating_add(1).min(self.rows - 1);
     }
 }
 
@@ -122,7 +149,7 @@ impl Perform for VteHandler {
         state.cursor_col = state.cursor_col.saturating_add(1);
         if state.cursor_col >= self.cols {
             state.cursor_col = 0;
-            state.cursor_row = state.cursor_row.saturating_add(1).min(self.rows - 1);
+            self.line_feed(&mut state);
         }
     }
 
@@ -132,9 +159,7 @@ impl Perform for VteHandler {
             // Carriage return
             0x0D => state.cursor_col = 0,
             // Line feed / newline
-            0x0A => {
-                state.cursor_row = state.cursor_row.saturating_add(1).min(self.rows - 1);
-            }
+            0x0A => self.line_feed(&mut state),
             // Backspace
             0x08 => {
                 state.cursor_col = state.cursor_col.saturating_sub(1);
@@ -170,26 +195,81 @@ impl Perform for VteHandler {
             return;
         }
 
-        // OSC 0, 1, 2 - Set window/icon title
-        if let Some(&[b'0' | b'1' | b'2']) = params.first() {
-            if let Some(title_bytes) = params.get(1) {
-                if let Ok(title) = std::str::from_utf8(title_bytes) {
+        match params.first() {
+            // OSC 0, 1, 2 - Set window/icon title
+            Some(&[b'0' | b'1' | b'2']) => {
+                if let Some(title_bytes) = params.get(1) {
+                    if let Ok(title) = std::str::from_utf8(title_bytes) {
+                        let mut state = self.state.write().unwrap();
+                        state.title = Some(title.to_string());
+                    }
+                }
+            }
+            // OSC 7 - Report working directory as a file:// URI. The parser
+            // splits on ';', which is legal in paths, so rejoin the rest.
+            Some(&[b'7']) if params.len() > 1 => {
+                if let Some(cwd) = parse_osc7_path(&params[1..].join(&b';')) {
                     let mut state = self.state.write().unwrap();
-                    state.title = Some(title.to_string());
+                    state.cwd = Some(cwd);
+                }
+            }
+            // OSC 133 - Shell integration prompt/command markers
+            Some(&[b'1', b'3', b'3']) if params.len() > 1 => {
+                let mut state = self.state.write().unwrap();
+                state.shell_integration = true;
+                let row = Some(state.cursor_row);
+                match params[1] {
+                    b"A" => {
+                        state.current_command = Some(CommandMarks {
+                            prompt_row: row,
+                            ..CommandMarks::default()
+                        });
+                    }
+                    b"B" => {
+                        let marks = state.current_command.get_or_insert_with(Default::default);
+                        marks.command_row = row;
+                    }
+                    b"C" => {
+                        let marks = state.current_command.get_or_insert_with(Default::default);
+                        marks.output_row = row;
+                    }
+                    // Shells also send D before their first prompt, when no
+                    // command has run; only a started command finishes.
+                    b"D" => {
+                        if let Some(mut marks) = state.current_command.take() {
+                            marks.end_row = row;
+                            marks.exit_code = params
+                                .get(2)
+                                .and_then(|code| std::str::from_utf8(code).ok())
+                                .and_then(|code| code.parse().ok());
+                            self.finished.push(marks.exit_code);
+                            state.last_command = Some(marks);
+                        }
+                    }
+                    _ => {}
                 }
             }
+            _ => {}
         }
     }
 
     fn csi_dispatch(
         &mut self,
         params: &vte::Params,
-        _intermediates: &[u8],
+        intermediates: &[u8],
         _ignore: bool,
         action: char,
     ) {
         let mut state = self.state.write().unwrap();
 
+        // SGR - Select Graphic Rendition (private forms like `CSI > 4 m` are not SGR)
+        if action == 'm' {
+            if intermediates.is_empty() {
+                apply_sgr(&mut state, params);
+            }
+            return;
+        }
+
         // Get first parameter with default
         let param = |idx: usize, default: u16| -> u16 {
             params
@@ -201,15 +281,25 @@ impl Perform for VteHandler {
         };
 
         match action {
-            // CUU - Cursor Up
+            // CUU - Cursor Up (stops at the region top when starting inside it)
             'A' => {
                 let n = param(0, 1);
-                state.cursor_row = state.cursor_row.saturating_sub(n);
+                let floor = if state.cursor_row >= state.scroll_top {
+                    state.scroll_top
+                } else {
+                    0
+                };
+                state.cursor_row = state.cursor_row.saturating_sub(n).max(floor);
             }
-            // CUD - Cursor Down
+            // CUD - Cursor Down (stops at the region bottom when starting inside it)
             'B' => {
                 let n = param(0, 1);
-                state.cursor_row = state.cursor_row.saturating_add(n).min(self.rows - 1);
+                let ceiling = if state.cursor_row <= state.scroll_bottom {
+                    state.scroll_bottom
+                } else {
+                    self.rows - 1
+                };
+                state.cursor_row = state.cursor_row.saturating_add(n).min(ceiling);
             }
             // CUF - Cursor Forward
             'C' => {
@@ -228,6 +318,18 @@ impl Perform for VteHandler {
                 state.cursor_row = row.min(self.rows - 1);
                 state.cursor_col = col.min(self.cols - 1);
             }
+            // DECSTBM - Set Top and Bottom Margins (`CSI ? r` is a private mode restore)
+            'r' if intermediates.is_empty() => {
+                let top = param(0, 1).saturating_sub(1);
+                let bottom = param(1, self.rows).saturating_sub(1).min(self.rows - 1);
+                // Invalid regions are ignored; a valid one homes the cursor
+                if top < bottom {
+                    state.scroll_top = top;
+                    state.scroll_bottom = bottom;
+                    state.cursor_row = 0;
+                    state.cursor_col = 0;
+                }
+            }
             // DECSCUSR - Set Cursor Shape
             'q' => {
                 let shape = param(0, 0);
@@ -254,6 +356,152 @@ impl Perform for VteHandler {
     }
 }
 
+/// Applies the color-related parts of an SGR sequence to `state`.
+///
+/// Handles reset (`0` or no parameters), the 8/16-color codes, default
+/// fg/bg (`39`/`49`), and extended colors in both the semicolon form
+/// (`38;5;n`, `38;2;r;g;b`) and the colon subparameter form (`38:5:n`,
+/// `38:2:r:g:b`, `38:2::r:g:b` with an empty color-space ID).
+/// Other attributes (bold, underline, ...) are not tracked.
+fn apply_sgr(state: &mut TerminalState, params: &vte::Params) {
+    if params.is_empty() {
+        state.fg = Color::Default;
+        state.bg = Color::Default;
+        return;
+    }
+
+    let mut iter = params.iter();
+    while let Some(param) = iter.next() {
+        match param {
+            [0] => {
+                state.fg = Color::Default;
+                state.bg = Color::Default;
+            }
+            [38, sub @ ..] => {
+                if let Some(color) = parse_extended_color(sub, &mut iter) {
+                    state.fg = color;
+                }
+            }
+            [48, sub @ ..] => {
+                if let Some(color) = parse_extended_color(sub, &mut iter) {
+                    state.bg = color;
+                }
+            }
+            [39] => state.fg = Color::Default,
+            [49] => state.bg = Color::Default,
+            [n @ 30..=37] => state.fg = Color::Indexed((n - 30) as u8),
+            [n @ 40..=47] => state.bg = Color::Indexed((n - 40) as u8),
+            [n @ 90..=97] => state.fg = Color::Indexed((n - 90 + 8) as u8),
+            [n @ 100..=107] => state.bg = Color::Indexed((n - 100 + 8) as u8),
+            _ => {}
+        }
+    }
+}
+
+/// Parses the color following a `38`/`48` SGR parameter.
+///
+/// `sub` holds colon-separated subparameters of the same parameter; when it
+/// is empty the semicolon form is assumed and the color is taken from the
+/// following parameters, which are consumed from `rest`. Out-of-range
+/// components yield `None` so a malformed sequence leaves the color untouched.
+fn parse_extended_color<'a>(
+    sub: &[u16],
+    rest: &mut impl Iterator<Item = &'a [u16]>,
+) -> Option<Color> {
+    let byte = |v: u16| u8::try_from(v).ok();
+
+    if !sub.is_empty() {
+        return match *sub {
+            [5, n] => byte(n).map(Color::Indexed),
+            [2, r, g, b] | [2, _, r, g, b] => Some(Color::Rgb(byte(r)?, byte(g)?, byte(b)?)),
+            _ => None,
+        };
+    }
+
+    let mut next = || rest.next().and_then(|p| p.first().copied());
+    match next()? {
+        5 => byte(next()?).map(Color::Indexed),
+        2 => {
+            let (r, g, b) = (next()?, next()?, next()?);
+            Some(Color::Rgb(byte(r)?, byte(g)?, byte(b)?))
+        }
+        _ => None,
+    }
+}
+
+/// Extracts the path from an OSC 7 `file://host/path` URI, percent-decoded.
+///
+/// The host is ignored. Returns `None` for other schemes or malformed input.
+fn parse_osc7_path(uri: &[u8]) -> Option<String> {
+    let rest = uri.strip_prefix(b"file://")?;
+    let path = &rest[rest.iter().position(|&b| b == b'/')?..];
+
+    let mut decoded = Vec::with_capacity(path.len());
+    let mut i = 0;
+    while i < path.len() {
+        if path[i] == b'%' {
+            let hex = path.get(i + 1..i + 3)?;
+            let byte = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(path[i]);
+            i += 1;
+        }
+    }
+    let decoded = String::from_utf8(decoded).ok()?;
+
+    // Windows shells report `/C:/Users/...`
+    #[cfg(windows)]
+    if decoded.as_bytes().get(2) == Some(&b':') {
+        return Some(decoded[1..].to_string());
+    }
+    Some(decoded)
+}
+
+/// Feeds raw PTY output through a VTE parser into a [`VteHandler`].
+///
+/// Used by `ProcessManager`, which owns its PTYs directly, to keep a
+/// `TerminalState` for each session without going through `VteBackend`.
+pub(crate) struct VteStateTracker {
+    parser: Parser,
+    handler: VteHandler,
+}
+
+impl VteStateTracker {
+    pub(crate) fn new(rows: u16, cols: u16) -> Self {
+        Self {
+            parser: Parser::new(),
+            handler: VteHandler::new(rows, cols),
+        }
+    }
+
+    /// Parses a chunk of PTY output, updating the tracked state.
+    ///
+    /// Returns the new working directory if the chunk's OSC 7 reports
+    /// changed it.
+    pub(crate) fn advance(&mut self, bytes: &[u8]) -> Option<String> {
+        let before = self.handler.state.read().unwrap().cwd.clone();
+        self.parser.advance(&mut self.handler, bytes);
+        let after = &self.handler.state.read().unwrap().cwd;
+        (*after != before).then(|| after.clone()).flatten()
+    }
+
+    /// Drains the exit codes of commands that finished (OSC 133 `D`) since
+    /// the last call, oldest first.
+    pub(crate) fn take_finished_commands(&mut self) -> Vec<Option<i32>> {
+        std::mem::take(&mut self.handler.finished)
+    }
+
+    pub(crate) fn resize(&mut self, rows: u16, cols: u16) {
+        self.handler.resize(rows, cols);
+    }
+
+    pub(crate) fn state(&self) -> TerminalState {
+        self.handler.get_state()
+    }
+}
+
 /// Internal session state for VTE backend.
 struct SessionState {
     writer: Box<dyn Write + Send>,
@@ -546,34 +794,7 @@ impl TerminalBackend for VteBackend {
         let pid = session.child_pid;
 
         #[cfg(unix)]
-        {
-            let pgid = session.pgid;
-
-            let term_result = unsafe { libc::kill(-pgid, libc::SIGTERM) };
-            if term_result != 0 {
-                log::warn!(
-                    "Failed to SIGTERM session {} (pgid={}): {}",
-                    session_id,
-                    pgid,
-                    std::io::Error::last_os_error()
-                );
-            }
-
-            std::thread::sleep(std::time::Duration::from_millis(100));
-
-            let alive = unsafe { libc::kill(pid, 0) } == 0;
-            if alive {
-                let kill_result = unsafe { libc::kill(-pgid, libc::SIGKILL) };
-                if kill_result != 0 {
-                    log::warn!(
-                        "Failed to SIGKILL session {} (pgid={}): {}",
-                        session_id,
-                        pgid,
-                        std::io::Error::last_os_error()
-                    );
-                }
-            }
-        }
+        terminate_process_group(session_id, pid, session.pgid, DEFAULT_KILL_GRACE);
 
         #[cfg(windows)]
         {
@@ -601,13 +822,9 @@ impl TerminalBackend for VteBackend {
     }
 
     fn capabilities(&self) -> BackendCapabilities {
-        BackendCapabilities {
-            enhanced_state: true,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "vte-parser",
-        }
+        let mut caps = Self::backend_type().capabilities();
+        caps.shell_integration = self.get_state().is_some_and(|s| s.shell_integration);
+        caps
     }
 }
 
@@ -632,3 +849,132 @@ impl Perform for DummyPerform {
     fn csi_dispatch(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _action: char) {}
     fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds raw bytes through a fresh parser into `handler`.
+    fn feed(handler: &mut VteHandler, bytes: &[u8]) {
+        let mut parser = Parser::new();
+        parser.advance(handler, bytes);
+    }
+
+    #[test]
+    fn test_sgr_truecolor() {
+        let mut handler = VteHandler::new(24, 80);
+        feed(&mut handler, b"\x1b[38;2;255;128;0m\x1b[48;2;10;20;30mtext");
+
+        let state = handler.get_state();
+        assert_eq!(state.fg, Color::Rgb(255, 128, 0));
+        assert_eq!(state.bg, Color::Rgb(10, 20, 30));
+    }
+
+    #[test]
+    fn test_sgr_256_color() {
+        let mut handler = VteHandler::new(24, 80);
+        feed(&mut handler, b"\x1b[1;38;5;202;48;5;17m");
+
+        let state = handler.get_state();
+        assert_eq!(state.fg, Color::Indexed(202));
+        assert_eq!(state.bg, Color::Indexed(17));
+    }
+
+    #[test]
+    fn test_sgr_colon_subparameters() {
+        let mut handler = VteHandler::new(24, 80);
+        feed(&mut handler, b"\x1b[38:2::1:2:3;48:5:99m");
+
+        let state = handler.get_state();
+        assert_eq!(state.fg, Color::Rgb(1, 2, 3));
+        assert_eq!(state.bg, Color::Indexed(99));
+    }
+
+    #[test]
+    fn test_sgr_reset_and_basic_colors() {
+        let mut handler = VteHandler::new(24, 80);
+        feed(&mut handler, b"\x1b[31;102m");
+        let state = handler.get_state();
+        assert_eq!(state.fg, Color::Indexed(1));
+        assert_eq!(state.bg, Color::Indexed(10));
+
+        feed(&mut handler, b"\x1b[0m");
+        let state = handler.get_state();
+        assert_eq!(state.fg, Color::Default);
+        assert_eq!(state.bg, Color::Default);
+
+        feed(&mut handler, b"\x1b[32m\x1b[m");
+        assert_eq!(handler.get_state().fg, Color::Default);
+    }
+
+    #[test]
+    fn test_decstbm_line_feeds_stop_at_region_bottom() {
+        let mut handler = VteHandler::new(24, 80);
+        // Region rows 5-10 (1-indexed), then move to its top and feed lines past the bottom
+        feed(&mut handler, b"\x1b[5;10r");
+        let state = handler.get_state();
+        assert_eq!((state.scroll_top, state.scroll_bottom), (4, 9));
+        assert_eq!((state.cursor_row, state.cursor_col), (0, 0));
+
+        feed(&mut handler, b"\x1b[5;1H");
+        feed(&mut handler, &[b'\n'; 12]);
+        assert_eq!(handler.get_state().cursor_row, 9);
+
+        // Resetting the region restores full-screen scrolling
+        feed(&mut handler, b"\x1b[r");
+        feed(&mut handler, &[b'\n'; 30]);
+        let state = handler.get_state();
+        assert_eq!((state.scroll_top, state.scroll_bottom), (0, 23));
+        assert_eq!(state.cursor_row, 23);
+    }
+
+    #[test]
+    fn test_osc7_reports_cwd_once_per_change() {
+        let mut tracker = VteStateTracker::new(24, 80);
+
+        let cwd = tracker.advance(b"\x1b]7;file://host/home/me/my%20app\x07$ ");
+        assert_eq!(cwd.as_deref(), Some("/home/me/my app"));
+        assert_eq!(tracker.state().cwd.as_deref(), Some("/home/me/my app"));
+
+        // Same directory again (e.g. after every prompt) is not a change
+        assert_eq!(
+            tracker.advance(b"\x1b]7;file://host/home/me/my%20app\x1b\\"),
+            None
+        );
+        assert_eq!(tracker.advance(b"plain output\r\n"), None);
+
+        let cwd = tracker.advance(b"\x1b]7;file:///tmp/a;b\x07");
+        assert_eq!(cwd.as_deref(), Some("/tmp/a;b"));
+        // Non-file URIs are ignored
+        assert_eq!(tracker.advance(b"\x1b]7;http://host/x\x07"), None);
+        assert_eq!(tracker.state().cwd.as_deref(), Some("/tmp/a;b"));
+    }
+
+    #[test]
+    fn test_osc133_tracks_command_boundaries_and_exit_code() {
+        let mut tracker = VteStateTracker::new(24, 80);
+        // A D before the first prompt has no command to finish
+        tracker.advance(b"\x1b]133;D\x07");
+        assert!(tracker.take_finished_commands().is_empty());
+
+        tracker.advance(b"\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07");
+        let state = tracker.state();
+        assert!(state.shell_integration);
+        assert_eq!(state.current_command.unwrap().command_row, Some(0));
+
+        tracker.advance(b"output\r\n\x1b]133;D;1\x1b\\");
+        assert_eq!(tracker.take_finished_commands(), vec![Some(1)]);
+        let state = tracker.state();
+        assert_eq!(state.current_command, None);
+        assert_eq!(
+            state.last_command,
+            Some(CommandMarks {
+                prompt_row: Some(0),
+                command_row: Some(0),
+                output_row: Some(1),
+                end_row: Some(2),
+                exit_code: Some(1),
+            })
+        );
+    }
+}
@@ -1,11 +1,76 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 
 use crate::git::{Git, GitError, WorktreeInfo};
 
-fn worktree_base_dir() -> PathBuf {
+/// How many directory levels `dir_size` descends before skipping a subtree.
+const MAX_USAGE_DEPTH: usize = 64;
+
+/// How a new worktree's files are populated.
+#[derive(Debug, Clone, Default)]
+pub struct CheckoutOptions {
+    /// Create the worktree with `--no-checkout` so huge repos don't pay for a
+    /// full checkout. The session then starts in an empty tree (HEAD is set,
+    /// every tracked file shows as deleted) until files are checked out.
+    pub no_checkout: bool,
+    /// Paths to check out from HEAD after a `no_checkout` creation. Ignored
+    /// for a normal checkout.
+    pub paths: Vec<String>,
+}
+
+/// Disk space taken by one managed worktree.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeUsage {
+    pub path: String,
+    /// `None` for detached worktrees.
+    pub branch: Option<String>,
+    pub bytes: u64,
+}
+
+/// What is wrong with a managed worktree, as found by [`WorktreeManager::audit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorktreeIssueKind {
+    /// The branch the worktree is on was deleted, so HEAD points at a ref
+    /// that no longer exists.
+    BranchMissing,
+    /// The worktree's directory is gone but git still lists it.
+    DirectoryMissing,
+    /// The directory exists but its `.git` link to the repository is gone.
+    LinkBroken,
+    /// The worktree is locked although its directory is gone, which keeps
+    /// `git worktree prune` from cleaning it up.
+    StaleLock,
+}
+
+/// Suggested way to fix a [`WorktreeIssueKind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeRemedy {
+    /// Drop the stale entry (`git worktree prune`, after `unlock` if locked).
+    Prune,
+    /// Re-link the directory with `git worktree repair`.
+    Repair,
+    /// Remove the worktree and create it again on an existing branch.
+    Recreate,
+}
+
+/// A problem with one managed worktree.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeIssue {
+    pub path: String,
+    pub branch: Option<String>,
+    pub kind: WorktreeIssueKind,
+    pub remedy: WorktreeRemedy,
+    /// Lock reason, for `StaleLock`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+}
+
+pub(crate) fn worktree_base_dir() -> PathBuf {
     directories::ProjectDirs::from("com", "maestro", "maestro")
         .map(|p| p.data_dir().to_path_buf())
         .unwrap_or_else(|| {
@@ -53,6 +118,41 @@ fn sanitize_branch(branch: &str) -> String {
     sanitized
 }
 
+/// Sums the apparent size of all files under `path`.
+///
+/// Symlinks are counted as links and never followed, and anything deeper than
+/// [`MAX_USAGE_DEPTH`] is skipped, so a link cycle or pathological tree cannot
+/// make the walk run away. Unreadable entries count as zero.
+fn dir_size(path: &Path, depth: usize) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    if depth >= MAX_USAGE_DEPTH {
+        log::warn!(
+            "Skipping {} in disk usage: too deeply nested",
+            path.display()
+        );
+        return 0;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| dir_size(&entry.path(), depth + 1))
+        .sum()
+}
+
+/// Runs [`dir_size`] on the blocking pool.
+async fn dir_size_blocking(path: PathBuf) -> u64 {
+    tokio::task::spawn_blocking(move || dir_size(&path, 0))
+        .await
+        .unwrap_or(0)
+}
+
 /// Manages Maestro-owned git worktrees under a deterministic, repo-specific
 /// directory inside XDG data dirs.
 ///
@@ -86,11 +186,13 @@ impl WorktreeManager {
     /// Checks that the branch is not already checked out in another worktree
     /// before creating (returns `BranchAlreadyCheckedOut` if so). Parent
     /// directories are created automatically. The worktree checks out the
-    /// existing branch -- no new branch is created.
+    /// existing branch -- no new branch is created. `checkout` controls
+    /// whether files are populated; see `CheckoutOptions`.
     pub async fn create(
         &self,
         branch: &str,
         repo_path: &Path,
+        checkout: &CheckoutOptions,
     ) -> Result<PathBuf, GitError> {
         let git = Git::new(repo_path);
 
@@ -122,7 +224,44 @@ impl WorktreeManager {
             })?;
         }
 
-        git.worktree_add(&wt_path, None, Some(branch)).await?;
+        git.worktree_add(&wt_path, None, Some(branch), checkout.no_checkout)
+            .await?;
+
+        if checkout.no_checkout && !checkout.paths.is_empty() {
+            Git::new(&wt_path).checkout_paths(&checkout.paths).await?;
+        }
+
+        Ok(wt_path)
+    }
+
+    /// Creates a worktree with a detached HEAD at `commit`, returning its path.
+    ///
+    /// `commit` should be a full SHA (see `Git::resolve_commit`); the worktree
+    /// directory is named after it so the same commit always maps to the same
+    /// path and never collides with a branch worktree.
+    pub async fn create_detached(
+        &self,
+        commit: &str,
+        repo_path: &Path,
+    ) -> Result<PathBuf, GitError> {
+        let git = Git::new(repo_path);
+        let short = &commit[..commit.len().min(12)];
+        let wt_path = self
+            .worktree_path(repo_path, &format!("detached-{}", short))
+            .await;
+
+        if let Some(parent) = wt_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| GitError::SpawnError {
+                    source: e,
+                    command: format!("create_dir_all {:?}", parent),
+                })?;
+        }
+
+        // A SHA (unlike a branch name) makes `worktree add` detach HEAD
+        git.worktree_add(&wt_path, None, Some(commit), false)
+            .await?;
 
         Ok(wt_path)
     }
@@ -156,13 +295,76 @@ impl WorktreeManager {
             .collect())
     }
 
+    /// Checks the managed worktrees of `repo_path` for problems that leave a
+    /// session working on stale refs or a missing directory.
+    ///
+    /// Each worktree is reported at most once, with the most fundamental
+    /// problem: a missing directory (locked or not) before a broken link,
+    /// before a deleted branch.
+    pub async fn audit(&self, repo_path: &Path) -> Result<Vec<WorktreeIssue>, GitError> {
+        let git = Git::new(repo_path);
+        let branches: HashSet<String> = git
+            .list_branches()
+            .await?
+            .into_iter()
+            .filter(|b| !b.is_remote)
+            .map(|b| b.name)
+            .collect();
+
+        let mut issues = Vec::new();
+        for wt in self.list_managed(repo_path).await? {
+            let dir = Path::new(&wt.path);
+            let (kind, remedy) = if !dir.exists() {
+                if wt.locked.is_some() {
+                    (WorktreeIssueKind::StaleLock, WorktreeRemedy::Prune)
+                } else {
+                    (WorktreeIssueKind::DirectoryMissing, WorktreeRemedy::Prune)
+                }
+            } else if !dir.join(".git").exists() {
+                (WorktreeIssueKind::LinkBroken, WorktreeRemedy::Repair)
+            } else if wt.branch.as_ref().is_some_and(|b| !branches.contains(b)) {
+                (WorktreeIssueKind::BranchMissing, WorktreeRemedy::Recreate)
+            } else {
+                continue;
+            };
+
+            log::warn!("Worktree {} has a problem: {:?}", wt.path, kind);
+            let lock_reason = (kind == WorktreeIssueKind::StaleLock)
+                .then_some(wt.locked)
+                .flatten();
+            issues.push(WorktreeIssue {
+                path: wt.path,
+                branch: wt.branch,
+                kind,
+                remedy,
+                lock_reason,
+            });
+        }
+        Ok(issues)
+    }
+
+    /// Reports how much disk each managed worktree of `repo_path` uses.
+    pub async fn disk_usage(&self, repo_path: &Path) -> Result<Vec<WorktreeUsage>, GitError> {
+        let mut usage = Vec::new();
+        for wt in self.list_managed(repo_path).await? {
+            let bytes = dir_size_blocking(PathBuf::from(&wt.path)).await;
+            usage.push(WorktreeUsage {
+                path: wt.path,
+                branch: wt.branch,
+                bytes,
+            });
+        }
+        Ok(usage)
+    }
+
     /// Prunes stale git worktree refs and removes orphaned directories.
     ///
     /// First runs `git worktree prune`, then scans the managed directory for
     /// subdirectories that are no longer in git's worktree list. Orphaned
     /// directories are deleted with `remove_dir_all`. No-ops gracefully if
-    /// the managed directory does not exist yet.
-    pub async fn prune(&self, repo_path: &Path) -> Result<(), GitError> {
+    /// the managed directory does not exist yet. Returns the number of bytes
+    /// freed.
+    pub async fn prune(&self, repo_path: &Path) -> Result<u64, GitError> {
         let git = Git::new(repo_path);
         git.worktree_prune().await?;
 
@@ -177,7 +379,7 @@ impl WorktreeManager {
                 command: format!("try_exists {:?}", managed_dir),
             })?;
         if !managed_exists {
-            return Ok(());
+            return Ok(0);
         }
 
         let active_raw: Vec<String> = git
@@ -195,6 +397,7 @@ impl WorktreeManager {
             active.insert(canonical.to_string_lossy().to_string());
         }
 
+        let mut reclaimed = 0;
         if let Ok(mut entries) = tokio::fs::read_dir(&managed_dir).await {
             while let Ok(Some(entry)) = entries.next_entry().await {
                 let path = entry.path();
@@ -208,12 +411,15 @@ impl WorktreeManager {
                     .unwrap_or(false);
                 if !active.contains(&entry_key) && is_dir {
                     log::info!("Removing orphaned worktree dir: {}", path.display());
-                    let _ = tokio::fs::remove_dir_all(&path).await;
+                    let bytes = dir_size_blocking(path.clone()).await;
+                    if tokio::fs::remove_dir_all(&path).await.is_ok() {
+                        reclaimed += bytes;
+                    }
                 }
             }
         }
 
-        Ok(())
+        Ok(reclaimed)
     }
 }
 
@@ -279,7 +485,10 @@ mod tests {
         git.run(&["branch", "feature-test"]).await.unwrap();
 
         let wm = WorktreeManager::new();
-        let wt_path = wm.create("feature-test", &path).await.unwrap();
+        let wt_path = wm
+            .create("feature-test", &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
 
         assert!(wt_path.exists());
 
@@ -305,7 +514,10 @@ mod tests {
         git.run(&["checkout", "fallback"]).await.unwrap();
 
         let wm = WorktreeManager::new();
-        let wt_path = wm.create(&current, &path).await.unwrap();
+        let wt_path = wm
+            .create(&current, &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
 
         assert!(wt_path.exists());
 
@@ -322,10 +534,15 @@ mod tests {
         let wm = WorktreeManager::new();
 
         // First creation should succeed
-        let wt_path1 = wm.create("dup-test", &path).await.unwrap();
+        let wt_path1 = wm
+            .create("dup-test", &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
 
         // Second creation should fail with BranchAlreadyCheckedOut
-        let result = wm.create("dup-test", &path).await;
+        let result = wm
+            .create("dup-test", &path, &CheckoutOptions::default())
+            .await;
         assert!(result.is_err());
         match result.unwrap_err() {
             GitError::BranchAlreadyCheckedOut { branch, .. } => {
@@ -338,6 +555,41 @@ mod tests {
         let _ = wm.remove(&path, &wt_path1).await;
     }
 
+    #[tokio::test]
+    async fn test_create_no_checkout_populates_only_requested_paths() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        tokio::fs::write(path.join("other.txt"), "other")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "second"]).await.unwrap();
+        git.run(&["branch", "sparse-test"]).await.unwrap();
+        git.run(&["branch", "empty-test"]).await.unwrap();
+
+        let wm = WorktreeManager::new();
+
+        let empty = CheckoutOptions {
+            no_checkout: true,
+            paths: Vec::new(),
+        };
+        let empty_path = wm.create("empty-test", &path, &empty).await.unwrap();
+        assert!(!empty_path.join("README.md").exists());
+        assert!(!empty_path.join("other.txt").exists());
+
+        let sparse = CheckoutOptions {
+            no_checkout: true,
+            paths: vec!["README.md".to_string()],
+        };
+        let sparse_path = wm.create("sparse-test", &path, &sparse).await.unwrap();
+        assert!(sparse_path.join("README.md").exists());
+        assert!(!sparse_path.join("other.txt").exists());
+
+        // Cleanup
+        let _ = wm.remove(&path, &empty_path).await;
+        let _ = wm.remove(&path, &sparse_path).await;
+    }
+
     #[tokio::test]
     async fn test_remove_worktree() {
         let (_dir, path) = create_test_repo().await;
@@ -345,7 +597,10 @@ mod tests {
         git.run(&["branch", "remove-test"]).await.unwrap();
 
         let wm = WorktreeManager::new();
-        let wt_path = wm.create("remove-test", &path).await.unwrap();
+        let wt_path = wm
+            .create("remove-test", &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
         assert!(wt_path.exists());
 
         wm.remove(&path, &wt_path).await.unwrap();
@@ -360,7 +615,10 @@ mod tests {
         git.run(&["branch", "managed-test"]).await.unwrap();
 
         let wm = WorktreeManager::new();
-        let wt_path = wm.create("managed-test", &path).await.unwrap();
+        let wt_path = wm
+            .create("managed-test", &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
 
         let managed = wm.list_managed(&path).await.unwrap();
         // Should contain only the managed worktree, not the main repo
@@ -373,6 +631,82 @@ mod tests {
         let _ = wm.remove(&path, &wt_path).await;
     }
 
+    #[tokio::test]
+    async fn test_disk_usage_reports_worktree_size() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        git.run(&["branch", "usage-test"]).await.unwrap();
+
+        let wm = WorktreeManager::new();
+        let wt_path = wm
+            .create("usage-test", &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
+        tokio::fs::write(wt_path.join("blob.bin"), vec![0u8; 100_000])
+            .await
+            .unwrap();
+
+        let usage = wm.disk_usage(&path).await.unwrap();
+        let _ = wm.remove(&path, &wt_path).await;
+
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].branch.as_deref(), Some("usage-test"));
+        // The blob plus README.md and the `.git` pointer file
+        assert!(
+            (100_000..110_000).contains(&usage[0].bytes),
+            "unexpected size {}",
+            usage[0].bytes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_flags_worktree_whose_branch_was_deleted() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        git.run(&["branch", "audit-gone"]).await.unwrap();
+        git.run(&["branch", "audit-kept"]).await.unwrap();
+
+        let wm = WorktreeManager::new();
+        let gone = wm
+            .create("audit-gone", &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
+        let kept = wm
+            .create("audit-kept", &path, &CheckoutOptions::default())
+            .await
+            .unwrap();
+        assert!(wm.audit(&path).await.unwrap().is_empty());
+
+        // `git branch -D` refuses a checked-out branch, so drop the ref directly
+        git.run(&["update-ref", "-d", "refs/heads/audit-gone"])
+            .await
+            .unwrap();
+        let issues = wm.audit(&path).await.unwrap();
+        let _ = wm.remove(&path, &gone).await;
+        let _ = wm.remove(&path, &kept).await;
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, WorktreeIssueKind::BranchMissing);
+        assert_eq!(issues[0].remedy, WorktreeRemedy::Recreate);
+        assert_eq!(issues[0].branch.as_deref(), Some("audit-gone"));
+        assert!(issues[0].lock_reason.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_size_does_not_follow_symlinks() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("data"), vec![0u8; 1000]).unwrap();
+        // A link back to the root would loop forever if followed
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("loop")).unwrap();
+
+        let size = dir_size(dir.path(), 0);
+        assert!(
+            (1000..1000 + 4096).contains(&size),
+            "unexpected size {size}"
+        );
+    }
+
     #[tokio::test]
     async fn test_list_managed_empty_for_fresh_repo() {
         let (_dir, path) = create_test_repo().await;
@@ -16,6 +16,10 @@ use tokio::sync::Notify;
 #[cfg(unix)]
 use libc;
 
+#[cfg(unix)]
+use super::process_manager::terminate_process_group;
+use super::process_manager::DEFAULT_KILL_GRACE;
+
 use super::terminal_backend::{
     BackendCapabilities, BackendType, SubscriptionHandle, TerminalBackend, TerminalConfig,
     TerminalError, TerminalState,
@@ -366,44 +370,10 @@ impl TerminalBackend for XtermPassthroughBackend {
         let session_id = self.session_id.lock().unwrap().unwrap_or(0);
         let pid = session.child_pid;
 
+        // Blocks for up to the grace period; in async context, call from
+        // a blocking task
         #[cfg(unix)]
-        {
-            let pgid = session.pgid;
-
-            // Send SIGTERM to the process group
-            let term_result = unsafe { libc::kill(-pgid, libc::SIGTERM) };
-            if term_result != 0 {
-                log::warn!(
-                    "Failed to SIGTERM session {} (pgid={}): {}",
-                    session_id,
-                    pgid,
-                    std::io::Error::last_os_error()
-                );
-            }
-
-            // Brief sync wait - in async context, caller should use async wrapper
-            std::thread::sleep(std::time::Duration::from_millis(100));
-
-            // Check if still alive and SIGKILL if needed
-            let alive = unsafe { libc::kill(pid, 0) } == 0;
-            if alive {
-                let kill_result = unsafe { libc::kill(-pgid, libc::SIGKILL) };
-                if kill_result != 0 {
-                    log::warn!(
-                        "Failed to SIGKILL session {} (pgid={}): {}",
-                        session_id,
-                        pgid,
-                        std::io::Error::last_os_error()
-                    );
-                }
-                log::warn!(
-                    "Session {} (pid={}, pgid={}) required SIGKILL",
-                    session_id,
-                    pid,
-                    pgid
-                );
-            }
-        }
+        terminate_process_group(session_id, pid, session.pgid, DEFAULT_KILL_GRACE);
 
         #[cfg(windows)]
         {
@@ -444,13 +414,7 @@ impl TerminalBackend for XtermPassthroughBackend {
     }
 
     fn capabilities(&self) -> BackendCapabilities {
-        BackendCapabilities {
-            enhanced_state: false,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "xterm-passthrough",
-        }
+        Self::backend_type().capabilities()
     }
 }
 
@@ -47,9 +47,46 @@ pub enum GitError {
     #[error("branch '{branch}' already checked out at {path}")]
     BranchAlreadyCheckedOut { branch: String, path: String },
 
+    /// A ref, tag, or SHA could not be resolved to a commit.
+    #[error("ref not found: {0}")]
+    RefNotFound(String),
+
     /// The specified worktree path does not exist in git's worktree list.
     #[error("worktree not found: {0}")]
     WorktreeNotFound(String),
+
+    /// A network operation needed credentials git could not obtain without
+    /// prompting (prompts are disabled for git subprocesses).
+    #[error("authentication required for remote '{remote}'; set up credentials for it")]
+    AuthenticationRequired { remote: String },
+
+    /// A conflict resolution was requested for a path with no conflict.
+    #[error("'{0}' is not conflicted")]
+    NotConflicted(String),
+
+    /// The resolution strategy does not apply to this kind of conflict
+    /// (e.g. a union merge when one side deleted the file).
+    #[error("cannot resolve '{path}' this way: {reason}")]
+    InvalidConflictResolution { path: String, reason: String },
+
+    /// A `git clean` was requested with a token that does not match the
+    /// current preview (the preview was never shown, or the set of untracked
+    /// files changed since).
+    #[error(
+        "clean confirmation does not match the current preview; preview again before cleaning"
+    )]
+    CleanNotConfirmed,
+
+    /// No hook (enabled or disabled) with this name exists in the hooks directory.
+    #[error("hook not found: {0}")]
+    HookNotFound(String),
+
+    /// A filesystem operation outside of a git subprocess failed.
+    #[error("I/O error at {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
 }
 
 /// Serializes the error as its `Display` string so the frontend receives a
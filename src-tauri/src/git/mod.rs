@@ -3,5 +3,5 @@ pub mod ops;
 pub mod runner;
 
 pub use error::GitError;
-pub use ops::{BranchInfo, CommitInfo, FileChange, FileChangeStatus, GitUserConfig, RemoteInfo, WorktreeInfo};
+pub use ops::{clean_confirmation_token, BranchDetail, BranchInfo, CommitGroup, CommitInfo, ConflictResolution, ConflictState, ConflictedFile, FileChange, FileChangeStatus, GitUserConfig, GroupBy, HeadInfo, HeadKind, HeadState, HookInfo, RemoteInfo, SignatureState, SignatureStatus, WorktreeInfo};
 pub use runner::Git;
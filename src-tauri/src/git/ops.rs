@@ -1,19 +1,48 @@
-use serde::Serialize;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use super::error::GitError;
 use super::runner::Git;
 
+/// `git log` format parsed by [`parse_commit_log`]: seven pipe-separated fields.
+const COMMIT_LOG_FORMAT: &str = "--format=%H|%h|%P|%an|%ae|%at|%s";
+
+/// [`COMMIT_LOG_FORMAT`] plus the signature check result (`%G?`) and signer
+/// (`%GS`) before the summary. Verifying runs gpg per commit, so it's opt-in.
+const SIGNED_COMMIT_LOG_FORMAT: &str = "--format=%H|%h|%P|%an|%ae|%at|%G?|%GS|%s";
+
 /// A local or remote branch returned by `list_branches`.
 ///
 /// Remote branches have `is_remote = true` and names like `origin/main`.
 /// Synthetic `HEAD` pointer entries (e.g. `origin/HEAD`) are filtered out
 /// during parsing and will never appear in results.
+///
+/// `upstream` and `has_worktree` are only filled in by
+/// `list_branches_with_details`; `list_branches` leaves them empty.
 #[derive(Debug, Clone, Serialize)]
 pub struct BranchInfo {
     pub name: String,
     pub is_remote: bool,
     pub is_current: bool,
+    /// Short name of the tracked upstream (e.g. `origin/main`), local branches only.
+    pub upstream: Option<String>,
+    /// Whether the branch is checked out in a linked (non-main) worktree.
+    pub has_worktree: bool,
+}
+
+/// A branch with its most recent commit, as returned by
+/// `Git::list_branches_detailed`.
+///
+/// `last_commit_date` is the committer date as a Unix epoch value.
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchDetail {
+    pub name: String,
+    pub is_remote: bool,
+    pub is_current: bool,
+    pub last_commit_date: i64,
+    pub last_commit_subject: String,
 }
 
 /// Metadata for a single git worktree, parsed from `git worktree list --porcelain`.
@@ -29,6 +58,9 @@ pub struct WorktreeInfo {
     /// `true` for the first entry returned by `git worktree list`, which is
     /// always the main working tree (the original clone directory).
     pub is_main_worktree: bool,
+    /// Lock reason for a locked worktree (empty if none was given), `None`
+    /// when it isn't locked.
+    pub locked: Option<String>,
 }
 
 /// A single commit entry parsed from `git log` output.
@@ -45,6 +77,70 @@ pub struct CommitInfo {
     pub author_email: String,
     pub timestamp: i64,
     pub summary: String,
+    /// Signature check result; only present when the log was asked to verify
+    /// signatures.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<SignatureStatus>,
+    /// Note from `refs/notes/commits`; only looked up when the log was asked
+    /// to include notes, and `None` for commits without one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// How `Git::commit_log_grouped` buckets commits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// One group per author name.
+    Author,
+    /// One group per UTC calendar day of the author date (`YYYY-MM-DD`).
+    Day,
+}
+
+/// Commits sharing an author or day, in log order.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitGroup {
+    pub key: String,
+    pub commits: Vec<CommitInfo>,
+}
+
+/// Outcome of verifying a commit's signature, condensed from `%G?`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureState {
+    /// `G`: valid signature from a trusted key.
+    Good,
+    /// `B`: the signature doesn't match the commit.
+    Bad,
+    /// `U`, `X`, `Y`, `R`, `E`: signed, but validity can't be fully vouched for
+    /// (untrusted, expired or revoked key, or the key is missing).
+    Unknown,
+    /// `N`: the commit isn't signed.
+    None,
+}
+
+/// Signature verification result for one commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignatureStatus {
+    pub state: SignatureState,
+    /// Signer name from `%GS`, when git could determine it.
+    pub signer: Option<String>,
+}
+
+impl SignatureStatus {
+    /// Builds the status from a commit's `%G?` code and `%GS` signer.
+    fn from_log_fields(code: &str, signer: &str) -> Self {
+        let state = match code {
+            "G" => SignatureState::Good,
+            "B" => SignatureState::Bad,
+            "N" | "" => SignatureState::None,
+            _ => SignatureState::Unknown,
+        };
+        Self {
+            state,
+            signer: (!signer.is_empty()).then(|| signer.to_string()),
+        }
+    }
 }
 
 /// Represents a file changed in a commit.
@@ -68,6 +164,100 @@ pub enum FileChangeStatus {
     Unknown,
 }
 
+/// What `HEAD` points at in a working tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum HeadState {
+    /// Checked out on a local branch (short name, e.g. `main`).
+    Branch(String),
+    /// Detached at a commit (full SHA).
+    Detached(String),
+}
+
+/// Whether [`HeadInfo`] names a branch or a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadKind {
+    Branch,
+    Detached,
+}
+
+/// `HEAD` of a working tree, for labeling it without mistaking a detached
+/// commit for a branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeadInfo {
+    pub kind: HeadKind,
+    /// Branch short name, or the full commit SHA when detached.
+    pub name_or_sha: String,
+    /// Upstream of the branch (e.g. `origin/main`); always `None` when
+    /// detached.
+    pub upstream: Option<String>,
+}
+
+/// How a path is unmerged, from the two-letter `git status --porcelain` code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictState {
+    /// `UU`: modified on both sides.
+    BothModified,
+    /// `AA`: added on both sides.
+    BothAdded,
+    /// `DD`: deleted on both sides.
+    BothDeleted,
+    /// `AU`: added by us.
+    AddedByUs,
+    /// `UA`: added by them.
+    AddedByThem,
+    /// `DU`: deleted by us.
+    DeletedByUs,
+    /// `UD`: deleted by them.
+    DeletedByThem,
+}
+
+impl ConflictState {
+    /// Maps a porcelain status code to a conflict state, or `None` if the
+    /// path is not unmerged.
+    fn from_porcelain(code: &str) -> Option<Self> {
+        match code {
+            "UU" => Some(Self::BothModified),
+            "AA" => Some(Self::BothAdded),
+            "DD" => Some(Self::BothDeleted),
+            "AU" => Some(Self::AddedByUs),
+            "UA" => Some(Self::AddedByThem),
+            "DU" => Some(Self::DeletedByUs),
+            "UD" => Some(Self::DeletedByThem),
+            _ => None,
+        }
+    }
+}
+
+/// How to settle a conflicted path with `Git::resolve_conflict`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    /// Keep our side of the file.
+    Ours,
+    /// Keep their side of the file.
+    Theirs,
+    /// Keep the lines from both sides (`git merge-file --union`).
+    Union,
+}
+
+/// An unmerged path left behind by a merge, rebase, or cherry-pick.
+///
+/// `path` is relative to the repository root. `marker_count` is the number of
+/// `<<<<<<<` markers in the working copy, or `None` if the file is absent.
+/// `ours` is the checked-out branch (or short SHA) and `theirs` the short SHA
+/// of the commit being merged in, when git still records one.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub state: ConflictState,
+    pub marker_count: Option<usize>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 /// Git user configuration (name and email).
 #[derive(Debug, Clone, Serialize)]
 pub struct GitUserConfig {
@@ -82,6 +272,25 @@ pub struct RemoteInfo {
     pub url: String,
 }
 
+/// Suffix `set_hook_enabled` appends to a hook file to disable it; git only
+/// runs hooks whose file name is exactly the hook name.
+const DISABLED_HOOK_SUFFIX: &str = ".disabled";
+
+/// A hook script in the hooks directory git uses for a repo or worktree.
+///
+/// `name` never includes the `.disabled` suffix. The `*.sample` files git
+/// ships are not listed.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookInfo {
+    pub name: String,
+    pub path: String,
+    /// Whether the file has an executable bit set (always `true` on
+    /// platforms without one).
+    pub executable: bool,
+    /// `false` if the hook was renamed with the `.disabled` suffix.
+    pub enabled: bool,
+}
+
 impl Git {
     /// Lists all local and remote branches, excluding `HEAD` pointer entries.
     ///
@@ -121,24 +330,125 @@ impl Git {
                 name,
                 is_remote,
                 is_current,
+                upstream: None,
+                has_worktree: false,
             });
         }
         Ok(branches)
     }
 
-    /// Returns the name of the currently checked-out branch.
+    /// Like `list_branches`, but also fills in each local branch's upstream
+    /// and whether a linked worktree has it checked out.
     ///
-    /// Uses `symbolic-ref` first; if that fails (detached HEAD), falls back to
-    /// `rev-parse --short HEAD` so the caller always gets a usable label.
-    pub async fn current_branch(&self) -> Result<String, GitError> {
-        match self.run(&["symbolic-ref", "--short", "HEAD"]).await {
+    /// Costs two extra git calls (`for-each-ref` and `worktree list`), so
+    /// callers that only need names should stick to `list_branches`.
+    pub async fn list_branches_with_details(&self) -> Result<Vec<BranchInfo>, GitError> {
+        let mut branches = self.list_branches().await?;
+
+        let output = self
+            .run(&[
+                "for-each-ref",
+                "--format=%(refname:short)%00%(upstream:short)",
+                "refs/heads",
+            ])
+            .await?;
+        let upstreams: HashMap<&str, &str> = output
+            .lines()
+            .into_iter()
+            .filter_map(|line| line.split_once('\0'))
+            .filter(|(_, upstream)| !upstream.is_empty())
+            .collect();
+
+        let worktree_branches: HashSet<String> = self
+            .worktree_list()
+            .await?
+            .into_iter()
+            .filter(|wt| !wt.is_main_worktree)
+            .filter_map(|wt| wt.branch)
+            .collect();
+
+        for branch in branches.iter_mut().filter(|b| !b.is_remote) {
+            branch.upstream = upstreams.get(branch.name.as_str()).map(|u| u.to_string());
+            branch.has_worktree = worktree_branches.contains(&branch.name);
+        }
+        Ok(branches)
+    }
+
+    /// Lists local and remote branches with their latest commit, most
+    /// recently committed first.
+    ///
+    /// Costlier than `list_branches` because git has to read every tip
+    /// commit; prefer that one on hot paths that only need names.
+    pub async fn list_branches_detailed(&self) -> Result<Vec<BranchDetail>, GitError> {
+        let output = self
+            .run(&[
+                "for-each-ref",
+                "--sort=-committerdate",
+                "--format=%(HEAD)%00%(refname)%00%(committerdate:unix)%00%(contents:subject)",
+                "refs/heads",
+                "refs/remotes",
+            ])
+            .await?;
+
+        let mut branches = Vec::new();
+        for line in output.lines() {
+            let parts: Vec<&str> = line.splitn(4, '\0').collect();
+            if parts.len() < 4 {
+                continue;
+            }
+
+            // Skip HEAD pointer entries like "refs/remotes/origin/HEAD"
+            let refname = parts[1];
+            if refname.ends_with("/HEAD") {
+                continue;
+            }
+            let (name, is_remote) = match refname.strip_prefix("refs/heads/") {
+                Some(name) => (name, false),
+                None => match refname.strip_prefix("refs/remotes/") {
+                    Some(name) => (name, true),
+                    None => continue,
+                },
+            };
+
+            branches.push(BranchDetail {
+                name: name.to_string(),
+                is_remote,
+                is_current: parts[0] == "*",
+                last_commit_date: parts[2].parse().unwrap_or(0),
+                last_commit_subject: parts[3].to_string(),
+            });
+        }
+        Ok(branches)
+    }
+
+    /// Resolves a branch, tag, or (abbreviated) SHA to a full commit SHA.
+    ///
+    /// Returns `RefNotFound` if `rev` does not name a commit. Revs starting
+    /// with `-` are rejected so they cannot be parsed as options.
+    pub async fn resolve_commit(&self, rev: &str) -> Result<String, GitError> {
+        if rev.is_empty() || rev.starts_with('-') {
+            return Err(GitError::RefNotFound(rev.to_string()));
+        }
+        let spec = format!("{}^{{commit}}", rev);
+        match self.run(&["rev-parse", "--verify", "--quiet", &spec]).await {
             Ok(output) => Ok(output.trimmed().to_string()),
+            Err(GitError::CommandFailed { .. }) => Err(GitError::RefNotFound(rev.to_string())),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Reports whether `HEAD` is on a branch or detached at a commit.
+    ///
+    /// Prefer this over `current_branch` when the result is compared against a
+    /// branch name: a detached HEAD's hash must never be mistaken for a branch.
+    pub async fn head_state(&self) -> Result<HeadState, GitError> {
+        match self.run(&["symbolic-ref", "--short", "HEAD"]).await {
+            Ok(output) => Ok(HeadState::Branch(output.trimmed().to_string())),
             Err(GitError::CommandFailed { code, stderr, .. }) => {
                 // Git returns: "fatal: ref HEAD is not a symbolic ref"
                 if stderr.contains("not a symbolic ref") {
-                    // Detached HEAD — fall back to short hash
-                    let output = self.run(&["rev-parse", "--short", "HEAD"]).await?;
-                    Ok(output.trimmed().to_string())
+                    let output = self.run(&["rev-parse", "HEAD"]).await?;
+                    Ok(HeadState::Detached(output.trimmed().to_string()))
                 } else {
                     // Real error — propagate
                     Err(GitError::CommandFailed {
@@ -152,6 +462,39 @@ impl Git {
         }
     }
 
+    /// Returns the name of the currently checked-out branch.
+    ///
+    /// On a detached HEAD, falls back to the short commit hash so the caller
+    /// always gets a usable display label.
+    pub async fn current_branch(&self) -> Result<String, GitError> {
+        match self.head_state().await? {
+            HeadState::Branch(name) => Ok(name),
+            HeadState::Detached(sha) => {
+                let output = self.run(&["rev-parse", "--short", &sha]).await?;
+                Ok(output.trimmed().to_string())
+            }
+        }
+    }
+
+    /// Like [`head_state`](Self::head_state), plus the branch's upstream.
+    pub async fn current_head(&self) -> Result<HeadInfo, GitError> {
+        match self.head_state().await? {
+            HeadState::Branch(name) => {
+                let upstream = self.upstream_branch(&name).await?;
+                Ok(HeadInfo {
+                    kind: HeadKind::Branch,
+                    name_or_sha: name,
+                    upstream,
+                })
+            }
+            HeadState::Detached(sha) => Ok(HeadInfo {
+                kind: HeadKind::Detached,
+                name_or_sha: sha,
+                upstream: None,
+            }),
+        }
+    }
+
     /// Returns the number of uncommitted changes (staged + unstaged + untracked).
     ///
     /// Counts non-empty lines from `git status --porcelain`. Each line represents
@@ -173,6 +516,7 @@ impl Git {
         let mut current_head = String::new();
         let mut current_branch: Option<String> = None;
         let mut current_bare = false;
+        let mut current_locked: Option<String> = None;
 
         for line in output.lines() {
             if let Some(path) = line.strip_prefix("worktree ") {
@@ -185,18 +529,24 @@ impl Git {
                         branch: current_branch,
                         is_bare: current_bare,
                         is_main_worktree: is_main,
+                        locked: current_locked,
                     });
                 }
                 current_path = path.to_string();
                 current_head = String::new();
                 current_branch = None;
                 current_bare = false;
+                current_locked = None;
             } else if let Some(head) = line.strip_prefix("HEAD ") {
                 current_head = head.to_string();
             } else if let Some(branch) = line.strip_prefix("branch refs/heads/") {
                 current_branch = Some(branch.to_string());
             } else if line == "bare" {
                 current_bare = true;
+            } else if line == "locked" {
+                current_locked = Some(String::new());
+            } else if let Some(reason) = line.strip_prefix("locked ") {
+                current_locked = Some(reason.to_string());
             }
         }
 
@@ -209,16 +559,28 @@ impl Git {
                 branch: current_branch,
                 is_bare: current_bare,
                 is_main_worktree: is_main,
+                locked: current_locked,
             });
         }
 
         Ok(worktrees)
     }
 
+    /// Lists the linked worktrees that sit on a branch, as created for
+    /// sessions: the main worktree and detached entries are left out.
+    pub async fn managed_worktrees(&self) -> Result<Vec<WorktreeInfo>, GitError> {
+        let mut worktrees = self.worktree_list().await?;
+        worktrees.retain(|wt| !wt.is_main_worktree && wt.branch.is_some());
+        Ok(worktrees)
+    }
+
     /// Creates a new worktree at the given path, optionally on a new branch.
     ///
     /// If `new_branch` is provided, passes `-b <branch>` to create it.
     /// If `checkout_ref` is provided, the new worktree checks out that ref.
+    /// With `no_checkout`, passes `--no-checkout`: HEAD is set but the working
+    /// tree and index stay empty until something is checked out (see
+    /// `checkout_paths`).
     /// After creation, reads back the HEAD and branch from the new worktree
     /// directory to return accurate metadata.
     pub async fn worktree_add(
@@ -226,9 +588,13 @@ impl Git {
         path: &Path,
         new_branch: Option<&str>,
         checkout_ref: Option<&str>,
+        no_checkout: bool,
     ) -> Result<WorktreeInfo, GitError> {
         let path_str = path.to_string_lossy();
         let mut args = vec!["worktree", "add"];
+        if no_checkout {
+            args.push("--no-checkout");
+        }
 
         // Collect owned strings to extend their lifetime
         let branch_flag;
@@ -271,6 +637,7 @@ impl Git {
             branch,
             is_bare: false,
             is_main_worktree: false,
+            locked: None,
         })
     }
 
@@ -302,46 +669,121 @@ impl Git {
         &self,
         max_count: usize,
         all_branches: bool,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        self.commit_log_page(max_count, 0, all_branches, false, false)
+            .await
+    }
+
+    /// Like [`commit_log`](Self::commit_log), but skips the first `skip`
+    /// commits (`git log --skip=N`) so older history can be loaded page by page.
+    /// With `verify_signatures`, each commit's `signature` is filled in; this
+    /// runs gpg for every signed commit, so it is noticeably slower.
+    /// With `include_notes`, each commit's `notes` is filled in as well.
+    pub async fn commit_log_page(
+        &self,
+        max_count: usize,
+        skip: usize,
+        all_branches: bool,
+        verify_signatures: bool,
+        include_notes: bool,
     ) -> Result<Vec<CommitInfo>, GitError> {
         let count_str = format!("-{}", max_count);
-        let mut args = vec![
-            "log",
-            "--format=%H|%h|%P|%an|%ae|%at|%s",
-            &count_str,
-            "--topo-order",
-        ];
+        let skip_str = format!("--skip={}", skip);
+        let format = if verify_signatures {
+            SIGNED_COMMIT_LOG_FORMAT
+        } else {
+            COMMIT_LOG_FORMAT
+        };
+        let mut args = vec!["log", format, &count_str, &skip_str, "--topo-order"];
         if all_branches {
             args.push("--all");
         }
 
         let output = self.run(&args).await?;
+        let mut commits = parse_commit_log(&output.stdout, verify_signatures);
+        if include_notes {
+            self.attach_notes(&mut commits).await?;
+        }
+        Ok(commits)
+    }
 
-        let mut commits = Vec::new();
-        for line in output.lines() {
-            let parts: Vec<&str> = line.splitn(7, '|').collect();
-            if parts.len() < 7 {
-                continue;
-            }
+    /// Returns up to `max` commits of the current branch bucketed by author or
+    /// by day, for a compact history overview.
+    ///
+    /// Groups are ordered by their newest commit, and commits keep their
+    /// topological order within a group.
+    pub async fn commit_log_grouped(
+        &self,
+        max: usize,
+        group_by: GroupBy,
+    ) -> Result<Vec<CommitGroup>, GitError> {
+        let commits = self.commit_log(max, false).await?;
+        Ok(group_commits(commits, group_by))
+    }
 
-            let timestamp = parts[5].parse::<i64>().unwrap_or(0);
-            let parent_hashes: Vec<String> = if parts[2].is_empty() {
-                Vec::new()
-            } else {
-                parts[2].split(' ').map(|s| s.to_string()).collect()
-            };
+    /// Fills in `notes` for the commits that have one in `refs/notes/commits`.
+    ///
+    /// Notes can span several lines, which would break the pipe-separated log
+    /// format, so each is read with its own `git notes show`. Only commits that
+    /// `git notes list` reports as annotated are looked up, so this runs at
+    /// most one extra git command per commit in the page.
+    async fn attach_notes(&self, commits: &mut [CommitInfo]) -> Result<(), GitError> {
+        let listed = self.run(&["notes", "list"]).await?;
+        // Each line is `<note blob> <annotated commit>`
+        let annotated: HashSet<&str> = listed
+            .stdout
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .collect();
 
-            commits.push(CommitInfo {
-                hash: parts[0].to_string(),
-                short_hash: parts[1].to_string(),
-                parent_hashes,
-                author_name: parts[3].to_string(),
-                author_email: parts[4].to_string(),
-                timestamp,
-                summary: parts[6].to_string(),
-            });
+        for commit in commits
+            .iter_mut()
+            .filter(|c| annotated.contains(c.hash.as_str()))
+        {
+            let note = self.run(&["notes", "show", &commit.hash]).await?;
+            commit.notes = Some(note.stdout.trim_end().to_string());
         }
+        Ok(())
+    }
 
-        Ok(commits)
+    /// Returns up to `max` commits reachable from `head` but not from `base`
+    /// (`git log base..head`), newest first in topological order.
+    ///
+    /// Returns `RefNotFound` if either ref does not name a commit.
+    pub async fn commits_between(
+        &self,
+        base: &str,
+        head: &str,
+        max: usize,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        // Resolving first rejects option-like refs and reports which one is missing
+        let base_sha = self.resolve_commit(base).await?;
+        let head_sha = self.resolve_commit(head).await?;
+
+        let count_str = format!("-{}", max);
+        let range = format!("{}..{}", base_sha, head_sha);
+        let output = self
+            .run(&["log", COMMIT_LOG_FORMAT, &count_str, "--topo-order", &range])
+            .await?;
+        Ok(parse_commit_log(&output.stdout, false))
+    }
+
+    /// Returns the best common ancestor of `a` and `b` (`git merge-base`), or
+    /// `None` when their histories are unrelated.
+    ///
+    /// Returns `RefNotFound` if either ref does not name a commit.
+    pub async fn merge_base(&self, a: &str, b: &str) -> Result<Option<String>, GitError> {
+        let a_sha = self.resolve_commit(a).await?;
+        let b_sha = self.resolve_commit(b).await?;
+
+        match self.run(&["merge-base", &a_sha, &b_sha]).await {
+            Ok(output) => Ok(Some(output.trimmed().to_string())),
+            // Exit 1 without a message means no common ancestor
+            Err(GitError::CommandFailed {
+                code: 1, stderr, ..
+            }) if stderr.trim().is_empty() => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
     /// Checks out a branch by name.
@@ -372,6 +814,18 @@ impl Git {
         Ok(())
     }
 
+    /// Checks whether `name` is acceptable as a new branch name (no spaces,
+    /// `..`, leading `-`, ...) via `git check-ref-format --branch`.
+    ///
+    /// Nothing is created; an invalid name is `Ok(false)`, not an error.
+    pub async fn check_ref_name(&self, name: &str) -> Result<bool, GitError> {
+        match self.run(&["check-ref-format", "--branch", name]).await {
+            Ok(_) => Ok(true),
+            Err(GitError::CommandFailed { code, .. }) if code > 0 => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Creates a new branch, optionally from a specific starting point.
     ///
     /// If `start_point` is None, creates from HEAD.
@@ -435,6 +889,15 @@ impl Git {
         Ok(files)
     }
 
+    /// Checks out `paths` from HEAD into the working tree and index, e.g. to
+    /// populate part of a worktree created with `--no-checkout`.
+    pub async fn checkout_paths(&self, paths: &[String]) -> Result<(), GitError> {
+        let mut args = vec!["checkout", "HEAD", "--"];
+        args.extend(paths.iter().map(String::as_str));
+        self.run(&args).await?;
+        Ok(())
+    }
+
     /// Gets the git user config (name and email) for this repository.
     ///
     /// First checks local config, falls back to global if not set.
@@ -561,12 +1024,13 @@ impl Git {
 
     /// Tests connectivity to a remote by running `git ls-remote --heads`.
     ///
-    /// Returns `true` if the remote is reachable, `false` otherwise.
+    /// Returns `true` if the remote is reachable, `false` otherwise, and
+    /// `AuthenticationRequired` if it is reachable but needs credentials.
     /// Uses a 10-second timeout to avoid hanging on unresponsive remotes.
     pub async fn test_remote(&self, remote_name: &str) -> Result<bool, GitError> {
         match tokio::time::timeout(
             std::time::Duration::from_secs(10),
-            self.run(&["ls-remote", "--heads", remote_name]),
+            self.run_remote(remote_name, &["ls-remote", "--heads", remote_name]),
         )
         .await
         {
@@ -602,6 +1066,30 @@ impl Git {
         }
     }
 
+    /// Returns the branch `remote`'s HEAD points at (e.g. `main`), as recorded
+    /// by `refs/remotes/<remote>/HEAD` when the repo was cloned or after
+    /// `git remote set-head`.
+    ///
+    /// Unlike `get_default_branch`, this reflects the current repository.
+    /// Returns `None` if the remote has no recorded HEAD.
+    pub async fn remote_default_branch(&self, remote: &str) -> Result<Option<String>, GitError> {
+        let head_ref = format!("refs/remotes/{}/HEAD", remote);
+        let output = match self
+            .run(&["symbolic-ref", "--quiet", "--short", &head_ref])
+            .await
+        {
+            Ok(output) => output,
+            // Missing or non-symbolic ref
+            Err(GitError::CommandFailed { code: 1 | 128, .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let prefix = format!("{}/", remote);
+        let target = output.trimmed();
+        let branch = target.strip_prefix(&prefix).unwrap_or(target);
+        Ok(Some(branch.to_string()))
+    }
+
     /// Sets the default branch name in git config (init.defaultBranch).
     ///
     /// If `global` is true, sets the global config; otherwise, sets repository-local config.
@@ -611,73 +1099,955 @@ impl Git {
         Ok(())
     }
 
-    /// Detaches HEAD at the current commit.
-    ///
-    /// Used when we need to free up a branch for worktree creation
-    /// but have no other branch to switch to.
-    pub async fn detach_head(&self) -> Result<(), GitError> {
-        self.run(&["checkout", "--detach"]).await?;
-        Ok(())
+    /// Fetches `remote` with `--prune` and returns the remote-tracking refs
+    /// that were deleted because their upstream branch is gone
+    /// (e.g. `origin/feature-x`).
+    pub async fn fetch_prune(&self, remote: &str) -> Result<Vec<String>, GitError> {
+        let output = self
+            .run_remote(remote, &["fetch", "--prune", "--", remote])
+            .await?;
+        Ok(parse_pruned_refs(&output.stderr))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::git::runner::Git;
-    use tempfile::tempdir;
+    /// Lists local branches whose configured upstream no longer exists
+    /// (shown as `[gone]` by `git branch -vv`).
+    ///
+    /// Only as fresh as the last fetch; run `fetch_prune` first to pick up
+    /// branches deleted on the remote.
+    pub async fn list_stale_tracking_branches(&self) -> Result<Vec<String>, GitError> {
+        let output = self
+            .run(&[
+                "for-each-ref",
+                "--format=%(refname:short)%00%(upstream:track)",
+                "refs/heads",
+            ])
+            .await?;
 
-    /// Helper: creates a temp git repo with an initial commit.
-    async fn create_test_repo() -> (tempfile::TempDir, Git) {
-        let dir = tempdir().unwrap();
-        let path = dir.path().to_path_buf();
-        let git = Git::new(&path);
+        Ok(output
+            .lines()
+            .into_iter()
+            .filter_map(|line| line.split_once('\0'))
+            .filter(|(_, track)| *track == "[gone]")
+            .map(|(name, _)| name.to_string())
+            .collect())
+    }
 
-        git.run(&["init"]).await.unwrap();
-        git.run(&["config", "user.email", "test@test.com"])
-            .await
-            .unwrap();
-        git.run(&["config", "user.name", "Test"]).await.unwrap();
+    /// Lists local branches fully merged into `into`, excluding `into` itself
+    /// and the currently checked-out branch.
+    ///
+    /// Returns `RefNotFound` if `into` does not name a commit.
+    pub async fn merged_branches(&self, into: &str) -> Result<Vec<String>, GitError> {
+        self.resolve_commit(into).await?;
+        let current = match self.head_state().await? {
+            HeadState::Branch(name) => Some(name),
+            HeadState::Detached(_) => None,
+        };
+        let into_name = into.strip_prefix("refs/heads/").unwrap_or(into);
 
-        let file_path = path.join("README.md");
-        tokio::fs::write(&file_path, "# Test").await.unwrap();
-        git.run(&["add", "."]).await.unwrap();
-        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        let merged = format!("--merged={}", into);
+        let output = self
+            .run(&[
+                "for-each-ref",
+                "--format=%(refname:short)",
+                &merged,
+                "refs/heads",
+            ])
+            .await?;
 
-        (dir, git)
+        Ok(output
+            .lines()
+            .into_iter()
+            .filter(|name| *name != into_name && current.as_deref() != Some(*name))
+            .map(str::to_string)
+            .collect())
     }
 
-    #[tokio::test]
-    async fn test_worktree_list_main_repo_only() {
-        let (_dir, git) = create_test_repo().await;
-        let worktrees = git.worktree_list().await.unwrap();
+    /// Deletes the local branches reported by `merged_branches`, skipping any
+    /// still checked out in a worktree.
+    ///
+    /// Returns the deleted branch names. With `dry_run`, returns the branches
+    /// that would be deleted without touching them.
+    pub async fn delete_merged_branches(
+        &self,
+        into: &str,
+        dry_run: bool,
+    ) -> Result<Vec<String>, GitError> {
+        let checked_out: HashSet<String> = self
+            .worktree_list()
+            .await?
+            .into_iter()
+            .filter_map(|wt| wt.branch)
+            .collect();
+        let branches: Vec<String> = self
+            .merged_branches(into)
+            .await?
+            .into_iter()
+            .filter(|name| !checked_out.contains(name))
+            .collect();
 
-        assert_eq!(worktrees.len(), 1);
-        assert!(worktrees[0].is_main_worktree);
+        if !dry_run {
+            for name in &branches {
+                // -D because `git branch -d` checks against HEAD, not `into`
+                self.run(&["branch", "-D", "--", name]).await?;
+            }
+        }
+        Ok(branches)
     }
 
-    #[tokio::test]
-    async fn test_worktree_list_with_added_worktree() {
-        let (dir, git) = create_test_repo().await;
-        git.run(&["branch", "test-branch"]).await.unwrap();
-
-        let wt_path = dir.path().join("wt-test");
-        git.worktree_add(&wt_path, None, Some("test-branch"))
-            .await
-            .unwrap();
-
-        let worktrees = git.worktree_list().await.unwrap();
-        assert_eq!(worktrees.len(), 2);
-        assert!(worktrees[0].is_main_worktree);
-        assert!(!worktrees[1].is_main_worktree);
+    /// Returns the upstream of a local branch (e.g. `origin/feature`), or
+    /// `None` if the branch does not track anything.
+    pub async fn upstream_branch(&self, branch: &str) -> Result<Option<String>, GitError> {
+        let refname = format!("refs/heads/{}", branch);
+        let output = self
+            .run(&["for-each-ref", "--format=%(upstream:short)", &refname])
+            .await?;
+        let upstream = output.trimmed();
+        Ok((!upstream.is_empty()).then(|| upstream.to_string()))
+    }
 
-        // Cleanup
-        git.worktree_remove(&wt_path, true).await.unwrap();
+    /// Pushes a local branch to `remote`.
+    ///
+    /// With `set_upstream`, the branch is also configured to track the pushed
+    /// ref (`git push --set-upstream`).
+    pub async fn push(
+        &self,
+        remote: &str,
+        branch: &str,
+        set_upstream: bool,
+    ) -> Result<(), GitError> {
+        let mut args = vec!["push"];
+        if set_upstream {
+            args.push("--set-upstream");
+        }
+        args.extend(["--", remote, branch]);
+        self.run_remote(remote, &args).await?;
+        Ok(())
     }
 
-    #[tokio::test]
-    async fn test_worktree_list_main_has_correct_branch() {
-        let (_dir, git) = create_test_repo().await;
+    /// Lists unmerged paths in the working tree, with conflict marker counts.
+    ///
+    /// Parses `git status --porcelain -z` for the unmerged codes (`UU`, `AA`,
+    /// `DD`, `AU`, `UA`, `DU`, `UD`). Returns an empty list when there are no
+    /// conflicts.
+    pub async fn conflicted_files(&self) -> Result<Vec<ConflictedFile>, GitError> {
+        let conflicts = self.unmerged_paths().await?;
+        if conflicts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let toplevel = self.run(&["rev-parse", "--show-toplevel"]).await?;
+        let root = PathBuf::from(toplevel.trimmed());
+        let ours = self.current_branch().await.ok();
+        let mut theirs = None;
+        for head in ["MERGE_HEAD", "REBASE_HEAD", "CHERRY_PICK_HEAD"] {
+            if let Ok(output) = self
+                .run(&["rev-parse", "--verify", "--quiet", "--short", head])
+                .await
+            {
+                theirs = Some(output.trimmed().to_string());
+                break;
+            }
+        }
+
+        let mut files = Vec::with_capacity(conflicts.len());
+        for (path, state) in conflicts {
+            let marker_count = tokio::fs::read(root.join(&path)).await.ok().map(|content| {
+                String::from_utf8_lossy(&content)
+                    .lines()
+                    .filter(|line| line.starts_with("<<<<<<<"))
+                    .count()
+            });
+            files.push(ConflictedFile {
+                path,
+                state,
+                marker_count,
+                ours: ours.clone(),
+                theirs: theirs.clone(),
+            });
+        }
+        Ok(files)
+    }
+
+    /// Resolves one conflicted path and stages the result.
+    ///
+    /// `Ours`/`Theirs` check out that side (`git checkout --ours/--theirs`),
+    /// or remove the file if that side deleted it. `Union` keeps both sides'
+    /// lines and is refused when either side deleted the file. Returns
+    /// `NotConflicted` if `path` (relative to the repository root) is not
+    /// currently unmerged.
+    pub async fn resolve_conflict(
+        &self,
+        path: &str,
+        strategy: ConflictResolution,
+    ) -> Result<(), GitError> {
+        let state = self
+            .unmerged_paths()
+            .await?
+            .into_iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, state)| state)
+            .ok_or_else(|| GitError::NotConflicted(path.to_string()))?;
+
+        // Pathspecs are relative to the root, matching `git status` output
+        let pathspec = format!(":(top){}", path);
+        let deleted_side = match strategy {
+            ConflictResolution::Ours => matches!(
+                state,
+                ConflictState::DeletedByUs | ConflictState::BothDeleted
+            ),
+            ConflictResolution::Theirs => matches!(
+                state,
+                ConflictState::DeletedByThem | ConflictState::BothDeleted
+            ),
+            ConflictResolution::Union => {
+                let both_present = matches!(
+                    state,
+                    ConflictState::BothModified | ConflictState::BothAdded
+                );
+                if !both_present {
+                    return Err(GitError::InvalidConflictResolution {
+                        path: path.to_string(),
+                        reason: "a union merge needs both sides of the file".to_string(),
+                    });
+                }
+                false
+            }
+        };
+        if deleted_side {
+            self.run(&["rm", "--quiet", "--", &pathspec]).await?;
+            return Ok(());
+        }
+
+        match strategy {
+            ConflictResolution::Ours => {
+                self.run(&["checkout", "--ours", "--", &pathspec]).await?;
+            }
+            ConflictResolution::Theirs => {
+                self.run(&["checkout", "--theirs", "--", &pathspec]).await?;
+            }
+            ConflictResolution::Union => self.union_merge(path, &pathspec).await?,
+        }
+        self.run(&["add", "--", &pathspec]).await?;
+        Ok(())
+    }
+
+    /// Rewrites a conflicted file as the union of both sides, using the
+    /// index stages as input (stage 1 may be absent for add/add conflicts).
+    ///
+    /// `git checkout-index --temp` copies the stage blobs byte for byte into
+    /// uniquely named files at the worktree root, so binary or non-UTF-8
+    /// content never passes through our output decoding.
+    async fn union_merge(&self, path: &str, pathspec: &str) -> Result<(), GitError> {
+        let toplevel = self.run(&["rev-parse", "--show-toplevel"]).await?;
+        let toplevel = PathBuf::from(toplevel.trimmed());
+        let output = self
+            .run_in(
+                &toplevel,
+                &["checkout-index", "--stage=all", "--temp", "--", path],
+            )
+            .await?;
+
+        // "<base> <ours> <theirs>\t<path>", with "." for a missing stage
+        let names = output.stdout.split('\t').next().unwrap_or_default();
+        let stages: Vec<Option<PathBuf>> = names
+            .split(' ')
+            .map(|name| (name != ".").then(|| toplevel.join(name)))
+            .collect();
+        let result = match stages.as_slice() {
+            [base, Some(ours), Some(theirs)] => {
+                self.merge_union_stages(
+                    pathspec,
+                    &toplevel.join(path),
+                    base.as_deref(),
+                    ours,
+                    theirs,
+                )
+                .await
+            }
+            _ => Err(GitError::ParseError {
+                message: format!("unexpected checkout-index output: {:?}", output.stdout),
+            }),
+        };
+        for temp in stages.iter().flatten() {
+            let _ = tokio::fs::remove_file(temp).await;
+        }
+        result
+    }
+
+    /// Checks out our side of `target` and merges the union of `base` and
+    /// `theirs` into it. Without a base, the unused copy of our stage is
+    /// emptied and stands in for it.
+    async fn merge_union_stages(
+        &self,
+        pathspec: &str,
+        target: &Path,
+        base: Option<&Path>,
+        ours: &Path,
+        theirs: &Path,
+    ) -> Result<(), GitError> {
+        let base = match base {
+            Some(base) => base,
+            None => {
+                tokio::fs::write(ours, b"")
+                    .await
+                    .map_err(|e| GitError::ParseError {
+                        message: format!("failed to stage union merge inputs: {}", e),
+                    })?;
+                ours
+            }
+        };
+
+        // merge-file writes the result into its first argument, so start from ours
+        self.run(&["checkout", "--ours", "--", pathspec]).await?;
+        let target = target.to_string_lossy();
+        let base = base.to_string_lossy();
+        let theirs = theirs.to_string_lossy();
+        self.run(&["merge-file", "--union", &target, &base, &theirs])
+            .await?;
+        Ok(())
+    }
+
+    /// Lists unmerged paths (relative to the root) from
+    /// `git status --porcelain -z`.
+    async fn unmerged_paths(&self) -> Result<Vec<(String, ConflictState)>, GitError> {
+        let output = self.run(&["status", "--porcelain", "-z"]).await?;
+
+        let mut conflicts = Vec::new();
+        let mut entries = output.stdout.split('\0');
+        while let Some(entry) = entries.next() {
+            let (Some(code), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+                continue;
+            };
+            // Renames and copies carry their source path as a separate entry
+            if code.starts_with('R') || code.starts_with('C') {
+                entries.next();
+            }
+            if let Some(state) = ConflictState::from_porcelain(code) {
+                conflicts.push((path.to_string(), state));
+            }
+        }
+        Ok(conflicts)
+    }
+
+    /// Detaches HEAD at the current commit.
+    ///
+    /// Used when we need to free up a branch for worktree creation
+    /// but have no other branch to switch to.
+    pub async fn detach_head(&self) -> Result<(), GitError> {
+        self.run(&["checkout", "--detach"]).await?;
+        Ok(())
+    }
+
+    /// Lists the paths `git clean` would remove, without touching anything.
+    ///
+    /// Runs `git clean -n`, adding `-d` for untracked directories and `-x`
+    /// for ignored files. Directory entries keep git's trailing `/`, and
+    /// names git prints C-quoted are decoded. "Would skip repository" lines
+    /// for nested repos are ignored.
+    pub async fn clean_preview(
+        &self,
+        include_dirs: bool,
+        include_ignored: bool,
+    ) -> Result<Vec<String>, GitError> {
+        let mut args = vec!["-c", "core.quotePath=false", "clean", "-n"];
+        if include_dirs {
+            args.push("-d");
+        }
+        if include_ignored {
+            args.push("-x");
+        }
+        let output = self.run(&args).await?;
+
+        // `git clean` has no -z, so names with special characters come quoted
+        output
+            .stdout
+            .split('\n')
+            .filter_map(|line| line.strip_prefix("Would remove "))
+            .map(unquote_git_path)
+            .collect()
+    }
+
+    /// Removes untracked files, but only if `confirmation_token` matches the
+    /// current preview (see [`clean_confirmation_token`]).
+    ///
+    /// The preview is re-computed first; if the token does not match, nothing
+    /// is removed and `CleanNotConfirmed` is returned. The removal is scoped to
+    /// the previewed paths, each passed as a `:(literal)` pathspec so names
+    /// containing glob characters match only themselves, and a file created
+    /// after the check is left alone. Returns the removed paths.
+    pub async fn clean_execute(
+        &self,
+        include_dirs: bool,
+        include_ignored: bool,
+        confirmation_token: &str,
+    ) -> Result<Vec<String>, GitError> {
+        let paths = self.clean_preview(include_dirs, include_ignored).await?;
+        if clean_confirmation_token(&paths) != confirmation_token {
+            return Err(GitError::CleanNotConfirmed);
+        }
+        if paths.is_empty() {
+            return Ok(paths);
+        }
+
+        let mut args = vec!["clean", "-f"];
+        if include_dirs {
+            args.push("-d");
+        }
+        if include_ignored {
+            args.push("-x");
+        }
+        args.push("--");
+        let pathspecs: Vec<String> = paths.iter().map(|p| format!(":(literal){}", p)).collect();
+        args.extend(pathspecs.iter().map(String::as_str));
+        self.run(&args).await?;
+
+        Ok(paths)
+    }
+
+    /// Resolves the hooks directory git actually uses here, following
+    /// linked worktrees and `core.hooksPath`.
+    pub async fn hooks_dir(&self) -> Result<PathBuf, GitError> {
+        let output = self.run(&["rev-parse", "--git-path", "hooks"]).await?;
+        // Relative results are relative to the directory git ran in
+        Ok(self.repo_path().join(output.trimmed()))
+    }
+
+    /// Lists hook scripts (enabled and disabled), sorted by name.
+    ///
+    /// A missing hooks directory yields an empty list.
+    pub async fn list_hooks(&self) -> Result<Vec<HookInfo>, GitError> {
+        let dir = self.hooks_dir().await?;
+        let io_err = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| GitError::Io { path, source }
+        };
+
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_err(&dir)(e)),
+        };
+
+        let mut hooks = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(io_err(&dir))? {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if file_name.ends_with(".sample") {
+                continue;
+            }
+            let path = entry.path();
+            let metadata = entry.metadata().await.map_err(io_err(&path))?;
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let (name, enabled) = match file_name.strip_suffix(DISABLED_HOOK_SUFFIX) {
+                Some(name) => (name.to_string(), false),
+                None => (file_name, true),
+            };
+            hooks.push(HookInfo {
+                name,
+                path: path.to_string_lossy().into_owned(),
+                executable: is_executable(&metadata),
+                enabled,
+            });
+        }
+
+        hooks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(hooks)
+    }
+
+    /// Enables or disables the hook `name` by renaming it to or from
+    /// `<name>.disabled`, which works the same on every platform (unlike the
+    /// executable bit). Already being in the requested state is not an error.
+    pub async fn set_hook_enabled(&self, name: &str, enabled: bool) -> Result<(), GitError> {
+        if name.is_empty()
+            || name.contains(['/', '\\'])
+            || name.starts_with('.')
+            || name.ends_with(DISABLED_HOOK_SUFFIX)
+        {
+            return Err(GitError::HookNotFound(name.to_string()));
+        }
+
+        let dir = self.hooks_dir().await?;
+        let active = dir.join(name);
+        let disabled = dir.join(format!("{}{}", name, DISABLED_HOOK_SUFFIX));
+        let (from, to) = if enabled {
+            (disabled, active)
+        } else {
+            (active, disabled)
+        };
+
+        if tokio::fs::try_exists(&from).await.unwrap_or(false) {
+            tokio::fs::rename(&from, &to)
+                .await
+                .map_err(|source| GitError::Io { path: from, source })
+        } else if tokio::fs::try_exists(&to).await.unwrap_or(false) {
+            Ok(())
+        } else {
+            Err(GitError::HookNotFound(name.to_string()))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    true
+}
+
+/// Parses `git log` output in [`COMMIT_LOG_FORMAT`], or in
+/// [`SIGNED_COMMIT_LOG_FORMAT`] when `with_signatures` is set.
+///
+/// Lines with too few fields are silently skipped (e.g., malformed or
+/// empty repos). The summary is last, so pipes inside it are preserved.
+fn parse_commit_log(stdout: &str, with_signatures: bool) -> Vec<CommitInfo> {
+    let fields = if with_signatures { 9 } else { 7 };
+    let mut commits = Vec::new();
+    for line in stdout.lines().filter(|l| !l.is_empty()) {
+        let parts: Vec<&str> = line.splitn(fields, '|').collect();
+        if parts.len() < fields {
+            continue;
+        }
+
+        let timestamp = parts[5].parse::<i64>().unwrap_or(0);
+        let parent_hashes: Vec<String> = if parts[2].is_empty() {
+            Vec::new()
+        } else {
+            parts[2].split(' ').map(|s| s.to_string()).collect()
+        };
+
+        commits.push(CommitInfo {
+            hash: parts[0].to_string(),
+            short_hash: parts[1].to_string(),
+            parent_hashes,
+            author_name: parts[3].to_string(),
+            author_email: parts[4].to_string(),
+            timestamp,
+            summary: parts[fields - 1].to_string(),
+            signature: with_signatures
+                .then(|| SignatureStatus::from_log_fields(parts[6], parts[7])),
+            notes: None,
+        });
+    }
+
+    commits
+}
+
+/// Buckets `commits` by `group_by`, keeping groups in order of first
+/// appearance.
+fn group_commits(commits: Vec<CommitInfo>, group_by: GroupBy) -> Vec<CommitGroup> {
+    let mut groups: Vec<CommitGroup> = Vec::new();
+    for commit in commits {
+        let key = match group_by {
+            GroupBy::Author => commit.author_name.clone(),
+            GroupBy::Day => chrono::DateTime::from_timestamp(commit.timestamp, 0)
+                .map(|t| t.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+        };
+        match groups.iter_mut().find(|g| g.key == key) {
+            Some(group) => group.commits.push(commit),
+            None => groups.push(CommitGroup {
+                key,
+                commits: vec![commit],
+            }),
+        }
+    }
+    groups
+}
+
+/// Extracts pruned refs from `git fetch --prune` stderr, where each one is
+/// reported as ` - [deleted]         (none)     -> origin/feature-x`.
+fn parse_pruned_refs(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("[deleted]"))
+        .filter_map(|line| line.rsplit_once("-> "))
+        .map(|(_, name)| name.trim().to_string())
+        .collect()
+}
+
+/// Decodes a path git printed C-quoted (e.g. `"a\"b"`), as it does for
+/// names with control characters, quotes, or backslashes. Unquoted paths
+/// are returned unchanged.
+fn unquote_git_path(path: &str) -> Result<String, GitError> {
+    let Some(inner) = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) else {
+        return Ok(path.to_string());
+    };
+    let invalid = || GitError::ParseError {
+        message: format!("invalid quoted path: {}", path),
+    };
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut rest = inner.bytes();
+    while let Some(byte) = rest.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let decoded = match rest.next().ok_or_else(invalid)? {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => 0x0b,
+            b'f' => 0x0c,
+            b'r' => b'\r',
+            c @ (b'"' | b'\\') => c,
+            // Three octal digits, e.g. \303 for a byte git chose to escape
+            first @ b'0'..=b'3' => {
+                let mut value = first - b'0';
+                for _ in 0..2 {
+                    match rest.next() {
+                        Some(digit @ b'0'..=b'7') => value = value * 8 + (digit - b'0'),
+                        _ => return Err(invalid()),
+                    }
+                }
+                value
+            }
+            _ => return Err(invalid()),
+        };
+        bytes.push(decoded);
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// Hashes a `clean_preview` result into the token `clean_execute` expects.
+///
+/// The token is the hex SHA-256 of the newline-joined paths, so it changes
+/// whenever the set of files that would be removed changes.
+pub fn clean_confirmation_token(paths: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for path in paths {
+        hasher.update(path.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::runner::Git;
+    use tempfile::tempdir;
+
+    /// Helper: creates a temp git repo with an initial commit.
+    async fn create_test_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_path_buf();
+        let git = Git::new(&path);
+
+        git.run(&["init"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+
+        let file_path = path.join("README.md");
+        tokio::fs::write(&file_path, "# Test").await.unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+
+        (dir, git)
+    }
+
+    #[tokio::test]
+    async fn test_commits_between_returns_only_head_commits() {
+        let (dir, git) = create_test_repo().await;
+        git.run(&["branch", "base"]).await.unwrap();
+        git.run(&["checkout", "-b", "head"]).await.unwrap();
+        for (file, message) in [("a.txt", "add a"), ("b.txt", "add b | with pipe")] {
+            tokio::fs::write(dir.path().join(file), file).await.unwrap();
+            git.run(&["add", "."]).await.unwrap();
+            git.run(&["commit", "-m", message]).await.unwrap();
+        }
+
+        let commits = git.commits_between("base", "head", 50).await.unwrap();
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["add b | with pipe", "add a"]);
+        assert_eq!(commits[0].parent_hashes, vec![commits[1].hash.clone()]);
+
+        let newest = git.commits_between("base", "head", 1).await.unwrap();
+        assert_eq!(newest.len(), 1);
+        let reversed = git.commits_between("head", "base", 50).await.unwrap();
+        assert!(reversed.is_empty());
+        assert!(matches!(
+            git.commits_between("base", "--all", 50).await,
+            Err(GitError::RefNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_commit_log_page_skips_to_older_commits() {
+        let (dir, git) = create_test_repo().await;
+        for n in 1..=4 {
+            let file = format!("{}.txt", n);
+            tokio::fs::write(dir.path().join(&file), &file)
+                .await
+                .unwrap();
+            git.run(&["add", "."]).await.unwrap();
+            git.run(&["commit", "-m", &format!("commit {}", n)])
+                .await
+                .unwrap();
+        }
+        let summaries =
+            |commits: Vec<CommitInfo>| commits.into_iter().map(|c| c.summary).collect::<Vec<_>>();
+
+        let first = git.commit_log(2, false).await.unwrap();
+        assert_eq!(summaries(first), vec!["commit 4", "commit 3"]);
+        let second = git
+            .commit_log_page(2, 2, false, false, false)
+            .await
+            .unwrap();
+        assert_eq!(summaries(second), vec!["commit 2", "commit 1"]);
+        let last = git
+            .commit_log_page(2, 4, false, false, false)
+            .await
+            .unwrap();
+        assert_eq!(summaries(last), vec!["initial"]);
+        let past_end = git
+            .commit_log_page(2, 5, false, false, false)
+            .await
+            .unwrap();
+        assert!(past_end.is_empty());
+    }
+
+    #[test]
+    fn test_parse_commit_log_maps_signature_codes() {
+        let stdout = [
+            "a1|a|p|Ann|ann@x|1|G|Ann Signer <ann@x>|good | piped",
+            "b1|b|p|Bob|bob@x|2|B|Bob <bob@x>|bad",
+            "c1|c|p|Cy|cy@x|3|U|Cy <cy@x>|untrusted",
+            "d1|d|p|Di|di@x|4|E||missing key",
+            "e1|e|p|Ed|ed@x|5|N||unsigned",
+        ]
+        .join("\n");
+
+        let commits = parse_commit_log(&stdout, true);
+        let states: Vec<SignatureState> = commits
+            .iter()
+            .map(|c| c.signature.as_ref().unwrap().state)
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                SignatureState::Good,
+                SignatureState::Bad,
+                SignatureState::Unknown,
+                SignatureState::Unknown,
+                SignatureState::None,
+            ]
+        );
+        let first = commits[0].signature.as_ref().unwrap();
+        assert_eq!(first.signer.as_deref(), Some("Ann Signer <ann@x>"));
+        assert_eq!(commits[0].summary, "good | piped");
+        assert_eq!(commits[4].signature.as_ref().unwrap().signer, None);
+
+        let plain = parse_commit_log("a1|a|p|Ann|ann@x|1|summary", false);
+        assert!(plain[0].signature.is_none());
+        assert!(!serde_json::to_string(&plain[0])
+            .unwrap()
+            .contains("signature"));
+    }
+
+    #[test]
+    fn test_group_commits_by_author_and_day() {
+        // 2024-03-01 10:00, 2024-03-01 09:00, 2024-02-29 23:00, 2024-02-29 22:00 UTC
+        let stdout = [
+            "a1|a1|p|Ann|ann@x|1709287200|ann day two",
+            "b1|b1|p|Bob|bob@x|1709283600|bob day two",
+            "a2|a2|p|Ann|ann@x|1709247600|ann day one",
+            "b2|b2|p|Bob|bob@x|1709244000|bob day one",
+        ]
+        .join("\n");
+        let buckets = |groups: Vec<CommitGroup>| {
+            groups
+                .into_iter()
+                .map(|g| (g.key, g.commits.into_iter().map(|c| c.hash).collect()))
+                .collect::<Vec<(String, Vec<String>)>>()
+        };
+
+        let by_author = group_commits(parse_commit_log(&stdout, false), GroupBy::Author);
+        assert_eq!(
+            buckets(by_author),
+            vec![
+                ("Ann".to_string(), vec!["a1".to_string(), "a2".to_string()]),
+                ("Bob".to_string(), vec!["b1".to_string(), "b2".to_string()]),
+            ]
+        );
+
+        let by_day = group_commits(parse_commit_log(&stdout, false), GroupBy::Day);
+        assert_eq!(
+            buckets(by_day),
+            vec![
+                (
+                    "2024-03-01".to_string(),
+                    vec!["a1".to_string(), "b1".to_string()]
+                ),
+                (
+                    "2024-02-29".to_string(),
+                    vec!["a2".to_string(), "b2".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_commit_log_verify_signatures_reports_unsigned() {
+        let (_dir, git) = create_test_repo().await;
+        let commits = git
+            .commit_log_page(10, 0, false, true, false)
+            .await
+            .unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(
+            commits[0].signature,
+            Some(SignatureStatus {
+                state: SignatureState::None,
+                signer: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_base_of_diverged_branches() {
+        let (dir, git) = create_test_repo().await;
+        let main = git.current_branch().await.unwrap();
+        let fork_point = git.resolve_commit("HEAD").await.unwrap();
+        git.run(&["branch", "feature"]).await.unwrap();
+        for (branch, file) in [(main.as_str(), "main.txt"), ("feature", "feature.txt")] {
+            git.run(&["checkout", "-q", branch]).await.unwrap();
+            tokio::fs::write(dir.path().join(file), file).await.unwrap();
+            git.run(&["add", "."]).await.unwrap();
+            git.run(&["commit", "-m", file]).await.unwrap();
+        }
+
+        let base = git.merge_base(&main, "feature").await.unwrap();
+        assert_eq!(base, Some(fork_point));
+        assert!(matches!(
+            git.merge_base(&main, "no-such-branch").await,
+            Err(GitError::RefNotFound(r)) if r == "no-such-branch"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_merge_base_of_unrelated_histories_is_none() {
+        let (_dir, git) = create_test_repo().await;
+        let main = git.current_branch().await.unwrap();
+        git.run(&["checkout", "-q", "--orphan", "unrelated"])
+            .await
+            .unwrap();
+        git.run(&["commit", "-m", "fresh start"]).await.unwrap();
+
+        assert_eq!(git.merge_base(&main, "unrelated").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_commit_log_include_notes_reads_multiline_note() {
+        let (dir, git) = create_test_repo().await;
+        // No notes ref yet: every commit comes back without notes
+        let commits = git
+            .commit_log_page(10, 0, false, false, true)
+            .await
+            .unwrap();
+        assert_eq!(commits[0].notes, None);
+
+        tokio::fs::write(dir.path().join("second.txt"), "second")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "second"]).await.unwrap();
+        git.run(&[
+            "notes",
+            "add",
+            "-m",
+            "Reviewed-by: Ann | ok",
+            "-m",
+            "LGTM",
+            "HEAD~1",
+        ])
+        .await
+        .unwrap();
+
+        let commits = git
+            .commit_log_page(10, 0, false, false, true)
+            .await
+            .unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].notes, None);
+        assert_eq!(
+            commits[1].notes.as_deref(),
+            Some("Reviewed-by: Ann | ok\n\nLGTM")
+        );
+
+        let without = git.commit_log(10, false).await.unwrap();
+        assert!(without.iter().all(|c| c.notes.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_worktree_list_main_repo_only() {
+        let (_dir, git) = create_test_repo().await;
+        let worktrees = git.worktree_list().await.unwrap();
+
+        assert_eq!(worktrees.len(), 1);
+        assert!(worktrees[0].is_main_worktree);
+    }
+
+    #[tokio::test]
+    async fn test_worktree_list_with_added_worktree() {
+        let (dir, git) = create_test_repo().await;
+        git.run(&["branch", "test-branch"]).await.unwrap();
+
+        let wt_path = dir.path().join("wt-test");
+        git.worktree_add(&wt_path, None, Some("test-branch"), false)
+            .await
+            .unwrap();
+
+        let worktrees = git.worktree_list().await.unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main_worktree);
+        assert!(!worktrees[1].is_main_worktree);
+
+        // Cleanup
+        git.worktree_remove(&wt_path, true).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_managed_worktrees_skip_main_and_detached() {
+        let (dir, git) = create_test_repo().await;
+        git.run(&["branch", "managed"]).await.unwrap();
+        let managed_path = dir.path().join("wt-managed");
+        git.worktree_add(&managed_path, None, Some("managed"), false)
+            .await
+            .unwrap();
+        let detached_path = dir.path().join("wt-detached");
+        git.run(&[
+            "worktree",
+            "add",
+            "--detach",
+            detached_path.to_str().unwrap(),
+        ])
+        .await
+        .unwrap();
+
+        let all = git.worktree_list().await.unwrap();
+        assert_eq!(all.len(), 3);
+        let main: Vec<bool> = all.iter().map(|wt| wt.is_main_worktree).collect();
+        assert_eq!(main, vec![true, false, false]);
+
+        let managed = git.managed_worktrees().await.unwrap();
+        assert_eq!(managed.len(), 1);
+        assert_eq!(managed[0].branch.as_deref(), Some("managed"));
+        assert!(!managed[0].is_main_worktree);
+    }
+
+    #[tokio::test]
+    async fn test_worktree_list_main_has_correct_branch() {
+        let (_dir, git) = create_test_repo().await;
         let current = git.current_branch().await.unwrap();
         let worktrees = git.worktree_list().await.unwrap();
 
@@ -705,10 +2075,10 @@ mod tests {
 
         let wt_a = dir.path().join("wt-a");
         let wt_b = dir.path().join("wt-b");
-        git.worktree_add(&wt_a, None, Some("branch-a"))
+        git.worktree_add(&wt_a, None, Some("branch-a"), false)
             .await
             .unwrap();
-        git.worktree_add(&wt_b, None, Some("branch-b"))
+        git.worktree_add(&wt_b, None, Some("branch-b"), false)
             .await
             .unwrap();
 
@@ -732,7 +2102,7 @@ mod tests {
 
         let wt_path = dir.path().join("wt-existing");
         let info = git
-            .worktree_add(&wt_path, None, Some("wt-existing"))
+            .worktree_add(&wt_path, None, Some("wt-existing"), false)
             .await
             .unwrap();
 
@@ -750,7 +2120,7 @@ mod tests {
 
         let wt_path = dir.path().join("wt-new");
         let info = git
-            .worktree_add(&wt_path, Some("new-wt-branch"), None)
+            .worktree_add(&wt_path, Some("new-wt-branch"), None, false)
             .await
             .unwrap();
 
@@ -776,6 +2146,116 @@ mod tests {
         assert!(local_names.contains(&"local-test"));
     }
 
+    #[tokio::test]
+    async fn test_list_branches_with_details_flags_worktree_and_upstream() {
+        let (dir, git) = create_test_repo().await;
+        let current = git.current_branch().await.unwrap();
+        git.run(&["branch", "in-worktree"]).await.unwrap();
+        git.run(&["branch", "--track", "tracking", &current])
+            .await
+            .unwrap();
+
+        let wt_path = dir.path().join("wt-details");
+        git.worktree_add(&wt_path, None, Some("in-worktree"), false)
+            .await
+            .unwrap();
+
+        let branches = git.list_branches_with_details().await.unwrap();
+        let find = |name: &str| branches.iter().find(|b| b.name == name).unwrap();
+
+        assert!(find("in-worktree").has_worktree);
+        assert!(!find("tracking").has_worktree);
+        // The main worktree's branch is `is_current`, not `has_worktree`
+        assert!(!find(&current).has_worktree);
+        assert_eq!(find("tracking").upstream.as_deref(), Some(current.as_str()));
+        assert_eq!(find("in-worktree").upstream, None);
+
+        // The cheap path leaves details empty
+        let plain = git.list_branches().await.unwrap();
+        assert!(plain
+            .iter()
+            .all(|b| !b.has_worktree && b.upstream.is_none()));
+
+        // Cleanup
+        git.worktree_remove(&wt_path, true).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_ref_name() {
+        let (_dir, git) = create_test_repo().await;
+
+        for valid in ["feature/login", "fix-123", "release_v2.0"] {
+            assert!(
+                git.check_ref_name(valid).await.unwrap(),
+                "{} should be valid",
+                valid
+            );
+        }
+        for invalid in ["foo bar", "-lead", "a..b", "ends.lock", "trailing/", ""] {
+            assert!(
+                !git.check_ref_name(invalid).await.unwrap(),
+                "{:?} should be invalid",
+                invalid
+            );
+        }
+
+        // Checking never creates the branch
+        let branches = git.list_branches().await.unwrap();
+        assert!(!branches.iter().any(|b| b.name == "feature/login"));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_disable_hooks() {
+        let (dir, git) = create_test_repo().await;
+        let hook = dir.path().join(".git").join("hooks").join("pre-commit");
+        tokio::fs::create_dir_all(hook.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(&hook, "#!/bin/sh\nexit 0\n")
+            .await
+            .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let hooks = git.list_hooks().await.unwrap();
+        assert_eq!(hooks.len(), 1, "samples are not listed: {:?}", hooks);
+        assert_eq!(hooks[0].name, "pre-commit");
+        assert!(hooks[0].enabled);
+        assert!(hooks[0].executable);
+
+        git.set_hook_enabled("pre-commit", false).await.unwrap();
+        assert!(!hook.exists());
+        let hooks = git.list_hooks().await.unwrap();
+        assert_eq!(hooks[0].name, "pre-commit");
+        assert!(!hooks[0].enabled);
+
+        // Linked worktrees share the main repo's hooks
+        git.run(&["branch", "hooks-wt"]).await.unwrap();
+        let wt_path = dir.path().join("wt-hooks");
+        git.worktree_add(&wt_path, None, Some("hooks-wt"), false)
+            .await
+            .unwrap();
+        let wt_git = Git::new(&wt_path);
+        wt_git.set_hook_enabled("pre-commit", true).await.unwrap();
+        assert!(hook.exists());
+        assert!(wt_git.list_hooks().await.unwrap()[0].enabled);
+
+        assert!(matches!(
+            git.set_hook_enabled("post-merge", false).await,
+            Err(GitError::HookNotFound(_))
+        ));
+        assert!(matches!(
+            git.set_hook_enabled("../config", false).await,
+            Err(GitError::HookNotFound(_))
+        ));
+
+        // Cleanup
+        git.worktree_remove(&wt_path, true).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_create_branch_from_head() {
         let (_dir, git) = create_test_repo().await;
@@ -809,4 +2289,384 @@ mod tests {
             current
         );
     }
+
+    #[tokio::test]
+    async fn test_head_state_on_branch_and_detached() {
+        let (_dir, git) = create_test_repo().await;
+        let branch = git.current_branch().await.unwrap();
+        assert_eq!(git.head_state().await.unwrap(), HeadState::Branch(branch));
+
+        git.detach_head().await.unwrap();
+        let sha = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        assert_eq!(
+            git.head_state().await.unwrap(),
+            HeadState::Detached(sha.trimmed().to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_current_head_reports_detached_sha_without_upstream() {
+        let (_dir, git) = create_test_repo().await;
+        let branch = git.current_branch().await.unwrap();
+        git.run(&["branch", "tracked"]).await.unwrap();
+        git.run(&["branch", "--set-upstream-to", "tracked"])
+            .await
+            .unwrap();
+        let on_branch = git.current_head().await.unwrap();
+        assert_eq!(on_branch.kind, HeadKind::Branch);
+        assert_eq!(on_branch.name_or_sha, branch);
+        assert_eq!(on_branch.upstream.as_deref(), Some("tracked"));
+
+        git.detach_head().await.unwrap();
+        let sha = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        assert_eq!(
+            git.current_head().await.unwrap(),
+            HeadInfo {
+                kind: HeadKind::Detached,
+                name_or_sha: sha.trimmed().to_string(),
+                upstream: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_prune_reports_deleted_upstream_branch() {
+        let (upstream_dir, upstream) = create_test_repo().await;
+        upstream.run(&["branch", "feature-x"]).await.unwrap();
+
+        let clone_dir = tempdir().unwrap();
+        let clone_path = clone_dir.path().join("clone");
+        let upstream_path = upstream_dir.path().to_string_lossy().into_owned();
+        Git::new(clone_dir.path())
+            .run(&["clone", &upstream_path, "clone"])
+            .await
+            .unwrap();
+        let clone = Git::new(&clone_path);
+        clone
+            .run(&["branch", "--track", "feature-x", "origin/feature-x"])
+            .await
+            .unwrap();
+        let stale = clone.list_stale_tracking_branches().await.unwrap();
+        assert!(stale.is_empty());
+
+        upstream.run(&["branch", "-D", "feature-x"]).await.unwrap();
+        let pruned = clone.fetch_prune("origin").await.unwrap();
+
+        assert_eq!(pruned, vec!["origin/feature-x"]);
+        let stale = clone.list_stale_tracking_branches().await.unwrap();
+        assert_eq!(stale, vec!["feature-x"]);
+        // Nothing left to prune on a second fetch
+        assert!(clone.fetch_prune("origin").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_merged_branches_skips_unmerged_and_checked_out() {
+        let (dir, git) = create_test_repo().await;
+        let main = git.current_branch().await.unwrap();
+        for branch in ["merged", "in-worktree", "unmerged"] {
+            git.run(&["branch", branch]).await.unwrap();
+        }
+        git.run(&["checkout", "unmerged"]).await.unwrap();
+        tokio::fs::write(dir.path().join("wip.txt"), "wip")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "wip"]).await.unwrap();
+        git.run(&["checkout", &main]).await.unwrap();
+
+        let wt_dir = tempdir().unwrap();
+        let wt_path = wt_dir.path().join("wt");
+        git.worktree_add(&wt_path, None, Some("in-worktree"), false)
+            .await
+            .unwrap();
+
+        let mut merged = git.merged_branches(&main).await.unwrap();
+        merged.sort();
+        assert_eq!(merged, vec!["in-worktree", "merged"]);
+
+        let planned = git.delete_merged_branches(&main, true).await.unwrap();
+        assert_eq!(planned, vec!["merged"]);
+        let names = |branches: Vec<BranchInfo>| -> Vec<String> {
+            branches.into_iter().map(|b| b.name).collect()
+        };
+        assert!(names(git.list_branches().await.unwrap()).contains(&"merged".to_string()));
+
+        let deleted = git.delete_merged_branches(&main, false).await.unwrap();
+        assert_eq!(deleted, vec!["merged"]);
+        let remaining = names(git.list_branches().await.unwrap());
+        assert!(!remaining.contains(&"merged".to_string()));
+        assert!(remaining.contains(&"in-worktree".to_string()));
+        assert!(remaining.contains(&"unmerged".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_list_branches_detailed_sorted_by_recency() {
+        let (dir, git) = create_test_repo().await;
+        let commit_at = |branch: &str, subject: &str, date: &str| {
+            git_at(dir.path(), &["checkout", "-q", "-B", branch], None);
+            git_at(
+                dir.path(),
+                &["commit", "-q", "--allow-empty", "-m", subject],
+                Some(date),
+            );
+        };
+        let base = git.current_branch().await.unwrap();
+        commit_at("older", "Older work", "2020-01-01T00:00:00Z");
+        commit_at("newest", "Newest work", "2030-01-01T00:00:00Z");
+        git_at(dir.path(), &["checkout", "-q", &base], None);
+
+        let branches = git.list_branches_detailed().await.unwrap();
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+
+        assert_eq!(names, vec!["newest", base.as_str(), "older"]);
+        assert_eq!(branches[0].last_commit_subject, "Newest work");
+        assert_eq!(branches[0].last_commit_date, 1_893_456_000);
+        assert_eq!(branches[2].last_commit_subject, "Older work");
+        assert!(branches[1].is_current);
+        assert!(branches.iter().all(|b| !b.is_remote));
+    }
+
+    /// Runs git synchronously, optionally pinning the committer date.
+    fn git_at(dir: &std::path::Path, args: &[&str], committer_date: Option<&str>) {
+        let mut cmd = std::process::Command::new("git");
+        cmd.current_dir(dir).args(args);
+        if let Some(date) = committer_date {
+            cmd.env("GIT_COMMITTER_DATE", date);
+        }
+        assert!(cmd.status().unwrap().success(), "git {:?} failed", args);
+    }
+
+    /// Helper: a repo mid-merge where `README.md` was changed on both sides.
+    /// Returns the checked-out branch name alongside the repo.
+    async fn create_conflicted_repo() -> (tempfile::TempDir, Git, String) {
+        let (dir, git) = create_test_repo().await;
+        let base = git.current_branch().await.unwrap();
+        let readme = dir.path().join("README.md");
+        git.run(&["checkout", "-b", "other"]).await.unwrap();
+        tokio::fs::write(&readme, "# Theirs\n").await.unwrap();
+        git.run(&["commit", "-am", "theirs"]).await.unwrap();
+        git.run(&["checkout", &base]).await.unwrap();
+        tokio::fs::write(&readme, "# Ours\n").await.unwrap();
+        git.run(&["commit", "-am", "ours"]).await.unwrap();
+        assert!(git.run(&["merge", "other"]).await.is_err());
+        (dir, git, base)
+    }
+
+    #[tokio::test]
+    async fn test_conflicted_files_reports_both_modified() {
+        let (_dir, git) = create_test_repo().await;
+        assert!(git.conflicted_files().await.unwrap().is_empty());
+
+        let (_dir, git, base) = create_conflicted_repo().await;
+        let conflicts = git.conflicted_files().await.unwrap();
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, "README.md");
+        assert_eq!(conflicts[0].state, ConflictState::BothModified);
+        assert_eq!(conflicts[0].marker_count, Some(1));
+        assert_eq!(conflicts[0].ours.as_deref(), Some(base.as_str()));
+        let other = git.run(&["rev-parse", "--short", "other"]).await.unwrap();
+        assert_eq!(conflicts[0].theirs.as_deref(), Some(other.trimmed()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_conflict_strategies() {
+        for (strategy, expected) in [
+            (ConflictResolution::Ours, "# Ours\n"),
+            (ConflictResolution::Theirs, "# Theirs\n"),
+            (ConflictResolution::Union, "# Ours\n# Theirs\n"),
+        ] {
+            let (dir, git, _) = create_conflicted_repo().await;
+
+            git.resolve_conflict("README.md", strategy).await.unwrap();
+
+            let content = tokio::fs::read_to_string(dir.path().join("README.md"))
+                .await
+                .unwrap();
+            assert_eq!(content, expected, "{:?}", strategy);
+            assert!(git.conflicted_files().await.unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_union_merge_keeps_non_utf8_bytes() {
+        let (dir, git) = create_test_repo().await;
+        let base = git.current_branch().await.unwrap();
+        let data = dir.path().join("data.bin");
+        tokio::fs::write(&data, b"head\n").await.unwrap();
+        git.run(&["add", "data.bin"]).await.unwrap();
+        git.run(&["commit", "-m", "base"]).await.unwrap();
+        git.run(&["checkout", "-b", "other"]).await.unwrap();
+        tokio::fs::write(&data, b"head\ntheirs \xff\n")
+            .await
+            .unwrap();
+        git.run(&["commit", "-am", "theirs"]).await.unwrap();
+        git.run(&["checkout", &base]).await.unwrap();
+        tokio::fs::write(&data, b"head\nours \xfe\n").await.unwrap();
+        git.run(&["commit", "-am", "ours"]).await.unwrap();
+        assert!(git.run(&["merge", "other"]).await.is_err());
+
+        git.resolve_conflict("data.bin", ConflictResolution::Union)
+            .await
+            .unwrap();
+
+        let merged = tokio::fs::read(&data).await.unwrap();
+        assert_eq!(merged, b"head\nours \xfe\ntheirs \xff\n");
+        // No stage copies are left behind in the worktree
+        let status = git
+            .run(&["status", "--porcelain", "--untracked-files=all"])
+            .await
+            .unwrap();
+        assert_eq!(status.trimmed(), "M  data.bin");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_conflict_refuses_clean_path() {
+        let (dir, git, _) = create_conflicted_repo().await;
+        tokio::fs::write(dir.path().join("notes.txt"), "x")
+            .await
+            .unwrap();
+
+        let err = git
+            .resolve_conflict("notes.txt", ConflictResolution::Ours)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitError::NotConflicted(ref p) if p == "notes.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_remote_default_branch_follows_remote_head() {
+        let (upstream_dir, upstream) = create_test_repo().await;
+        upstream.run(&["checkout", "-b", "trunk"]).await.unwrap();
+        assert_eq!(
+            upstream.remote_default_branch("origin").await.unwrap(),
+            None
+        );
+
+        let clone_dir = tempdir().unwrap();
+        let upstream_path = upstream_dir.path().to_string_lossy().into_owned();
+        Git::new(clone_dir.path())
+            .run(&["clone", &upstream_path, "clone"])
+            .await
+            .unwrap();
+        let clone = Git::new(clone_dir.path().join("clone"));
+
+        assert_eq!(
+            clone
+                .remote_default_branch("origin")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("trunk")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_set_upstream_configures_tracking() {
+        let (dir, git) = create_test_repo().await;
+        let remote_path = dir.path().join("remote.git");
+        let remote_str = remote_path.to_string_lossy().into_owned();
+        git.run(&["init", "--bare", &remote_str]).await.unwrap();
+        git.run(&["remote", "add", "origin", &remote_str])
+            .await
+            .unwrap();
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+
+        assert_eq!(git.upstream_branch("feature").await.unwrap(), None);
+
+        git.push("origin", "feature", true).await.unwrap();
+
+        assert_eq!(
+            git.upstream_branch("feature").await.unwrap().as_deref(),
+            Some("origin/feature")
+        );
+        let remote_heads = Git::new(&remote_path)
+            .run(&["branch", "--list", "feature"])
+            .await
+            .unwrap();
+        assert!(remote_heads.trimmed().contains("feature"));
+    }
+
+    #[tokio::test]
+    async fn test_clean_preview_lists_untracked_file() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("artifact.o"), "bin")
+            .await
+            .unwrap();
+
+        let paths = git.clean_preview(false, false).await.unwrap();
+        assert_eq!(paths, vec!["artifact.o".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_clean_execute_removes_only_previewed_file() {
+        let (dir, git) = create_test_repo().await;
+        let artifact = dir.path().join("artifact.o");
+        tokio::fs::write(&artifact, "bin").await.unwrap();
+
+        let paths = git.clean_preview(false, false).await.unwrap();
+        let token = clean_confirmation_token(&paths);
+        let removed = git.clean_execute(false, false, &token).await.unwrap();
+
+        assert_eq!(removed, vec!["artifact.o".to_string()]);
+        assert!(!artifact.exists());
+        assert!(dir.path().join("README.md").exists());
+        assert!(git.clean_preview(false, false).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_clean_execute_treats_previewed_paths_literally() {
+        let (dir, git) = create_test_repo().await;
+        let glob = dir.path().join("*.o");
+        let quoted = dir.path().join("say \"hi\".o");
+        tokio::fs::write(&glob, "bin").await.unwrap();
+        tokio::fs::write(&quoted, "bin").await.unwrap();
+        // Not previewed without -d, but a `*.o` pathspec would reach it
+        let nested = dir.path().join("nested");
+        tokio::fs::create_dir(&nested).await.unwrap();
+        tokio::fs::write(nested.join("keep.o"), "bin")
+            .await
+            .unwrap();
+
+        let paths = git.clean_preview(false, false).await.unwrap();
+        assert_eq!(paths, vec!["*.o".to_string(), "say \"hi\".o".to_string()]);
+
+        let token = clean_confirmation_token(&paths);
+        git.clean_execute(false, false, &token).await.unwrap();
+
+        assert!(!glob.exists());
+        assert!(!quoted.exists());
+        assert!(nested.join("keep.o").exists());
+    }
+
+    #[test]
+    fn test_unquote_git_path() {
+        assert_eq!(unquote_git_path("plain.txt").unwrap(), "plain.txt");
+        assert_eq!(unquote_git_path(r#""a\"b\\c""#).unwrap(), "a\"b\\c");
+        assert_eq!(
+            unquote_git_path(r#""line\nbreak\t""#).unwrap(),
+            "line\nbreak\t"
+        );
+        assert_eq!(unquote_git_path(r#""caf\303\251""#).unwrap(), "café");
+        assert!(unquote_git_path(r#""bad\q""#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_clean_execute_rejects_stale_token() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("first.o"), "bin")
+            .await
+            .unwrap();
+        let token = clean_confirmation_token(&git.clean_preview(false, false).await.unwrap());
+
+        // A new file appears after the preview was shown
+        tokio::fs::write(dir.path().join("second.o"), "bin")
+            .await
+            .unwrap();
+
+        let result = git.clean_execute(false, false, &token).await;
+        assert!(matches!(result, Err(GitError::CleanNotConfirmed)));
+        assert!(dir.path().join("first.o").exists());
+        assert!(dir.path().join("second.o").exists());
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
@@ -27,6 +28,30 @@ impl GitOutput {
     }
 }
 
+/// Stderr fragments git prints when it needed credentials it could not get.
+const AUTH_FAILURE_MARKERS: [&str; 5] = [
+    "could not read Username",
+    "could not read Password",
+    "terminal prompts disabled",
+    "Authentication failed",
+    "Permission denied (publickey",
+];
+
+/// Turns a failed network command into `AuthenticationRequired` when its
+/// stderr shows git wanted credentials. Other errors pass through.
+pub(crate) fn map_auth_failure(err: GitError, remote: &str) -> GitError {
+    match err {
+        GitError::CommandFailed { ref stderr, .. }
+            if AUTH_FAILURE_MARKERS.iter().any(|m| stderr.contains(m)) =>
+        {
+            GitError::AuthenticationRequired {
+                remote: remote.to_string(),
+            }
+        }
+        other => other,
+    }
+}
+
 /// Low-level git command runner bound to a specific repository path.
 ///
 /// All commands are invoked via `tokio::process::Command` with `git -C <repo>`,
@@ -36,6 +61,8 @@ impl GitOutput {
 #[derive(Debug, Clone)]
 pub struct Git {
     repo_path: PathBuf,
+    /// Extra environment applied to every git subprocess.
+    env: HashMap<String, String>,
 }
 
 impl Git {
@@ -43,9 +70,25 @@ impl Git {
     pub fn new(repo_path: impl Into<PathBuf>) -> Self {
         Self {
             repo_path: repo_path.into(),
+            env: HashMap::new(),
         }
     }
 
+    /// The repository (or worktree) directory commands run in.
+    pub fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
+    /// Adds environment variables (e.g. `GIT_SSH_COMMAND`, `HTTPS_PROXY`)
+    /// for every git command this runner spawns.
+    ///
+    /// These may override the `GIT_TERMINAL_PROMPT=0` default, but not
+    /// `LC_ALL=C`, which output parsing depends on.
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env.extend(env);
+        self
+    }
+
     /// Executes a git subcommand and returns its captured output.
     ///
     /// Returns `GitNotFound` if the git binary is missing, `SpawnError` for
@@ -57,6 +100,7 @@ impl Git {
             .arg(&self.repo_path)
             .args(args)
             .env("GIT_TERMINAL_PROMPT", "0")
+            .envs(&self.env)
             .env("LC_ALL", "C")
             .kill_on_drop(true)
             .hide_console_window();
@@ -95,10 +139,21 @@ impl Git {
         }
     }
 
+    /// Runs a command that talks to `remote` (fetch, push, ls-remote, ...).
+    ///
+    /// Same as [`run`](Self::run), except a failure caused by missing
+    /// credentials is reported as `AuthenticationRequired`.
+    pub async fn run_remote(&self, remote: &str, args: &[&str]) -> Result<GitOutput, GitError> {
+        self.run(args)
+            .await
+            .map_err(|e| map_auth_failure(e, remote))
+    }
+
     /// Convenience wrapper that runs a git command in a different directory
-    /// by constructing a temporary `Git` instance for that path.
+    /// by constructing a temporary `Git` instance for that path. The extra
+    /// environment carries over.
     pub async fn run_in(&self, path: &Path, args: &[&str]) -> Result<GitOutput, GitError> {
-        Git::new(path).run(args).await
+        Git::new(path).with_env(self.env.clone()).run(args).await
     }
 }
 
@@ -156,6 +211,28 @@ mod tests {
         assert!(result.is_ok(), "git status should succeed in repo");
     }
 
+    #[tokio::test]
+    async fn test_with_env_reaches_git_subprocess() {
+        let git = Git::new(".").with_env(HashMap::from([
+            ("GIT_SSH_COMMAND".to_string(), "ssh -i /tmp/key".to_string()),
+            ("LC_ALL".to_string(), "de_DE.UTF-8".to_string()),
+        ]));
+        // A shell alias runs as a git subprocess with git's environment
+        let echo = |var: &str| format!("alias.echo-env=!printenv {}", var);
+
+        let ssh = git.run(&["-c", &echo("GIT_SSH_COMMAND"), "echo-env"]).await;
+        assert_eq!(ssh.unwrap().trimmed(), "ssh -i /tmp/key");
+
+        let prompt = git
+            .run(&["-c", &echo("GIT_TERMINAL_PROMPT"), "echo-env"])
+            .await;
+        assert_eq!(prompt.unwrap().trimmed(), "0");
+
+        // LC_ALL stays pinned for parseable output
+        let locale = git.run(&["-c", &echo("LC_ALL"), "echo-env"]).await;
+        assert_eq!(locale.unwrap().trimmed(), "C");
+    }
+
     #[tokio::test]
     async fn test_git_not_a_repo_error() {
         use tempfile::tempdir;
@@ -176,6 +253,38 @@ mod tests {
 
     // Error handling tests
 
+    #[test]
+    fn test_auth_failure_stderr_maps_to_authentication_required() {
+        let failed = |stderr: &str| GitError::CommandFailed {
+            code: 128,
+            stderr: stderr.to_string(),
+            command: "git fetch origin".to_string(),
+        };
+
+        let err = map_auth_failure(
+            failed("fatal: could not read Username for 'https://github.com': terminal prompts disabled"),
+            "origin",
+        );
+        assert!(
+            matches!(err, GitError::AuthenticationRequired { ref remote } if remote == "origin")
+        );
+
+        let err = map_auth_failure(
+            failed("git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository."),
+            "upstream",
+        );
+        assert!(
+            matches!(err, GitError::AuthenticationRequired { ref remote } if remote == "upstream")
+        );
+
+        // Unrelated failures keep their original error
+        let err = map_auth_failure(
+            failed("fatal: 'nope' does not appear to be a git repository"),
+            "nope",
+        );
+        assert!(matches!(err, GitError::CommandFailed { .. }));
+    }
+
     #[test]
     fn test_git_not_found_error_message() {
         let err = GitError::GitNotFound;
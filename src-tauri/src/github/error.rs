@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 /// All possible errors from GitHub CLI operations, serialized as a string to the
 /// Tauri frontend via the custom `Serialize` impl below.
 #[derive(Debug, thiserror::Error)]
@@ -22,6 +24,10 @@ pub enum GitHubError {
     #[error("gh command was killed by signal")]
     Killed { command: String },
 
+    /// A gh command ran past its deadline and was killed.
+    #[error("gh command timed out after {timeout:?}: {command}")]
+    Timeout { command: String, timeout: Duration },
+
     /// The gh process could not be spawned (e.g., permission denied).
     #[error("failed to spawn gh process: {source}")]
     SpawnError {
@@ -49,9 +55,14 @@ pub enum GitHubError {
     #[error("Discussions are not enabled for this repository")]
     DiscussionsNotEnabled,
 
-    /// Rate limit exceeded.
-    #[error("GitHub API rate limit exceeded. Try again later.")]
-    RateLimitExceeded,
+    /// Rate limit exceeded. `reset_at` is the Unix time the limit resets, when
+    /// gh's output included the `X-RateLimit-Reset` header.
+    #[error("{}", rate_limit_message(*reset_at))]
+    RateLimited { reset_at: Option<i64> },
+
+    /// gh could not reach GitHub (DNS failure, refused connection, timeout).
+    #[error("Could not connect to GitHub: {message}")]
+    NetworkError { message: String },
 
     /// Pull request not found.
     #[error("Pull request #{number} not found")]
@@ -60,6 +71,25 @@ pub enum GitHubError {
     /// Issue not found.
     #[error("Issue #{number} not found")]
     IssueNotFound { number: u64 },
+
+    /// Discussion not found.
+    #[error("Discussion #{number} not found")]
+    DiscussionNotFound { number: u64 },
+
+    /// A `gh api` request was refused before running (unsupported method or
+    /// malformed endpoint).
+    #[error("Invalid API request: {message}")]
+    InvalidApiRequest { message: String },
+}
+
+fn rate_limit_message(reset_at: Option<i64>) -> String {
+    match reset_at.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)) {
+        Some(reset) => format!(
+            "GitHub API rate limit exceeded. Resets at {}.",
+            reset.with_timezone(&chrono::Local).format("%H:%M")
+        ),
+        None => "GitHub API rate limit exceeded. Try again later.".to_string(),
+    }
 }
 
 /// Serializes the error as its `Display` string so the frontend receives a
@@ -0,0 +1,174 @@
+//! Renders pull request and issue threads as standalone markdown documents,
+//! for offline review or pasting into notes.
+
+use std::fmt::Write;
+
+use super::ops::{Comment, CommentReactions, IssueDetail, PrLabel, PullRequestDetail};
+
+/// Renders a pull request with its description and conversation comments.
+pub fn pr_to_markdown(pr: &PullRequestDetail) -> String {
+    let mut out = format!("# {} (#{})\n\n", pr.title, pr.number);
+    let state = if pr.is_draft {
+        "draft".to_string()
+    } else {
+        pr.state.to_lowercase()
+    };
+    let _ = writeln!(
+        out,
+        "**{}** · opened by @{} on {} · {}\n",
+        state,
+        pr.author.login,
+        format_date(&pr.created_at),
+        pr.url
+    );
+    let _ = writeln!(
+        out,
+        "`{}` → `{}` · +{} −{} in {} files\n",
+        pr.head_ref_name, pr.base_ref_name, pr.additions, pr.deletions, pr.changed_files
+    );
+    push_labels(&mut out, &pr.labels);
+    push_body(&mut out, &pr.body);
+    push_comments(&mut out, &pr.comments);
+    out
+}
+
+/// Renders an issue with its description and comments.
+pub fn issue_to_markdown(issue: &IssueDetail) -> String {
+    let mut out = format!("# {} (#{})\n\n", issue.title, issue.number);
+    let _ = writeln!(
+        out,
+        "**{}** · opened by @{} on {} · {}\n",
+        issue.state.to_lowercase(),
+        issue.author.login,
+        format_date(&issue.created_at),
+        issue.url
+    );
+    push_labels(&mut out, &issue.labels);
+    push_body(&mut out, &issue.body);
+    push_comments(&mut out, &issue.comments);
+    out
+}
+
+fn push_labels(out: &mut String, labels: &[PrLabel]) {
+    if labels.is_empty() {
+        return;
+    }
+    let names: Vec<&str> = labels.iter().map(|l| l.name.as_str()).collect();
+    let _ = writeln!(out, "Labels: {}\n", names.join(", "));
+}
+
+fn push_body(out: &mut String, body: &str) {
+    let body = body.trim();
+    if body.is_empty() {
+        out.push_str("_No description provided._\n");
+    } else {
+        let _ = writeln!(out, "{body}");
+    }
+}
+
+fn push_comments(out: &mut String, comments: &[Comment]) {
+    if comments.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "\n## Comments ({})", comments.len());
+    for comment in comments {
+        let _ = writeln!(
+            out,
+            "\n### @{} · {}\n\n{}",
+            comment.author.login,
+            format_date(&comment.created_at),
+            comment.body.trim()
+        );
+        if let Some(reactions) = reaction_summary(&comment.reactions) {
+            let _ = writeln!(out, "\nReactions: {reactions}");
+        }
+    }
+}
+
+/// Formats non-zero reaction counts as e.g. `👍 2 · ❤️ 1`.
+fn reaction_summary(reactions: &CommentReactions) -> Option<String> {
+    let counts = [
+        ("👍", reactions.thumbs_up),
+        ("👎", reactions.thumbs_down),
+        ("😄", reactions.laugh),
+        ("🎉", reactions.hooray),
+        ("😕", reactions.confused),
+        ("❤️", reactions.heart),
+        ("🚀", reactions.rocket),
+        ("👀", reactions.eyes),
+    ];
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(_, n)| *n > 0)
+        .map(|(emoji, n)| format!("{emoji} {n}"))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// Shortens an RFC 3339 timestamp to `YYYY-MM-DD HH:MM UTC`, leaving
+/// anything unparseable as is.
+fn format_date(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| {
+            t.with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M UTC")
+                .to_string()
+        })
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::PrAuthor;
+
+    fn comment(login: &str, body: &str, created_at: &str) -> Comment {
+        Comment {
+            id: format!("c-{login}"),
+            author: PrAuthor {
+                login: login.to_string(),
+            },
+            body: body.to_string(),
+            created_at: created_at.to_string(),
+            updated_at: None,
+            reactions: CommentReactions::default(),
+            is_answer: false,
+        }
+    }
+
+    #[test]
+    fn test_issue_to_markdown_renders_comments_with_authors() {
+        let mut first = comment("bob", "Can reproduce on macOS.\n", "2024-03-02T09:15:00Z");
+        first.reactions.thumbs_up = 2;
+        first.reactions.heart = 1;
+        let issue = IssueDetail {
+            number: 42,
+            title: "Terminal flickers on resize".to_string(),
+            body: "Steps:\n1. Resize the window".to_string(),
+            state: "OPEN".to_string(),
+            author: PrAuthor {
+                login: "alice".to_string(),
+            },
+            created_at: "2024-03-01T12:00:00Z".to_string(),
+            updated_at: "2024-03-02T10:00:00Z".to_string(),
+            url: "https://github.com/o/r/issues/42".to_string(),
+            labels: vec![PrLabel {
+                name: "bug".to_string(),
+                color: "d73a4a".to_string(),
+            }],
+            closed_at: None,
+            comments: vec![first, comment("carol", "Fixed in #43.", "not-a-date")],
+        };
+
+        let md = issue_to_markdown(&issue);
+        assert!(md.starts_with("# Terminal flickers on resize (#42)\n"));
+        assert!(md.contains("**open** · opened by @alice on 2024-03-01 12:00 UTC"));
+        assert!(md.contains("Labels: bug\n"));
+        assert!(md.contains("Steps:\n1. Resize the window\n"));
+        assert!(md.contains("## Comments (2)\n"));
+        assert!(md.contains("### @bob · 2024-03-02 09:15 UTC\n\nCan reproduce on macOS.\n"));
+        assert!(md.contains("Reactions: 👍 2 · ❤️ 1\n"));
+        assert!(md.contains("### @carol · not-a-date\n\nFixed in #43.\n"));
+        assert_eq!(md.matches("Reactions:").count(), 1);
+    }
+}
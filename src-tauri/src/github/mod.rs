@@ -1,11 +1,15 @@
 pub mod error;
+pub mod markdown;
 pub mod ops;
 pub mod runner;
 
 pub use error::GitHubError;
+pub use markdown::{issue_to_markdown, pr_to_markdown};
 pub use ops::{
-    AuthStatus, Comment, CommentReactions, CreatePullRequestOptions, DiscussionCategory,
-    DiscussionDetail, DiscussionInfo, IssueDetail, IssueFilter, IssueInfo, MergeMethod, PrAuthor,
-    PrLabel, PullRequestDetail, PullRequestFilter, PullRequestInfo,
+    group_review_threads, parse_remote_host, AuthStatus, Comment, CommentReactions,
+    CreatePullRequestOptions, DiscussionCategory, DiscussionDetail, DiscussionInfo, ForkParent,
+    IssueDetail, IssueFilter, IssueInfo, MergeMethod, PrAuthor, PrLabel, PullRequestDetail,
+    PullRequestFilter, PullRequestInfo, RateLimitInfo, RateLimitResource, RepoInfo, RepoMetadata,
+    ReviewComment, ReviewThread, UserInfo,
 };
 pub use runner::GitHub;
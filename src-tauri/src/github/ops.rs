@@ -1,3 +1,6 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex};
+
 use serde::{Deserialize, Serialize};
 
 use super::error::GitHubError;
@@ -11,6 +14,24 @@ pub struct AuthStatus {
     pub scopes: Vec<String>,
 }
 
+/// Quota for one GitHub API resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitResource {
+    pub limit: u64,
+    pub remaining: u64,
+    pub used: u64,
+    /// Unix time (seconds) at which `remaining` resets to `limit`.
+    pub reset: i64,
+}
+
+/// API quotas from `gh api rate_limit` for the resources Maestro uses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    pub core: RateLimitResource,
+    pub graphql: RateLimitResource,
+    pub search: RateLimitResource,
+}
+
 /// Pull request information returned from `gh pr list`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -97,6 +118,168 @@ pub struct IssueInfo {
     pub closed_at: Option<String>,
 }
 
+/// Owner and name of the GitHub repository a runner points at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RepoInfo {
+    pub owner: String,
+    pub name: String,
+}
+
+/// Identity of a project's GitHub repository, for labels and links.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoMetadata {
+    pub owner: String,
+    pub name: String,
+    pub default_branch: String,
+    pub remote_url: String,
+    pub is_private: bool,
+}
+
+/// Display info for a GitHub user, from `gh api users/{login}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct UserInfo {
+    pub login: String,
+    /// Profile name; `None` when the user hasn't set one.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub avatar_url: String,
+}
+
+/// How many users [`GitHub::user_info`] keeps cached.
+pub const USER_INFO_CACHE_CAPACITY: usize = 256;
+
+/// Key of a cached user: lowercased `(host, login)`. A login is unique per
+/// host, so GitHub Enterprise users never shadow github.com ones.
+type UserKey = (String, String);
+
+/// Least-recently-used cache of [`UserInfo`].
+#[derive(Debug)]
+struct UserInfoCache {
+    entries: HashMap<UserKey, UserInfo>,
+    /// Keys from least to most recently used.
+    order: VecDeque<UserKey>,
+    capacity: usize,
+}
+
+impl UserInfoCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&mut self, key: &UserKey) -> Option<UserInfo> {
+        let info = self.entries.get(key)?.clone();
+        self.touch(key);
+        Some(info)
+    }
+
+    fn insert(&mut self, key: UserKey, info: UserInfo) {
+        if self.entries.insert(key.clone(), info).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+    }
+
+    /// Marks `key` as most recently used.
+    fn touch(&mut self, key: &UserKey) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(pos) {
+                self.order.push_back(k);
+            }
+        }
+    }
+}
+
+/// Users looked up by any runner. Repositories on the same host share
+/// entries.
+static USER_INFO_CACHE: LazyLock<Mutex<UserInfoCache>> =
+    LazyLock::new(|| Mutex::new(UserInfoCache::new(USER_INFO_CACHE_CAPACITY)));
+
+/// Whether `host` is a plain hostname that can be handed to
+/// `gh api --hostname`.
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && !host.starts_with(['-', '.'])
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+/// Whether `login` is a plausible GitHub login, including app bots such as
+/// `dependabot[bot]`, so it can't smuggle extra path segments into the
+/// `users/{login}` endpoint.
+fn is_valid_login(login: &str) -> bool {
+    let name = login.strip_suffix("[bot]").unwrap_or(login);
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Splits a remote URL into its host (without user or port) and path.
+/// Returns `None` for local paths.
+fn split_remote_url(url: &str) -> Option<(&str, &str)> {
+    let url = url.trim();
+    let (authority, path) = if let Some((_, rest)) = url.split_once("://") {
+        // scheme://[user@]host[:port]/owner/name
+        let (authority, path) = rest.split_once('/')?;
+        let authority = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+        (authority.split(':').next()?, path)
+    } else {
+        // [user@]host:owner/name
+        let (authority, path) = url.split_once(':')?;
+        if authority.is_empty() || authority.contains('/') {
+            return None;
+        }
+        (
+            authority.rsplit_once('@').map_or(authority, |(_, h)| h),
+            path,
+        )
+    };
+    if authority.is_empty() {
+        return None;
+    }
+    Some((authority, path))
+}
+
+/// Extracts the lowercased host from a remote URL, in the forms
+/// [`parse_remote_url`] accepts.
+pub fn parse_remote_host(url: &str) -> Option<String> {
+    split_remote_url(url).map(|(host, _)| host.to_ascii_lowercase())
+}
+
+/// Extracts owner/name from a remote URL.
+///
+/// Accepts scp-style SSH (`git@github.com:o/r.git`), `ssh://`, `https://`,
+/// `http://` and `git://` forms, with or without a trailing `.git`.
+/// Returns `None` for local paths and URLs without an `owner/name` path.
+pub fn parse_remote_url(url: &str) -> Option<RepoInfo> {
+    let (_, path) = split_remote_url(url)?;
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.split_once('/')?;
+    if owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+    Some(RepoInfo {
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
 /// Discussion information returned from GraphQL API.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -135,6 +318,30 @@ pub struct Comment {
     pub is_answer: bool,
 }
 
+/// An inline review comment attached to a line of a pull request's diff.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewComment {
+    pub id: u64,
+    pub path: String,
+    /// Line in the current diff; `None` once the comment is outdated.
+    pub line: Option<u64>,
+    pub body: String,
+    pub author: PrAuthor,
+    /// ID of the comment this one replies to, if it is a reply.
+    pub in_reply_to: Option<u64>,
+    pub created_at: String,
+}
+
+/// A review comment and its replies, oldest first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewThread {
+    pub path: String,
+    pub line: Option<u64>,
+    pub comments: Vec<ReviewComment>,
+}
+
 /// Reactions on a comment.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -212,9 +419,10 @@ pub struct IssueFilter {
 }
 
 /// Merge method for pull requests.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MergeMethod {
+    #[default]
     Merge,
     Squash,
     Rebase,
@@ -238,6 +446,65 @@ pub struct CreatePullRequestOptions {
     pub base: String,
     pub head: String,
     pub draft: bool,
+    /// Fork the head branch was pushed to, as `owner/name`. When set, the PR
+    /// is opened against the fork's parent with an `owner:branch` head.
+    #[serde(default)]
+    pub head_repo: Option<String>,
+}
+
+/// The repository a fork was created from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkParent {
+    pub owner: String,
+    pub name: String,
+    /// Suggested base for PRs from the fork; empty if the parent is empty.
+    pub default_branch: String,
+}
+
+/// Splits a `head_repo` slug and returns its owner, rejecting anything that
+/// isn't a plain `owner/name`.
+fn head_repo_owner(head_repo: &str) -> Result<&str, GitHubError> {
+    match head_repo.split_once('/') {
+        Some((owner, name)) if is_valid_login(owner) && !name.is_empty() && !name.contains('/') => {
+            Ok(owner)
+        }
+        _ => Err(GitHubError::InvalidApiRequest {
+            message: format!("'{head_repo}' is not an owner/name repository"),
+        }),
+    }
+}
+
+/// Builds the `gh pr create` arguments. With `upstream` set, the head is
+/// qualified with the fork owner and the PR targets `upstream` via `--repo`.
+fn pr_create_args(
+    options: &CreatePullRequestOptions,
+    upstream: Option<&RepoInfo>,
+) -> Result<Vec<String>, GitHubError> {
+    let head = match &options.head_repo {
+        Some(repo) => format!("{}:{}", head_repo_owner(repo)?, options.head),
+        None => options.head.clone(),
+    };
+    let mut args: Vec<String> = vec![
+        "pr".into(),
+        "create".into(),
+        "--title".into(),
+        options.title.clone(),
+        "--body".into(),
+        options.body.clone(),
+        "--base".into(),
+        options.base.clone(),
+        "--head".into(),
+        head,
+    ];
+    if let Some(upstream) = upstream {
+        args.push("--repo".into());
+        args.push(format!("{}/{}", upstream.owner, upstream.name));
+    }
+    if options.draft {
+        args.push("--draft".into());
+    }
+    Ok(args)
 }
 
 /// GitHub operations using the `gh` CLI.
@@ -276,6 +543,13 @@ impl GitHub {
         }
     }
 
+    /// Returns the remaining API quota. Querying `rate_limit` doesn't count
+    /// against it, so this is safe to poll.
+    pub async fn rate_limit(&self) -> Result<RateLimitInfo, GitHubError> {
+        let output = self.run(&["api", "rate_limit"]).await?;
+        parse_rate_limit(&output.stdout)
+    }
+
     /// Lists pull requests with optional filtering.
     pub async fn list_pull_requests(
         &self,
@@ -311,9 +585,18 @@ impl GitHub {
 
     /// Gets detailed information about a specific pull request.
     pub async fn get_pull_request(&self, number: u64) -> Result<PullRequestDetail, GitHubError> {
-        let number_str = number.to_string();
+        self.view_pull_request(&number.to_string(), number).await
+    }
+
+    /// Fetches a pull request by `selector`, a number or URL, reporting
+    /// `number` if it doesn't exist.
+    async fn view_pull_request(
+        &self,
+        selector: &str,
+        number: u64,
+    ) -> Result<PullRequestDetail, GitHubError> {
         let args = vec![
-            "pr", "view", &number_str,
+            "pr", "view", selector,
             "--json", "number,title,body,state,author,createdAt,updatedAt,headRefName,baseRefName,isDraft,additions,deletions,changedFiles,url,labels,mergedAt,closedAt,mergeable,reviewDecision,comments",
         ];
 
@@ -374,14 +657,11 @@ impl GitHub {
             total_count: u64,
         }
 
-        let response: PrViewResponse = self.run_json(&args).await.map_err(|e| {
-            if let GitHubError::CommandFailed { stderr, .. } = &e {
-                if stderr.contains("Could not resolve") || stderr.contains("not found") {
-                    return GitHubError::PullRequestNotFound { number };
-                }
-            }
-            e
-        })?;
+        // Auth, rate-limit and network failures are already typed by the runner
+        let response: PrViewResponse = self
+            .run_json(&args)
+            .await
+            .map_err(|e| not_found_or(e, GitHubError::PullRequestNotFound { number }))?;
 
         // Convert raw comments to Comment struct
         let comments: Vec<Comment> = response.comments.into_iter().map(|c| {
@@ -436,22 +716,56 @@ impl GitHub {
         })
     }
 
+    /// Lists the inline review comments on a pull request's diff via
+    /// `gh api repos/{owner}/{repo}/pulls/{n}/comments`, oldest first.
+    ///
+    /// These are separate from the conversation comments in
+    /// [`PullRequestDetail::comments`]; see [`group_review_threads`].
+    pub async fn pr_review_comments(&self, number: u64) -> Result<Vec<ReviewComment>, GitHubError> {
+        // gh fills in {owner}/{repo} from the current repository
+        let endpoint = format!("repos/{{owner}}/{{repo}}/pulls/{}/comments", number);
+        let output = self
+            .run(&["api", "--paginate", &endpoint])
+            .await
+            .map_err(|e| {
+                if let GitHubError::CommandFailed { stderr, .. } = &e {
+                    if stderr.contains("Not Found") {
+                        return GitHubError::PullRequestNotFound { number };
+                    }
+                }
+                e
+            })?;
+        parse_review_comments(&output.stdout)
+    }
+
     /// Creates a new pull request.
+    ///
+    /// With `head_repo` set, the PR goes from that fork into its parent
+    /// repository; an empty `base` falls back to the parent's default branch.
     pub async fn create_pull_request(
         &self,
-        options: CreatePullRequestOptions,
+        mut options: CreatePullRequestOptions,
     ) -> Result<PullRequestInfo, GitHubError> {
-        let mut args = vec![
-            "pr", "create",
-            "--title", &options.title,
-            "--body", &options.body,
-            "--base", &options.base,
-            "--head", &options.head,
-        ];
-
-        if options.draft {
-            args.push("--draft");
-        }
+        let upstream = match &options.head_repo {
+            Some(repo) => {
+                head_repo_owner(repo)?;
+                let parent = self.fork_parent(Some(repo)).await?.ok_or_else(|| {
+                    GitHubError::InvalidApiRequest {
+                        message: format!("'{repo}' is not a fork"),
+                    }
+                })?;
+                if options.base.is_empty() {
+                    options.base = parent.default_branch;
+                }
+                Some(RepoInfo {
+                    owner: parent.owner,
+                    name: parent.name,
+                })
+            }
+            None => None,
+        };
+        let args = pr_create_args(&options, upstream.as_ref())?;
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
         // Create the PR and get its number from the output URL
         let output = self.run(&args).await?;
@@ -466,8 +780,12 @@ impl GitHub {
                 message: format!("Could not parse PR number from URL: {}", url),
             })?;
 
-        // Fetch the full PR info
-        let detail = self.get_pull_request(number).await?;
+        // Fetch the full PR info; a fork PR lives upstream, so go by URL
+        let detail = if upstream.is_some() {
+            self.view_pull_request(url, number).await?
+        } else {
+            self.get_pull_request(number).await?
+        };
 
         Ok(PullRequestInfo {
             number: detail.number,
@@ -606,14 +924,10 @@ impl GitHub {
             total_count: u64,
         }
 
-        let response: IssueViewResponse = self.run_json(&args).await.map_err(|e| {
-            if let GitHubError::CommandFailed { stderr, .. } = &e {
-                if stderr.contains("Could not resolve") || stderr.contains("not found") {
-                    return GitHubError::IssueNotFound { number };
-                }
-            }
-            e
-        })?;
+        let response: IssueViewResponse = self
+            .run_json(&args)
+            .await
+            .map_err(|e| not_found_or(e, GitHubError::IssueNotFound { number }))?;
 
         // Convert raw comments to Comment struct
         let comments: Vec<Comment> = response.comments.into_iter().map(|c| {
@@ -680,6 +994,156 @@ impl GitHub {
         Ok(())
     }
 
+    /// Builds the metadata for the repository behind `remote_url`.
+    ///
+    /// Owner/name come from the URL itself; a single `gh repo view` fills in
+    /// the default branch and visibility. Returns `NotGitHubRepo` if the URL
+    /// has no `owner/name` path.
+    pub async fn repo_metadata(&self, remote_url: &str) -> Result<RepoMetadata, GitHubError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RawRepoView {
+            default_branch_ref: Option<BranchRef>,
+            is_private: bool,
+        }
+
+        #[derive(Deserialize)]
+        struct BranchRef {
+            name: String,
+        }
+
+        let repo = parse_remote_url(remote_url).ok_or(GitHubError::NotGitHubRepo)?;
+        let slug = format!("{}/{}", repo.owner, repo.name);
+        let raw: RawRepoView = self
+            .run_json(&[
+                "repo",
+                "view",
+                &slug,
+                "--json",
+                "defaultBranchRef,isPrivate",
+            ])
+            .await?;
+
+        Ok(RepoMetadata {
+            owner: repo.owner,
+            name: repo.name,
+            // Empty repositories have no default branch yet
+            default_branch: raw.default_branch_ref.map(|b| b.name).unwrap_or_default(),
+            remote_url: remote_url.to_string(),
+            is_private: raw.is_private,
+        })
+    }
+
+    /// Returns the repository's owner and name via `gh repo view`.
+    ///
+    /// The result is cached per repository path, so repeated discussion calls
+    /// only pay for one `gh repo view` per TTL window.
+    pub async fn repo_info(&self) -> Result<RepoInfo, GitHubError> {
+        if let Some(info) = self.cached_repo_info() {
+            return Ok(info);
+        }
+
+        #[derive(Deserialize)]
+        struct RawRepoInfo {
+            owner: RepoOwner,
+            name: String,
+        }
+
+        #[derive(Deserialize)]
+        struct RepoOwner {
+            login: String,
+        }
+
+        let raw: RawRepoInfo = self
+            .run_json(&["repo", "view", "--json", "owner,name"])
+            .await?;
+        let info = RepoInfo {
+            owner: raw.owner.login,
+            name: raw.name,
+        };
+        self.store_repo_info(info.clone());
+        Ok(info)
+    }
+
+    /// Returns the parent of `repo` (or of the current repository when
+    /// `None`) via `gh repo view --json parent`, or `None` if it isn't a fork.
+    pub async fn fork_parent(&self, repo: Option<&str>) -> Result<Option<ForkParent>, GitHubError> {
+        #[derive(Deserialize)]
+        struct RawRepoParent {
+            parent: Option<RawParent>,
+        }
+
+        #[derive(Deserialize)]
+        struct RawParent {
+            owner: RawOwner,
+            name: String,
+        }
+
+        #[derive(Deserialize)]
+        struct RawOwner {
+            login: String,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RawDefaultBranch {
+            default_branch_ref: Option<BranchRef>,
+        }
+
+        #[derive(Deserialize)]
+        struct BranchRef {
+            name: String,
+        }
+
+        let mut args = vec!["repo", "view"];
+        args.extend(repo);
+        args.extend(["--json", "parent"]);
+        let raw: RawRepoParent = self.run_json(&args).await?;
+        let Some(parent) = raw.parent else {
+            return Ok(None);
+        };
+
+        // `parent` doesn't carry the default branch, so ask the parent itself
+        let slug = format!("{}/{}", parent.owner.login, parent.name);
+        let view: RawDefaultBranch = self
+            .run_json(&["repo", "view", &slug, "--json", "defaultBranchRef"])
+            .await?;
+        Ok(Some(ForkParent {
+            owner: parent.owner.login,
+            name: parent.name,
+            default_branch: view.default_branch_ref.map(|b| b.name).unwrap_or_default(),
+        }))
+    }
+
+    /// Returns a user's name and avatar via `gh api users/{login}`.
+    ///
+    /// `host` is the host of the repository's remote; the request goes to
+    /// it via `--hostname`. Results are kept in a process-wide LRU cache of
+    /// [`USER_INFO_CACHE_CAPACITY`] users keyed by host and login, so
+    /// rendering a long thread only asks once per participant.
+    pub async fn user_info(&self, host: &str, login: &str) -> Result<UserInfo, GitHubError> {
+        if !is_valid_login(login) {
+            return Err(GitHubError::InvalidApiRequest {
+                message: format!("'{login}' is not a valid GitHub login"),
+            });
+        }
+        if !is_valid_host(host) {
+            return Err(GitHubError::InvalidApiRequest {
+                message: format!("'{host}' is not a valid GitHub host"),
+            });
+        }
+        let key = (host.to_ascii_lowercase(), login.to_ascii_lowercase());
+        if let Some(info) = USER_INFO_CACHE.lock().unwrap().get(&key) {
+            return Ok(info);
+        }
+
+        let info: UserInfo = self
+            .run_json(&["api", "--hostname", &key.0, &format!("users/{login}")])
+            .await?;
+        USER_INFO_CACHE.lock().unwrap().insert(key, info.clone());
+        Ok(info)
+    }
+
     /// Lists discussions using the GraphQL API.
     pub async fn list_discussions(&self, limit: u32) -> Result<Vec<DiscussionInfo>, GitHubError> {
         let query = format!(
@@ -707,24 +1171,11 @@ impl GitHub {
         );
 
         // We need to get repo info first to fill in OWNER/REPO
-        let repo_output = self.run(&["repo", "view", "--json", "owner,name"]).await?;
-
-        #[derive(Deserialize)]
-        struct RepoInfo {
-            owner: RepoOwner,
-            name: String,
-        }
-
-        #[derive(Deserialize)]
-        struct RepoOwner {
-            login: String,
-        }
-
-        let repo_info: RepoInfo = serde_json::from_str(&repo_output.stdout)?;
+        let repo_info = self.repo_info().await?;
 
         let query = query
-            .replace("OWNER", &repo_info.owner.login)
-            .replace("REPO", &repo_info.name);
+            .replace("OWNER", &graphql_escape(&repo_info.owner))
+            .replace("REPO", &graphql_escape(&repo_info.name));
 
         let result = self.graphql(&query).await;
 
@@ -766,20 +1217,7 @@ impl GitHub {
     /// Gets detailed information about a specific discussion using GraphQL.
     pub async fn get_discussion(&self, number: u64) -> Result<DiscussionDetail, GitHubError> {
         // Get repo info first
-        let repo_output = self.run(&["repo", "view", "--json", "owner,name"]).await?;
-
-        #[derive(Deserialize)]
-        struct RepoInfo {
-            owner: RepoOwner,
-            name: String,
-        }
-
-        #[derive(Deserialize)]
-        struct RepoOwner {
-            login: String,
-        }
-
-        let repo_info: RepoInfo = serde_json::from_str(&repo_output.stdout)?;
+        let repo_info = self.repo_info().await?;
 
         let query = format!(
             r#"{{
@@ -825,18 +1263,22 @@ impl GitHub {
                     }}
                 }}
             }}"#,
-            repo_info.owner.login, repo_info.name, number
+            graphql_escape(&repo_info.owner),
+            graphql_escape(&repo_info.name),
+            number
         );
 
-        let json = self.graphql(&query).await?;
+        let json = self
+            .graphql(&query)
+            .await
+            .map_err(|e| not_found_or(e, GitHubError::DiscussionNotFound { number }))?;
 
         let discussion = json
             .get("data")
             .and_then(|d| d.get("repository"))
             .and_then(|r| r.get("discussion"))
-            .ok_or_else(|| GitHubError::ParseError {
-                message: format!("Discussion #{} not found", number),
-            })?;
+            .filter(|d| !d.is_null())
+            .ok_or(GitHubError::DiscussionNotFound { number })?;
 
         // Parse the response
         #[derive(Deserialize)]
@@ -934,20 +1376,7 @@ impl GitHub {
     /// Adds a comment to a discussion using GraphQL mutation.
     pub async fn comment_discussion(&self, number: u64, body: &str) -> Result<(), GitHubError> {
         // Get repo info first
-        let repo_output = self.run(&["repo", "view", "--json", "owner,name"]).await?;
-
-        #[derive(Deserialize)]
-        struct RepoInfo {
-            owner: RepoOwner,
-            name: String,
-        }
-
-        #[derive(Deserialize)]
-        struct RepoOwner {
-            login: String,
-        }
-
-        let repo_info: RepoInfo = serde_json::from_str(&repo_output.stdout)?;
+        let repo_info = self.repo_info().await?;
 
         // First, get the discussion ID (GraphQL node ID)
         let id_query = format!(
@@ -958,7 +1387,9 @@ impl GitHub {
                     }}
                 }}
             }}"#,
-            repo_info.owner.login, repo_info.name, number
+            graphql_escape(&repo_info.owner),
+            graphql_escape(&repo_info.name),
+            number
         );
 
         let id_json = self.graphql(&id_query).await?;
@@ -973,32 +1404,446 @@ impl GitHub {
                 message: format!("Could not get discussion ID for #{}", number),
             })?;
 
-        // Escape the body for GraphQL
-        let escaped_body = body
-            .replace('\\', "\\\\")
-            .replace('"', "\\\"")
-            .replace('\n', "\\n");
-
-        // Now add the comment using mutation
-        let mutation = format!(
-            r#"mutation {{
-                addDiscussionComment(input: {{discussionId: "{}", body: "{}"}}) {{
-                    comment {{
+        // Pass user text as variables so it never has to be escaped into the query
+        let mutation = r#"mutation($discussionId: ID!, $body: String!) {
+                addDiscussionComment(input: {discussionId: $discussionId, body: $body}) {
+                    comment {
                         id
-                    }}
-                }}
-            }}"#,
-            discussion_id, escaped_body
-        );
+                    }
+                }
+            }"#;
 
-        self.graphql(&mutation).await?;
+        self.graphql_with_variables(mutation, &[("discussionId", discussion_id), ("body", body)])
+            .await?;
         Ok(())
     }
 }
 
+/// Parses `gh api --paginate` output for review comments, which is one JSON
+/// array per page written back to back.
+fn parse_review_comments(stdout: &str) -> Result<Vec<ReviewComment>, GitHubError> {
+    #[derive(Deserialize)]
+    struct RawReviewComment {
+        id: u64,
+        path: String,
+        #[serde(default)]
+        line: Option<u64>,
+        body: String,
+        // Null when the author's account was deleted
+        user: Option<PrAuthor>,
+        #[serde(default)]
+        in_reply_to_id: Option<u64>,
+        created_at: String,
+    }
+
+    let mut comments = Vec::new();
+    for page in serde_json::Deserializer::from_str(stdout).into_iter::<Vec<RawReviewComment>>() {
+        comments.extend(page?.into_iter().map(|c| ReviewComment {
+            id: c.id,
+            path: c.path,
+            line: c.line,
+            body: c.body,
+            author: c.user.unwrap_or_else(|| PrAuthor {
+                login: "ghost".to_string(),
+            }),
+            in_reply_to: c.in_reply_to_id,
+            created_at: c.created_at,
+        }));
+    }
+    Ok(comments)
+}
+
+/// Groups review comments into threads: each comment that replies to nothing
+/// starts a thread, and replies join the thread of the comment they answer.
+///
+/// Threads keep the order of their first comment; replies whose parent is
+/// missing (e.g. deleted) start their own thread.
+pub fn group_review_threads(comments: Vec<ReviewComment>) -> Vec<ReviewThread> {
+    use std::collections::HashMap;
+
+    let parents: HashMap<u64, Option<u64>> =
+        comments.iter().map(|c| (c.id, c.in_reply_to)).collect();
+    let root_of = |mut id: u64| {
+        // Bounded walk so a malformed reply cycle can't loop forever
+        for _ in 0..parents.len() {
+            match parents.get(&id) {
+                Some(Some(parent)) if parents.contains_key(parent) => id = *parent,
+                _ => break,
+            }
+        }
+        id
+    };
+
+    let mut threads: Vec<ReviewThread> = Vec::new();
+    let mut thread_index: HashMap<u64, usize> = HashMap::new();
+    for comment in comments {
+        let root = root_of(comment.id);
+        let index = *thread_index.entry(root).or_insert_with(|| {
+            threads.push(ReviewThread {
+                path: comment.path.clone(),
+                line: comment.line,
+                comments: Vec::new(),
+            });
+            threads.len() - 1
+        });
+        threads[index].comments.push(comment);
+    }
+    threads
+}
+
+/// Extracts the resources Maestro cares about from a `rate_limit` response,
+/// ignoring the others and the deprecated top-level `rate`.
+fn parse_rate_limit(stdout: &str) -> Result<RateLimitInfo, GitHubError> {
+    #[derive(Deserialize)]
+    struct RateLimitResponse {
+        resources: RateLimitInfo,
+    }
+
+    let response: RateLimitResponse = serde_json::from_str(stdout)?;
+    Ok(response.resources)
+}
+
+/// Replaces a generic command failure whose stderr says the item doesn't
+/// exist with `not_found`; other errors pass through unchanged.
+fn not_found_or(error: GitHubError, not_found: GitHubError) -> GitHubError {
+    match &error {
+        GitHubError::CommandFailed { stderr, .. }
+            if stderr.contains("Could not resolve") || stderr.contains("not found") =>
+        {
+            not_found
+        }
+        _ => error,
+    }
+}
+
+/// Escapes `s` for use inside a double-quoted GraphQL string literal.
+///
+/// Only for values that must be interpolated into a query; user-authored
+/// text should go through `graphql_with_variables` instead.
+fn graphql_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(unix)]
+    use crate::github::runner::fake_gh;
+
+    /// Writes a fake `gh` that logs its arguments to `log` and answers
+    /// `repo view` with the current directory's name as the repo name.
+    #[cfg(unix)]
+    fn write_gh_spy(dir: &std::path::Path, log: &std::path::Path) -> GitHub {
+        let script = format!(
+            "echo \"$*\" >> '{}'\n\
+             case \"$1\" in\n\
+             repo) printf '{{\"owner\":{{\"login\":\"octo\"}},\"name\":\"%s\"}}' \"$(basename \"$PWD\")\" ;;\n\
+             api) printf '{{\"data\":{{\"repository\":{{\"discussions\":{{\"nodes\":[]}}}}}}}}' ;;\n\
+             esac\n",
+            log.display()
+        );
+        fake_gh(dir, &script)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_repo_info_is_cached_per_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("calls.log");
+        let spy = write_gh_spy(dir.path(), &log);
+        let repo_a = dir.path().join("repo-a");
+        let repo_b = dir.path().join("repo-b");
+        std::fs::create_dir(&repo_a).unwrap();
+        std::fs::create_dir(&repo_b).unwrap();
+        let repo_views = || {
+            std::fs::read_to_string(&log)
+                .unwrap()
+                .lines()
+                .filter(|l| l.starts_with("repo view"))
+                .count()
+        };
+
+        // Each call builds a fresh runner, as the commands do
+        spy.for_repo(&repo_a).list_discussions(5).await.unwrap();
+        spy.for_repo(&repo_a).list_discussions(5).await.unwrap();
+        assert_eq!(repo_views(), 1);
+        let info = spy.for_repo(&repo_a).repo_info().await.unwrap();
+        assert_eq!(info.name, "repo-a");
+
+        let info = spy.for_repo(&repo_b).repo_info().await.unwrap();
+        assert_eq!(info.owner, "octo");
+        assert_eq!(info.name, "repo-b");
+        assert_eq!(repo_views(), 2);
+    }
+
+    #[test]
+    fn test_parse_remote_url_ssh_and_https() {
+        let expected = Some(RepoInfo {
+            owner: "o".to_string(),
+            name: "r".to_string(),
+        });
+        assert_eq!(parse_remote_url("git@github.com:o/r.git"), expected);
+        assert_eq!(parse_remote_url("git@github.com:o/r"), expected);
+        assert_eq!(parse_remote_url("https://github.com/o/r"), expected);
+        assert_eq!(parse_remote_url("https://github.com/o/r.git"), expected);
+        assert_eq!(parse_remote_url("https://github.com/o/r/"), expected);
+        assert_eq!(
+            parse_remote_url("ssh://git@github.com:22/o/r.git"),
+            expected
+        );
+        assert_eq!(parse_remote_url("https://user@github.com/o/r"), expected);
+
+        assert_eq!(parse_remote_url("/srv/git/repo.git"), None);
+        assert_eq!(parse_remote_url("https://github.com/o"), None);
+        assert_eq!(parse_remote_url("https://example.com/a/b/c"), None);
+        assert_eq!(parse_remote_url(""), None);
+    }
+
+    #[test]
+    fn test_parse_remote_host_drops_user_and_port() {
+        let host = |url: &str| parse_remote_host(url);
+        assert_eq!(
+            host("git@github.com:o/r.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            host("ssh://git@GHE.example.com:22/o/r.git").as_deref(),
+            Some("ghe.example.com")
+        );
+        assert_eq!(
+            host("https://user@github.com/o/r").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(host("/srv/git/repo.git"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_repo_metadata_combines_url_and_repo_view() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("calls.log");
+        let script = format!(
+            "echo \"$*\" >> '{}'\n\
+             printf '{{\"defaultBranchRef\":{{\"name\":\"trunk\"}},\"isPrivate\":true}}'\n",
+            log.display()
+        );
+        let gh = fake_gh(dir.path(), &script);
+        let meta = gh
+            .repo_metadata("git@github.com:octo/widgets.git")
+            .await
+            .unwrap();
+        assert_eq!(
+            meta,
+            RepoMetadata {
+                owner: "octo".to_string(),
+                name: "widgets".to_string(),
+                default_branch: "trunk".to_string(),
+                remote_url: "git@github.com:octo/widgets.git".to_string(),
+                is_private: true,
+            }
+        );
+        let calls = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(
+            calls.trim(),
+            "repo view octo/widgets --json defaultBranchRef,isPrivate"
+        );
+
+        assert!(matches!(
+            gh.repo_metadata("/srv/git/widgets.git").await,
+            Err(GitHubError::NotGitHubRepo)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_view_failures_map_to_typed_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let stderr_file = dir.path().join("stderr.txt");
+        let script = format!("cat '{}' >&2\nexit 1\n", stderr_file.display());
+        let gh = fake_gh(dir.path(), &script);
+
+        let fail_with = |stderr: &str| std::fs::write(&stderr_file, stderr).unwrap();
+
+        fail_with("GraphQL: Could not resolve to a PullRequest with the number of 42.");
+        assert!(matches!(
+            gh.get_pull_request(42).await,
+            Err(GitHubError::PullRequestNotFound { number: 42 })
+        ));
+        fail_with("HTTP 403: API rate limit exceeded for user ID 1.");
+        assert!(matches!(
+            gh.get_pull_request(42).await,
+            Err(GitHubError::RateLimited { .. })
+        ));
+        fail_with("You are not logged into any GitHub hosts. Run gh auth login to authenticate.");
+        assert!(matches!(
+            gh.get_pull_request(42).await,
+            Err(GitHubError::NotAuthenticated)
+        ));
+        fail_with("error connecting to api.github.com");
+        assert!(matches!(
+            gh.get_pull_request(42).await,
+            Err(GitHubError::NetworkError { .. })
+        ));
+        fail_with("GraphQL: Resource not accessible by integration (HTTP 403)");
+        assert!(matches!(
+            gh.get_pull_request(42).await,
+            Err(GitHubError::CommandFailed { .. })
+        ));
+
+        fail_with("GraphQL: Could not resolve to an Issue with the number of 9.");
+        assert!(matches!(
+            gh.get_issue(9).await,
+            Err(GitHubError::IssueNotFound { number: 9 })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_user_info_is_fetched_once_then_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("calls.log");
+        // Trimmed `users/{login}` response
+        let fixture = r#"{"login":"Cache-Spy","id":583231,"name":"Cache Spy","avatar_url":"https://avatars.githubusercontent.com/u/583231?v=4","type":"User"}"#;
+        let script = format!(
+            "echo \"$*\" >> '{}'\nprintf '%s' '{}'\n",
+            log.display(),
+            fixture
+        );
+        let gh = fake_gh(dir.path(), &script);
+
+        let first = gh.user_info("github.com", "Cache-Spy").await.unwrap();
+        assert_eq!(first.name.as_deref(), Some("Cache Spy"));
+        assert_eq!(
+            first.avatar_url,
+            "https://avatars.githubusercontent.com/u/583231?v=4"
+        );
+        // Logins and hosts are case-insensitive, so this is a cache hit too
+        let second = gh.user_info("GitHub.com", "cache-spy").await.unwrap();
+        assert_eq!(second, first);
+        let calls = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(
+            calls.lines().collect::<Vec<_>>(),
+            vec!["api --hostname github.com users/Cache-Spy"]
+        );
+
+        // The same login on another host is a different user
+        gh.user_info("ghe.example.com", "Cache-Spy").await.unwrap();
+        let calls = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(
+            calls.lines().last(),
+            Some("api --hostname ghe.example.com users/Cache-Spy")
+        );
+
+        assert!(matches!(
+            gh.user_info("github.com", "../orgs/x").await,
+            Err(GitHubError::InvalidApiRequest { .. })
+        ));
+        assert!(matches!(
+            gh.user_info("--evil", "octocat").await,
+            Err(GitHubError::InvalidApiRequest { .. })
+        ));
+    }
+
+    #[test]
+    fn test_user_info_cache_evicts_least_recently_used() {
+        let user = |login: &str| UserInfo {
+            login: login.to_string(),
+            name: None,
+            avatar_url: String::new(),
+        };
+        let key = |login: &str| ("github.com".to_string(), login.to_string());
+        let mut cache = UserInfoCache::new(2);
+        cache.insert(key("a"), user("a"));
+        cache.insert(key("b"), user("b"));
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), user("c"));
+
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("c")).is_some());
+    }
+
+    #[test]
+    fn test_review_comments_are_grouped_into_threads() {
+        // Two pages as printed by `gh api --paginate`
+        let stdout = r#"[
+            {"id": 1, "path": "src/lib.rs", "line": 10, "body": "Why clone here?",
+             "user": {"login": "alice"}, "created_at": "2024-01-01T00:00:00Z"},
+            {"id": 2, "path": "src/main.rs", "line": null, "body": "Outdated nit",
+             "user": {"login": "bob"}, "created_at": "2024-01-01T00:01:00Z"}
+        ][
+            {"id": 3, "path": "src/lib.rs", "line": 10, "body": "Borrow checker",
+             "user": {"login": "carol"}, "in_reply_to_id": 1,
+             "created_at": "2024-01-01T00:02:00Z"},
+            {"id": 4, "path": "src/lib.rs", "line": 10, "body": "Fair",
+             "user": null, "in_reply_to_id": 1, "created_at": "2024-01-01T00:03:00Z"}
+        ]"#;
+
+        let comments = parse_review_comments(stdout).unwrap();
+        assert_eq!(comments.len(), 4);
+        assert_eq!(comments[2].in_reply_to, Some(1));
+        assert_eq!(comments[3].author.login, "ghost");
+
+        let threads = group_review_threads(comments);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].path, "src/lib.rs");
+        assert_eq!(threads[0].line, Some(10));
+        let ids: Vec<u64> = threads[0].comments.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+        assert_eq!(threads[1].path, "src/main.rs");
+        assert_eq!(threads[1].line, None);
+        assert_eq!(threads[1].comments.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let stdout = r#"{
+            "resources": {
+                "core": {"limit": 5000, "used": 4990, "remaining": 10, "reset": 1760000000},
+                "search": {"limit": 30, "used": 0, "remaining": 30, "reset": 1760000060},
+                "graphql": {"limit": 5000, "used": 12, "remaining": 4988, "reset": 1760003600},
+                "code_scanning_upload": {"limit": 1000, "used": 0, "remaining": 1000, "reset": 1760003600, "resource": "code_scanning_upload"}
+            },
+            "rate": {"limit": 5000, "used": 4990, "remaining": 10, "reset": 1760000000}
+        }"#;
+
+        let info = parse_rate_limit(stdout).unwrap();
+        assert_eq!(info.core.remaining, 10);
+        assert_eq!(info.core.used, 4990);
+        assert_eq!(info.core.reset, 1760000000);
+        assert_eq!(info.graphql.remaining, 4988);
+        assert_eq!(info.graphql.reset, 1760003600);
+        assert_eq!(
+            info.search,
+            RateLimitResource {
+                limit: 30,
+                remaining: 30,
+                used: 0,
+                reset: 1760000060,
+            }
+        );
+    }
+
+    #[test]
+    fn test_graphql_escape() {
+        assert_eq!(graphql_escape(r#"say "hi""#), r#"say \"hi\""#);
+        assert_eq!(graphql_escape(r"C:\temp"), r"C:\\temp");
+        assert_eq!(graphql_escape("a\r\nb\tc"), r"a\r\nb\tc");
+        assert_eq!(graphql_escape("bell\u{7}"), r"bell\u0007");
+        assert_eq!(graphql_escape("plain émoji 🎉"), "plain émoji 🎉");
+    }
 
     #[test]
     fn test_merge_method_flag() {
@@ -1076,4 +1921,49 @@ mod tests {
         assert_eq!(issue.number, 456);
         assert_eq!(issue.title, "Test Issue");
     }
+
+    #[test]
+    fn test_pr_create_args_qualifies_head_for_fork() {
+        let mut options = CreatePullRequestOptions {
+            title: "Fix typo".to_string(),
+            body: "".to_string(),
+            base: "main".to_string(),
+            head: "fix-typo".to_string(),
+            draft: true,
+            head_repo: None,
+        };
+        let args = pr_create_args(&options, None).unwrap();
+        assert_eq!(
+            args,
+            [
+                "pr", "create", "--title", "Fix typo", "--body", "", "--base", "main", "--head",
+                "fix-typo", "--draft"
+            ]
+        );
+
+        options.head_repo = Some("alice/widgets".to_string());
+        let upstream = RepoInfo {
+            owner: "octo".to_string(),
+            name: "widgets".to_string(),
+        };
+        let args = pr_create_args(&options, Some(&upstream)).unwrap();
+        assert_eq!(
+            &args[6..],
+            [
+                "--base",
+                "main",
+                "--head",
+                "alice:fix-typo",
+                "--repo",
+                "octo/widgets",
+                "--draft"
+            ]
+        );
+
+        options.head_repo = Some("alice".to_string());
+        assert!(matches!(
+            pr_create_args(&options, Some(&upstream)),
+            Err(GitHubError::InvalidApiRequest { .. })
+        ));
+    }
 }
@@ -1,8 +1,15 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use dashmap::DashMap;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
 use super::error::GitHubError;
+use super::ops::RepoInfo;
 use crate::core::windows_process::TokioCommandExt;
 
 /// Captured stdout/stderr from a completed gh subprocess.
@@ -26,14 +33,33 @@ impl GitHubOutput {
     }
 }
 
+/// How long a gh command may run before it is killed, unless run through
+/// [`GitHub::run_with_timeout`].
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// HTTP methods [`GitHub::api`] accepts. DELETE is left out: removals go
+/// through dedicated commands that confirm what they delete.
+pub const API_METHODS: &[&str] = &["GET", "POST", "PATCH", "PUT"];
+
+/// How long a resolved owner/name is reused before `gh repo view` runs again.
+pub const REPO_INFO_TTL: Duration = Duration::from_secs(300);
+
+/// Owner/name per repository path, with the time it was resolved. Shared by
+/// every runner, since commands build a fresh [`GitHub`] per call.
+static REPO_INFO: LazyLock<DashMap<PathBuf, (RepoInfo, Instant)>> = LazyLock::new(DashMap::new);
+
 /// Low-level GitHub CLI command runner bound to a specific repository path.
 ///
 /// All commands are invoked via `tokio::process::Command` with the working
-/// directory set to the repository path. Subprocesses are killed on drop
-/// via `kill_on_drop(true)`.
+/// directory set to the repository path. Each command is bounded by
+/// [`DEFAULT_TIMEOUT`]; on expiry the child is killed and reaped. Subprocesses
+/// are also killed on drop via `kill_on_drop(true)`.
+///
+/// The repository's owner/name is cached per path for [`REPO_INFO_TTL`].
 #[derive(Debug, Clone)]
 pub struct GitHub {
     repo_path: PathBuf,
+    program: PathBuf,
 }
 
 impl GitHub {
@@ -41,76 +67,132 @@ impl GitHub {
     pub fn new(repo_path: impl Into<PathBuf>) -> Self {
         Self {
             repo_path: repo_path.into(),
+            program: PathBuf::from("gh"),
         }
     }
 
-    /// Returns the repository path.
-    pub fn repo_path(&self) -> &Path {
-        &self.repo_path
+    /// Returns a runner for `repo_path` that invokes the same program, so one
+    /// [`fake_gh`] can stand in for several repositories.
+    #[cfg(test)]
+    pub(crate) fn for_repo(&self, repo_path: impl Into<PathBuf>) -> Self {
+        Self {
+            repo_path: repo_path.into(),
+            program: self.program.clone(),
+        }
+    }
+
+    /// Returns the cached owner/name if it was resolved for this runner's
+    /// directory within [`REPO_INFO_TTL`].
+    pub(super) fn cached_repo_info(&self) -> Option<RepoInfo> {
+        REPO_INFO
+            .get(&self.repo_path)
+            .filter(|entry| entry.1.elapsed() < REPO_INFO_TTL)
+            .map(|entry| entry.0.clone())
+    }
+
+    /// Remembers `info` as the owner/name of this runner's directory.
+    pub(super) fn store_repo_info(&self, info: RepoInfo) {
+        REPO_INFO.insert(self.repo_path.clone(), (info, Instant::now()));
     }
 
     /// Executes a gh subcommand and returns its captured output.
     ///
     /// Returns `GhNotFound` if the gh binary is missing, `SpawnError` for
-    /// other I/O failures, and `CommandFailed` for non-zero exit codes.
+    /// other I/O failures, `Timeout` after [`DEFAULT_TIMEOUT`], and
+    /// `CommandFailed` for non-zero exit codes.
     /// Both stdout and stderr are decoded as UTF-8 (returns `InvalidUtf8` on failure).
     pub async fn run(&self, args: &[&str]) -> Result<GitHubOutput, GitHubError> {
-        let mut cmd = Command::new("gh");
+        self.run_with_timeout(args, DEFAULT_TIMEOUT).await
+    }
+
+    /// Like [`run`](Self::run), but with a one-off timeout for slow operations
+    /// such as fetching logs.
+    pub async fn run_with_timeout(
+        &self,
+        args: &[&str],
+        limit: Duration,
+    ) -> Result<GitHubOutput, GitHubError> {
+        let program = self.program.to_string_lossy().into_owned();
+        self.execute(&program, args, limit).await
+    }
+
+    /// Spawns `program` in the repository directory and collects its output,
+    /// killing and reaping the child if it outlives `limit`.
+    async fn execute(
+        &self,
+        program: &str,
+        args: &[&str],
+        limit: Duration,
+    ) -> Result<GitHubOutput, GitHubError> {
+        let mut cmd = Command::new(program);
         cmd.current_dir(&self.repo_path)
             .args(args)
             .env("GH_PROMPT_DISABLED", "1")
             .env("NO_COLOR", "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
             .kill_on_drop(true)
             .hide_console_window();
 
-        let command_str = format!("gh {}", args.join(" "));
+        let command_str = format!("{} {}", program, redact_fields(args).join(" "));
+
+        let mut child = cmd.spawn().map_err(|source| {
+            if source.kind() == std::io::ErrorKind::NotFound {
+                GitHubError::GhNotFound
+            } else {
+                GitHubError::SpawnError {
+                    source,
+                    command: command_str.clone(),
+                }
+            }
+        })?;
+
+        let mut stdout_pipe = child.stdout.take().expect("stdout is piped");
+        let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+        let mut stdout_buf = Vec::new();
+        let mut stderr_buf = Vec::new();
+
+        let collected = timeout(limit, async {
+            let (out, err, status) = tokio::join!(
+                stdout_pipe.read_to_end(&mut stdout_buf),
+                stderr_pipe.read_to_end(&mut stderr_buf),
+                child.wait(),
+            );
+            out?;
+            err?;
+            status
+        })
+        .await;
 
-        let output = timeout(Duration::from_secs(30), cmd.output())
-            .await
-            .map_err(|_| GitHubError::CommandFailed {
-                code: -1,
-                stderr: format!("Command timed out after 30s: {}", command_str),
+        let status = match collected {
+            Ok(result) => result.map_err(|source| GitHubError::SpawnError {
+                source,
                 command: command_str.clone(),
-            })?
-            .map_err(|source| {
-                if source.kind() == std::io::ErrorKind::NotFound {
-                    GitHubError::GhNotFound
-                } else {
-                    GitHubError::SpawnError {
-                        source,
-                        command: command_str.clone(),
-                    }
+            })?,
+            Err(_) => {
+                // kill() also waits, so the child doesn't linger as a zombie
+                if let Err(e) = child.kill().await {
+                    log::warn!("Failed to kill timed-out `{}`: {}", command_str, e);
                 }
-            })?;
+                return Err(GitHubError::Timeout {
+                    command: command_str,
+                    timeout: limit,
+                });
+            }
+        };
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = String::from_utf8(stdout_buf)?;
+        let stderr = String::from_utf8(stderr_buf)?;
 
-        if output.status.success() {
+        if status.success() {
             Ok(GitHubOutput { stdout, stderr })
         } else {
-            // Check for specific error conditions
-            let stderr_lower = stderr.to_lowercase();
-            if stderr_lower.contains("not logged in")
-                || stderr_lower.contains("authentication")
-                || stderr_lower.contains("gh auth login")
-            {
-                return Err(GitHubError::NotAuthenticated);
-            }
-            if stderr_lower.contains("rate limit") {
-                return Err(GitHubError::RateLimitExceeded);
-            }
-            if stderr_lower.contains("not a git repository")
-                || stderr_lower.contains("could not determine")
-            {
-                return Err(GitHubError::NotGitHubRepo);
-            }
-
-            Err(GitHubError::CommandFailed {
-                code: output.status.code().unwrap_or(-1),
-                stderr: stderr.trim().to_string(),
-                command: command_str,
-            })
+            Err(classify_failure(
+                &stderr,
+                status.code().unwrap_or(-1),
+                command_str,
+            ))
         }
     }
 
@@ -126,10 +208,211 @@ impl GitHub {
 
     /// Executes a GraphQL query via `gh api graphql`.
     pub async fn graphql(&self, query: &str) -> Result<serde_json::Value, GitHubError> {
-        let output = self.run(&["api", "graphql", "-f", &format!("query={}", query)]).await?;
+        self.graphql_with_variables(query, &[]).await
+    }
+
+    /// Executes a GraphQL query, passing each `(name, value)` as a string
+    /// variable (`-f name=value`) that the query declares as `$name`.
+    ///
+    /// Prefer this over interpolating user text into the query: gh sends the
+    /// values as JSON, so no escaping is needed.
+    pub async fn graphql_with_variables(
+        &self,
+        query: &str,
+        variables: &[(&str, &str)],
+    ) -> Result<serde_json::Value, GitHubError> {
+        let args = graphql_args(query, variables);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self.run(&args).await?;
         let parsed: serde_json::Value = serde_json::from_str(&output.stdout)?;
         Ok(parsed)
     }
+
+    /// Calls an arbitrary REST endpoint via `gh api -X <method> <endpoint>`,
+    /// sending each `(key, value)` as a string field (`-f key=value`; query
+    /// parameters for GET).
+    ///
+    /// `method` must be one of [`API_METHODS`]. Field values may hold secrets,
+    /// so they are masked in logs and error messages. An empty response (e.g.
+    /// 204 No Content) yields `null`; other output that isn't JSON is a
+    /// `ParseError`.
+    pub async fn api(
+        &self,
+        method: &str,
+        endpoint: &str,
+        fields: Vec<(String, String)>,
+    ) -> Result<serde_json::Value, GitHubError> {
+        let args = api_args(method, endpoint, &fields)?;
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self.run(&args).await?;
+        parse_api_output(output.trimmed())
+    }
+}
+
+/// Maps a failed gh invocation's stderr to a typed error, falling back to
+/// [`GitHubError::CommandFailed`] for anything unrecognized.
+///
+/// A 403 only counts as rate limiting when gh's output says so: GitHub also
+/// answers 403 for missing permissions, which retrying won't fix.
+fn classify_failure(stderr: &str, code: i32, command: String) -> GitHubError {
+    let stderr_lower = stderr.to_lowercase();
+    if stderr_lower.contains("rate limit") || stderr_lower.contains("x-ratelimit-remaining: 0") {
+        return GitHubError::RateLimited {
+            reset_at: parse_rate_limit_reset(&stderr_lower),
+        };
+    }
+    if stderr_lower.contains("not logged")
+        || stderr_lower.contains("authentication")
+        || stderr_lower.contains("gh auth login")
+    {
+        return GitHubError::NotAuthenticated;
+    }
+    if [
+        "error connecting to",
+        "could not resolve host",
+        "no such host",
+        "connection refused",
+        "connection reset",
+        "network is unreachable",
+        "i/o timeout",
+        "tls handshake timeout",
+    ]
+    .iter()
+    .any(|pattern| stderr_lower.contains(pattern))
+    {
+        return GitHubError::NetworkError {
+            message: stderr.lines().next().unwrap_or_default().trim().to_string(),
+        };
+    }
+    if stderr_lower.contains("not a git repository") || stderr_lower.contains("could not determine")
+    {
+        return GitHubError::NotGitHubRepo;
+    }
+
+    GitHubError::CommandFailed {
+        code,
+        stderr: stderr.trim().to_string(),
+        command,
+    }
+}
+
+/// Reads the `X-RateLimit-Reset` epoch from response headers gh printed
+/// (e.g. under `GH_DEBUG=api` or `gh api --include`).
+fn parse_rate_limit_reset(stderr_lower: &str) -> Option<i64> {
+    stderr_lower.lines().find_map(|line| {
+        line.trim()
+            .trim_start_matches(['<', ' '])
+            .strip_prefix("x-ratelimit-reset:")
+            .and_then(|value| value.trim().parse().ok())
+    })
+}
+
+/// Builds the `gh api graphql` argument list for a query and its variables.
+fn graphql_args(query: &str, variables: &[(&str, &str)]) -> Vec<String> {
+    let mut args = vec![
+        "api".to_string(),
+        "graphql".to_string(),
+        "-f".to_string(),
+        format!("query={}", query),
+    ];
+    for (name, value) in variables {
+        args.push("-f".to_string());
+        args.push(format!("{}={}", name, value));
+    }
+    args
+}
+
+/// Builds the `gh api` argument list, rejecting methods outside
+/// [`API_METHODS`] and any endpoint that isn't a relative REST path.
+fn api_args(
+    method: &str,
+    endpoint: &str,
+    fields: &[(String, String)],
+) -> Result<Vec<String>, GitHubError> {
+    let method = method.trim().to_uppercase();
+    if !API_METHODS.contains(&method.as_str()) {
+        return Err(GitHubError::InvalidApiRequest {
+            message: format!("method {method} is not allowed (use one of {API_METHODS:?})"),
+        });
+    }
+    let endpoint = endpoint.trim();
+    if !is_rest_path(endpoint) {
+        return Err(GitHubError::InvalidApiRequest {
+            message: format!("invalid endpoint {endpoint:?} (expected a relative REST path)"),
+        });
+    }
+
+    let mut args = vec![
+        "api".to_string(),
+        "-X".to_string(),
+        method,
+        endpoint.to_string(),
+    ];
+    for (key, value) in fields {
+        args.push("-f".to_string());
+        args.push(format!("{}={}", key, value));
+    }
+    Ok(args)
+}
+
+/// Whether `endpoint` is a relative REST path such as `repos/o/r/pulls`.
+///
+/// Rejects flags (`--hostname`, ...), absolute URLs, which gh would send
+/// to whatever host they name, and the `graphql` endpoint, which takes
+/// mutations through a POST regardless of [`API_METHODS`].
+fn is_rest_path(endpoint: &str) -> bool {
+    let path = endpoint.split(['?', '#']).next().unwrap_or_default();
+    let path = path.strip_prefix('/').unwrap_or(path);
+    let first = path.split('/').next().unwrap_or_default();
+    !path.is_empty()
+        && !endpoint.starts_with('-')
+        && !endpoint.starts_with("//")
+        && !endpoint.contains("://")
+        && !endpoint.chars().any(char::is_whitespace)
+        && !first.eq_ignore_ascii_case("graphql")
+        && !path.split('/').any(|segment| segment == "..")
+}
+
+/// Parses `gh api` output, treating an empty body as `null`.
+fn parse_api_output(stdout: &str) -> Result<serde_json::Value, GitHubError> {
+    if stdout.is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_str(stdout).map_err(|e| GitHubError::ParseError {
+        message: format!("gh api returned non-JSON output: {e}"),
+    })
+}
+
+/// Masks the values of `-f`/`-F` fields, which can hold tokens or other
+/// secrets, so the command can be logged and reported safely.
+fn redact_fields(args: &[&str]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut after_field_flag = false;
+    for arg in args {
+        if after_field_flag {
+            let key = arg.split_once('=').map_or(*arg, |(key, _)| key);
+            redacted.push(format!("{key}=***"));
+        } else {
+            redacted.push(arg.to_string());
+        }
+        after_field_flag = matches!(*arg, "-f" | "-F" | "--field" | "--raw-field");
+    }
+    redacted
+}
+
+/// Writes `script` as an executable fake `gh` in `dir` and returns a runner
+/// for `dir` that invokes it instead of the real binary.
+#[cfg(all(test, unix))]
+pub(crate) fn fake_gh(dir: &std::path::Path, script: &str) -> GitHub {
+    use std::os::unix::fs::PermissionsExt;
+
+    let program = dir.join("gh-fake");
+    std::fs::write(&program, format!("#!/bin/sh\n{script}")).unwrap();
+    std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+    GitHub {
+        repo_path: dir.to_path_buf(),
+        program,
+    }
 }
 
 #[cfg(test)]
@@ -187,6 +470,46 @@ mod tests {
         }
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_and_reaps_child() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let gh = GitHub::new(dir.path());
+        let script = format!("echo $$ > {}; exec sleep 30", pid_file.display());
+
+        let started = std::time::Instant::now();
+        let result = gh
+            .execute("sh", &["-c", &script], Duration::from_millis(300))
+            .await;
+
+        assert!(
+            matches!(result, Err(GitHubError::Timeout { .. })),
+            "expected Timeout, got {:?}",
+            result
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let pid: i32 = std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        // A reaped process no longer exists; a zombie would still accept signal 0
+        let alive = unsafe { libc::kill(pid, 0) } == 0;
+        assert!(!alive, "timed-out child {} was not reaped", pid);
+    }
+
+    #[test]
+    fn test_graphql_args_pass_values_verbatim() {
+        let body = "He said \"hi\"\r\nC:\\path\tend";
+        let args = graphql_args("mutation($body: String!) { x }", &[("body", body)]);
+        assert_eq!(args[..3], ["api", "graphql", "-f"]);
+        assert_eq!(args[3], "query=mutation($body: String!) { x }");
+        assert_eq!(args[4], "-f");
+        assert_eq!(args[5], format!("body={}", body));
+    }
+
     #[test]
     fn test_github_error_serialization() {
         let err = GitHubError::GhNotFound;
@@ -199,4 +522,114 @@ mod tests {
         let err = GitHubError::NotAuthenticated;
         assert!(err.to_string().contains("gh auth login"));
     }
+
+    #[test]
+    fn test_classify_failure_stderr_shapes() {
+        let classify = |stderr: &str| classify_failure(stderr, 1, "gh pr view 7".to_string());
+
+        assert!(matches!(
+            classify("GraphQL: API rate limit exceeded for user ID 1234."),
+            GitHubError::RateLimited { reset_at: None }
+        ));
+        assert!(matches!(
+            classify(
+                "< HTTP/2.0 403 Forbidden\n< X-Ratelimit-Remaining: 0\n< X-Ratelimit-Reset: 1760000000\n\
+                 gh: API rate limit exceeded for 203.0.113.7. (HTTP 403)"
+            ),
+            GitHubError::RateLimited {
+                reset_at: Some(1760000000)
+            }
+        ));
+        assert!(matches!(
+            classify("You are not logged into any GitHub hosts. To log in, run: gh auth login"),
+            GitHubError::NotAuthenticated
+        ));
+        match classify(
+            "error connecting to api.github.com\n\
+             check your internet connection or https://githubstatus.com",
+        ) {
+            GitHubError::NetworkError { message } => {
+                assert_eq!(message, "error connecting to api.github.com")
+            }
+            other => panic!("expected NetworkError, got {:?}", other),
+        }
+        assert!(matches!(
+            classify("Post \"https://api.github.com/graphql\": dial tcp: lookup api.github.com: no such host"),
+            GitHubError::NetworkError { .. }
+        ));
+
+        // A permissions 403 and a missing PR stay generic for callers to map
+        for stderr in [
+            "GraphQL: Resource not accessible by integration (HTTP 403)",
+            "GraphQL: Could not resolve to a PullRequest with the number of 7. (repository.pullRequest)",
+        ] {
+            assert!(matches!(
+                classify(stderr),
+                GitHubError::CommandFailed { code: 1, .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_api_args_get_with_fields() {
+        let fields = vec![
+            ("per_page".to_string(), "50".to_string()),
+            ("role".to_string(), "admin".to_string()),
+        ];
+        let args = api_args("get", "orgs/octo/teams", &fields).unwrap();
+        assert_eq!(
+            args,
+            [
+                "api",
+                "-X",
+                "GET",
+                "orgs/octo/teams",
+                "-f",
+                "per_page=50",
+                "-f",
+                "role=admin"
+            ]
+        );
+
+        assert!(matches!(
+            api_args("DELETE", "repos/o/r", &[]),
+            Err(GitHubError::InvalidApiRequest { .. })
+        ));
+        for endpoint in [
+            "--hostname=evil",
+            "--hostname",
+            "graphql",
+            "/GraphQL",
+            "https://evil.example/repos/o/r",
+            "//evil.example/repos/o/r",
+            "repos/o/r --hostname evil",
+            "repos/../graphql",
+        ] {
+            assert!(
+                matches!(
+                    api_args("POST", endpoint, &[]),
+                    Err(GitHubError::InvalidApiRequest { .. })
+                ),
+                "{endpoint} should be rejected"
+            );
+        }
+        assert!(api_args("GET", "/repos/o/r/pulls?state=open", &[]).is_ok());
+
+        let args: Vec<&str> = ["api", "-f", "token=s3cret", "-X", "POST"].to_vec();
+        assert_eq!(redact_fields(&args).join(" "), "api -f token=*** -X POST");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_api_surfaces_non_json_output_as_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let gh = fake_gh(dir.path(), "echo '<html>Not Found</html>'\n");
+        let result = gh.api("GET", "orgs/octo/teams", Vec::new()).await;
+        assert!(
+            matches!(result, Err(GitHubError::ParseError { .. })),
+            "expected ParseError, got {:?}",
+            result
+        );
+        assert_eq!(parse_api_output("").unwrap(), serde_json::Value::Null);
+    }
 }
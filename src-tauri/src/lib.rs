@@ -121,6 +121,23 @@ pub fn run() {
                 StatusServer::start(app_handle, instance_id).await
             });
 
+            // Restore marketplace sources and installed plugins saved last run
+            if let Err(e) =
+                commands::marketplace::hydrate(app.handle(), &app.state::<MarketplaceManager>())
+            {
+                log::warn!("Failed to load marketplace data: {}", e);
+            }
+
+            // Push session registry changes to the frontend
+            let emit_handle = app.handle().clone();
+            app.state::<SessionManager>().set_emitter(Arc::new(
+                move |event: &str, payload: serde_json::Value| {
+                    if let Err(e) = emit_handle.emit(event, payload) {
+                        log::warn!("Failed to emit {} event: {}", event, e);
+                    }
+                },
+            ));
+
             match server {
                 Some(server) => {
                     log::info!(
@@ -143,23 +160,42 @@ pub fn run() {
             // PTY commands (existing)
             commands::terminal::spawn_shell,
             commands::terminal::write_stdin,
+            commands::terminal::interrupt_session,
             commands::terminal::resize_pty,
+            commands::terminal::get_pty_size,
             commands::terminal::kill_session,
+            commands::terminal::restart_session,
+            commands::terminal::detach_session,
+            commands::terminal::record_session,
+            commands::terminal::stop_recording,
+            commands::terminal::get_terminal_state,
             commands::terminal::kill_all_sessions,
+            commands::terminal::shutdown_app,
+            commands::terminal::set_spawn_limit,
             commands::terminal::check_cli_available,
             commands::terminal::get_backend_info,
+            commands::terminal::list_backends,
+            commands::terminal::backend_capabilities,
             commands::terminal::get_session_process_tree,
             commands::terminal::get_all_process_trees,
             commands::terminal::kill_process,
             // Git commands
             commands::git::git_branches,
+            commands::git::git_list_branches_detailed,
             commands::git::git_current_branch,
+            commands::git::git_current_head,
             commands::git::git_uncommitted_count,
             commands::git::git_worktree_list,
             commands::git::git_worktree_add,
             commands::git::git_worktree_remove,
             commands::git::git_commit_log,
+            commands::git::git_commit_log_grouped,
+            commands::git::git_commits_between,
+            commands::git::git_merge_base,
             commands::git::git_checkout_branch,
+            commands::git::git_check_branch_name,
+            commands::git::git_list_hooks,
+            commands::git::git_set_hook_enabled,
             commands::git::git_create_branch,
             commands::git::git_commit_files,
             commands::git::git_user_config,
@@ -167,11 +203,20 @@ pub fn run() {
             commands::git::git_list_remotes,
             commands::git::git_add_remote,
             commands::git::git_remove_remote,
+            commands::git::git_fetch_prune,
+            commands::git::git_list_stale_branches,
+            commands::git::git_list_merged_branches,
+            commands::git::git_delete_merged_branches,
             commands::git::git_refs_for_commit,
             commands::git::git_test_remote,
             commands::git::git_set_remote_url,
             commands::git::git_get_default_branch,
+            commands::git::git_remote_default_branch,
             commands::git::git_set_default_branch,
+            commands::git::git_conflicted_files,
+            commands::git::git_resolve_conflict,
+            commands::git::git_clean_preview,
+            commands::git::git_clean_execute,
             commands::git::is_git_repository,
             commands::git::detect_repositories,
             // Session commands (new)
@@ -179,12 +224,21 @@ pub fn run() {
             commands::session::create_session,
             commands::session::update_session_status,
             commands::session::assign_session_branch,
+            commands::session::get_recent_branches,
+            commands::session::set_session_tags,
             commands::session::remove_session,
             commands::session::get_sessions_for_project,
+            commands::session::get_sessions_for_branch,
             commands::session::remove_sessions_for_project,
             // Worktree commands
             commands::worktree::prepare_session_worktree,
+            commands::worktree::prepare_existing_worktree,
+            commands::worktree::prepare_worktree_at_ref,
+            commands::worktree::switch_worktree_branch,
             commands::worktree::cleanup_session_worktree,
+            commands::worktree::get_worktree_disk_usage,
+            commands::worktree::prune_worktrees,
+            commands::worktree::audit_worktrees,
             // MCP commands
             commands::mcp::get_project_mcp_servers,
             commands::mcp::refresh_project_mcp_servers,
@@ -193,23 +247,39 @@ pub fn run() {
             commands::mcp::get_session_mcp_count,
             commands::mcp::save_project_mcp_defaults,
             commands::mcp::load_project_mcp_defaults,
+            commands::mcp::load_project_mcp_defaults_resolved,
             commands::mcp::add_mcp_project,
             commands::mcp::remove_mcp_project,
             commands::mcp::remove_session_status,
+            commands::mcp::register_session_status,
+            commands::mcp::unregister_session_status,
             commands::mcp::write_session_mcp_config,
+            commands::mcp::resolve_effective_mcp_servers,
             commands::mcp::remove_session_mcp_config,
+            commands::mcp::inspect_mcp_json,
+            commands::mcp::repair_mcp_json,
             commands::mcp::generate_project_hash,
             commands::mcp::get_custom_mcp_servers,
             commands::mcp::save_custom_mcp_server,
+            commands::mcp::set_custom_mcp_server_tags,
             commands::mcp::delete_custom_mcp_server,
             commands::mcp::get_status_server_info,
+            commands::mcp::status_server_alive,
+            commands::mcp::get_session_status_history,
+            commands::mcp::get_maestro_mcp_path,
             // Plugin commands
             commands::plugin::get_project_plugins,
             commands::plugin::refresh_project_plugins,
             commands::plugin::get_session_skills,
+            commands::plugin::get_session_skills_validated,
             commands::plugin::set_session_skills,
+            commands::plugin::set_all_session_skills,
+            commands::plugin::resolve_skill,
+            commands::plugin::get_skill_preview,
+            commands::plugin::validate_legacy_plugins,
             commands::plugin::get_session_plugins,
             commands::plugin::set_session_plugins,
+            commands::plugin::set_all_session_plugins,
             commands::plugin::get_session_skills_count,
             commands::plugin::get_session_plugins_count,
             commands::plugin::save_project_skill_defaults,
@@ -218,21 +288,37 @@ pub fn run() {
             commands::plugin::load_project_plugin_defaults,
             commands::plugin::write_session_plugin_config,
             commands::plugin::remove_session_plugin_config,
+            commands::plugin::write_enabled_plugins,
+            commands::plugin::clear_enabled_plugins,
             commands::plugin::delete_skill,
             commands::plugin::delete_plugin,
             commands::plugin::save_branch_config,
             commands::plugin::load_branch_config,
+            // File manager commands
+            commands::reveal::reveal_path,
+            commands::links::open_url,
+            // Session config export/import
+            commands::session_config::export_session_config,
+            commands::session_config::import_session_config,
+            commands::session_config::list_launch_profiles,
+            commands::session_config::save_launch_profile,
+            commands::session_config::apply_launch_profile,
             // Marketplace commands
             commands::marketplace::load_marketplace_data,
+            commands::marketplace::flush_marketplace_data,
             commands::marketplace::get_marketplace_sources,
             commands::marketplace::add_marketplace_source,
             commands::marketplace::remove_marketplace_source,
             commands::marketplace::toggle_marketplace_source,
+            commands::marketplace::reorder_marketplace_sources,
             commands::marketplace::refresh_marketplace,
             commands::marketplace::refresh_all_marketplaces,
             commands::marketplace::get_available_plugins,
             commands::marketplace::get_installed_plugins,
+            commands::marketplace::check_plugin_updates,
+            commands::marketplace::get_plugin_provenance,
             commands::marketplace::install_marketplace_plugin,
+            commands::marketplace::cancel_install,
             commands::marketplace::uninstall_plugin,
             commands::marketplace::is_marketplace_plugin_installed,
             commands::marketplace::get_session_marketplace_config,
@@ -242,22 +328,37 @@ pub fn run() {
             commands::claudemd::check_claude_md,
             commands::claudemd::read_claude_md,
             commands::claudemd::write_claude_md,
+            // Permission discovery commands
+            commands::permissions::read_project_permissions,
             // Font detection commands
             commands::fonts::get_available_fonts,
             commands::fonts::check_font_available,
+            // Diagnostics commands
+            commands::diagnostics::run_diagnostics,
             // Usage tracking commands
             commands::usage::get_claude_usage,
             // GitHub commands
             commands::github::github_auth_status,
+            commands::github::github_rate_limit,
+            commands::github::github_user_info,
+            commands::github::github_api,
+            commands::github::get_repo_metadata,
             commands::github::github_list_prs,
             commands::github::github_get_pr,
+            commands::github::export_pr_markdown,
+            commands::github::github_pr_review_comments,
             commands::github::github_create_pr,
+            commands::github::github_create_pr_for_session,
+            commands::github::github_fork_parent,
             commands::github::github_merge_pr,
+            commands::github::get_merge_defaults,
+            commands::github::set_merge_defaults,
             commands::github::github_close_pr,
             commands::github::github_comment_pr,
             commands::github::github_list_issues,
             commands::github::github_list_discussions,
             commands::github::github_get_issue,
+            commands::github::export_issue_markdown,
             commands::github::github_comment_issue,
             commands::github::github_close_issue,
             commands::github::github_reopen_issue,
@@ -268,8 +369,16 @@ pub fn run() {
             commands::update::download_and_install_update,
             commands::update::get_app_version,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running Maestro");
+        .build(tauri::generate_context!())
+        .expect("error while building Maestro")
+        .run(|app, event| {
+            // Give sessions their SIGTERM grace period and flush pending
+            // state before the process goes away
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                let grace = core::process_manager::DEFAULT_KILL_GRACE;
+                tauri::async_runtime::block_on(commands::terminal::shutdown(app, grace));
+            }
+        });
 }
 
 // Note: We intentionally don't check git availability at startup.
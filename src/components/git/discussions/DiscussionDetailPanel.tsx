@@ -7,6 +7,7 @@ import {
 } from "lucide-react";
 import { useState } from "react";
 import { useGitHubStore } from "../../../stores/useGitHubStore";
+import { openUrl } from "../../../lib/platform";
 import { MarkdownBody } from "../shared/MarkdownBody";
 import { CommentList } from "../shared/CommentList";
 import { parseEmoji } from "../shared/emojiUtils";
@@ -122,8 +123,12 @@ export function DiscussionDetailPanel({
         {/* Open on GitHub */}
         <a
           href={selectedDiscussion.url}
-          target="_blank"
-          rel="noopener noreferrer"
+          onClick={(e) => {
+            e.preventDefault();
+            openUrl(selectedDiscussion.url).catch((err) =>
+              console.error("Failed to open URL:", err),
+            );
+          }}
           className="mb-3 flex items-center gap-1 text-xs text-maestro-accent hover:underline"
         >
           <ExternalLink size={12} />
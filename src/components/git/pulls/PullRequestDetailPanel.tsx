@@ -10,6 +10,7 @@ import {
 } from "lucide-react";
 import { useState } from "react";
 import { useGitHubStore } from "../../../stores/useGitHubStore";
+import { openUrl } from "../../../lib/platform";
 import { MergePRModal } from "./MergePRModal";
 import { MarkdownBody } from "../shared/MarkdownBody";
 import { CommentList } from "../shared/CommentList";
@@ -210,8 +211,12 @@ export function PullRequestDetailPanel({
         {/* Open on GitHub */}
         <a
           href={selectedPR.url}
-          target="_blank"
-          rel="noopener noreferrer"
+          onClick={(e) => {
+            e.preventDefault();
+            openUrl(selectedPR.url).catch((err) =>
+              console.error("Failed to open URL:", err),
+            );
+          }}
           className="mb-3 flex items-center gap-1 text-xs text-maestro-accent hover:underline"
         >
           <ExternalLink size={12} />
@@ -9,6 +9,7 @@ import {
   Cpu,
   Edit2,
   FileText,
+  FolderOpen,
   GitBranch,
   Globe,
   Home,
@@ -43,6 +44,7 @@ import { useUsageStore } from "@/stores/useUsageStore";
 import { GitSettingsModal, RemoteStatusIndicator } from "@/components/git";
 import { QuickActionsManager } from "@/components/quickactions/QuickActionsManager";
 import { MarketplaceBrowser } from "@/components/marketplace";
+import { revealPath } from "@/lib/platform";
 import { McpServerEditorModal } from "@/components/mcp";
 import { ClaudeMdEditorModal } from "@/components/claudemd";
 import { CliSettingsModal } from "@/components/terminal/CliSettingsModal";
@@ -1083,6 +1085,12 @@ function PluginsSection() {
     await deleteSkill(skillId, skillDir, projectPath);
   }, [deleteSkill, projectPath]);
 
+  // Show a plugin or skill in the OS file manager
+  const handleReveal = useCallback((e: React.MouseEvent, path: string) => {
+    e.stopPropagation();
+    revealPath(path).catch((err) => console.error("Failed to reveal path:", err));
+  }, []);
+
   // Check if a plugin can be uninstalled (installed or marketplace, not builtin)
   const canUninstallPlugin = (plugin: typeof plugins[0]) => {
     return plugin.plugin_source === "installed" || plugin.plugin_source === "marketplace";
@@ -1194,6 +1202,16 @@ function PluginsSection() {
                             <span className="text-[10px] text-maestro-muted">{pluginSkills.length}</span>
                           )}
                           <span className="text-[10px] text-maestro-muted">v{plugin.version}</span>
+                          {plugin.path && (
+                            <button
+                              type="button"
+                              onClick={(e) => handleReveal(e, plugin.path!)}
+                              className="shrink-0 rounded p-0.5 opacity-0 group-hover:opacity-100 hover:bg-maestro-border/60 transition-opacity"
+                              title="Reveal in file manager"
+                            >
+                              <FolderOpen size={10} className="text-maestro-muted" />
+                            </button>
+                          )}
                           {canUninstallPlugin(plugin) && (
                             <button
                               type="button"
@@ -1251,6 +1269,16 @@ function PluginsSection() {
                         <span className={`shrink-0 rounded px-1 text-[9px] ${badge.className}`}>
                           {badge.text}
                         </span>
+                        {skill.path && (
+                          <button
+                            type="button"
+                            onClick={(e) => handleReveal(e, skill.path!)}
+                            className="shrink-0 rounded p-0.5 opacity-0 group-hover:opacity-100 hover:bg-maestro-border/60 transition-opacity"
+                            title="Reveal in file manager"
+                          >
+                            <FolderOpen size={10} className="text-maestro-muted" />
+                          </button>
+                        )}
                         {canDeleteSkill(skill) && (
                           <button
                             type="button"
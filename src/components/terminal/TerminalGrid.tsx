@@ -6,7 +6,6 @@ import { getBranchesWithWorktreeStatus, type BranchWithWorktreeStatus } from "@/
 import { removeSessionMcpConfig, setSessionMcpServers, writeSessionMcpConfig, type McpServerConfig } from "@/lib/mcp";
 import {
   loadBranchConfig,
-  removeSessionPluginConfig,
   saveBranchConfig,
   setSessionPlugins,
   setSessionSkills,
@@ -553,12 +552,17 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
             // This allows the CLI to discover MCP servers including the Maestro status server
             if (workingDirectory && slot.mode === "Claude") {
               try {
-                await writeSessionMcpConfig(
+                const mcpStatus = await writeSessionMcpConfig(
                   workingDirectory,
                   sessionId,
                   projectPath ?? workingDirectory,
                   slot.enabledMcpServers
                 );
+                if (!mcpStatus.available) {
+                  console.warn(
+                    "MCP status server not found — reinstall Maestro to enable session status reporting"
+                  );
+                }
               } catch (err) {
                 console.error("Failed to write MCP config:", err);
                 // Non-fatal - continue with CLI launch, MCP servers just won't be available
@@ -673,10 +677,8 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
       removeSessionMcpConfig(workingDir, sessionId).catch(console.error);
     }
 
-    // Clean up session-specific plugin config (fire-and-forget)
-    if (workingDir) {
-      removeSessionPluginConfig(workingDir).catch(console.error);
-    }
+    // Plugin config is removed by the backend's kill_session, which knows
+    // whether another session still shares the working directory
 
     // Clean up worktree if one was created (fire-and-forget)
     // Use effectiveRepoPath for worktree cleanup since worktrees are git-repo specific
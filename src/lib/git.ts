@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { CommitInfo } from "../stores/useGitStore";
 import { listWorktrees } from "./worktreeManager";
 
 /** Branch info from the backend. */
@@ -6,8 +7,46 @@ export interface BranchInfo {
   name: string;
   is_remote: boolean;
   is_current: boolean;
+  /** Tracked upstream; only set when fetched with `includeDetails`. */
+  upstream: string | null;
+  /** Checked out in a linked worktree; only set when fetched with `includeDetails`. */
+  has_worktree: boolean;
 }
 
+/** Branch info with its latest commit, from `git_list_branches_detailed`. */
+export interface BranchDetail {
+  name: string;
+  is_remote: boolean;
+  is_current: boolean;
+  /** Committer date of the tip commit (Unix seconds). */
+  last_commit_date: number;
+  last_commit_subject: string;
+}
+
+/** How a path is unmerged (from `git status --porcelain`). */
+export type ConflictState =
+  | "bothModified"
+  | "bothAdded"
+  | "bothDeleted"
+  | "addedByUs"
+  | "addedByThem"
+  | "deletedByUs"
+  | "deletedByThem";
+
+/** An unmerged path reported by `git_conflicted_files`. */
+export interface ConflictedFile {
+  /** Path relative to the repository root. */
+  path: string;
+  state: ConflictState;
+  /** Number of `<<<<<<<` markers, or null if the file is absent. */
+  marker_count: number | null;
+  ours: string | null;
+  theirs: string | null;
+}
+
+/** Strategy for `resolveConflict`. */
+export type ConflictResolution = "ours" | "theirs" | "union";
+
 /** Extended branch info with worktree status for UI display. */
 export interface BranchWithWorktreeStatus {
   name: string;
@@ -25,10 +64,168 @@ const activeFetches = new Map<string, Promise<string>>();
 /**
  * Fetches all branches for a repository.
  * @param repoPath - Path to the git repository
+ * @param includeDetails - Also fill in `upstream` and `has_worktree` (costs two extra git calls)
  * @returns List of branch info from the backend
  */
-export async function getBranches(repoPath: string): Promise<BranchInfo[]> {
-  return invoke<BranchInfo[]>("git_branches", { repoPath });
+export async function getBranches(
+  repoPath: string,
+  includeDetails = false
+): Promise<BranchInfo[]> {
+  return invoke<BranchInfo[]>("git_branches", { repoPath, includeDetails });
+}
+
+/** A hook script in a repo's (or worktree's) hooks directory. */
+export interface HookInfo {
+  name: string;
+  path: string;
+  executable: boolean;
+  /** `false` if renamed with the `.disabled` suffix. */
+  enabled: boolean;
+}
+
+/**
+ * Lists hook scripts (enabled and disabled) git would use for a repo or worktree.
+ * @param repoPath - Path to the git repository or worktree
+ */
+export async function listHooks(repoPath: string): Promise<HookInfo[]> {
+  return invoke<HookInfo[]>("git_list_hooks", { repoPath });
+}
+
+/**
+ * Enables or disables a hook by renaming it to/from `<name>.disabled`.
+ * @param repoPath - Path to the git repository or worktree
+ * @param name - Hook name, e.g. `pre-commit`
+ * @param enabled - Whether git should run the hook
+ */
+export async function setHookEnabled(
+  repoPath: string,
+  name: string,
+  enabled: boolean
+): Promise<void> {
+  return invoke("git_set_hook_enabled", { repoPath, name, enabled });
+}
+
+/**
+ * Checks whether a branch name is valid (`git check-ref-format --branch`)
+ * without creating anything.
+ * @param repoPath - Path to the git repository
+ * @param branchName - The proposed branch name
+ * @returns Whether git would accept the name
+ */
+export async function checkBranchName(repoPath: string, branchName: string): Promise<boolean> {
+  return invoke<boolean>("git_check_branch_name", { repoPath, branchName });
+}
+
+/**
+ * Fetches all branches with their latest commit, most recent first.
+ * Slower than `getBranches`; use it where recency ordering matters.
+ * @param repoPath - Path to the git repository
+ */
+export async function getBranchesDetailed(repoPath: string): Promise<BranchDetail[]> {
+  return invoke<BranchDetail[]>("git_list_branches_detailed", { repoPath });
+}
+
+/** How `getCommitLogGrouped` buckets commits; days are UTC. */
+export type CommitGroupBy = "author" | "day";
+
+/** Commits sharing an author name or a `YYYY-MM-DD` day, in log order. */
+export interface CommitGroup {
+  key: string;
+  commits: CommitInfo[];
+}
+
+/**
+ * Returns the current branch's recent commits grouped by author or by day,
+ * groups ordered by their newest commit.
+ * @param repoPath - Path to the git repository
+ * @param groupBy - Whether to group by author or by day
+ * @param max - Maximum number of commits to group
+ */
+export async function getCommitLogGrouped(
+  repoPath: string,
+  groupBy: CommitGroupBy,
+  max = 200
+): Promise<CommitGroup[]> {
+  return invoke<CommitGroup[]>("git_commit_log_grouped", { repoPath, max, groupBy });
+}
+
+/**
+ * Lists the commits `head` adds over `base` (`git log base..head`), newest first.
+ * @param repoPath - Path to the git repository
+ * @param base - Branch or commit the head is compared against, e.g. "main"
+ * @param head - Branch or commit whose extra commits are listed
+ * @param max - Maximum number of commits to return
+ */
+export async function getCommitsBetween(
+  repoPath: string,
+  base: string,
+  head: string,
+  max = 100
+): Promise<CommitInfo[]> {
+  return invoke<CommitInfo[]>("git_commits_between", { repoPath, base, head, max });
+}
+
+/**
+ * Returns the commit where two branches diverged (`git merge-base`).
+ *
+ * @param repoPath - Path to the git repository
+ * @param a - First branch or commit
+ * @param b - Second branch or commit
+ * @returns The common ancestor's hash, or null for unrelated histories
+ */
+export async function getMergeBase(
+  repoPath: string,
+  a: string,
+  b: string
+): Promise<string | null> {
+  return invoke<string | null>("git_merge_base", { repoPath, a, b });
+}
+
+/**
+ * Lists local branches already merged into `into`, excluding `into` and the
+ * current branch.
+ * @param repoPath - Path to the git repository
+ * @param into - Branch the others are checked against, usually the default branch
+ */
+export async function getMergedBranches(repoPath: string, into: string): Promise<string[]> {
+  return invoke<string[]>("git_list_merged_branches", { repoPath, into });
+}
+
+/**
+ * Deletes local branches merged into `into`, skipping any checked out in a worktree.
+ * @param repoPath - Path to the git repository
+ * @param into - Branch the others are checked against
+ * @param dryRun - Only report what would be deleted
+ * @returns The deleted (or, with `dryRun`, deletable) branch names
+ */
+export async function deleteMergedBranches(
+  repoPath: string,
+  into: string,
+  dryRun: boolean
+): Promise<string[]> {
+  return invoke<string[]>("git_delete_merged_branches", { repoPath, into, dryRun });
+}
+
+/**
+ * Lists files left unmerged by a merge, rebase, or cherry-pick.
+ * @param repoPath - Path to the git repository or worktree
+ */
+export async function getConflictedFiles(repoPath: string): Promise<ConflictedFile[]> {
+  return invoke<ConflictedFile[]>("git_conflicted_files", { repoPath });
+}
+
+/**
+ * Resolves one conflicted file with the given strategy and stages the result.
+ * @param repoPath - Path to the git repository or worktree
+ * @param path - Conflicted path, relative to the repository root
+ * @param strategy - Keep our side, their side, or the union of both
+ */
+export async function resolveConflict(
+  repoPath: string,
+  path: string,
+  strategy: ConflictResolution,
+): Promise<void> {
+  return invoke("git_resolve_conflict", { repoPath, path, strategy });
 }
 
 /**
@@ -67,6 +264,24 @@ export async function getCurrentBranch(repoPath: string): Promise<string> {
   return invoke<string>("git_current_branch", { repoPath });
 }
 
+/** HEAD of a working tree, from `getCurrentHead`. */
+export interface HeadInfo {
+  kind: "branch" | "detached";
+  /** Branch short name, or the full commit SHA when detached. */
+  name_or_sha: string;
+  /** Upstream of the branch (e.g. "origin/main"); null when detached or untracked. */
+  upstream: string | null;
+}
+
+/**
+ * Gets what HEAD points at. Prefer this over `getCurrentBranch` when a detached
+ * commit must not be shown as a branch.
+ * @param repoPath - Path to the git repository
+ */
+export async function getCurrentHead(repoPath: string): Promise<HeadInfo> {
+  return invoke<HeadInfo>("git_current_head", { repoPath });
+}
+
 /**
  * Gets the current branch name, deduplicating simultaneous requests for the same path.
  * Useful when multiple sessions or components need the branch status at once.
@@ -5,23 +5,35 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type {
   InstallScope,
   InstalledPlugin,
   MarketplacePlugin,
+  MarketplaceRefreshDone,
+  MarketplaceRefreshProgress,
   MarketplaceSource,
+  Provenance,
   SessionMarketplaceConfig,
 } from "@/types/marketplace";
 
 // ========== Data Loading ==========
 
 /**
- * Loads persisted marketplace data from the store.
+ * Reloads persisted marketplace data from the store (the backend also loads it at startup).
  */
 export async function loadMarketplaceData(): Promise<void> {
   return invoke("load_marketplace_data");
 }
 
+/**
+ * Writes marketplace data to the store now instead of waiting for the
+ * debounced save that follows each mutation.
+ */
+export async function flushMarketplaceData(): Promise<void> {
+  return invoke("flush_marketplace_data");
+}
+
 // ========== Source Management ==========
 
 /**
@@ -73,11 +85,34 @@ export async function refreshMarketplace(sourceId: string): Promise<MarketplaceP
 
 /**
  * Refreshes all enabled marketplace sources.
+ * Progress is reported through `onMarketplaceRefreshProgress` while this runs.
  */
 export async function refreshAllMarketplaces(): Promise<void> {
   return invoke("refresh_all_marketplaces");
 }
 
+/**
+ * Subscribes to refresh progress: `onProgress` fires as each source finishes,
+ * `onDone` once all have. Returns a function that removes both listeners.
+ */
+export async function onMarketplaceRefreshProgress(
+  onProgress: (progress: MarketplaceRefreshProgress) => void,
+  onDone?: (done: MarketplaceRefreshDone) => void,
+): Promise<UnlistenFn> {
+  const unlistenProgress = await listen<MarketplaceRefreshProgress>(
+    "marketplace-refresh-progress",
+    (event) => onProgress(event.payload),
+  );
+  const unlistenDone = await listen<MarketplaceRefreshDone>(
+    "marketplace-refresh-done",
+    (event) => onDone?.(event.payload),
+  );
+  return () => {
+    unlistenProgress();
+    unlistenDone();
+  };
+}
+
 /**
  * Gets all available plugins from enabled marketplaces.
  */
@@ -94,21 +129,39 @@ export async function getInstalledPlugins(): Promise<InstalledPlugin[]> {
   return invoke<InstalledPlugin[]>("get_installed_plugins");
 }
 
+/**
+ * Reports whether an installed plugin's marketplace and catalog entry still exist.
+ */
+export async function getPluginProvenance(installedPluginId: string): Promise<Provenance> {
+  return invoke<Provenance>("get_plugin_provenance", { installedPluginId });
+}
+
 /**
  * Installs a plugin from a marketplace.
+ * Pass `operationId` to be able to cancel the install with `cancelInstall`;
+ * otherwise one is generated and announced in `marketplace:install-started`.
  */
 export async function installMarketplacePlugin(
   marketplacePluginId: string,
   scope: InstallScope,
-  projectPath?: string
+  projectPath?: string,
+  operationId?: string
 ): Promise<InstalledPlugin> {
   return invoke<InstalledPlugin>("install_marketplace_plugin", {
     marketplacePluginId,
     scope,
     projectPath,
+    operationId,
   });
 }
 
+/**
+ * Cancels an in-progress install. Resolves to `false` if it already finished.
+ */
+export async function cancelInstall(operationId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_install", { operationId });
+}
+
 /**
  * Uninstalls a plugin by its installed ID.
  */
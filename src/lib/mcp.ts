@@ -30,10 +30,20 @@ export interface McpCustomServer {
   isEnabled: boolean;
   /** ISO timestamp of when the server was created. */
   createdAt: string;
+  /** Session tags this server is limited to; untagged servers go to every session. */
+  tags?: string[];
 }
 
-/** Source of an MCP server discovery. */
-export type McpServerSource = "project" | "user" | "local" | "custom";
+/**
+ * Source of an MCP server discovery. Plugin-bundled servers name the plugin
+ * (its Maestro plugin ID) they come from.
+ */
+export type McpServerSource =
+  | "project"
+  | "user"
+  | "local"
+  | "custom"
+  | { plugin: { plugin_id: string } };
 
 /**
  * Stdio MCP server config (flattened from backend).
@@ -78,14 +88,15 @@ export async function refreshProjectMcpServers(projectPath: string): Promise<Mcp
 }
 
 /**
- * Gets the enabled MCP server names for a specific session.
- * If not explicitly set, returns all available servers.
+ * Gets the MCP servers enabled for a specific session.
+ * If not explicitly set, all discovered servers are enabled. Servers bundled
+ * in the session's enabled plugins follow, with a plugin `source`.
  */
 export async function getSessionMcpServers(
   projectPath: string,
   sessionId: number
-): Promise<string[]> {
-  return invoke<string[]>("get_session_mcp_servers", { projectPath, sessionId });
+): Promise<McpServerConfig[]> {
+  return invoke<McpServerConfig[]>("get_session_mcp_servers", { projectPath, sessionId });
 }
 
 /**
@@ -130,6 +141,40 @@ export async function loadProjectMcpDefaults(
   return invoke<string[] | null>("load_project_mcp_defaults", { projectPath });
 }
 
+/** A project's default MCP servers, resolved from every layer. */
+export interface ResolvedMcpDefaults {
+  /** Servers enabled after merging, sorted by name. */
+  enabled: string[];
+  /**
+   * `.maestro/mcp-defaults.json` files that contributed, outermost first.
+   * Empty when the saved selection applied, since it replaces the files.
+   */
+  sources: string[];
+  /** Whether the selection saved with `saveProjectMcpDefaults` applied. */
+  saved: boolean;
+}
+
+/**
+ * Resolves a project's default MCP servers: any `.maestro/mcp-defaults.json`
+ * files between it and its git root (nearer files win), replaced by the
+ * selection saved with `saveProjectMcpDefaults` if any.
+ * Returns null if neither exists.
+ */
+export async function loadProjectMcpDefaultsResolved(
+  projectPath: string
+): Promise<ResolvedMcpDefaults | null> {
+  return invoke<ResolvedMcpDefaults | null>("load_project_mcp_defaults_resolved", {
+    projectPath,
+  });
+}
+
+/** Whether a session's `.mcp.json` got the Maestro status server entry. */
+export interface MaestroMcpStatus {
+  /** False when maestro-mcp-server could not be found; status reporting won't work. */
+  available: boolean;
+  path: string | null;
+}
+
 /**
  * Writes a session-specific `.mcp.json` to the working directory.
  *
@@ -140,14 +185,15 @@ export async function loadProjectMcpDefaults(
  * @param sessionId - Session ID for the Maestro MCP server env vars
  * @param projectPath - Project path for hash generation and server lookup
  * @param enabledServerNames - Names of MCP servers enabled for this session
+ * @returns Whether the Maestro status server binary was found
  */
 export async function writeSessionMcpConfig(
   workingDir: string,
   sessionId: number,
   projectPath: string,
   enabledServerNames: string[]
-): Promise<void> {
-  return invoke("write_session_mcp_config", {
+): Promise<MaestroMcpStatus> {
+  return invoke<MaestroMcpStatus>("write_session_mcp_config", {
     workingDir,
     sessionId,
     projectPath,
@@ -155,6 +201,65 @@ export async function writeSessionMcpConfig(
   });
 }
 
+/**
+ * Origin of a server a session receives. Plugin servers are loaded by Claude
+ * through their plugin rather than written into the session's `.mcp.json`.
+ */
+export type EffectiveMcpSource =
+  | "maestro"
+  | "discovered"
+  | "custom"
+  | { plugin: { plugin_id: string } };
+
+/** An MCP server a session will receive (command, args, and env omitted). */
+export interface EffectiveMcpServer {
+  name: string;
+  source: EffectiveMcpSource;
+  transport: "stdio" | "http";
+}
+
+/**
+ * Lists the MCP servers a session will actually receive, tagged by origin.
+ *
+ * @param projectPath - Project path used for server lookup
+ * @param sessionId - Session whose enabled servers are resolved
+ */
+export async function resolveEffectiveMcpServers(
+  projectPath: string,
+  sessionId: number,
+): Promise<EffectiveMcpServer[]> {
+  return invoke<EffectiveMcpServer[]>("resolve_effective_mcp_servers", {
+    projectPath,
+    sessionId,
+  });
+}
+
+/** Whether a working directory's `.mcp.json` can be merged into. */
+export interface McpJsonHealth {
+  /** False when the file exists but isn't valid; a missing file is valid. */
+  valid: boolean;
+  /** Why the file is invalid, including the parse error's position. */
+  error: string | null;
+  server_count: number;
+}
+
+/**
+ * Checks the working directory's `.mcp.json`, e.g. after writing a session's
+ * MCP config failed.
+ */
+export async function inspectMcpJson(workingDir: string): Promise<McpJsonHealth> {
+  return invoke<McpJsonHealth>("inspect_mcp_json", { workingDir });
+}
+
+/**
+ * Backs up an invalid `.mcp.json` and replaces it with an empty config.
+ *
+ * @returns The backup's path, or null if the file was already valid
+ */
+export async function repairMcpJson(workingDir: string): Promise<string | null> {
+  return invoke<string | null>("repair_mcp_json", { workingDir });
+}
+
 /**
  * Removes a session-specific Maestro server from `.mcp.json`.
  *
@@ -171,6 +276,46 @@ export async function removeSessionMcpConfig(
   return invoke("remove_session_mcp_config", { workingDir, sessionId });
 }
 
+/**
+ * Registers a session with the status server after it is relaunched or
+ * restored, so its status reports route to the right project. Flushes any
+ * status buffered while the session was unregistered.
+ *
+ * @param sessionId - Session to (re-)register
+ * @param projectPath - Project the session belongs to
+ */
+export async function registerSessionStatus(
+  sessionId: number,
+  projectPath: string,
+): Promise<void> {
+  return invoke("register_session_status", { sessionId, projectPath });
+}
+
+/**
+ * Unregisters a session from the status server, dropping buffered statuses.
+ *
+ * @param sessionId - Session to unregister
+ */
+export async function unregisterSessionStatus(sessionId: number): Promise<void> {
+  return invoke("unregister_session_status", { sessionId });
+}
+
+/** Payload of the periodic `status-server-heartbeat` event. */
+export interface StatusServerHeartbeat {
+  instance_id: string;
+  port: number;
+  /** False once the server has stopped accepting status reports. */
+  alive: boolean;
+}
+
+/**
+ * Whether the status server is accepting status reports. Changes are also
+ * pushed as `status-server-heartbeat` events.
+ */
+export async function statusServerAlive(): Promise<boolean> {
+  return invoke<boolean>("status_server_alive");
+}
+
 /**
  * Gets all custom MCP servers configured by the user.
  * Custom servers are stored globally and available across all projects.
@@ -187,6 +332,14 @@ export async function saveCustomMcpServer(server: McpCustomServer): Promise<void
   return invoke("save_custom_mcp_server", { server });
 }
 
+/**
+ * Limits a custom MCP server to sessions sharing one of `tags`. An empty list
+ * makes it available to every session again.
+ */
+export async function setCustomMcpServerTags(serverId: string, tags: string[]): Promise<void> {
+  return invoke("set_custom_mcp_server_tags", { serverId, tags });
+}
+
 /**
  * Deletes a custom MCP server by ID.
  */
@@ -1,6 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+
 /**
  * Detect whether the app is running on macOS (for platform-specific UI such as native traffic lights).
  */
 export function isMac(): boolean {
   return navigator.platform.toLowerCase().includes("mac");
 }
+
+/**
+ * Opens the OS file manager with the given path selected.
+ *
+ * Only paths within ~/.claude, a managed worktree, or a project with an open
+ * session are accepted (plugins, skills, worktrees, .mcp.json).
+ */
+export async function revealPath(path: string): Promise<void> {
+  return invoke("reveal_path", { path });
+}
+
+/**
+ * Opens a GitHub pull request, issue or discussion URL in the default browser.
+ *
+ * Only https URLs on github.com or the enterprise host `gh` uses (`GH_HOST`)
+ * are accepted.
+ */
+export async function openUrl(url: string): Promise<void> {
+  return invoke("open_url", { url });
+}
@@ -43,6 +43,8 @@ interface BaseSkillConfig {
   context: string | null;
   /** Subagent type when context="fork". */
   agent: string | null;
+  /** Author-defined frontmatter keys (e.g. `category`, `tags`) for grouping and filtering. */
+  metadata?: Record<string, string>;
 }
 
 /**
@@ -91,6 +93,8 @@ interface BasePluginConfig {
   icon: string | null;
   /** Claude CLI plugin ID (e.g. "name@marketplace") for enabledPlugins config. */
   cli_id: string | null;
+  /** Scope the CLI installed this plugin under, if from installed_plugins.json. */
+  install_scope?: "user" | "project" | "local" | null;
   skills: string[];
   mcp_servers: string[];
   hooks: HookConfig[];
@@ -164,6 +168,28 @@ export async function refreshProjectPlugins(projectPath: string): Promise<Projec
   return invoke<ProjectPlugins>("refresh_project_plugins", { projectPath });
 }
 
+/** A legacy `.plugins.json` skill entry that is ignored when loading. */
+export interface SkippedLegacyEntry {
+  id: string;
+  reason: string;
+}
+
+/** Report from `validateLegacyPlugins`. */
+export interface LegacyPluginsReport {
+  valid_skills: string[];
+  skipped: SkippedLegacyEntry[];
+  /** One step per valid skill describing its `.claude/` equivalent. */
+  suggested_migration: string[];
+}
+
+/**
+ * Checks the project's legacy `.plugins.json`, reporting skipped skill entries
+ * and suggested `.claude/` equivalents. Rejects if the file is missing or malformed.
+ */
+export async function validateLegacyPlugins(projectPath: string): Promise<LegacyPluginsReport> {
+  return invoke<LegacyPluginsReport>("validate_legacy_plugins", { projectPath });
+}
+
 /**
  * Gets the enabled skill IDs for a specific session.
  * If not explicitly set, returns all available skills.
@@ -175,6 +201,17 @@ export async function getSessionSkills(
   return invoke<string[]>("get_session_skills", { projectPath, sessionId });
 }
 
+/**
+ * Like `getSessionSkills`, but drops file-based skills whose file was deleted
+ * since discovery. The backend refreshes its stale cache in the background.
+ */
+export async function getSessionSkillsValidated(
+  projectPath: string,
+  sessionId: number
+): Promise<string[]> {
+  return invoke<string[]>("get_session_skills_validated", { projectPath, sessionId });
+}
+
 /**
  * Sets the enabled skill IDs for a specific session.
  */
@@ -293,6 +330,23 @@ export async function removeSessionPluginConfig(workingDir: string): Promise<voi
   return invoke("remove_session_plugin_config", { workingDir });
 }
 
+/**
+ * Merges the session's enabled plugins into the `enabledPlugins` object of its
+ * worktree's (or project's) .claude/settings.local.json, keeping other entries.
+ * The backend removes them again when the session is killed.
+ */
+export async function writeEnabledPlugins(projectPath: string, sessionId: number): Promise<void> {
+  return invoke("write_enabled_plugins", { projectPath, sessionId });
+}
+
+/**
+ * Removes the plugin config written for a session, unless another session
+ * shares its working directory. `killSession` already does this.
+ */
+export async function clearEnabledPlugins(sessionId: number): Promise<void> {
+  return invoke("clear_enabled_plugins", { sessionId });
+}
+
 /**
  * Deletes a skill directory from the filesystem.
  *
@@ -353,3 +407,52 @@ export async function loadBranchConfig(
 ): Promise<BranchConfig | null> {
   return invoke<BranchConfig | null>("load_branch_config", { projectPath, branch });
 }
+
+/**
+ * A named per-project session setup: branch, env overrides, and the
+ * skills/plugins/MCP servers to enable.
+ */
+export interface LaunchProfile {
+  name: string;
+  /** Branch, or pattern like `feature/*`, the session should check out. */
+  branchPattern?: string | null;
+  env?: Record<string, string>;
+  enabledSkills?: string[];
+  enabledPlugins?: string[];
+  enabledMcpServers?: string[];
+}
+
+/** Result of applying a launch profile to a session. */
+export interface LaunchProfileApplied {
+  branchPattern: string | null;
+  /** Env to pass to `spawnShell` for the session. */
+  env: Record<string, string>;
+  skippedSkills: string[];
+  skippedPlugins: string[];
+  skippedMcpServers: string[];
+}
+
+/** Lists the project's launch profiles. */
+export async function listLaunchProfiles(projectPath: string): Promise<LaunchProfile[]> {
+  return invoke<LaunchProfile[]>("list_launch_profiles", { projectPath });
+}
+
+/** Saves a launch profile, replacing any profile with the same name. */
+export async function saveLaunchProfile(
+  projectPath: string,
+  profile: LaunchProfile
+): Promise<void> {
+  return invoke("save_launch_profile", { projectPath, profile });
+}
+
+/**
+ * Applies a launch profile's skill/plugin/MCP selection to a session.
+ * Items the project no longer has are skipped and reported.
+ */
+export async function applyLaunchProfile(
+  projectPath: string,
+  sessionId: number,
+  name: string
+): Promise<LaunchProfileApplied> {
+  return invoke<LaunchProfileApplied>("apply_launch_profile", { projectPath, sessionId, name });
+}
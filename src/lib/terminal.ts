@@ -15,10 +15,44 @@ import type { BackendCapabilities, BackendType } from "./terminalTheme";
  * @param env - Environment variables to pass to the shell process. These are inherited
  *   by all child processes (including Claude CLI → MCP server). MAESTRO_SESSION_ID is
  *   automatically set by the backend.
+ * @param backend - Terminal backend for this session (see `listBackends`); when omitted
+ *   or not compiled in, the backend default is used.
+ * @param shell - Shell binary to run instead of `$SHELL`; replaced by a fallback shell
+ *   if missing or not executable.
+ * @param loadDotenv - Also seed the env from `<cwd>/.env` (or `dotenvPath`). Values in
+ *   `env` win over the file, and the file never overrides app variables the shell inherits.
+ * @param dotenvPath - `.env` file to load instead, absolute or relative to `cwd`.
+ * @param envAllowlist - Start from an empty environment and inherit only these of the
+ *   app's variables, plus PATH, HOME, TERM and the locale. `env` and `.env` entries are
+ *   still added.
  * @returns The numeric session ID assigned by the backend.
  */
-export async function spawnShell(cwd?: string, env?: Record<string, string>): Promise<number> {
-  return invoke<number>("spawn_shell", { cwd: cwd ?? null, env: env ?? null });
+export async function spawnShell(
+  cwd?: string,
+  env?: Record<string, string>,
+  backend?: BackendType,
+  shell?: string,
+  loadDotenv?: boolean,
+  dotenvPath?: string,
+  envAllowlist?: string[],
+): Promise<number> {
+  return invoke<number>("spawn_shell", {
+    cwd: cwd ?? null,
+    env: env ?? null,
+    shell: shell ?? null,
+    backend: backend ?? null,
+    loadDotenv: loadDotenv ?? null,
+    dotenvPath: dotenvPath ?? null,
+    envAllowlist: envAllowlist ?? null,
+  });
+}
+
+/**
+ * Sets how many shells may spawn at once (default 8); further `spawnShell`
+ * calls queue until one finishes setting up. Resolves to the applied limit.
+ */
+export async function setSpawnLimit(limit: number): Promise<number> {
+  return invoke<number>("set_spawn_limit", { limit });
 }
 
 /** Writes raw bytes to the PTY stdin of the given session. */
@@ -26,14 +60,82 @@ export async function writeStdin(sessionId: number, data: string): Promise<void>
   return invoke("write_stdin", { sessionId, data });
 }
 
+/**
+ * Sends Ctrl-C to whatever is running in the session. The shell itself
+ * keeps running; use `killSession` to end it.
+ */
+export async function interruptSession(sessionId: number): Promise<void> {
+  return invoke("interrupt_session", { sessionId });
+}
+
 /** Notifies the backend PTY of a terminal dimension change (rows x cols). */
 export async function resizePty(sessionId: number, rows: number, cols: number): Promise<void> {
   return invoke("resize_pty", { sessionId, rows, cols });
 }
 
-/** Terminates the backend PTY process and cleans up the session. */
-export async function killSession(sessionId: number): Promise<void> {
-  return invoke("kill_session", { sessionId });
+/**
+ * Returns the size currently applied to the backend PTY. A resize that was
+ * coalesced shows up here once the backend flushes it.
+ */
+export async function getPtySize(sessionId: number): Promise<{ rows: number; cols: number }> {
+  return invoke("get_pty_size", { sessionId });
+}
+
+/**
+ * Terminates the backend PTY process and cleans up the session.
+ * The process gets `graceMs` (default 2000) to exit after SIGTERM before SIGKILL.
+ */
+export async function killSession(sessionId: number, graceMs?: number): Promise<void> {
+  return invoke("kill_session", { sessionId, graceMs: graceMs ?? null });
+}
+
+/** What `shutdownApp` cleaned up. */
+export interface ShutdownReport {
+  sessionsKilled: number;
+  sessionsRemoved: number;
+  statusesUnregistered: number;
+  marketplaceFlushed: boolean;
+}
+
+/**
+ * Terminates every session, each with `graceMs` (default 2000) between SIGTERM
+ * and SIGKILL, and flushes pending state. Also runs automatically on quit.
+ */
+export async function shutdownApp(graceMs?: number): Promise<ShutdownReport> {
+  return invoke<ShutdownReport>("shutdown_app", { graceMs: graceMs ?? null });
+}
+
+/**
+ * Starts recording the session's raw PTY output to `path`, for debugging rendering issues.
+ * @param includeInput - Also record data written to stdin (default false).
+ * @param maxBytes - Size at which the file rotates to `path.1`; defaults to 16 MiB.
+ */
+export async function recordSession(
+  sessionId: number,
+  path: string,
+  includeInput?: boolean,
+  maxBytes?: number,
+): Promise<void> {
+  return invoke("record_session", {
+    sessionId,
+    path,
+    includeInput: includeInput ?? null,
+    maxBytes: maxBytes ?? null,
+  });
+}
+
+/** Stops recording the session. Resolves to whether a recording was in progress. */
+export async function stopRecording(sessionId: number): Promise<boolean> {
+  return invoke<boolean>("stop_recording", { sessionId });
+}
+
+/**
+ * Stops managing the session without killing it and resolves to the shell's PID.
+ * Maestro closes its end of the terminal, so only processes that ignore SIGHUP
+ * (e.g. started with `nohup` or `setsid`) keep running.
+ */
+export async function detachSession(sessionId: number): Promise<number> {
+  return invoke<number>("detach_session", { sessionId });
 }
 
 /** AI mode variants matching the backend enum. */
@@ -83,6 +185,7 @@ export interface SessionConfig {
   status: string;
   worktree_path: string | null;
   project_path: string;
+  tags: string[];
 }
 
 /** Creates a session in the SessionManager (separate from PTY spawning). */
@@ -103,6 +206,36 @@ export async function assignSessionBranch(
   return invoke<SessionConfig>("assign_session_branch", { sessionId, branch, worktreePath });
 }
 
+/** A branch a project recently launched a session on. */
+export interface RecentBranch {
+  branch: string;
+  /** ISO timestamp of the latest assignment. */
+  lastUsedAt: string;
+}
+
+/**
+ * Lists the branches `assignSessionBranch` recently recorded for a project,
+ * most recent first, without duplicates. Persists across restarts.
+ * @param limit - Maximum number to return (the backend keeps at most 20)
+ */
+export async function getRecentBranches(
+  projectPath: string,
+  limit?: number
+): Promise<RecentBranch[]> {
+  return invoke<RecentBranch[]>("get_recent_branches", { projectPath, limit: limit ?? null });
+}
+
+/**
+ * Replaces a session's tags, which decide the tagged custom MCP servers its
+ * next MCP config includes.
+ */
+export async function setSessionTags(
+  sessionId: number,
+  tags: string[]
+): Promise<SessionConfig> {
+  return invoke<SessionConfig>("set_session_tags", { sessionId, tags });
+}
+
 /**
  * Subscribes to the per-session `pty-output-{sessionId}` Tauri event.
  * Returns a promise that resolves to an unlisten function. The caller must
@@ -117,6 +250,49 @@ export function onPtyOutput(
   });
 }
 
+/**
+ * Subscribes to the per-session `session-cwd-changed-{sessionId}` Tauri event,
+ * emitted when a shell reports a new working directory via OSC 7 (VTE backend only).
+ * Returns a promise that resolves to an unlisten function.
+ */
+export function onSessionCwdChanged(
+  sessionId: number,
+  callback: (cwd: string) => void,
+): Promise<UnlistenFn> {
+  return listen<string>(`session-cwd-changed-${sessionId}`, (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Subscribes to the per-session `command-finished-{sessionId}` Tauri event,
+ * emitted with the exit code (null if the shell omitted it) when a shell with
+ * OSC 133 integration finishes a command (VTE backend only).
+ * Returns a promise that resolves to an unlisten function.
+ */
+export function onCommandFinished(
+  sessionId: number,
+  callback: (exitCode: number | null) => void,
+): Promise<UnlistenFn> {
+  return listen<number | null>(`command-finished-${sessionId}`, (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Subscribes to the per-session `pty-resized-{sessionId}` Tauri event, emitted
+ * once a burst of `resizePty` calls has settled, with the size finally applied.
+ * Returns a promise that resolves to an unlisten function.
+ */
+export function onPtyResized(
+  sessionId: number,
+  callback: (size: { rows: number; cols: number }) => void,
+): Promise<UnlistenFn> {
+  return listen<{ rows: number; cols: number }>(`pty-resized-${sessionId}`, (event) => {
+    callback(event.payload);
+  });
+}
+
 /** Backend info as returned by the Rust backend. */
 export interface BackendInfo {
   backendType: BackendType;
@@ -138,6 +314,18 @@ export async function getBackendInfo(): Promise<BackendInfo> {
   return cachedBackendInfo;
 }
 
+/** Lists the terminal backends compiled into the app, default first. */
+export async function listBackends(): Promise<BackendInfo[]> {
+  return invoke<BackendInfo[]>("list_backends");
+}
+
+/** Returns the capabilities of the backend a specific session runs on. */
+export async function getSessionBackendCapabilities(
+  sessionId: number,
+): Promise<BackendCapabilities> {
+  return invoke<BackendCapabilities>("backend_capabilities", { sessionId });
+}
+
 /** Checks if the current backend supports enhanced terminal state. */
 export async function hasEnhancedState(): Promise<boolean> {
   const info = await getBackendInfo();
@@ -209,7 +209,7 @@ export interface BackendCapabilities {
   textReflow: boolean;
   /** Backend supports Kitty graphics protocol */
   kittyGraphics: boolean;
-  /** Backend supports shell integration hooks */
+  /** Shell integration hooks are active (the session's shell emitted OSC 133 markers) */
   shellIntegration: boolean;
   /** Name of the backend implementation */
   backendName: string;
@@ -233,4 +233,28 @@ export interface TerminalState {
   scrollbackTotal: number;
   /** Terminal title (set by shell escape sequences) */
   title: string | null;
+  /** Shell working directory, as last reported via OSC 7 */
+  cwd?: string | null;
+  /** Whether the shell has emitted OSC 133 shell integration markers */
+  shellIntegration?: boolean;
+  /** Marks of the command in progress */
+  currentCommand?: CommandMarks | null;
+  /** Marks and exit code of the most recently finished command */
+  lastCommand?: CommandMarks | null;
+}
+
+/**
+ * Boundaries of a shell command reported via OSC 133, as cursor rows.
+ */
+export interface CommandMarks {
+  /** Row where the prompt started (OSC 133 A) */
+  promptRow: number | null;
+  /** Row where command input started (OSC 133 B) */
+  commandRow: number | null;
+  /** Row where command output started (OSC 133 C) */
+  outputRow: number | null;
+  /** Row where the command finished (OSC 133 D) */
+  endRow: number | null;
+  /** Exit code, or null if the shell omitted it */
+  exitCode: number | null;
 }
@@ -9,6 +9,28 @@ export interface WorktreeInfo {
   is_bare: boolean;
   /** True for the main working tree (the original clone directory). */
   is_main_worktree: boolean;
+  /** Lock reason ("" if none was given), or null when not locked. */
+  locked: string | null;
+}
+
+/** What is wrong with a managed worktree, from `auditWorktrees`. */
+export type WorktreeIssueKind =
+  | "branchMissing"
+  | "directoryMissing"
+  | "linkBroken"
+  | "staleLock";
+
+/** Suggested fix: `git worktree prune`, `git worktree repair`, or recreating it. */
+export type WorktreeRemedy = "prune" | "repair" | "recreate";
+
+/** A problem with one managed worktree. */
+export interface WorktreeIssue {
+  path: string;
+  branch: string | null;
+  kind: WorktreeIssueKind;
+  remedy: WorktreeRemedy;
+  /** Lock reason, for `staleLock`. */
+  lock_reason?: string;
 }
 
 /** Result of preparing a worktree for a session. */
@@ -145,10 +167,14 @@ export async function removeSessionWorktree(
  * Lists all worktrees for a repository.
  *
  * @param repoPath - The path to the main repository
+ * @param managedOnly - Leave out the main worktree and detached worktrees
  * @returns List of worktree info
  */
-export async function listWorktrees(repoPath: string): Promise<WorktreeInfo[]> {
-  return invoke<WorktreeInfo[]>("git_worktree_list", { repoPath });
+export async function listWorktrees(
+  repoPath: string,
+  managedOnly = false
+): Promise<WorktreeInfo[]> {
+  return invoke<WorktreeInfo[]>("git_worktree_list", { repoPath, managedOnly });
 }
 
 /**
@@ -193,18 +219,27 @@ export async function getWorktreeForBranch(
  *
  * On any failure, falls back to the project path so sessions always launch.
  *
+ * With `noCheckout`, a newly created worktree starts with an empty working
+ * tree (useful for huge repos); only `checkoutPaths` are checked out.
+ *
  * @param projectPath - The path to the main repository
  * @param branch - The branch to checkout in the worktree (null to skip worktree)
+ * @param noCheckout - Create the worktree with `--no-checkout`
+ * @param checkoutPaths - Paths to check out after a `noCheckout` creation
  * @returns The preparation result with the working directory to use
  */
 export async function prepareSessionWorktree(
   projectPath: string,
-  branch: string | null
+  branch: string | null,
+  noCheckout?: boolean,
+  checkoutPaths?: string[]
 ): Promise<WorktreePreparationResult> {
   try {
     const result = await invoke<WorktreePreparationResult>("prepare_session_worktree", {
       projectPath,
       branch,
+      noCheckout,
+      checkoutPaths,
     });
 
     if (result.warning) {
@@ -232,6 +267,24 @@ export async function prepareSessionWorktree(
   }
 }
 
+/**
+ * Switches an existing worktree to another branch, keeping its directory.
+ *
+ * The branch is checked out (or created from the worktree's HEAD) inside the
+ * worktree; the main repo is not touched. Sessions in the worktree are
+ * reassigned to the new branch. Rejects branches checked out elsewhere.
+ *
+ * @param worktreePath - The worktree to switch
+ * @param newBranch - The branch to check out (local or remote ref)
+ * @returns The local branch name now checked out in the worktree
+ */
+export async function switchWorktreeBranch(
+  worktreePath: string,
+  newBranch: string
+): Promise<string> {
+  return invoke<string>("switch_worktree_branch", { worktreePath, newBranch });
+}
+
 /**
  * Cleans up a worktree when a session ends.
  *
@@ -266,3 +319,14 @@ export async function cleanupSessionWorktree(
     return false;
   }
 }
+
+/**
+ * Checks a project's managed worktrees for a deleted branch, a missing
+ * directory, a broken `.git` link, or a stale lock, so sessions don't keep
+ * working on stale refs.
+ *
+ * @param projectPath - The path to the main repository
+ */
+export async function auditWorktrees(projectPath: string): Promise<WorktreeIssue[]> {
+  return invoke<WorktreeIssue[]>("audit_worktrees", { projectPath });
+}
@@ -44,6 +44,139 @@ export interface Comment {
   isAnswer: boolean;
 }
 
+/** An inline review comment attached to a line of a PR's diff. */
+export interface ReviewComment {
+  id: number;
+  path: string;
+  /** Line in the current diff; null once the comment is outdated. */
+  line: number | null;
+  body: string;
+  author: PrAuthor;
+  /** ID of the comment this one replies to, if it is a reply. */
+  inReplyTo: number | null;
+  createdAt: string;
+}
+
+/** A review comment and its replies, oldest first. */
+export interface ReviewThread {
+  path: string;
+  line: number | null;
+  comments: ReviewComment[];
+}
+
+/** Quota for one GitHub API resource. */
+export interface RateLimitResource {
+  limit: number;
+  remaining: number;
+  used: number;
+  /** Unix time (seconds) at which `remaining` resets to `limit`. */
+  reset: number;
+}
+
+/** Remaining GitHub API quota per resource. */
+export interface RateLimitInfo {
+  core: RateLimitResource;
+  graphql: RateLimitResource;
+  search: RateLimitResource;
+}
+
+/** Returns the remaining GitHub API quota. Doesn't itself count against it. */
+export async function getRateLimit(repoPath: string): Promise<RateLimitInfo> {
+  return invoke<RateLimitInfo>("github_rate_limit", { repoPath });
+}
+
+/** A GitHub user's display info, for rendering avatars next to logins. */
+export interface UserInfo {
+  login: string;
+  /** Profile name, or null when the user hasn't set one. */
+  name: string | null;
+  avatarUrl: string;
+}
+
+/**
+ * Looks up a user's name and avatar. The backend caches recent users, so this
+ * is cheap to call for every comment author.
+ */
+export async function getUserInfo(repoPath: string, login: string): Promise<UserInfo> {
+  return invoke<UserInfo>("github_user_info", { repoPath, login });
+}
+
+/** The repository a fork was created from. */
+export interface ForkParent {
+  owner: string;
+  name: string;
+  /** Suggested PR base; empty if the parent has no branches yet. */
+  defaultBranch: string;
+}
+
+/**
+ * Returns the parent of the project's repository, or null if it isn't a fork.
+ * Used to pre-fill the base when opening a PR from a fork.
+ */
+export async function getForkParent(repoPath: string): Promise<ForkParent | null> {
+  return invoke<ForkParent | null>("github_fork_parent", { repoPath });
+}
+
+/** HTTP methods accepted by `githubApi`. */
+export type GitHubApiMethod = "GET" | "POST" | "PATCH" | "PUT";
+
+/**
+ * Calls a GitHub REST endpoint via `gh api` (e.g. `orgs/{org}/teams`) and
+ * resolves to the parsed JSON, or null for an empty response. `fields` are
+ * sent as string fields, i.e. query parameters for GET.
+ */
+export async function githubApi<T = unknown>(
+  repoPath: string,
+  method: GitHubApiMethod,
+  endpoint: string,
+  fields?: Record<string, string>,
+): Promise<T> {
+  return invoke<T>("github_api", {
+    repoPath,
+    method,
+    endpoint,
+    fields: fields ? Object.entries(fields) : null,
+  });
+}
+
+/** Renders a pull request's description and comments as a markdown document. */
+export async function exportPrMarkdown(repoPath: string, number: number): Promise<string> {
+  return invoke<string>("export_pr_markdown", { repoPath, number });
+}
+
+/** Renders an issue's description and comments as a markdown document. */
+export async function exportIssueMarkdown(repoPath: string, number: number): Promise<string> {
+  return invoke<string>("export_issue_markdown", { repoPath, number });
+}
+
+/** Identity of a project's GitHub repository. */
+export interface RepoMetadata {
+  owner: string;
+  name: string;
+  defaultBranch: string;
+  remoteUrl: string;
+  isPrivate: boolean;
+}
+
+/**
+ * Returns the project's GitHub repository metadata. Cached per project on the
+ * backend; pass `refresh` to look it up again.
+ */
+export async function getRepoMetadata(
+  projectPath: string,
+  refresh?: boolean
+): Promise<RepoMetadata> {
+  return invoke<RepoMetadata>("get_repo_metadata", { projectPath, refresh });
+}
+
+/** Returns a pull request's inline review comments, grouped into threads. */
+export async function getPrReviewThreads(
+  repoPath: string,
+  number: number
+): Promise<ReviewThread[]> {
+  return invoke<ReviewThread[]>("github_pr_review_comments", { repoPath, number });
+}
+
 /** Pull request information from GitHub. */
 export interface PullRequestInfo {
   number: number;
@@ -117,6 +250,25 @@ export interface DiscussionDetail extends DiscussionInfo {
 /** Merge method for pull requests. */
 export type MergeMethod = "merge" | "squash" | "rebase";
 
+/** A project's merge policy, used when a merge doesn't specify its own. */
+export interface MergeDefaults {
+  method: MergeMethod;
+  deleteBranch: boolean;
+}
+
+/** Returns the project's merge defaults (merge commit, keep branch if unset). */
+export async function getMergeDefaults(projectPath: string): Promise<MergeDefaults> {
+  return invoke<MergeDefaults>("get_merge_defaults", { projectPath });
+}
+
+/** Saves the merge method and branch deletion used when a merge omits them. */
+export async function setMergeDefaults(
+  projectPath: string,
+  defaults: MergeDefaults
+): Promise<void> {
+  await invoke("set_merge_defaults", { projectPath, defaults });
+}
+
 /** Filter state for PRs. */
 export type PrFilterState = "open" | "closed" | "merged" | "all";
 
@@ -173,13 +325,16 @@ interface GitHubState {
     body: string,
     base: string,
     head: string,
-    draft: boolean
+    draft: boolean,
+    /** Fork `head` was pushed to (`owner/name`); the PR then targets its parent. */
+    headRepo?: string
   ) => Promise<PullRequestInfo>;
+  /** Omitted options fall back to the project's merge defaults. */
   mergePullRequest: (
     repoPath: string,
     number: number,
-    method: MergeMethod,
-    deleteBranch: boolean
+    method?: MergeMethod,
+    deleteBranch?: boolean
   ) => Promise<void>;
   closePullRequest: (repoPath: string, number: number) => Promise<void>;
   commentPullRequest: (
@@ -278,7 +433,8 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
     body: string,
     base: string,
     head: string,
-    draft: boolean
+    draft: boolean,
+    headRepo?: string
   ) => {
     const pr = await invoke<PullRequestInfo>("github_create_pr", {
       repoPath,
@@ -287,6 +443,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
       base,
       head,
       draft,
+      headRepo: headRepo ?? null,
     });
     // Refresh PR list after creation
     await get().fetchPullRequests(repoPath);
@@ -296,8 +453,8 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
   mergePullRequest: async (
     repoPath: string,
     number: number,
-    method: MergeMethod,
-    deleteBranch: boolean
+    method?: MergeMethod,
+    deleteBranch?: boolean
   ) => {
     await invoke("github_merge_pr", {
       repoPath,
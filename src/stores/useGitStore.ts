@@ -6,6 +6,10 @@ export interface BranchInfo {
   name: string;
   is_remote: boolean;
   is_current: boolean;
+  /** Tracked upstream; only set when fetched with `includeDetails`. */
+  upstream: string | null;
+  /** Checked out in a linked worktree; only set when fetched with `includeDetails`. */
+  has_worktree: boolean;
 }
 
 /** Commit info returned from the backend. */
@@ -17,6 +21,19 @@ export interface CommitInfo {
   author_email: string;
   timestamp: number;
   summary: string;
+  /** Present only when the log was fetched with `verifySignatures`. */
+  signature?: SignatureStatus;
+  /** Git note on the commit; only looked up when fetched with `includeNotes`. */
+  notes?: string;
+}
+
+/** Outcome of verifying a commit's GPG signature. */
+export type SignatureState = "good" | "bad" | "unknown" | "none";
+
+/** Signature verification result for one commit. */
+export interface SignatureStatus {
+  state: SignatureState;
+  signer: string | null;
 }
 
 /** File change status enum. */
@@ -173,16 +190,16 @@ export const useGitStore = create<GitState>()((set, get) => ({
 
     set({ isLoadingMore: true });
     try {
-      const newCount = commits.length + LOAD_MORE_COUNT;
-      const allCommits = await invoke<CommitInfo[]>("git_commit_log", {
+      const olderCommits = await invoke<CommitInfo[]>("git_commit_log", {
         repoPath,
-        maxCount: newCount,
+        maxCount: LOAD_MORE_COUNT,
         allBranches,
+        skip: commits.length,
       });
       set({
-        commits: allCommits,
+        commits: [...commits, ...olderCommits],
         isLoadingMore: false,
-        hasMoreCommits: allCommits.length >= newCount,
+        hasMoreCommits: olderCommits.length >= LOAD_MORE_COUNT,
       });
     } catch (err) {
       console.error("Failed to load more commits:", err);
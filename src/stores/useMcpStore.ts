@@ -12,7 +12,7 @@ import {
   refreshProjectMcpServers,
   setSessionMcpServers as setSessionMcpServersApi,
   saveProjectMcpDefaults,
-  loadProjectMcpDefaults,
+  loadProjectMcpDefaultsResolved,
   getCustomMcpServers,
   saveCustomMcpServer,
   deleteCustomMcpServer as deleteCustomMcpServerApi,
@@ -143,12 +143,12 @@ export const useMcpStore = create<McpState>()((set, get) => ({
       // Fetch servers and load persisted defaults in parallel
       const [servers, defaults] = await Promise.all([
         getProjectMcpServers(projectPath),
-        loadProjectMcpDefaults(projectPath),
+        loadProjectMcpDefaultsResolved(projectPath),
       ]);
 
       set((state) => ({
         projectServers: { ...state.projectServers, [projectPath]: servers },
-        projectDefaults: { ...state.projectDefaults, [projectPath]: defaults },
+        projectDefaults: { ...state.projectDefaults, [projectPath]: defaults?.enabled ?? null },
         isLoading: { ...state.isLoading, [projectPath]: false },
       }));
       return servers;
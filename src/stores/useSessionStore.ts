@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { create } from "zustand";
+import { registerSessionStatus } from "@/lib/mcp";
 
 /** AI provider variants supported by the backend orchestrator. */
 export type AiMode = "Claude" | "Gemini" | "Codex" | "Plain";
@@ -29,8 +30,10 @@ const SESSION_STARTUP_TIMEOUT_MS = 30000;
  * @property branch - Git branch the session operates on, or null for the default branch.
  * @property worktree_path - Filesystem path to the git worktree, if one was created.
  * @property project_path - Canonicalized project directory this session belongs to.
+ * @property tags - Labels that decide which tagged custom MCP servers the session gets.
  * @property statusMessage - Brief description of what the agent is doing (from MCP status).
  * @property needsInputPrompt - When status is NeedsInput, the specific question for the user.
+ * @property rawState - State string the MCP server reported, kept even when it has no known status.
  */
 export interface SessionConfig {
   id: number;
@@ -39,8 +42,10 @@ export interface SessionConfig {
   status: BackendSessionStatus;
   worktree_path: string | null;
   project_path: string;
+  tags: string[];
   statusMessage?: string;
   needsInputPrompt?: string;
+  rawState?: string | null;
 }
 
 /** Shape of the Tauri `session-status-changed` event payload. */
@@ -50,6 +55,27 @@ interface SessionStatusPayload {
   status: BackendSessionStatus;
   message?: string;
   needs_input_prompt?: string;
+  /** Unmapped MCP state, e.g. "compacting" when `status` is the generic "Unknown". */
+  raw_state?: string | null;
+}
+
+/** Mutation reported by the Tauri `sessions-changed` event. */
+export type SessionChangeKind =
+  | "Created"
+  | "StatusUpdated"
+  | "BranchAssigned"
+  | "TagsUpdated"
+  | "Removed";
+
+/**
+ * Shape of the Tauri `sessions-changed` event payload, emitted after every
+ * registry mutation. `sessions` is the affected project's list afterwards.
+ */
+export interface SessionsChangedPayload {
+  kind: SessionChangeKind;
+  session_id: number;
+  project_path: string;
+  sessions: SessionConfig[];
 }
 
 /**
@@ -57,6 +83,8 @@ interface SessionStatusPayload {
  *
  * @property sessions - Authoritative list of sessions fetched from the backend.
  * @property fetchSessions - Performs a one-shot IPC fetch to replace the session list.
+ * @property fetchSessionsForProject - Re-fetches one project's sessions when it is reopened,
+ *   leaving other projects' sessions in place.
  * @property initListeners - Subscribes to the global `session-status-changed` Tauri event.
  *   Returns an unlisten function; callers must invoke the cleanup to decrement
  *   a reference count and remove the listener when the last subscriber exits.
@@ -96,6 +124,21 @@ const pendingStatusUpdates: Map<string, SessionStatusPayload> = new Map();
  */
 const startupTimeouts: Map<number, ReturnType<typeof setTimeout>> = new Map();
 
+/**
+ * Re-registers restored sessions with the status server so their reports
+ * route to the right project. Failures are logged, not thrown: a session
+ * that can't be registered still shows, it just won't get status updates.
+ */
+async function registerRestoredSessions(sessions: SessionConfig[]): Promise<void> {
+  await Promise.all(
+    sessions.map((s) =>
+      registerSessionStatus(s.id, s.project_path).catch((err) => {
+        console.error(`Failed to register session ${s.id} with status server:`, err);
+      })
+    )
+  );
+}
+
 /** Generate a unique key for buffering status updates */
 function statusBufferKey(sessionId: number, projectPath: string): string {
   return `${sessionId}:${projectPath}`;
@@ -123,6 +166,7 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
     try {
       const sessions = await invoke<SessionConfig[]>("get_sessions");
       set({ sessions, isLoading: false });
+      await registerRestoredSessions(sessions);
     } catch (err) {
       console.error("Failed to fetch sessions:", err);
       set({ error: String(err), isLoading: false });
@@ -135,7 +179,14 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
       const sessions = await invoke<SessionConfig[]>("get_sessions_for_project", {
         projectPath,
       });
-      set({ sessions, isLoading: false });
+      set((state) => ({
+        sessions: [
+          ...state.sessions.filter((s) => s.project_path !== projectPath),
+          ...sessions,
+        ],
+        isLoading: false,
+      }));
+      await registerRestoredSessions(sessions);
     } catch (err) {
       console.error("Failed to fetch sessions for project:", err);
       set({ error: String(err), isLoading: false });
@@ -171,6 +222,7 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
         status: bufferedStatus.status,
         statusMessage: bufferedStatus.message,
         needsInputPrompt: bufferedStatus.needs_input_prompt,
+        rawState: bufferedStatus.raw_state,
       };
     }
 
@@ -265,7 +317,8 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
       if (!activeUnlisten) {
         if (!pendingInit) {
           pendingInit = listen<SessionStatusPayload>("session-status-changed", (event) => {
-            const { session_id, project_path, status, message, needs_input_prompt } = event.payload;
+            const { session_id, project_path, status, message, needs_input_prompt, raw_state } =
+              event.payload;
 
             // Check if session exists in store
             const sessionExists = get().sessions.some(
@@ -293,6 +346,7 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
                       status,
                       statusMessage: message,
                       needsInputPrompt: needs_input_prompt,
+                      rawState: raw_state,
                     }
                   : s
               ),
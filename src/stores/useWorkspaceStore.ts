@@ -3,6 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { create } from "zustand";
 import { createJSONStorage, persist, type StateStorage } from "zustand/middleware";
 import { killSession } from "@/lib/terminal";
+import { useSessionStore } from "@/stores/useSessionStore";
 
 // --- Types ---
 
@@ -134,7 +135,8 @@ function basename(path: string): string {
  *
  * Key behaviors:
  * - `openProject` deduplicates by `projectPath` -- opening the same path twice
- *   simply activates the existing tab.
+ *   simply activates the existing tab. A newly opened tab picks up any
+ *   sessions the backend still holds for that project.
  * - `closeTab` auto-activates the first remaining tab when the closed tab was active.
  */
 export const useWorkspaceStore = create<WorkspaceState & WorkspaceActions>()(
@@ -198,6 +200,10 @@ export const useWorkspaceStore = create<WorkspaceState & WorkspaceActions>()(
             },
           ],
         });
+
+        // A reopened project may still have sessions in the backend; pick
+        // them up and re-register them with the status server
+        await useSessionStore.getState().fetchSessionsForProject(path);
       },
 
       selectTab: (id: string) => {
@@ -78,6 +78,8 @@ export interface MarketplacePlugin {
   downloads: number | null;
   /** Star/rating count (if tracked by marketplace). */
   stars: number | null;
+  /** Whether the catalog marks this plugin as featured/curated. */
+  featured: boolean;
 }
 
 /** Source of an installed plugin - marketplace variant. */
@@ -146,6 +148,36 @@ export interface InstalledPlugin {
   is_enabled: boolean;
 }
 
+/** Payload of `marketplace-refresh-progress`, sent as each source finishes refreshing. */
+export interface MarketplaceRefreshProgress {
+  source_id: string;
+  /** Human-readable name of the source. */
+  name: string;
+  status: "ok" | "error";
+  /** Plugins in the fetched catalog; 0 when the fetch failed. */
+  plugin_count: number;
+  /** Why the fetch failed, if it did. */
+  error: string | null;
+}
+
+/** Payload of `marketplace-refresh-done`, sent once every source was refreshed. */
+export interface MarketplaceRefreshDone {
+  total: number;
+  failed: number;
+}
+
+/** Where an installed plugin came from, checked against the current sources. */
+export interface Provenance {
+  /** Whether the marketplace the plugin was installed from is still configured. */
+  source_present: boolean;
+  /** Name of that marketplace, if it is still configured. */
+  source_name: string | null;
+  /** Whether the marketplace's fetched catalog still lists the plugin. */
+  catalog_entry_present: boolean;
+  /** `[installed, latest]` versions when the catalog entry's version differs. */
+  version_drift: [string, string] | null;
+}
+
 /** Session-specific marketplace plugin configuration. */
 export interface SessionMarketplaceConfig {
   /** IDs of enabled installed plugins. */